@@ -1,24 +1,38 @@
+use std::collections::BTreeMap;
 use std::io::Write as _;
 
 use anyhow::{anyhow, Context as _};
+use async_trait::async_trait;
 use lazy_static::lazy_static;
 use maplit::hashmap;
-use reqwest::blocking::{Client, Response};
 use reqwest::redirect::Policy;
-use reqwest::{StatusCode, Url};
+use reqwest::{Client, Proxy, Response, StatusCode, Url};
 
 use crate::abs_path::AbsPathBuf;
-use crate::config::SessionConfig;
-use crate::dropbox::DbxAuthorizer;
-use crate::full::{fetch_full, TestcaseIter};
-use crate::model::{Contest, ContestId, LangName, LangNameRef, Problem, ProblemId};
-use crate::page::{ExtractCsrfToken as _, ExtractLangId as _};
+use crate::aoj::AojApi;
+use crate::config::{Credentials, SessionConfig, TestcasesSource};
+use crate::dropbox::{is_auth_error, DbxAuthorizer};
+use crate::error::{ErrorKind, WithErrorKind as _};
+#[cfg(feature = "fixtures")]
+use crate::fixtures::FixtureKind;
+use crate::full::{fetch_full, save_testcase, FullOpts, TestcaseIter};
+use crate::model::{
+    Asset, Contest, ContestId, ContestInfo, LangName, LangNameRef, Problem, ProblemId, Sample,
+    ServiceKind, Standings, Statement, Submission,
+};
 use crate::page::{
-    HasHeader as _, LoginPageBuilder, SettingsPageBuilder, SubmitPageBuilder, TasksPageBuilder,
-    TasksPrintPageBuilder, BASE_URL,
+    BuildPage, ContestPageBuilder, HasHeader as _, LoginPageBuilder, MySubmissionsPageBuilder,
+    NotLoggedIn, SettingsPageBuilder, StandingsPageBuilder, SubmissionPageBuilder,
+    SubmitPageBuilder, TasksPageBuilder, TasksPrintPageBuilder, BASE_URL,
 };
-use crate::service::session::WithRetry as _;
-use crate::service::{Act, ResponseExt as _};
+use crate::page::{ExtractCsrfToken as _, ExtractLangId as _};
+use crate::practice::{PracticeProblem, ProblemsApi};
+use crate::service::act::LangNotFound;
+use crate::service::scrape::{PatternReport, Scrape as _};
+use crate::service::session::{may_have_reached, WithRetry as _};
+use crate::service::throttle::set_rate_limit;
+use crate::service::{Act, CookieStorage, ResponseExt as _};
+use crate::storage::{build_client, DropboxStorage, LocalStorage};
 use crate::web::open_in_browser;
 use crate::{Config, Console, Error, Result};
 
@@ -42,30 +56,48 @@ static USER_AGENT: &str = concat!(
     env!("CARGO_PKG_REPOSITORY"),
     ")"
 );
+static SESSION_COOKIE_NAME: &str = "REVEL_SESSION";
 static DBX_REDIRECT_PORT: u16 = 4100;
 static DBX_REDIRECT_PATH: &str = "/oauth2/callback";
 
 #[derive(Debug)]
-pub struct AtcoderActor<'a> {
+pub struct AtcoderActor {
     client: Client,
-    session: &'a SessionConfig,
+    session: SessionConfig,
 }
 
-impl<'a> AtcoderActor<'a> {
-    pub fn new(session: &'a SessionConfig) -> Self {
-        let client = Client::builder()
+impl AtcoderActor {
+    pub fn new(session: &SessionConfig) -> Result<Self> {
+        if session.offline() {
+            return Err(ErrorKind::Network.tag(anyhow!(
+                "Could not access AtCoder in offline mode. Run the command without `--offline` option."
+            )));
+        }
+        let mut builder = Client::builder()
             .referer(false)
             .redirect(Policy::none()) // redirects manually
             .user_agent(USER_AGENT)
-            .timeout(Some(session.timeout()))
-            .build()
-            .expect("Could not setup client. \
-                TLS backend cannot be initialized, or the resolver cannot load the system configuration.");
-        AtcoderActor { client, session }
+            .timeout(Some(session.timeout()));
+        if let Some(proxy) = session.proxy() {
+            let proxy = Proxy::all(&proxy)
+                .with_context(|| format!("Could not parse proxy url : {}", proxy))?;
+            builder = builder.proxy(proxy);
+        }
+        let client = builder.build().context(
+            "Could not setup client. \
+             TLS backend cannot be initialized, or the resolver cannot load the system configuration.",
+        )?;
+        if let (Some(interval), Some(host)) = (session.request_interval(), BASE_URL.host_str()) {
+            set_rate_limit(host, interval);
+        }
+        Ok(AtcoderActor {
+            client,
+            session: session.clone(),
+        })
     }
 }
 
-impl AtcoderActor<'_> {
+impl AtcoderActor {
     fn problem_url(contest_id: &ContestId, problem: &Problem) -> Result<Url> {
         let path = format!("/contests/{}/tasks/{}", contest_id, &problem.url_name());
         BASE_URL
@@ -80,6 +112,38 @@ impl AtcoderActor<'_> {
             .context(format!("Could not parse submissions url : {}", path))
     }
 
+    /// Builds the page again after logging in with credentials from non-interactive providers
+    /// if it fails because the session has expired and `session.auto_relogin` is enabled.
+    async fn with_relogin<B: BuildPage>(&self, builder: B, cnsl: &mut Console) -> Result<B::Page> {
+        let result = match builder.clone().build(&self.client, cnsl).await {
+            Err(err) if self.session.auto_relogin() && err.is::<NotLoggedIn>() => {
+                self.relogin(cnsl)
+                    .await
+                    .context("Could not log in again")
+                    .with_kind(ErrorKind::Auth)?;
+                builder.build(&self.client, cnsl).await
+            }
+            result => result,
+        };
+        result.map_err(|err| {
+            if err.is::<NotLoggedIn>() {
+                ErrorKind::Auth.tag(err)
+            } else {
+                err
+            }
+        })
+    }
+
+    async fn relogin(&self, cnsl: &mut Console) -> Result<()> {
+        writeln!(cnsl, "Session has expired. Logging in again ...")?;
+        let Credentials { username, password } =
+            self.session
+                .credentials()
+                .read(ServiceKind::Atcoder, false, cnsl)?;
+        self.login(username, password, cnsl).await?;
+        Ok(())
+    }
+
     fn validate_login_response(res: &Response) -> Result<()> {
         if res.status() != StatusCode::FOUND {
             return Err(Error::msg("Received invalid response code"));
@@ -87,6 +151,13 @@ impl AtcoderActor<'_> {
         Ok(())
     }
 
+    fn validate_participate_response(res: &Response) -> Result<()> {
+        if res.status() != StatusCode::FOUND {
+            return Err(Error::msg("Received invalid response code"));
+        }
+        Ok(())
+    }
+
     fn validate_submit_response(res: &Response, contest_id: &ContestId) -> Result<()> {
         if res.status() != StatusCode::FOUND {
             return Err(Error::msg("Received invalid response code"));
@@ -100,24 +171,169 @@ impl AtcoderActor<'_> {
         Ok(())
     }
 
-    pub fn fetch_full(
+    async fn fetch_asset(
+        &self,
+        url: &str,
+        used_names: &[String],
+        cnsl: &mut Console,
+    ) -> Result<Asset> {
+        let Self { client, session } = self;
+
+        let abs_url = BASE_URL
+            .join(url)
+            .with_context(|| format!("Could not parse asset url : {}", url))?;
+        let res = client
+            .get(abs_url.clone())
+            .with_retry(
+                client,
+                session.cookies_path(),
+                session.retry_limit(),
+                session.retry_interval(),
+            )
+            .retry_send(cnsl)
+            .await?;
+        if !res.status().is_success() {
+            return Err(anyhow!("Received invalid response code : {}", res.status()));
+        }
+        let content = res.bytes().await.context("Could not read asset")?.to_vec();
+
+        let name = abs_url
+            .path_segments()
+            .and_then(|segs| segs.last())
+            .filter(|name| !name.is_empty())
+            .unwrap_or("asset");
+        let file_name = if used_names.iter().any(|used| used == name) {
+            format!("{}_{}", used_names.len(), name)
+        } else {
+            name.to_owned()
+        };
+        Ok(Asset::new(url, file_name, content))
+    }
+
+    pub async fn fetch_full(
         contest_id: &ContestId,
         problems: &[Problem],
         token_path: &AbsPathBuf,
         access_token: Option<String>,
+        opts: FullOpts,
         conf: &Config,
         cnsl: &mut Console,
     ) -> Result<()> {
+        if let TestcasesSource::Local { dir } = conf.service().testcases_source() {
+            let storage = LocalStorage::new(dir.clone());
+            return fetch_full(&storage, contest_id, problems, opts, conf, cnsl).await;
+        }
+
+        if conf.session().offline() {
+            return Err(ErrorKind::Network.tag(anyhow!(
+                "Could not access Dropbox in offline mode. \
+                 Specify local directory as `testcases_source` in config file instead."
+            )));
+        }
+
         // authorize Dropbox account
-        let dropbox = DbxAuthorizer::new(
+        let authorizer = DbxAuthorizer::new(
             &DBX_APP_KEY,
             DBX_REDIRECT_PORT,
             DBX_REDIRECT_PATH,
             token_path,
-        )
-        .load_or_request(access_token, cnsl)?;
+            build_client(conf)?,
+        );
+        let is_given = access_token.is_some();
+        let dropbox = authorizer.load_or_request(access_token, cnsl).await?;
+
+        let storage = DropboxStorage::new(&dropbox, opts.jobs);
+        match fetch_full(&storage, contest_id, problems, opts, conf, cnsl).await {
+            // stored token may have been revoked or expired
+            Err(err) if !is_given && is_auth_error(&err) => {
+                let dropbox = authorizer.reauthorize(cnsl).await?;
+                let storage = DropboxStorage::new(&dropbox, opts.jobs);
+                fetch_full(&storage, contest_id, problems, opts, conf, cnsl).await
+            }
+            Err(err) if is_given && is_auth_error(&err) => Err(ErrorKind::Auth
+                .tag(err.context("Dropbox access token in ACICK_DBX_ACCESS_TOKEN is invalid"))),
+            result => result,
+        }
+    }
+
+    /// Fetches problems listed by AtCoder Problems, marking the ones accepted by `user` as solved.
+    pub async fn fetch_practice_problems(
+        &self,
+        user: Option<&str>,
+        cnsl: &mut Console,
+    ) -> Result<Vec<PracticeProblem>> {
+        ProblemsApi::new(&self.client, &self.session)
+            .fetch_problems(user, cnsl)
+            .await
+    }
+
+    /// Fetches difficulties of problems in the contest estimated by AtCoder Problems.
+    pub async fn fetch_difficulties(
+        &self,
+        contest_id: &ContestId,
+        cnsl: &mut Console,
+    ) -> Result<BTreeMap<ProblemId, i64>> {
+        ProblemsApi::new(&self.client, &self.session)
+            .fetch_difficulties(contest_id, cnsl)
+            .await
+    }
+
+    /// Fetches problem of Aizu Online Judge with all of its testcases as samples.
+    pub async fn fetch_aoj_problem(&self, problem_id: &str, cnsl: &mut Console) -> Result<Problem> {
+        AojApi::new(&self.client, &self.session)
+            .fetch_problem(problem_id, cnsl)
+            .await
+    }
+
+    /// Fetches the latest rating of the user, or None if the user has not been rated.
+    pub async fn fetch_rating(&self, user: &str, cnsl: &mut Console) -> Result<Option<i64>> {
+        ProblemsApi::new(&self.client, &self.session)
+            .fetch_rating(user, cnsl)
+            .await
+    }
 
-        fetch_full(&dropbox, contest_id, problems, conf, cnsl)
+    /// Records html of pages of the contest into `dir` as fixtures for tests of scrapers.
+    ///
+    /// Recorded pages contain the name of the user logged in.
+    #[cfg(feature = "fixtures")]
+    pub async fn record_fixtures(
+        &self,
+        contest_id: &ContestId,
+        dir: &AbsPathBuf,
+        cnsl: &mut Console,
+    ) -> Result<Vec<AbsPathBuf>> {
+        use strum::IntoEnumIterator as _;
+
+        let session = &self.session;
+        let mut paths = Vec::new();
+        for kind in FixtureKind::iter() {
+            let html = match kind {
+                FixtureKind::Tasks => self
+                    .with_relogin(TasksPageBuilder::new(contest_id, session), cnsl)
+                    .await?
+                    .elem()
+                    .html(),
+                FixtureKind::TasksPrint => self
+                    .with_relogin(TasksPrintPageBuilder::new(contest_id, session), cnsl)
+                    .await?
+                    .elem()
+                    .html(),
+                FixtureKind::Submit => self
+                    .with_relogin(SubmitPageBuilder::new(contest_id, session), cnsl)
+                    .await?
+                    .elem()
+                    .html(),
+            };
+            let path = kind.path(dir, contest_id);
+            path.save_pretty(
+                |mut file| Ok(file.write_all(html.as_bytes())?),
+                true,
+                None,
+                cnsl,
+            )?;
+            paths.push(path);
+        }
+        Ok(paths)
     }
 
     pub fn load_testcases(
@@ -126,25 +342,51 @@ impl AtcoderActor<'_> {
     ) -> Result<TestcaseIter> {
         TestcaseIter::load(testcases_dir, sample_name)
     }
+
+    pub fn load_testcases_with_names(
+        testcases_dir: AbsPathBuf,
+        testcase_names: Vec<String>,
+    ) -> TestcaseIter {
+        TestcaseIter::with_names(testcases_dir, testcase_names)
+    }
+
+    pub fn save_testcases(testcases_dir: &AbsPathBuf, samples: &[Sample]) -> Result<()> {
+        samples
+            .iter()
+            .try_for_each(|sample| save_testcase(testcases_dir, sample))
+    }
 }
 
-impl Act for AtcoderActor<'_> {
-    fn current_user(&self, cnsl: &mut Console) -> Result<Option<String>> {
+#[async_trait(?Send)]
+impl Act for AtcoderActor {
+    async fn current_user(&self, cnsl: &mut Console) -> Result<Option<String>> {
         let Self { client, session } = self;
-        let login_page = LoginPageBuilder::new(session).build(client, cnsl)?;
+        let login_page = LoginPageBuilder::new(session).build(client, cnsl).await?;
         login_page.current_user()
     }
 
-    fn login(&self, user: String, pass: String, cnsl: &mut Console) -> Result<bool> {
+    fn has_stored_session(&self) -> Result<bool> {
+        let storage = CookieStorage::open(self.session.cookies_path())
+            .context("Could not open cookie storage")?;
+        // session cookie of AtCoder contains screen name only if user is logged in
+        let has_session = storage
+            .get_value(&BASE_URL, SESSION_COOKIE_NAME)
+            .map_or(false, |value| value.contains("UserScreenName"));
+        Ok(has_session)
+    }
+
+    async fn login(&self, user: String, pass: String, cnsl: &mut Console) -> Result<bool> {
         let Self { client, session } = self;
 
         // check if user is already logged in
-        let login_page = LoginPageBuilder::new(session).build(client, cnsl)?;
+        let login_page = LoginPageBuilder::new(session).build(client, cnsl).await?;
         let current_user = login_page.current_user()?;
         if let Some(current_user) = current_user {
             // already logged in
             if current_user != user {
-                return Err(anyhow!("Logged in as another user: {}", current_user));
+                return Err(
+                    ErrorKind::Auth.tag(anyhow!("Logged in as another user: {}", current_user))
+                );
             }
             return Ok(false);
         }
@@ -167,66 +409,136 @@ impl Act for AtcoderActor<'_> {
                 session.retry_limit(),
                 session.retry_interval(),
             )
-            .retry_send(cnsl)?;
+            .retry_send(cnsl)
+            .await?;
 
         // check if login succeeded
-        Self::validate_login_response(&res).context("Login rejected by service")?;
-        let settings_page = SettingsPageBuilder::new(session).build(client, cnsl)?;
+        Self::validate_login_response(&res)
+            .context("Login rejected by service")
+            .with_kind(ErrorKind::Auth)?;
+        let settings_page = SettingsPageBuilder::new(session)
+            .build(client, cnsl)
+            .await?;
         let current_user = settings_page.current_user()?;
         match current_user {
-            None => Err(anyhow!("Failed to log in")),
+            None => Err(ErrorKind::Auth.tag(anyhow!("Failed to log in"))),
             Some(current_user) if current_user != user => {
-                Err(anyhow!("Logged in as another user: {}", current_user))
+                Err(ErrorKind::Auth.tag(anyhow!("Logged in as another user: {}", current_user)))
             }
             _ => Ok(true),
         }
     }
 
-    fn fetch(
+    async fn participate(&self, contest_id: &ContestId, cnsl: &mut Console) -> Result<bool> {
+        let Self { client, session } = self;
+
+        // check if user has already registered
+        let contest_page = self
+            .with_relogin(ContestPageBuilder::new(contest_id, session), cnsl)
+            .await?;
+        if contest_page.is_registered() {
+            return Ok(false);
+        }
+        let register_path = contest_page.extract_register_path().context(
+            "Could not find registration form. Check if registration for the contest is open.",
+        )?;
+        let register_url = BASE_URL
+            .join(register_path)
+            .context(format!("Could not parse register url : {}", register_path))?;
+
+        // prepare payload
+        let csrf_token = contest_page.extract_csrf_token()?;
+        let payload = hashmap!("csrf_token" => csrf_token);
+
+        // post registration
+        let res = client
+            .post(register_url)
+            .form(&payload)
+            .with_retry(
+                client,
+                session.cookies_path(),
+                session.retry_limit(),
+                session.retry_interval(),
+            )
+            .retry_send(cnsl)
+            .await?;
+
+        // check if registration succeeded
+        Self::validate_participate_response(&res).context("Registration rejected by service")?;
+        let contest_page = self
+            .with_relogin(ContestPageBuilder::new(contest_id, session), cnsl)
+            .await?;
+        if !contest_page.is_registered() {
+            return Err(anyhow!("Failed to participate in contest {}", contest_id));
+        }
+        Ok(true)
+    }
+
+    async fn fetch(
         &self,
         contest_id: &ContestId,
-        problem_id: &Option<ProblemId>,
+        problem_ids: &[ProblemId],
         cnsl: &mut Console,
-    ) -> Result<(Contest, Vec<Problem>)> {
-        let Self { client, session } = self;
-
-        let tasks_page = TasksPageBuilder::new(contest_id, session).build(client, cnsl)?;
-        let contest_name = tasks_page
-            .extract_contest_name()
-            .context("Could not extract contest name")?;
+    ) -> Result<(Contest, Vec<Problem>, PatternReport)> {
+        let session = &self.session;
+
+        let tasks_page = self
+            .with_relogin(TasksPageBuilder::new(contest_id, session), cnsl)
+            .await?;
+        let contest_name = tasks_page.save_html_on_error(
+            "tasks",
+            tasks_page
+                .extract_contest_name()
+                .context("Could not extract contest name"),
+        )?;
         let mut problems: Vec<Problem> = tasks_page
-            .extract_problems(cnsl)?
+            .save_html_on_error("tasks", tasks_page.extract_problems(cnsl))?
             .into_iter()
-            .filter(|problem| {
-                if let Some(problem_id) = problem_id {
-                    problem.id() == problem_id
-                } else {
-                    true
-                }
-            })
+            .filter(|problem| problem_ids.is_empty() || problem_ids.contains(problem.id()))
             .collect();
+        if let Some(problem_id) = problem_ids
+            .iter()
+            .find(|&problem_id| problems.iter().all(|problem| problem.id() != problem_id))
+        {
+            return Err(anyhow!(
+                "Could not find problem \"{}\" in contest {}",
+                problem_id,
+                contest_id
+            ));
+        }
         if problems.is_empty() {
-            let err = if let Some(problem_id) = problem_id {
-                Err(anyhow!(
-                    "Could not find problem \"{}\" in contest {}",
-                    problem_id,
-                    contest_id
-                ))
-            } else {
-                Err(anyhow!(
-                    "Could not find any problems in contest {}",
-                    contest_id
-                ))
-            };
-            return err;
+            return Err(anyhow!(
+                "Could not find any problems in contest {}",
+                contest_id
+            ));
         }
 
-        let tasks_print_page =
-            TasksPrintPageBuilder::new(contest_id, session).build(client, cnsl)?;
-        let mut samples_map = tasks_print_page.extract_samples_map()?;
+        let tasks_print_page = self
+            .with_relogin(TasksPrintPageBuilder::new(contest_id, session), cnsl)
+            .await?;
+        let mut report = PatternReport::default();
+        let mut samples_map = tasks_print_page.save_html_on_error(
+            "tasks_print",
+            tasks_print_page.extract_samples_map(&mut report, session.lax_samples()),
+        )?;
+        let mut points_map = tasks_print_page
+            .save_html_on_error("tasks_print", tasks_print_page.extract_points_map())?;
+        if cnsl.is_verbose() {
+            for hit in report.hits() {
+                writeln!(cnsl, "Matched pattern for {}", hit)?;
+            }
+        }
         for problem in problems.iter_mut() {
             if let Some(samples) = samples_map.remove(problem.id()) {
+                if samples.is_empty() && !session.lax_samples() {
+                    cnsl.warn(&format!(
+                        "Could not extract samples for problem {}. \
+                         Try --lax-samples if the layout of statement is new.",
+                        problem.id()
+                    ))?;
+                }
                 problem.set_samples(samples);
+                problem.set_points(points_map.remove(problem.id()));
             } else {
                 // found problem on TasksPage but not found on TasksPrintPage
                 return Err(anyhow!(
@@ -237,21 +549,93 @@ impl Act for AtcoderActor<'_> {
         }
 
         let contest = Contest::new(contest_id.to_owned(), contest_name);
-        Ok((contest, problems))
+        Ok((contest, problems, report))
     }
 
-    fn submit<'a>(
+    async fn fetch_contest_info(
+        &self,
+        contest_id: &ContestId,
+        cnsl: &mut Console,
+    ) -> Result<ContestInfo> {
+        let session = &self.session;
+
+        let contest_page = self
+            .with_relogin(ContestPageBuilder::new(contest_id, session), cnsl)
+            .await?;
+        let contest_name = contest_page.save_html_on_error(
+            "contest",
+            contest_page
+                .extract_contest_name()
+                .context("Could not extract contest name"),
+        )?;
+        let (start_at, end_at) = contest_page.save_html_on_error(
+            "contest",
+            contest_page
+                .extract_duration()
+                .context("Could not extract contest duration"),
+        )?;
+        let contest = Contest::new(contest_id.to_owned(), contest_name);
+        Ok(ContestInfo::new(contest, start_at, end_at))
+    }
+
+    async fn fetch_statements(
+        &self,
+        contest_id: &ContestId,
+        problems: &[Problem],
+        cnsl: &mut Console,
+    ) -> Result<Vec<Statement>> {
+        let session = &self.session;
+
+        let tasks_print_page = self
+            .with_relogin(TasksPrintPageBuilder::new(contest_id, session), cnsl)
+            .await?;
+        let mut statements_map = tasks_print_page
+            .save_html_on_error("tasks_print", tasks_print_page.extract_statements_map())?;
+        let mut statements = Vec::new();
+        for problem in problems {
+            let (html, urls) = statements_map.remove(problem.id()).with_context(|| {
+                format!("Could not extract statement for problem : {}", problem.id())
+            })?;
+            let mut assets: Vec<Asset> = Vec::new();
+            for url in urls {
+                let used_names: Vec<String> =
+                    assets.iter().map(|a| a.file_name().to_owned()).collect();
+                match self.fetch_asset(&url, &used_names, cnsl).await {
+                    Ok(asset) => assets.push(asset),
+                    Err(err) => cnsl.warn(&format!("Could not fetch asset {} : {}", url, err))?,
+                }
+            }
+            statements.push(Statement::new(problem.id().clone(), html, assets));
+        }
+        Ok(statements)
+    }
+
+    async fn fetch_lang_names(
+        &self,
+        contest_id: &ContestId,
+        cnsl: &mut Console,
+    ) -> Result<Vec<LangName>> {
+        let submit_page = self
+            .with_relogin(SubmitPageBuilder::new(contest_id, &self.session), cnsl)
+            .await?;
+        Ok(submit_page.extract_lang_names())
+    }
+
+    async fn submit<'a>(
         &self,
         contest_id: &ContestId,
         problem: &Problem,
         lang_names: &'a [LangName],
         source: &str,
+        is_dry_run: bool,
         cnsl: &mut Console,
     ) -> Result<LangNameRef<'a>> {
         let Self { client, session } = self;
 
         // get submit page
-        let submit_page = SubmitPageBuilder::new(contest_id, session).build(client, cnsl)?;
+        let submit_page = self
+            .with_relogin(SubmitPageBuilder::new(contest_id, session), cnsl)
+            .await?;
 
         // extract lang id
         let (lang_id, lang_name) = lang_names
@@ -261,12 +645,7 @@ impl Act for AtcoderActor<'_> {
                     .extract_lang_id(lang_name)
                     .map(|lang_id| (lang_id, lang_name))
             })
-            .with_context(|| {
-                format!(
-                    "Could not find available language from the given language list: {}",
-                    lang_names.join(", ")
-                )
-            })?;
+            .ok_or_else(|| LangNotFound(lang_names.to_vec()))?;
 
         // prepare payload
         let csrf_token = submit_page.extract_csrf_token()?;
@@ -277,17 +656,41 @@ impl Act for AtcoderActor<'_> {
             "sourceCode" => source,
         );
 
-        // submit source code
+        // show what would be submitted without sending it
+        if is_dry_run {
+            let req = client.post(submit_page.url()?).form(&payload).build()?;
+            let payload_size = req
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map_or(0, <[u8]>::len);
+            writeln!(
+                cnsl,
+                "Would submit {} as {} (lang id: {}, payload size: {} Bytes)",
+                problem.url_name(),
+                lang_name,
+                lang_id,
+                payload_size
+            )?;
+            return Ok(lang_name);
+        }
+
+        // submit source code (not retried, which could submit twice if the request has reached)
         let res = client
             .post(submit_page.url()?)
             .form(&payload)
-            .with_retry(
-                client,
-                session.cookies_path(),
-                session.retry_limit(),
-                session.retry_interval(),
-            )
-            .retry_send(cnsl)?;
+            .with_retry(client, session.cookies_path(), 0, session.retry_interval())
+            .retry_send(cnsl)
+            .await
+            .map_err(|err| {
+                if may_have_reached(&err) {
+                    err.context(
+                        "Could not confirm that the submission was accepted. \
+                         Check your submissions on the service before submitting again.",
+                    )
+                } else {
+                    err
+                }
+            })?;
 
         // check response
         Self::validate_submit_response(&res, contest_id)
@@ -296,6 +699,84 @@ impl Act for AtcoderActor<'_> {
         Ok(lang_name)
     }
 
+    async fn fetch_submission(
+        &self,
+        contest_id: &ContestId,
+        submission_id: &str,
+        cnsl: &mut Console,
+    ) -> Result<Submission> {
+        let session = &self.session;
+
+        let submission_page = self
+            .with_relogin(
+                SubmissionPageBuilder::new(contest_id, submission_id, session),
+                cnsl,
+            )
+            .await?;
+        submission_page.save_html_on_error(
+            "submission",
+            submission_page
+                .extract_submission()
+                .context("Could not extract submission detail"),
+        )
+    }
+
+    async fn fetch_submission_source(
+        &self,
+        contest_id: &ContestId,
+        submission_id: &str,
+        cnsl: &mut Console,
+    ) -> Result<String> {
+        let session = &self.session;
+
+        let submission_page = self
+            .with_relogin(
+                SubmissionPageBuilder::new(contest_id, submission_id, session),
+                cnsl,
+            )
+            .await?;
+        submission_page.save_html_on_error(
+            "submission",
+            submission_page
+                .extract_source()
+                .context("Could not extract source code of submission"),
+        )
+    }
+
+    async fn fetch_latest_submission(
+        &self,
+        contest_id: &ContestId,
+        problem: &Problem,
+        cnsl: &mut Console,
+    ) -> Result<Option<Submission>> {
+        let session = &self.session;
+
+        let my_submissions_page = self
+            .with_relogin(
+                MySubmissionsPageBuilder::new(contest_id, problem, session),
+                cnsl,
+            )
+            .await?;
+        match my_submissions_page.extract_latest_submission_id() {
+            Some(submission_id) => self
+                .fetch_submission(contest_id, &submission_id, cnsl)
+                .await
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    async fn fetch_standings(
+        &self,
+        contest_id: &ContestId,
+        cnsl: &mut Console,
+    ) -> Result<Standings> {
+        let standings_page = self
+            .with_relogin(StandingsPageBuilder::new(contest_id, &self.session), cnsl)
+            .await?;
+        Ok(standings_page.extract_standings())
+    }
+
     fn open_problem_url(
         &self,
         contest_id: &ContestId,
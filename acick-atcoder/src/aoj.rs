@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _};
+use lazy_static::lazy_static;
+use reqwest::{Client, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::config::SessionConfig;
+use crate::model::{Byte, Compare, Problem, Sample};
+use crate::service::session::WithRetry as _;
+use crate::service::throttle::set_rate_limit;
+use crate::{Console, Result};
+
+lazy_static! {
+    static ref API_BASE_URL: Url = Url::parse("https://judgeapi.u-aizu.ac.jp/").unwrap();
+    static ref DATA_BASE_URL: Url = Url::parse("https://judgedat.u-aizu.ac.jp/").unwrap();
+}
+
+/// Interval between requests, which keeps load of AOJ low while downloading many testcases.
+const REQUEST_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+struct ApiDescription {
+    /// Time limit in seconds
+    time_limit: u64,
+    /// Memory limit in kilobytes
+    memory_limit: u64,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+struct ApiTestcaseHeaders {
+    headers: Vec<ApiTestcaseHeader>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+struct ApiTestcaseHeader {
+    serial: u64,
+    name: String,
+}
+
+/// Client of API of Aizu Online Judge (http://developers.u-aizu.ac.jp/index).
+#[derive(Debug)]
+pub(crate) struct AojApi<'a> {
+    client: &'a Client,
+    session: &'a SessionConfig,
+}
+
+impl<'a> AojApi<'a> {
+    pub fn new(client: &'a Client, session: &'a SessionConfig) -> Self {
+        for url in &[&*API_BASE_URL, &*DATA_BASE_URL] {
+            if let Some(host) = url.host_str() {
+                set_rate_limit(host, REQUEST_INTERVAL);
+            }
+        }
+        Self { client, session }
+    }
+
+    /// Fetches the problem with all of its judge data as samples.
+    pub async fn fetch_problem(&self, problem_id: &str, cnsl: &mut Console) -> Result<Problem> {
+        let description: ApiDescription = self
+            .get_json(
+                API_BASE_URL.join(&format!("resources/descriptions/en/{}", problem_id))?,
+                cnsl,
+            )
+            .await
+            .context("Could not fetch problem from AOJ")?;
+        let headers: ApiTestcaseHeaders = self
+            .get_json(
+                DATA_BASE_URL.join(&format!("testcases/{}/header", problem_id))?,
+                cnsl,
+            )
+            .await
+            .context("Could not fetch testcases from AOJ")?;
+
+        let mut samples = Vec::with_capacity(headers.headers.len());
+        for header in headers.headers {
+            let url = |inout: &str| {
+                DATA_BASE_URL.join(&format!(
+                    "testcases/{}/{}/{}",
+                    problem_id, header.serial, inout
+                ))
+            };
+            let input = self.get_text(url("in")?, cnsl).await?;
+            let output = self.get_text(url("out")?, cnsl).await?;
+            let name = format!("{:02}_{}", header.serial, header.name);
+            samples.push(Sample::new(name, input, output));
+        }
+        Ok(Problem::new(
+            problem_id,
+            problem_id,
+            problem_id,
+            Some(Duration::from_secs(description.time_limit)),
+            Some(Byte::from(description.memory_limit * 1024)),
+            Compare::Default,
+            samples,
+        ))
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: Url, cnsl: &mut Console) -> Result<T> {
+        let text = self.get_text(url, cnsl).await?;
+        Ok(serde_json::from_str(&text).context("Could not parse response")?)
+    }
+
+    async fn get_text(&self, url: Url, cnsl: &mut Console) -> Result<String> {
+        let res = self
+            .client
+            .get(url)
+            .with_retry(
+                self.client,
+                self.session.cookies_path(),
+                self.session.retry_limit(),
+                self.session.retry_interval(),
+            )
+            .without_cookies()
+            .retry_send(cnsl)
+            .await?;
+        match res.status() {
+            StatusCode::OK => Ok(res.text().await.context("Could not read response")?),
+            status => Err(anyhow!("Received invalid response code : {}", status)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_headers() -> anyhow::Result<()> {
+        let headers: ApiTestcaseHeaders = serde_json::from_str(
+            r#"{"problemId":"ITP1_1_A","headers":[{"serial":1,"name":"judge_data","inputSize":0,"outputSize":13,"score":100}]}"#,
+        )?;
+        assert_eq!(
+            headers.headers,
+            vec![ApiTestcaseHeader {
+                serial: 1,
+                name: String::from("judge_data")
+            }]
+        );
+        Ok(())
+    }
+}
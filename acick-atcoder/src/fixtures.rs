@@ -0,0 +1,114 @@
+//! Pages recorded from AtCoder, which are replayed in tests of scrapers without network.
+//!
+//! Fixtures are recorded by `acick debug record-fixtures`, which is available only if acick is
+//! built with `fixtures` feature, into `<FIXTURES_DIR>/<contest>/<page>.html`.
+
+use std::fs;
+
+use anyhow::Context as _;
+use scraper::Html;
+
+use crate::abs_path::AbsPathBuf;
+use crate::model::ContestId;
+use crate::Result;
+
+/// Directory of fixtures replayed in tests.
+pub static FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+
+/// Page recorded as a fixture.
+#[derive(EnumIter, IntoStaticStr, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[strum(serialize_all = "snake_case")]
+pub enum FixtureKind {
+    Tasks,
+    TasksPrint,
+    Submit,
+}
+
+impl FixtureKind {
+    /// Returns path of the fixture of the page of contest.
+    pub fn path(self, dir: &AbsPathBuf, contest_id: &ContestId) -> AbsPathBuf {
+        let name: &str = self.into();
+        dir.join(contest_id.to_string())
+            .join(format!("{}.html", name))
+    }
+
+    /// Loads html of the fixture of the page of contest.
+    pub fn load(self, dir: &AbsPathBuf, contest_id: &ContestId) -> Result<Html> {
+        let path = self.path(dir, contest_id);
+        let html = fs::read_to_string(path.as_ref())
+            .with_context(|| format!("Could not read fixture : {}", path))?;
+        Ok(Html::parse_document(&html))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator as _;
+
+    use super::*;
+    use crate::config::SessionConfig;
+    use crate::console::Console;
+    use crate::page::{
+        ExtractCsrfToken as _, SubmitPage, SubmitPageBuilder, TasksPage, TasksPageBuilder,
+        TasksPrintPage, TasksPrintPageBuilder,
+    };
+    use crate::service::scrape::PatternReport;
+
+    /// Returns ids of contests whose fixtures are all recorded.
+    fn recorded_contests(dir: &AbsPathBuf) -> anyhow::Result<Vec<ContestId>> {
+        if !dir.as_ref().is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut contest_ids = Vec::new();
+        for entry in fs::read_dir(dir.as_ref())? {
+            let entry = entry?;
+            let contest_id = ContestId::from(entry.file_name().to_string_lossy().as_ref());
+            if FixtureKind::iter().all(|kind| kind.path(dir, &contest_id).as_ref().is_file()) {
+                contest_ids.push(contest_id);
+            }
+        }
+        Ok(contest_ids)
+    }
+
+    #[test]
+    fn replay_fixtures() -> anyhow::Result<()> {
+        let dir = AbsPathBuf::try_new(FIXTURES_DIR)?;
+        let session = SessionConfig::default();
+        let mut cnsl = Console::sink(Default::default());
+        let contest_ids = recorded_contests(&dir)?;
+        assert!(!contest_ids.is_empty(), "Found no fixtures in {}", dir);
+        for contest_id in contest_ids {
+            let tasks_page = TasksPage::from_html(
+                TasksPageBuilder::new(&contest_id, &session),
+                FixtureKind::Tasks.load(&dir, &contest_id)?,
+            );
+            let problems = tasks_page.extract_problems(&mut cnsl)?;
+            assert!(!problems.is_empty(), "{}", contest_id);
+
+            let tasks_print_page = TasksPrintPage::from_html(
+                TasksPrintPageBuilder::new(&contest_id, &session),
+                FixtureKind::TasksPrint.load(&dir, &contest_id)?,
+            );
+            let samples_map =
+                tasks_print_page.extract_samples_map(&mut PatternReport::default(), false)?;
+            for problem in &problems {
+                assert!(
+                    samples_map.contains_key(problem.id()),
+                    "{} {}",
+                    contest_id,
+                    problem.id()
+                );
+            }
+            // some problems (e.g. interactive ones) have no samples, but not all of them
+            assert!(samples_map.values().any(|samples| !samples.is_empty()));
+
+            let submit_page = SubmitPage::from_html(
+                SubmitPageBuilder::new(&contest_id, &session),
+                FixtureKind::Submit.load(&dir, &contest_id)?,
+            );
+            submit_page.extract_csrf_token()?;
+            assert!(!submit_page.extract_lang_names().is_empty());
+        }
+        Ok(())
+    }
+}
@@ -1,21 +1,22 @@
-use std::fs::read_dir;
+use std::fmt;
+use std::fs::{self, read_dir};
 use std::io::{self, Read as _, Write as _};
 use std::path::Path;
+use std::time::Instant;
 use std::vec::IntoIter;
 
-use anyhow::{anyhow, Context as _};
-use rayon::prelude::*;
+use anyhow::Context as _;
+use futures::stream::{self, StreamExt as _, TryStreamExt as _};
 use strum::IntoEnumIterator as _;
 use tempfile::tempdir;
 
 use crate::abs_path::AbsPathBuf;
-use crate::dropbox::{Dropbox, FileMetadata};
+use crate::metrics::{Metrics, Phase};
 use crate::model::{AsSamples, ContestId, Problem, Sample};
+use crate::service::throttle::{ByteRate, Throttle};
+use crate::storage::StorageProvider;
 use crate::{Config, Console, Error, Result};
 
-static DBX_TESTCASES_URL: &str =
-    "https://www.dropbox.com/sh/arnpe0ef5wds8cv/AAAk_SECQ2Nc6SVGii3rHX6Fa?dl=0";
-
 #[derive(AsRefStr, EnumIter, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[strum(serialize_all = "kebab-case")]
 pub enum InOut {
@@ -23,48 +24,140 @@ pub enum InOut {
     Out,
 }
 
-impl InOut {
-    fn par_iter() -> impl ParallelIterator<Item = Self> {
-        Self::iter().collect::<Vec<_>>().into_par_iter()
-    }
+/// Options of downloading full testcases.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FullOpts {
+    /// Limits transfer rate of downloads
+    pub limit_rate: Option<ByteRate>,
+    /// Number of parallel downloads (defaults to the number of CPUs)
+    pub jobs: Option<usize>,
 }
 
-pub fn fetch_full(
-    dropbox: &Dropbox,
+pub async fn fetch_full(
+    storage: &dyn StorageProvider,
     contest_id: &ContestId,
     problems: &[Problem],
+    opts: FullOpts,
     conf: &Config,
     cnsl: &mut Console,
 ) -> Result<()> {
-    writeln!(cnsl, "Downloading testcase files from Dropbox ...")?;
+    let started_at = Instant::now();
+    let result = fetch_full_inner(storage, contest_id, problems, opts, conf, cnsl).await;
+    Metrics::record(Phase::Dropbox, started_at.elapsed());
+    result
+}
 
-    // find dropbox folder that corresponds to the contest
-    let folders = dropbox.list_all_folders("", Some(DBX_TESTCASES_URL))?;
-    let folder = folders
+async fn fetch_full_inner(
+    storage: &dyn StorageProvider,
+    contest_id: &ContestId,
+    problems: &[Problem],
+    opts: FullOpts,
+    conf: &Config,
+    cnsl: &mut Console,
+) -> Result<()> {
+    writeln!(
+        cnsl,
+        "Downloading testcase files from {} ...",
+        storage.name()
+    )?;
+    let throttle = opts.limit_rate.map(Throttle::new);
+    let jobs = opts.jobs.unwrap_or_else(rayon::current_num_threads);
+
+    // list testcase files of all problems
+    let files_per_problem = storage.list_testcase_files(contest_id, problems).await?;
+
+    // setup progress bar of all problems
+    let total_size = files_per_problem
         .iter()
-        .find(|folder| &ContestId::from(&folder.name) == contest_id)
-        .ok_or_else(|| {
-            anyhow!(
-                "Could not find folder for the contest on Dropbox : {}",
-                contest_id
-            )
-        })?;
-
-    // download and save testcase files
-    problems.iter().try_for_each(|problem| -> Result<()> {
-        // setup temp dir
-        let tmp_testcases_dir =
-            tempdir().context("Could not create temp dir for downloading testcase files")?;
-        let tmp_testcases_abs_dir = AbsPathBuf::try_new(tmp_testcases_dir.path().to_owned())?;
-
-        // download testcase files for the problem
-        fetch_problem_full(dropbox, &folder.name, problem, &tmp_testcases_abs_dir, cnsl)?;
-
-        // move temp dir to testcases dir specified in config
-        conf.move_testcases_dir(problem, &tmp_testcases_abs_dir, cnsl)?;
+        .flatten()
+        .map(|file| file.size)
+        .sum();
+    let pb = cnsl.build_pb_bytes(total_size);
+    pb.set_prefix(contest_id.as_ref());
 
-        Ok(())
-    })
+    // setup temp dirs
+    let tmp_dirs = problems
+        .iter()
+        .map(|_| -> Result<_> {
+            let tmp_testcases_dir =
+                tempdir().context("Could not create temp dir for downloading testcase files")?;
+            let tmp_testcases_abs_dir = AbsPathBuf::try_new(tmp_testcases_dir.path().to_owned())?;
+            Ok((tmp_testcases_dir, tmp_testcases_abs_dir))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // testcases of problems without files are moved first because no download completes them
+    let mut remaining: Vec<usize> = files_per_problem.iter().map(Vec::len).collect();
+    for (problem, (n_files, (_, tmp_testcases_abs_dir))) in
+        problems.iter().zip(remaining.iter().zip(&tmp_dirs))
+    {
+        if *n_files == 0 {
+            save_testcases_dir(problem, tmp_testcases_abs_dir, conf, cnsl)?;
+        }
+    }
+
+    // download testcase files of all problems in parallel
+    let downloads = problems
+        .iter()
+        .zip(&files_per_problem)
+        .zip(&tmp_dirs)
+        .enumerate()
+        .flat_map(|(i, ((problem, files), (_, testcases_dir)))| {
+            files
+                .iter()
+                .map(move |file| (i, problem, file, testcases_dir))
+        });
+    let throttle = throttle.as_ref();
+    let on_progress = |bytes| pb.inc(bytes);
+    let on_progress = &on_progress;
+    let mut downloaded = stream::iter(downloads)
+        .map(|(i, problem, file, testcases_dir)| async move {
+            let testcase_name = get_testcase_name(&file.name)
+                .ok_or_else(|| Error::msg("Failed to get testcase name from file name"))?;
+            let abs_path = testcases_dir
+                .join(file.inout.as_ref())
+                .join(get_testcase_file_name(testcase_name));
+            storage
+                .download_file(file, &abs_path, throttle, on_progress)
+                .await
+                .with_context(|| format!("Could not download testcases of {}", problem.id()))?;
+            Ok::<_, Error>(i)
+        })
+        .buffer_unordered(jobs);
+    // move testcases of each problem as soon as all of its files are downloaded,
+    // so that they are available even if downloads of other problems fail
+    while let Some(i) = downloaded.try_next().await? {
+        remaining[i] -= 1;
+        if remaining[i] == 0 {
+            save_testcases_dir(&problems[i], &tmp_dirs[i].1, conf, cnsl)?;
+        }
+    }
+    pb.finish();
+
+    Ok(())
+}
+
+/// Normalizes and validates testcase files downloaded into `tmp_testcases_abs_dir`,
+/// and moves them to testcases dir of the problem specified in config.
+fn save_testcases_dir(
+    problem: &Problem,
+    tmp_testcases_abs_dir: &AbsPathBuf,
+    conf: &Config,
+    cnsl: &mut Console,
+) -> Result<()> {
+    if conf.normalize_testcases() {
+        normalize_testcases(tmp_testcases_abs_dir)?;
+    }
+    let issues = validate_testcases(tmp_testcases_abs_dir)?;
+    for issue in issues.iter() {
+        cnsl.warn(&format!(
+            "Found invalid testcase of {} : {}",
+            problem.id(),
+            issue
+        ))?;
+    }
+    conf.move_testcases_dir(problem, tmp_testcases_abs_dir, cnsl)?;
+    Ok(())
 }
 
 static TESTCASE_EXT: &str = "txt";
@@ -97,75 +190,126 @@ fn get_testcase_file_name(testcase_name: &str) -> String {
     file_name
 }
 
-fn list_testcase_files(
-    dropbox: &Dropbox,
-    folder_name: &str,
-    problem: &Problem,
-) -> Result<Vec<(InOut, FileMetadata)>> {
-    // fetch testcase files metadata
-    let files_arr: Vec<(InOut, Vec<FileMetadata>)> = InOut::par_iter()
-        .map(|inout| {
-            let files = dropbox
-                .list_all_files(
-                    format!("/{}/{}/{}", folder_name, problem.id(), inout.as_ref()),
-                    Some(DBX_TESTCASES_URL),
-                )
-                .context("Could not list testcase files on Dropbox")?;
-            Ok((inout, files))
-        })
-        .collect::<Result<Vec<_>>>()?;
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TestcaseIssue {
+    Empty(InOut, String),
+    MissingCounterpart(InOut, String),
+    CountMismatch(usize, usize),
+}
 
-    // flatten testcase files metadata
-    let files: Vec<(InOut, FileMetadata)> = files_arr
-        .into_iter()
-        .map(|(inout, files)| files.into_iter().map(move |file| (inout, file)))
-        .flatten()
-        .collect();
-    Ok(files)
+impl fmt::Display for TestcaseIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty(inout, name) => write!(f, "{}put file {} is empty", inout.as_ref(), name),
+            Self::MissingCounterpart(inout, name) => {
+                write!(f, "{}put file {} has no counterpart", inout.as_ref(), name)
+            }
+            Self::CountMismatch(n_in, n_out) => {
+                write!(f, "found {} input files but {} output files", n_in, n_out)
+            }
+        }
+    }
 }
 
-fn fetch_problem_full(
-    dropbox: &Dropbox,
-    folder_name: &str,
-    problem: &Problem,
-    testcases_dir: &AbsPathBuf,
-    cnsl: &mut Console,
-) -> Result<()> {
-    let files = list_testcase_files(dropbox, folder_name, problem)?;
+fn list_testcase_names(testcases_dir: &AbsPathBuf, inout: InOut) -> Result<Vec<String>> {
+    let dir = testcases_dir.join(inout.as_ref());
+    if !dir.as_ref().is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut names = Vec::new();
+    for entry in read_dir(dir.as_ref())? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let file_name = entry.file_name();
+        if let Some(name) = validate_testcase_file_name(&file_name.to_string_lossy()) {
+            names.push(name.to_owned());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
 
-    // setup progress bar
-    let total_size = files.iter().map(|(_, file)| file.size).sum();
-    let pb = cnsl.build_pb_bytes(total_size);
-    pb.set_prefix(problem.id().as_ref());
-
-    // fetch and save
-    files
-        .into_par_iter()
-        .try_for_each::<_, Result<()>>(|(inout, file)| {
-            let dbx_path = format!(
-                "/{}/{}/{}/{}",
-                folder_name,
-                problem.id(),
-                inout.as_ref(),
-                file.name
-            );
-            let mut reader = dropbox.get_shared_link_file(DBX_TESTCASES_URL, dbx_path)?;
-            let testcase_name = get_testcase_name(&file.name)
-                .ok_or_else(|| Error::msg("Failed to get testcase name from Dropbox file name"))?;
-            let file_name = get_testcase_file_name(testcase_name);
-            let abs_path = testcases_dir.join(inout.as_ref()).join(file_name);
-            abs_path.save(
-                |mut file| {
-                    io::copy(&mut reader, &mut file).context("Could not save testcase to file")?;
-                    Ok(())
-                },
-                true,
-            )?;
-            pb.inc(file.size);
-            Ok(())
-        })?;
+/// Converts CRLF to LF and appends a newline to the end of each testcase file if missing.
+fn normalize_testcases(testcases_dir: &AbsPathBuf) -> Result<()> {
+    for inout in InOut::iter() {
+        for name in list_testcase_names(testcases_dir, inout)? {
+            let abs_path = testcases_dir
+                .join(inout.as_ref())
+                .join(get_testcase_file_name(&name));
+            let content = fs::read(abs_path.as_ref())?;
+            if let Some(normalized) = normalize_newlines(&content) {
+                fs::write(abs_path.as_ref(), normalized)
+                    .with_context(|| format!("Could not normalize testcase file : {}", name))?;
+            }
+        }
+    }
+    Ok(())
+}
 
-    pb.finish();
+/// Returns `None` if the content is already normalized.
+fn normalize_newlines(content: &[u8]) -> Option<Vec<u8>> {
+    let has_crlf = content.windows(2).any(|w| w == b"\r\n");
+    let lacks_last_newline = !content.is_empty() && !content.ends_with(b"\n");
+    if !has_crlf && !lacks_last_newline {
+        return None;
+    }
+    let mut normalized = Vec::with_capacity(content.len() + 1);
+    let mut iter = content.iter().peekable();
+    while let Some(&b) = iter.next() {
+        if b == b'\r' && iter.peek() == Some(&&b'\n') {
+            continue;
+        }
+        normalized.push(b);
+    }
+    if !normalized.ends_with(b"\n") {
+        normalized.push(b'\n');
+    }
+    Some(normalized)
+}
+
+fn validate_testcases(testcases_dir: &AbsPathBuf) -> Result<Vec<TestcaseIssue>> {
+    let in_names = list_testcase_names(testcases_dir, InOut::In)?;
+    let out_names = list_testcase_names(testcases_dir, InOut::Out)?;
+    let mut issues = Vec::new();
+    if in_names.len() != out_names.len() {
+        issues.push(TestcaseIssue::CountMismatch(
+            in_names.len(),
+            out_names.len(),
+        ));
+    }
+    for (inout, names, others) in &[
+        (InOut::In, &in_names, &out_names),
+        (InOut::Out, &out_names, &in_names),
+    ] {
+        for name in names.iter() {
+            if !others.contains(name) {
+                issues.push(TestcaseIssue::MissingCounterpart(*inout, name.clone()));
+            }
+            let abs_path = testcases_dir
+                .join(inout.as_ref())
+                .join(get_testcase_file_name(name));
+            if fs::metadata(abs_path.as_ref())?.len() == 0 {
+                issues.push(TestcaseIssue::Empty(*inout, name.clone()));
+            }
+        }
+    }
+    Ok(issues)
+}
+
+/// Saves input and output of sample as a testcase in `testcases_dir`.
+pub fn save_testcase(testcases_dir: &AbsPathBuf, sample: &Sample) -> Result<()> {
+    let file_name = get_testcase_file_name(sample.name());
+    for (inout, content) in &[(InOut::In, sample.input()), (InOut::Out, sample.output())] {
+        testcases_dir.join(inout.as_ref()).join(&file_name).save(
+            |mut file| {
+                file.write_all(content.as_bytes())
+                    .with_context(|| format!("Could not save testcase to file : {}", file_name))
+            },
+            true,
+        )?;
+    }
     Ok(())
 }
 
@@ -204,15 +348,18 @@ impl TestcaseIter {
             names.sort();
             names
         };
+        Ok(Self::with_names(dir, names))
+    }
 
+    /// Creates iterator over testcases of the given names in dir.
+    pub fn with_names(dir: AbsPathBuf, names: Vec<String>) -> Self {
         let max_name_len = names.iter().map(|name| name.len()).max().unwrap_or(0);
-
-        Ok(TestcaseIter {
+        TestcaseIter {
             dir,
             len: names.len(),
             max_name_len,
             names_iter: names.into_iter(),
-        })
+        }
     }
 
     fn load_file(&self, inout: InOut, testcase_name: &str) -> Result<String> {
@@ -264,7 +411,9 @@ mod tests {
 
     use super::*;
     use crate::console::ConsoleConfig;
+    use crate::dropbox::Dropbox;
     use crate::model::Compare;
+    use crate::storage::{DropboxStorage, LocalStorage};
 
     fn get_test_problems() -> Vec<Problem> {
         vec![
@@ -308,17 +457,62 @@ mod tests {
     }
 
     #[test]
-    fn test_fetch_full() -> Result<()> {
+    fn test_normalize_newlines() {
+        assert_eq!(normalize_newlines(b"1 2\n"), None);
+        assert_eq!(normalize_newlines(b""), None);
+        assert_eq!(
+            normalize_newlines(b"1 2\r\n3\r\n"),
+            Some(b"1 2\n3\n".to_vec())
+        );
+        assert_eq!(normalize_newlines(b"1 2"), Some(b"1 2\n".to_vec()));
+    }
+
+    #[test]
+    fn test_validate_testcases() -> Result<()> {
         let test_dir = tempdir()?;
+        let dir = AbsPathBuf::try_new(test_dir.path().to_owned())?;
+        dir.join("in").create_dir_all()?;
+        dir.join("out").create_dir_all()?;
+        fs::write(dir.join("in/01.txt").as_ref(), "1\n")?;
+        fs::write(dir.join("out/01.txt").as_ref(), "")?;
+        fs::write(dir.join("in/02.txt").as_ref(), "2\n")?;
+
+        let issues = validate_testcases(&dir)?;
+        assert_eq!(
+            issues,
+            vec![
+                TestcaseIssue::CountMismatch(2, 1),
+                TestcaseIssue::MissingCounterpart(InOut::In, "02".into()),
+                TestcaseIssue::Empty(InOut::Out, "01".into()),
+            ]
+        );
+        Ok(())
+    }
 
-        let dropbox = Dropbox::from_access_token(std::env::var("ACICK_DBX_ACCESS_TOKEN").unwrap());
+    #[tokio::test]
+    async fn test_fetch_full() -> Result<()> {
+        let test_dir = tempdir()?;
+
+        let dropbox = Dropbox::new(
+            reqwest::Client::new(),
+            std::env::var("ACICK_DBX_ACCESS_TOKEN").unwrap(),
+        );
         let contest_id = ContestId::from("arc100");
         let problems = get_test_problems();
         let base_dir = AbsPathBuf::try_new(test_dir.path().to_owned()).unwrap();
         let conf = Config::default_in_dir(base_dir);
         let mut cnsl = Console::buf(ConsoleConfig::default());
-
-        let result = fetch_full(&dropbox, &contest_id, &problems[0..1], &conf, &mut cnsl);
+        let storage = DropboxStorage::new(&dropbox, None);
+
+        let result = fetch_full(
+            &storage,
+            &contest_id,
+            &problems[0..1],
+            FullOpts::default(),
+            &conf,
+            &mut cnsl,
+        )
+        .await;
         let output_str = cnsl.take_output()?;
         eprintln!("{}", output_str);
         result?;
@@ -336,6 +530,84 @@ mod tests {
         Ok(())
     }
 
+    /// Storage that fails to download files of problem D.
+    struct FailingStorage(LocalStorage);
+
+    #[async_trait::async_trait(?Send)]
+    impl StorageProvider for FailingStorage {
+        fn name(&self) -> &str {
+            self.0.name()
+        }
+
+        async fn list_testcase_files(
+            &self,
+            contest_id: &ContestId,
+            problems: &[Problem],
+        ) -> Result<Vec<Vec<crate::storage::TestcaseFile>>> {
+            self.0.list_testcase_files(contest_id, problems).await
+        }
+
+        async fn download_file(
+            &self,
+            file: &crate::storage::TestcaseFile,
+            abs_path: &AbsPathBuf,
+            throttle: Option<&Throttle>,
+            on_progress: &(dyn Fn(u64) + Sync),
+        ) -> Result<()> {
+            if Path::new(&file.path)
+                .components()
+                .any(|component| component.as_os_str() == "D")
+            {
+                return Err(Error::msg("Connection reset"));
+            }
+            self.0
+                .download_file(file, abs_path, throttle, on_progress)
+                .await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_full_moves_completed_problems() -> Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path().to_owned())?;
+        let storage_dir = base_dir.join("storage");
+        for path in &[
+            "ARC100/C/in/01.txt",
+            "ARC100/C/out/01.txt",
+            "ARC100/D/in/01.txt",
+        ] {
+            let abs_path = storage_dir.join(path);
+            abs_path.parent().unwrap().create_dir_all()?;
+            fs::write(abs_path.as_ref(), "1\n")?;
+        }
+        let storage = FailingStorage(LocalStorage::new(storage_dir));
+        let conf = Config::default_in_dir(base_dir);
+        let mut cnsl = Console::buf(ConsoleConfig::default());
+        let opts = FullOpts {
+            jobs: Some(1),
+            ..Default::default()
+        };
+
+        let problems = get_test_problems();
+        let result = fetch_full(
+            &storage,
+            &"arc100".into(),
+            &problems[0..2],
+            opts,
+            &conf,
+            &mut cnsl,
+        )
+        .await;
+        assert!(result.is_err());
+        // testcases of C are saved even though downloads of D failed
+        assert!(test_dir
+            .path()
+            .join("atcoder/arc100/c/testcases/out/01.txt")
+            .is_file());
+        assert!(!test_dir.path().join("atcoder/arc100/d/testcases").exists());
+        Ok(())
+    }
+
     #[test]
     fn test_get_testcase_name() {
         let fixture = &[
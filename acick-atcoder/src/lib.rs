@@ -4,17 +4,25 @@
 extern crate strum;
 
 mod actor;
+mod aoj;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
 mod full;
 mod page;
+mod practice;
+mod storage;
 
 use acick_config as config;
 use acick_dropbox as dropbox;
-use acick_util::{abs_path, console, model, service, web};
+use acick_util::{abs_path, console, error, metrics, model, service, web};
 
 use crate::config::Config;
 use crate::console::Console;
 
 pub use actor::AtcoderActor;
+pub use full::FullOpts;
+pub use practice::PracticeProblem;
+pub use storage::{DropboxStorage, LocalStorage, StorageProvider, TestcaseFile};
 
 pub type Error = anyhow::Error;
 pub type Result<T> = anyhow::Result<T>;
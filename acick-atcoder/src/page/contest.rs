@@ -0,0 +1,156 @@
+use acick_util::select;
+use anyhow::Context as _;
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
+use reqwest::{Client, Url};
+use scraper::{ElementRef, Html};
+
+use crate::config::SessionConfig;
+use crate::model::ContestId;
+use crate::page::{BuildPage, ExtractCsrfToken, GetHtmlRestricted, HasHeader, BASE_URL};
+use crate::service::scrape::{GetHtml, Scrape};
+use crate::{Console, Result};
+
+static TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%z";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContestPageBuilder<'a> {
+    contest_id: &'a ContestId,
+    session: &'a SessionConfig,
+}
+
+impl<'a> ContestPageBuilder<'a> {
+    pub fn new(contest_id: &'a ContestId, session: &'a SessionConfig) -> Self {
+        Self {
+            contest_id,
+            session,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> BuildPage for ContestPageBuilder<'a> {
+    type Page = ContestPage<'a>;
+
+    async fn build(self, client: &Client, cnsl: &mut Console) -> Result<ContestPage<'a>> {
+        self.get_html_restricted(client, self.session, cnsl)
+            .await
+            .map(|html| ContestPage {
+                builder: self,
+                content: html,
+            })
+    }
+}
+
+impl GetHtml for ContestPageBuilder<'_> {
+    fn url(&self) -> Result<Url> {
+        let path = format!("/contests/{}", self.contest_id);
+        BASE_URL
+            .join(&path)
+            .context(format!("Could not parse url path: {}", path))
+    }
+}
+
+impl GetHtmlRestricted for ContestPageBuilder<'_> {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContestPage<'a> {
+    builder: ContestPageBuilder<'a>,
+    content: Html,
+}
+
+impl ContestPage<'_> {
+    /// Extracts start time and end time of the contest.
+    pub fn extract_duration(&self) -> Result<(DateTime<FixedOffset>, DateTime<FixedOffset>)> {
+        let mut times = self
+            .content
+            .select(select!(".contest-duration time"))
+            .map(|elem| DateTime::parse_from_str(elem.inner_text().trim(), TIME_FORMAT));
+        let start_at = times
+            .next()
+            .context("Could not find start time of contest")?
+            .context("Could not parse start time of contest")?;
+        let end_at = times
+            .next()
+            .context("Could not find end time of contest")?
+            .context("Could not parse end time of contest")?;
+        Ok((start_at, end_at))
+    }
+}
+
+impl ContestPage<'_> {
+    /// Extracts path to which the registration form is posted.
+    ///
+    /// Returns `None` if registration is not open or the user has already registered.
+    pub fn extract_register_path(&self) -> Option<&str> {
+        self.find_first(select!("form[action$=\"/register\"]"))
+            .and_then(|elem| elem.value().attr("action"))
+    }
+
+    pub fn is_registered(&self) -> bool {
+        self.find_first(select!("form[action$=\"/unregister\"]"))
+            .is_some()
+    }
+}
+
+impl Scrape for ContestPage<'_> {
+    fn elem(&self) -> ElementRef {
+        self.content.root_element()
+    }
+}
+
+impl HasHeader for ContestPage<'_> {}
+
+impl ExtractCsrfToken for ContestPage<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_duration() -> anyhow::Result<()> {
+        let html = Html::parse_document(
+            r##"<small class="contest-duration">Contest Duration:
+<a href="#"><time class="fixtime fixtime-full">2018-07-01 21:00:00+0900</time></a> ~
+<a href="#"><time class="fixtime fixtime-full">2018-07-01 22:40:00+0900</time></a>
+(local time) (100 minutes)</small>"##,
+        );
+        let contest_id = ContestId::default();
+        let session = SessionConfig::default();
+        let page = ContestPage {
+            builder: ContestPageBuilder::new(&contest_id, &session),
+            content: html,
+        };
+        let (start_at, end_at) = page.extract_duration()?;
+        assert_eq!(start_at.to_rfc3339(), "2018-07-01T21:00:00+09:00");
+        assert_eq!(end_at.to_rfc3339(), "2018-07-01T22:40:00+09:00");
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_register_path() {
+        let contest_id = ContestId::default();
+        let session = SessionConfig::default();
+        let page = |html| ContestPage {
+            builder: ContestPageBuilder::new(&contest_id, &session),
+            content: Html::parse_document(html),
+        };
+
+        let not_registered = page(
+            r#"<form method="POST" action="/contests/arc100/register">
+<input type="hidden" name="csrf_token" value="token"></form>"#,
+        );
+        assert_eq!(
+            not_registered.extract_register_path(),
+            Some("/contests/arc100/register")
+        );
+        assert!(!not_registered.is_registered());
+
+        let registered = page(
+            r#"<form method="POST" action="/contests/arc100/unregister">
+<input type="hidden" name="csrf_token" value="token"></form>"#,
+        );
+        assert_eq!(registered.extract_register_path(), None);
+        assert!(registered.is_registered());
+    }
+}
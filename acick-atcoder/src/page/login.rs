@@ -1,9 +1,9 @@
-use reqwest::blocking::Client;
-use reqwest::{StatusCode, Url};
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode, Url};
 use scraper::{ElementRef, Html};
 
 use crate::config::SessionConfig;
-use crate::page::{ExtractCsrfToken, HasHeader, BASE_URL};
+use crate::page::{BuildPage, ExtractCsrfToken, HasHeader, BASE_URL};
 use crate::service::scrape::{GetHtml, Scrape};
 use crate::{Console, Error, Result};
 
@@ -18,15 +18,22 @@ impl<'a> LoginPageBuilder<'a> {
     pub fn new(session: &'a SessionConfig) -> Self {
         Self { session }
     }
+}
+
+#[async_trait(?Send)]
+impl<'a> BuildPage for LoginPageBuilder<'a> {
+    type Page = LoginPage<'a>;
 
-    pub fn build(self, client: &Client, cnsl: &mut Console) -> Result<LoginPage<'a>> {
-        let (status, html) = self.get_html(
-            client,
-            self.session.cookies_path(),
-            self.session.retry_limit(),
-            self.session.retry_interval(),
-            cnsl,
-        )?;
+    async fn build(self, client: &Client, cnsl: &mut Console) -> Result<LoginPage<'a>> {
+        let (status, html) = self
+            .get_html(
+                client,
+                self.session.cookies_path(),
+                self.session.retry_limit(),
+                self.session.retry_interval(),
+                cnsl,
+            )
+            .await?;
         match status {
             StatusCode::OK => Ok(LoginPage {
                 builder: self,
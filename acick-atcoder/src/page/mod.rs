@@ -1,8 +1,10 @@
+use std::fmt;
+
 use acick_util::select;
 use anyhow::Context as _;
+use async_trait::async_trait;
 use lazy_static::lazy_static;
-use reqwest::blocking::Client;
-use reqwest::{StatusCode, Url};
+use reqwest::{Client, StatusCode, Url};
 use scraper::{ElementRef, Html};
 
 use crate::config::SessionConfig;
@@ -10,14 +12,22 @@ use crate::model::{LangId, LangNameRef};
 use crate::service::scrape::{GetHtml, Scrape};
 use crate::{Console, Error, Result};
 
+mod contest;
 mod login;
+mod my_submissions;
 mod settings;
+mod standings;
+mod submission;
 mod submit;
 mod tasks;
 mod tasks_print;
 
+pub use contest::{ContestPage, ContestPageBuilder};
 pub use login::{LoginPage, LoginPageBuilder};
+pub use my_submissions::{MySubmissionsPage, MySubmissionsPageBuilder};
 pub use settings::{SettingsPage, SettingsPageBuilder};
+pub use standings::{StandingsPage, StandingsPageBuilder};
+pub use submission::{SubmissionPage, SubmissionPageBuilder};
 pub use submit::{SubmitPage, SubmitPageBuilder};
 pub use tasks::{TasksPage, TasksPageBuilder};
 pub use tasks_print::{TasksPrintPage, TasksPrintPageBuilder};
@@ -26,6 +36,16 @@ lazy_static! {
     pub static ref BASE_URL: Url = Url::parse("https://atcoder.jp").unwrap();
 }
 
+/// Builds a page from html received from the service.
+///
+/// Builders are cloned so that the page can be requested again (e.g. after logging in again).
+#[async_trait(?Send)]
+pub trait BuildPage: Clone {
+    type Page;
+
+    async fn build(self, client: &Client, cnsl: &mut Console) -> Result<Self::Page>;
+}
+
 pub trait ExtractCsrfToken: Scrape {
     fn extract_csrf_token(&self) -> Result<&str> {
         let token = self
@@ -85,23 +105,45 @@ pub trait HasHeader: Scrape {
     }
 }
 
+#[async_trait(?Send)]
 pub trait GetHtmlRestricted: GetHtml {
-    fn get_html_restricted(
+    async fn get_html_restricted(
         &self,
         client: &Client,
         session: &SessionConfig,
         cnsl: &mut Console,
     ) -> Result<Html> {
-        let (status, html) = self.get_html(
-            client,
-            session.cookies_path(),
-            session.retry_limit(),
-            session.retry_interval(),
-            cnsl,
-        )?;
+        let anonymous_result = if session.anonymous() {
+            Some(
+                self.get_html_anonymous(
+                    client,
+                    session.cookies_path(),
+                    session.retry_limit(),
+                    session.retry_interval(),
+                    cnsl,
+                )
+                .await?,
+            )
+        } else {
+            None
+        };
+        let (status, html) = match anonymous_result {
+            Some((status, html)) if status != StatusCode::FOUND => (status, html),
+            // sends request with cookies because the page requires login
+            _ => {
+                self.get_html(
+                    client,
+                    session.cookies_path(),
+                    session.retry_limit(),
+                    session.retry_interval(),
+                    cnsl,
+                )
+                .await?
+            }
+        };
         match status {
             StatusCode::OK => Ok(html),
-            StatusCode::FOUND => Err(Error::msg("User not logged in")),
+            StatusCode::FOUND => Err(Error::new(NotLoggedIn)),
             StatusCode::NOT_FOUND if NotFoundPage(&html).is_not_found() => Err(Error::msg(
                 "Could not find contest. Check if the contest id is correct.",
             )),
@@ -113,6 +155,18 @@ pub trait GetHtmlRestricted: GetHtml {
     }
 }
 
+/// Error that indicates the page requires login.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NotLoggedIn;
+
+impl fmt::Display for NotLoggedIn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("User not logged in")
+    }
+}
+
+impl std::error::Error for NotLoggedIn {}
+
 struct NotFoundPage<'a>(&'a Html);
 
 impl NotFoundPage<'_> {
@@ -0,0 +1,130 @@
+use acick_util::select;
+use anyhow::Context as _;
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use scraper::{ElementRef, Html};
+
+use crate::config::SessionConfig;
+use crate::model::{ContestId, Problem};
+use crate::page::{BuildPage, GetHtmlRestricted, HasHeader, BASE_URL};
+use crate::service::scrape::{GetHtml, Scrape};
+use crate::{Console, Result};
+
+/// Builder of the list of submissions of the logged-in user to a problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MySubmissionsPageBuilder<'a> {
+    contest_id: &'a ContestId,
+    problem: &'a Problem,
+    session: &'a SessionConfig,
+}
+
+impl<'a> MySubmissionsPageBuilder<'a> {
+    pub fn new(
+        contest_id: &'a ContestId,
+        problem: &'a Problem,
+        session: &'a SessionConfig,
+    ) -> Self {
+        Self {
+            contest_id,
+            problem,
+            session,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> BuildPage for MySubmissionsPageBuilder<'a> {
+    type Page = MySubmissionsPage<'a>;
+
+    async fn build(self, client: &Client, cnsl: &mut Console) -> Result<MySubmissionsPage<'a>> {
+        self.get_html_restricted(client, self.session, cnsl)
+            .await
+            .map(|html| MySubmissionsPage {
+                builder: self,
+                content: html,
+            })
+    }
+}
+
+impl GetHtml for MySubmissionsPageBuilder<'_> {
+    fn url(&self) -> Result<Url> {
+        let path = format!("/contests/{}/submissions/me", self.contest_id);
+        let mut url = BASE_URL
+            .join(&path)
+            .context(format!("Could not parse url path: {}", path))?;
+        url.query_pairs_mut()
+            .append_pair("f.Task", self.problem.url_name());
+        Ok(url)
+    }
+}
+
+impl GetHtmlRestricted for MySubmissionsPageBuilder<'_> {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MySubmissionsPage<'a> {
+    builder: MySubmissionsPageBuilder<'a>,
+    content: Html,
+}
+
+impl MySubmissionsPage<'_> {
+    /// Extracts id of the latest submission, which is listed first.
+    pub fn extract_latest_submission_id(&self) -> Option<String> {
+        self.content
+            .select(select!("#main-container table tbody tr a"))
+            .filter_map(|a| a.value().attr("href"))
+            .find_map(parse_submission_id)
+    }
+}
+
+impl Scrape for MySubmissionsPage<'_> {
+    fn elem(&self) -> ElementRef {
+        self.content.root_element()
+    }
+}
+
+impl HasHeader for MySubmissionsPage<'_> {}
+
+/// Parses id of submission from link to its detail (e.g. `/contests/arc100/submissions/1234`).
+fn parse_submission_id(href: &str) -> Option<String> {
+    let pos = href.rfind('/')?;
+    let (path, id) = (&href[..pos], &href[pos + 1..]);
+    if path.ends_with("/submissions") && !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+        Some(id.to_owned())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Compare;
+
+    #[test]
+    fn test_extract_latest_submission_id() {
+        let html = Html::parse_document(
+            r#"<div id="main-container"><table class="table"><tbody>
+<tr><td><time>2018-07-01 21:40:00+0900</time></td><td><a href="/contests/arc100/tasks/arc100_a">C - Linear Approximation</a></td><td><a href="/users/test_user">test_user</a></td><td>AC</td><td><a href="/contests/arc100/submissions/1235">Detail</a></td></tr>
+<tr><td><time>2018-07-01 21:30:00+0900</time></td><td><a href="/contests/arc100/tasks/arc100_a">C - Linear Approximation</a></td><td><a href="/users/test_user">test_user</a></td><td>WA</td><td><a href="/contests/arc100/submissions/1234">Detail</a></td></tr>
+</tbody></table></div>"#,
+        );
+        let contest_id = ContestId::from("arc100");
+        let problem = Problem::new("C", "", "arc100_a", None, None, Compare::Default, vec![]);
+        let session = SessionConfig::default();
+        let page = MySubmissionsPage {
+            builder: MySubmissionsPageBuilder::new(&contest_id, &problem, &session),
+            content: html,
+        };
+        assert_eq!(page.extract_latest_submission_id().as_deref(), Some("1235"));
+        assert_eq!(
+            page.builder.url().unwrap().as_str(),
+            "https://atcoder.jp/contests/arc100/submissions/me?f.Task=arc100_a"
+        );
+
+        let page = MySubmissionsPage {
+            builder: page.builder,
+            content: Html::parse_document(r#"<div id="main-container"></div>"#),
+        };
+        assert_eq!(page.extract_latest_submission_id(), None);
+    }
+}
@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _};
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode, Url};
+use serde::Deserialize;
+
+use crate::config::SessionConfig;
+use crate::model::{ContestId, Standings, StandingsRow};
+use crate::page::{BuildPage, NotLoggedIn, BASE_URL};
+use crate::service::session::WithRetry as _;
+use crate::{Console, Error, Result};
+
+/// Builder of standings, which are received as JSON
+/// because the standings page is rendered by JavaScript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandingsPageBuilder<'a> {
+    contest_id: &'a ContestId,
+    session: &'a SessionConfig,
+}
+
+impl<'a> StandingsPageBuilder<'a> {
+    pub fn new(contest_id: &'a ContestId, session: &'a SessionConfig) -> Self {
+        Self {
+            contest_id,
+            session,
+        }
+    }
+
+    fn url(&self) -> Result<Url> {
+        let path = format!("/contests/{}/standings/json", self.contest_id);
+        BASE_URL
+            .join(&path)
+            .context(format!("Could not parse url path: {}", path))
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> BuildPage for StandingsPageBuilder<'a> {
+    type Page = StandingsPage;
+
+    async fn build(self, client: &Client, cnsl: &mut Console) -> Result<StandingsPage> {
+        let session = self.session;
+        let res = client
+            .get(self.url()?)
+            .with_retry(
+                client,
+                session.cookies_path(),
+                session.retry_limit(),
+                session.retry_interval(),
+            )
+            .retry_send(cnsl)
+            .await?;
+        match res.status() {
+            StatusCode::OK => {
+                let text = res.text().await.context("Could not read standings")?;
+                let content = serde_json::from_str(&text).context("Could not parse standings")?;
+                Ok(StandingsPage { content })
+            }
+            StatusCode::FOUND => Err(Error::new(NotLoggedIn)),
+            StatusCode::NOT_FOUND => Err(Error::msg(
+                "Could not find standings. Check if the contest id is correct and the contest has started.",
+            )),
+            status => Err(anyhow!("Received invalid response code : {}", status)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandingsPage {
+    content: StandingsJson,
+}
+
+impl StandingsPage {
+    pub fn extract_standings(&self) -> Standings {
+        let rows = self
+            .content
+            .standings_data
+            .iter()
+            .map(|data| {
+                let total = &data.total_result;
+                StandingsRow::new(
+                    data.rank,
+                    &data.user_screen_name,
+                    format_score(total.score),
+                    total.penalty,
+                    Duration::from_nanos(total.elapsed),
+                )
+            })
+            .collect();
+        Standings::new(rows)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+struct StandingsJson {
+    standings_data: Vec<StandingsData>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+struct StandingsData {
+    rank: usize,
+    user_screen_name: String,
+    total_result: TotalResult,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+struct TotalResult {
+    /// Score multiplied by 100
+    score: u64,
+    penalty: usize,
+    /// Elapsed time in nanoseconds
+    elapsed: u64,
+}
+
+fn format_score(score: u64) -> String {
+    if score % 100 == 0 {
+        (score / 100).to_string()
+    } else {
+        format!("{}.{:02}", score / 100, score % 100)
+            .trim_end_matches('0')
+            .to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_standings() -> anyhow::Result<()> {
+        let content = serde_json::from_str(
+            r#"{"Fixed":true,"TaskInfo":[],"StandingsData":[
+{"Rank":1,"UserScreenName":"first_user","IsRated":true,"TotalResult":{"Count":4,"Accepted":4,"Penalty":0,"Score":300000,"Elapsed":4416000000000}},
+{"Rank":2,"UserScreenName":"test_user","IsRated":true,"TotalResult":{"Count":5,"Accepted":3,"Penalty":2,"Score":150050,"Elapsed":5025000000000}}
+]}"#,
+        )?;
+        let standings = StandingsPage { content }.extract_standings();
+        assert_eq!(standings.rows().len(), 2);
+        let row = standings.find_user("Test_User").unwrap();
+        assert_eq!(row.rank(), 2);
+        assert_eq!(row.score(), "1500.5");
+        assert_eq!(row.penalty(), 2);
+        assert_eq!(row.elapsed(), Duration::from_secs(5025));
+        assert_eq!(standings.rows()[0].score(), "3000");
+        Ok(())
+    }
+}
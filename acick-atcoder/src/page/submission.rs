@@ -0,0 +1,221 @@
+use std::path::Path;
+use std::time::Duration;
+
+use acick_util::select;
+use anyhow::Context as _;
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use scraper::{ElementRef, Html};
+
+use crate::config::SessionConfig;
+use crate::model::{Byte, ContestId, JudgeResult, Submission, TestcaseResult};
+use crate::page::{BuildPage, GetHtmlRestricted, HasHeader, BASE_URL};
+use crate::service::scrape::{GetHtml, Scrape};
+use crate::{Console, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmissionPageBuilder<'a> {
+    contest_id: &'a ContestId,
+    submission_id: &'a str,
+    session: &'a SessionConfig,
+}
+
+impl<'a> SubmissionPageBuilder<'a> {
+    pub fn new(
+        contest_id: &'a ContestId,
+        submission_id: &'a str,
+        session: &'a SessionConfig,
+    ) -> Self {
+        Self {
+            contest_id,
+            submission_id,
+            session,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> BuildPage for SubmissionPageBuilder<'a> {
+    type Page = SubmissionPage<'a>;
+
+    async fn build(self, client: &Client, cnsl: &mut Console) -> Result<SubmissionPage<'a>> {
+        self.get_html_restricted(client, self.session, cnsl)
+            .await
+            .map(|html| SubmissionPage {
+                builder: self,
+                content: html,
+            })
+    }
+}
+
+impl GetHtml for SubmissionPageBuilder<'_> {
+    fn url(&self) -> Result<Url> {
+        let path = format!(
+            "/contests/{}/submissions/{}",
+            self.contest_id, self.submission_id
+        );
+        BASE_URL
+            .join(&path)
+            .context(format!("Could not parse url path: {}", path))
+    }
+}
+
+impl GetHtmlRestricted for SubmissionPageBuilder<'_> {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmissionPage<'a> {
+    builder: SubmissionPageBuilder<'a>,
+    content: Html,
+}
+
+impl SubmissionPage<'_> {
+    pub fn extract_submission(&self) -> Result<Submission> {
+        let info = |header: &str| {
+            self.find_info(header)
+                .with_context(|| format!("Could not find {} of submission", header))
+        };
+        let result = JudgeResult::new(
+            info("Status")?,
+            self.find_info("Exec Time")
+                .and_then(|s| parse_exec_time(&s)),
+            self.find_info("Memory").and_then(|s| s.parse().ok()),
+        );
+        let testcases = self
+            .content
+            .select(select!("#main-container table"))
+            .filter(|table| {
+                table
+                    .select(select!("thead th"))
+                    .any(|th| th.inner_text().trim() == "Case Name")
+            })
+            .flat_map(|table| table.select(select!("tbody tr")))
+            .map(extract_testcase_result)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Submission::new(
+            self.builder.submission_id,
+            info("Task")?,
+            info("User")?,
+            info("Language")?,
+            info("Score")?,
+            result,
+            testcases,
+        ))
+    }
+
+    pub fn extract_source(&self) -> Result<String> {
+        self.find_first(select!("#submission-code"))
+            .context("Could not find source code of submission")
+            .map(|pre| pre.inner_text())
+    }
+
+    /// Finds value in the table of submission info by its header.
+    fn find_info(&self, header: &str) -> Option<String> {
+        self.content
+            .select(select!("#main-container table tr"))
+            .find(|tr| {
+                tr.select(select!("th"))
+                    .next()
+                    .map_or(false, |th| th.inner_text().trim() == header)
+            })
+            .and_then(|tr| tr.select(select!("td")).next())
+            .map(|td| td.inner_text().trim().to_owned())
+    }
+}
+
+impl Scrape for SubmissionPage<'_> {
+    fn elem(&self) -> ElementRef {
+        self.content.root_element()
+    }
+}
+
+impl HasHeader for SubmissionPage<'_> {}
+
+fn extract_testcase_result(tr: ElementRef) -> Result<TestcaseResult> {
+    let mut tds = tr
+        .select(select!("td"))
+        .map(|td| td.inner_text().trim().to_owned());
+    let file_name = tds.next().context("Could not find name of testcase")?;
+    let verdict = tds.next().context("Could not find verdict of testcase")?;
+    let exec_time = tds.next().and_then(|s| parse_exec_time(&s));
+    let memory = tds.next().and_then(|s| s.parse::<Byte>().ok());
+    // strip extension so that the name matches local testcase name
+    let name = Path::new(&file_name)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(&file_name);
+    Ok(TestcaseResult::new(
+        name,
+        JudgeResult::new(verdict, exec_time, memory),
+    ))
+}
+
+fn parse_exec_time(s: &str) -> Option<Duration> {
+    s.trim_end_matches("ms")
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_submission() -> anyhow::Result<()> {
+        let html = Html::parse_document(
+            r#"<div id="main-container"><div class="panel panel-default">
+<table class="table table-bordered table-striped">
+<tr><th>Submission Time</th><td class="text-center"><time>2018-07-01 21:30:00+0900</time></td></tr>
+<tr><th>Task</th><td class="text-center"><a href="/contests/arc100/tasks/arc100_a">C - Linear Approximation</a></td></tr>
+<tr><th>User</th><td class="text-center"><a href="/users/test_user">test_user</a></td></tr>
+<tr><th>Language</th><td class="text-center">C++ (GCC 9.2.1)</td></tr>
+<tr><th>Score</th><td class="text-center">0</td></tr>
+<tr><th>Code Size</th><td class="text-center">512 Byte</td></tr>
+<tr><th>Status</th><td id="judge-status" class="text-center"><span class="label label-warning">WA</span></td></tr>
+<tr><th>Exec Time</th><td class="text-center">25 ms</td></tr>
+<tr><th>Memory</th><td class="text-center">3640 KB</td></tr>
+</table></div>
+<h4>Judge Result</h4>
+<table class="table table-bordered table-striped th-center">
+<thead><tr><th>Set Name</th><th>Test Cases</th></tr></thead>
+<tbody><tr><td class="text-center">All</td><td>sample_01.txt, subtask_1_01.txt</td></tr></tbody>
+</table>
+<table class="table table-bordered table-striped th-center">
+<thead><tr><th>Case Name</th><th>Status</th><th>Exec Time</th><th>Memory</th></tr></thead>
+<tbody>
+<tr><td class="text-center">sample_01.txt</td><td class="text-center"><span class="label label-success">AC</span></td><td class="text-right">2 ms</td><td class="text-right">3584 KB</td></tr>
+<tr><td class="text-center">subtask_1_01.txt</td><td class="text-center"><span class="label label-warning">WA</span></td><td class="text-right">25 ms</td><td class="text-right">3640 KB</td></tr>
+</tbody></table>
+<pre id="submission-code" data-ace-mode="c_cpp">#include &lt;iostream&gt;
+int main() {}
+</pre></div>"#,
+        );
+        let contest_id = ContestId::default();
+        let session = SessionConfig::default();
+        let page = SubmissionPage {
+            builder: SubmissionPageBuilder::new(&contest_id, "1234", &session),
+            content: html,
+        };
+        let submission = page.extract_submission()?;
+        assert_eq!(submission.id(), "1234");
+        assert_eq!(submission.task(), "C - Linear Approximation");
+        assert_eq!(submission.problem_id(), Some("C".into()));
+        assert_eq!(submission.user(), "test_user");
+        assert_eq!(submission.lang_name(), "C++ (GCC 9.2.1)");
+        assert_eq!(submission.result().verdict(), "WA");
+        assert_eq!(
+            submission.result().exec_time(),
+            Some(Duration::from_millis(25))
+        );
+        let names: Vec<_> = submission.testcases().iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["sample_01", "subtask_1_01"]);
+        assert!(submission.testcases()[0].result().is_accepted());
+        assert!(!submission.testcases()[1].result().is_accepted());
+        assert_eq!(
+            page.extract_source()?,
+            "#include <iostream>\nint main() {}\n"
+        );
+        Ok(())
+    }
+}
@@ -1,12 +1,14 @@
 use acick_util::select;
 use anyhow::Context as _;
-use reqwest::blocking::Client;
-use reqwest::Url;
+use async_trait::async_trait;
+use reqwest::{Client, Url};
 use scraper::{ElementRef, Html};
 
 use crate::config::SessionConfig;
 use crate::model::{ContestId, LangId, LangIdRef, LangName, LangNameRef};
-use crate::page::{ExtractCsrfToken, ExtractLangId, GetHtmlRestricted, HasHeader, BASE_URL};
+use crate::page::{
+    BuildPage, ExtractCsrfToken, ExtractLangId, GetHtmlRestricted, HasHeader, BASE_URL,
+};
 use crate::service::scrape::{GetHtml, Scrape};
 use crate::{Console, Result};
 
@@ -23,9 +25,15 @@ impl<'a> SubmitPageBuilder<'a> {
             session,
         }
     }
+}
+
+#[async_trait(?Send)]
+impl<'a> BuildPage for SubmitPageBuilder<'a> {
+    type Page = SubmitPage<'a>;
 
-    pub fn build(self, client: &Client, cnsl: &mut Console) -> Result<SubmitPage<'a>> {
+    async fn build(self, client: &Client, cnsl: &mut Console) -> Result<SubmitPage<'a>> {
         self.get_html_restricted(client, self.session, cnsl)
+            .await
             .map(|html| SubmitPage {
                 builder: self,
                 content: html,
@@ -50,6 +58,14 @@ pub struct SubmitPage<'a> {
     content: Html,
 }
 
+#[cfg(test)]
+impl<'a> SubmitPage<'a> {
+    /// Builds page from html of a fixture.
+    pub(crate) fn from_html(builder: SubmitPageBuilder<'a>, content: Html) -> Self {
+        Self { builder, content }
+    }
+}
+
 impl SubmitPage<'_> {
     fn select_lang_options(&self) -> impl Iterator<Item = LangOptElem> {
         self.content
@@ -62,6 +78,12 @@ impl SubmitPage<'_> {
     pub fn url(&self) -> Result<Url> {
         self.builder.url()
     }
+
+    pub fn extract_lang_names(&self) -> Vec<LangName> {
+        self.select_lang_options()
+            .map(|opt| opt.extract_lang_name())
+            .collect()
+    }
 }
 
 impl Scrape for SubmitPage<'_> {
@@ -1,13 +1,13 @@
 use acick_util::select;
 use anyhow::Context as _;
+use async_trait::async_trait;
 use humantime::parse_duration;
-use reqwest::blocking::Client;
-use reqwest::Url;
+use reqwest::{Client, Url};
 use scraper::{ElementRef, Html};
 
 use crate::config::SessionConfig;
 use crate::model::{Compare, ContestId, Problem, ProblemId};
-use crate::page::{GetHtmlRestricted, HasHeader, BASE_URL};
+use crate::page::{BuildPage, GetHtmlRestricted, HasHeader, BASE_URL};
 use crate::service::scrape::{GetHtml, Scrape};
 use crate::{Console, Result};
 
@@ -24,9 +24,15 @@ impl<'a> TasksPageBuilder<'a> {
             session,
         }
     }
+}
+
+#[async_trait(?Send)]
+impl<'a> BuildPage for TasksPageBuilder<'a> {
+    type Page = TasksPage<'a>;
 
-    pub fn build(self, client: &Client, cnsl: &mut Console) -> Result<TasksPage<'a>> {
+    async fn build(self, client: &Client, cnsl: &mut Console) -> Result<TasksPage<'a>> {
         self.get_html_restricted(client, self.session, cnsl)
+            .await
             .map(|html| TasksPage {
                 builder: self,
                 content: html,
@@ -51,6 +57,14 @@ pub struct TasksPage<'a> {
     content: Html,
 }
 
+#[cfg(test)]
+impl<'a> TasksPage<'a> {
+    /// Builds page from html of a fixture.
+    pub(crate) fn from_html(builder: TasksPageBuilder<'a>, content: Html) -> Self {
+        Self { builder, content }
+    }
+}
+
 impl TasksPage<'_> {
     pub fn extract_problems(&self, cnsl: &mut Console) -> Result<Vec<Problem>> {
         self.select_problem_rows()
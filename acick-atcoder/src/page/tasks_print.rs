@@ -2,16 +2,16 @@ use std::collections::BTreeMap;
 
 use acick_util::{regex, select};
 use anyhow::Context as _;
+use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use reqwest::blocking::Client;
-use reqwest::Url;
+use reqwest::{Client, Url};
 use scraper::{ElementRef, Html, Selector};
 
 use crate::config::SessionConfig;
 use crate::model::{ContestId, ProblemId, Sample};
-use crate::page::{GetHtmlRestricted, BASE_URL};
-use crate::service::scrape::{parse_zenkaku_digits, GetHtml, Scrape};
+use crate::page::{BuildPage, GetHtmlRestricted, BASE_URL};
+use crate::service::scrape::{parse_zenkaku_digits, GetHtml, PatternReport, Scrape};
 use crate::{Console, Result};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -27,9 +27,15 @@ impl<'a> TasksPrintPageBuilder<'a> {
             session,
         }
     }
+}
+
+#[async_trait(?Send)]
+impl<'a> BuildPage for TasksPrintPageBuilder<'a> {
+    type Page = TasksPrintPage<'a>;
 
-    pub fn build(self, client: &Client, cnsl: &mut Console) -> Result<TasksPrintPage<'a>> {
+    async fn build(self, client: &Client, cnsl: &mut Console) -> Result<TasksPrintPage<'a>> {
         self.get_html_restricted(client, self.session, cnsl)
+            .await
             .map(|html| TasksPrintPage {
                 builder: self,
                 content: html,
@@ -54,17 +60,56 @@ pub struct TasksPrintPage<'a> {
     content: Html,
 }
 
+#[cfg(test)]
+impl<'a> TasksPrintPage<'a> {
+    /// Builds page from html of a fixture.
+    pub(crate) fn from_html(builder: TasksPrintPageBuilder<'a>, content: Html) -> Self {
+        Self { builder, content }
+    }
+}
+
 impl TasksPrintPage<'_> {
-    pub fn extract_samples_map(&self) -> Result<BTreeMap<ProblemId, Vec<Sample>>> {
+    /// Extracts samples of each problem.
+    ///
+    /// If `lax` is true, samples are extracted even from unknown layouts by guessing.
+    pub fn extract_samples_map(
+        &self,
+        report: &mut PatternReport,
+        lax: bool,
+    ) -> Result<BTreeMap<ProblemId, Vec<Sample>>> {
         let mut samples_map = BTreeMap::new();
         for elem in self.select_problems() {
             let (id, _) = elem.extract_id_name()?;
-            let samples = elem.select_statement()?.extract_samples();
+            let (samples, pattern) = elem.select_statement()?.extract_samples(lax);
+            report.record("samples", id.to_string(), pattern);
             samples_map.insert(id, samples);
         }
         Ok(samples_map)
     }
 
+    /// Extracts points of problems whose statement shows them.
+    pub fn extract_points_map(&self) -> Result<BTreeMap<ProblemId, u64>> {
+        let mut points_map = BTreeMap::new();
+        for elem in self.select_problems() {
+            let (id, _) = elem.extract_id_name()?;
+            if let Some(points) = elem.select_statement()?.extract_points() {
+                points_map.insert(id, points);
+            }
+        }
+        Ok(points_map)
+    }
+
+    /// Extracts html of statements and urls of the files they refer to.
+    pub fn extract_statements_map(&self) -> Result<BTreeMap<ProblemId, (String, Vec<String>)>> {
+        let mut statements_map = BTreeMap::new();
+        for elem in self.select_problems() {
+            let (id, _) = elem.extract_id_name()?;
+            let statement = elem.select_statement()?;
+            statements_map.insert(id, (statement.0.html(), statement.extract_asset_urls()));
+        }
+        Ok(statements_map)
+    }
+
     fn select_problems(&self) -> impl Iterator<Item = ProblemElem> {
         self.content
             .select(select!(
@@ -114,48 +159,203 @@ impl Scrape for ProblemElem<'_> {
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct StatementElem<'a>(ElementRef<'a>);
 
+/// A stage of the pipeline that extracts samples from statement.
+///
+/// All stages are tried and the samples with the best score are taken,
+/// so that a new stage does not break extraction of contests that other stages handle.
+trait ExtractSamples {
+    /// Name of the stage shown in fetch diagnostics.
+    fn name(&self) -> String;
+
+    fn extract(&self, statement: &StatementElem) -> Option<Vec<Sample>>;
+}
+
+/// Extracts samples from `pre` blocks following headings (e.g. `Sample Input 1`).
+struct HeadingPattern {
+    name: &'static str,
+    selector: &'static Lazy<Selector>,
+    lang: &'static str,
+    re_input: &'static Lazy<Regex>,
+    re_output: &'static Lazy<Regex>,
+}
+
+impl ExtractSamples for HeadingPattern {
+    fn name(&self) -> String {
+        format!("{}/{}", self.name, self.lang)
+    }
+
+    fn extract(&self, statement: &StatementElem) -> Option<Vec<Sample>> {
+        statement.try_extract_samples(self.selector, self.re_input, self.re_output)
+    }
+}
+
+/// Pairs up all `pre` blocks without variables as inputs and outputs in order.
+///
+/// This ignores headings, so it is used only as a fallback for unknown layouts.
+struct PrePairs;
+
+impl ExtractSamples for PrePairs {
+    fn name(&self) -> String {
+        String::from("pre-pairs")
+    }
+
+    fn extract(&self, statement: &StatementElem) -> Option<Vec<Sample>> {
+        // prefer english statement to avoid taking the same samples twice
+        let root = statement
+            .find_first(select!("span.lang-en"))
+            .or_else(|| statement.find_first(select!("span.lang-ja")))
+            .unwrap_or(statement.0);
+        // blocks of input format contain variables
+        let blocks: Vec<String> = root
+            .select(select!("pre"))
+            .filter(|pre| pre.select(select!("var")).next().is_none())
+            .map(|pre| pre.inner_text())
+            .collect();
+        let samples: Vec<Sample> = blocks
+            .chunks_exact(2)
+            .enumerate()
+            .map(|(i, pair)| Sample::new((i + 1).to_string(), pair[0].clone(), pair[1].clone()))
+            .collect();
+        if samples.is_empty() {
+            None
+        } else {
+            Some(samples)
+        }
+    }
+}
+
+fn sample_pipeline() -> Vec<HeadingPattern> {
+    static IN_OUT_REGEXS: &[(&str, &Lazy<Regex>, &Lazy<Regex>)] = &[
+        (
+            "en",
+            regex!(r"(?s)\A\s*Sample Input\s?([0-9]{1,2}).*\z"),
+            regex!(r"(?s)\A\s*Sample Output\s?([0-9]{1,2}).*\z"),
+        ),
+        (
+            "ja",
+            regex!(r"\A[\s\n]*入力例\s*(\d{1,2})[.\n]*\z"),
+            regex!(r"\A[\s\n]*出力例\s*(\d{1,2})[.\n]*\z"),
+        ),
+    ];
+    // Patterns are named after the contests in which they were first seen
+    // so that unused ones can be found from fetch diagnostics.
+    static PS: &[(&str, &Lazy<Selector>)] = &[
+        // Current style (Japanese)
+        ("current-ja", select!("span.lang > span.lang-ja > div.part > section > h3, span.lang > span.lang-ja > div.part > section > pre")),
+        // Current style (English)
+        ("current-en", select!("span.lang > span.lang-en > div.part > section > h3, span.lang > span.lang-en > div.part > section > pre")),
+        // ARC019..ARC057 \ {ARC019/C, ARC046/D, ARC050, ARC052/{A, C}, ARC053, ARC055},
+        // ABC007..ABC040 \ {ABC036}, ATC001, ATC002
+        ("arc019", select!("div.part > section > h3, div.part > section > pre")),
+        // ARC002..ARC018, ARC019/C, ABC001..ABC006
+        ("arc002", select!("div.part > h3, div.part > section > pre")),
+        // ARC001, dwacon2018-final/{A, B}
+        ("arc001", select!("h3, section > pre")),
+        // ARC046/D, ARC050, ARC052/{A, C}, ARC053, ARC055, ABC036, ABC041
+        ("arc046", select!("section > h3, section > pre")),
+        // ABC034
+        ("abc034", select!("span.lang > span.lang-ja > section > h3, span.lang > span.lang-ja > section > pre")),
+        // practice contest (Japanese)
+        ("practice-ja", select!("span.lang > span.lang-ja > div.part > h3, span.lang > span.lang-ja > div.part > section > pre")),
+        // kupc2015
+        ("kupc2015", select!("h3, pre")),
+    ];
+
+    PS.iter()
+        .flat_map(|&(name, selector)| {
+            IN_OUT_REGEXS
+                .iter()
+                .map(move |&(lang, re_input, re_output)| HeadingPattern {
+                    name,
+                    selector,
+                    lang,
+                    re_input,
+                    re_output,
+                })
+        })
+        .collect()
+}
+
+/// Scores samples by how likely they are the real ones.
+///
+/// More samples are better, and samples numbered from 1 without gaps are preferred.
+fn score_samples(samples: &[Sample]) -> usize {
+    let is_sequential = samples
+        .iter()
+        .enumerate()
+        .all(|(i, sample)| sample.name() == &(i + 1).to_string());
+    let n_empty = samples
+        .iter()
+        .filter(|sample| sample.input().trim().is_empty())
+        .count();
+    (samples.len() - n_empty) * 2 + is_sequential as usize
+}
+
 impl StatementElem<'_> {
-    fn extract_samples(&self) -> Vec<Sample> {
-        static IN_OUT_REGEXS: &[(&Lazy<Regex>, &Lazy<Regex>)] = &[
-            (
-                regex!(r"\ASample Input\s?([0-9]{1,2}).*\z"),
-                regex!(r"\ASample Output\s?([0-9]{1,2}).*\z"),
-            ),
-            (
-                regex!(r"\A[\s\n]*入力例\s*(\d{1,2})[.\n]*\z"),
-                regex!(r"\A[\s\n]*出力例\s*(\d{1,2})[.\n]*\z"),
-            ),
-        ];
-        static PS: &[&Lazy<Selector>] = &[
-            // Current style (Japanese)
-            select!("span.lang > span.lang-ja > div.part > section > h3, span.lang > span.lang-ja > div.part > section > pre"),
-            // Current style (English)
-            select!("span.lang > span.lang-en > div.part > section > h3, span.lang > span.lang-en > div.part > section > pre"),
-            // ARC019..ARC057 \ {ARC019/C, ARC046/D, ARC050, ARC052/{A, C}, ARC053, ARC055},
-            // ABC007..ABC040 \ {ABC036}, ATC001, ATC002
-            select!("div.part > section > h3, div.part > section > pre"),
-            // ARC002..ARC018, ARC019/C, ABC001..ABC006
-            select!("div.part > h3, div.part > section > pre"),
-            // ARC001, dwacon2018-final/{A, B}
-            select!("h3, section > pre"),
-            // ARC046/D, ARC050, ARC052/{A, C}, ARC053, ARC055, ABC036, ABC041
-            select!("section > h3, section > pre"),
-            // ABC034
-            select!("span.lang > span.lang-ja > section > h3, span.lang > span.lang-ja > section > pre"),
-            // practice contest (Japanese)
-            select!("span.lang > span.lang-ja > div.part > h3, span.lang > span.lang-ja > div.part > section > pre"),
-            // kupc2015
-            select!("h3, pre"),
+    /// Extracts samples and returns them with the name of the stage that extracted them.
+    ///
+    /// Falls back to pairing up all `pre` blocks if `lax` is true and no heading pattern matches.
+    fn extract_samples(&self, lax: bool) -> (Vec<Sample>, Option<String>) {
+        let mut best: Option<(usize, Vec<Sample>, String)> = None;
+        for stage in sample_pipeline() {
+            if let Some(samples) = stage.extract(self) {
+                let score = score_samples(&samples);
+                // earlier stage wins a tie
+                if best
+                    .as_ref()
+                    .map_or(true, |(best_score, ..)| score > *best_score)
+                {
+                    best = Some((score, samples, stage.name()));
+                }
+            }
+        }
+        if best.is_none() && lax {
+            best = PrePairs
+                .extract(self)
+                .map(|samples| (0, samples, PrePairs.name()));
+        }
+        match best {
+            Some((_, samples, name)) => (samples, Some(name)),
+            None => (vec![], None),
+        }
+    }
+
+    /// Extracts points from the line at the top of statement (e.g. `Score : 300 points`).
+    fn extract_points(&self) -> Option<u64> {
+        let re_points = regex!(r"\A\s*(?:Score|配点)\s*[:：]\s*([0-9０-９]+)\s*(?:points?|点)");
+        self.0.select(select!("p")).find_map(|elem| {
+            let text = elem.inner_text();
+            let caps = re_points.captures(&text)?;
+            parse_zenkaku_digits(&caps[1]).ok()
+        })
+    }
+
+    fn extract_asset_urls(&self) -> Vec<String> {
+        static ATTACHMENT_EXTS: &[&str] = &[
+            "zip", "gz", "tgz", "bz2", "xz", "7z", "pdf", "png", "jpg", "jpeg", "gif", "svg",
         ];
 
-        for p in PS {
-            for (re_in, re_out) in IN_OUT_REGEXS {
-                if let Some(samples) = self.try_extract_samples(p, re_in, re_out) {
-                    return samples;
-                }
+        let mut urls: Vec<String> = Vec::new();
+        for elem in self.0.select(select!("img[src], a[href]")) {
+            let url = match elem
+                .value()
+                .attr("src")
+                .or_else(|| elem.value().attr("href"))
+            {
+                Some(url) => url,
+                None => continue,
+            };
+            let is_asset = elem.value().name() == "img"
+                || url
+                    .rsplit('.')
+                    .next()
+                    .map(|ext| ATTACHMENT_EXTS.contains(&ext.to_lowercase().as_str()))
+                    .unwrap_or(false);
+            if is_asset && !url.starts_with("data:") && !urls.iter().any(|u| u == url) {
+                urls.push(url.to_owned());
             }
         }
-        return vec![];
+        urls
     }
 
     fn try_extract_samples(
@@ -207,3 +407,56 @@ impl Scrape for StatementElem<'_> {
         self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_samples() {
+        // English-only statement with spaces around headings
+        let html = Html::parse_fragment(
+            r#"<div id="task-statement"><span class="lang"><span class="lang-en">
+<div class="part"><section><h3>Input</h3><pre><var>N</var></pre></section></div>
+<div class="part"><section><h3> Sample Input 1
+</h3><pre>3
+</pre></section></div>
+<div class="part"><section><h3>Sample Output 1</h3><pre>6
+</pre></section></div>
+</span></span></div>"#,
+        );
+        let statement = StatementElem(html.root_element());
+        let (samples, pattern) = statement.extract_samples(false);
+        assert_eq!(samples, vec![Sample::new("1", "3\n", "6\n")]);
+        assert_eq!(pattern.as_deref(), Some("current-en/en"));
+
+        // unknown layout
+        let html = Html::parse_fragment(
+            r#"<div id="task-statement"><pre><var>N</var></pre>
+<h4>Example 1</h4><pre>3
+</pre><pre>6
+</pre></div>"#,
+        );
+        let statement = StatementElem(html.root_element());
+        assert_eq!(statement.extract_samples(false), (vec![], None));
+        let (samples, pattern) = statement.extract_samples(true);
+        assert_eq!(samples, vec![Sample::new("1", "3\n", "6\n")]);
+        assert_eq!(pattern.as_deref(), Some("pre-pairs"));
+    }
+
+    #[test]
+    fn test_extract_points() {
+        let html = Html::parse_fragment(
+            r#"<div id="task-statement"><span class="lang">
+<span class="lang-ja"><p>配点 : <var>300</var> 点</p></span>
+<span class="lang-en"><p>Score : <var>300</var> points</p></span>
+</span></div>"#,
+        );
+        let statement = StatementElem(html.root_element());
+        assert_eq!(statement.extract_points(), Some(300));
+
+        let html = Html::parse_fragment(r#"<div id="task-statement"><p>No score</p></div>"#);
+        let statement = StatementElem(html.root_element());
+        assert_eq!(statement.extract_points(), None);
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _};
+use getset::{CopyGetters, Getters};
+use lazy_static::lazy_static;
+use reqwest::{Client, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::config::SessionConfig;
+use crate::model::{ContestId, ProblemId};
+use crate::page::BASE_URL;
+use crate::service::session::WithRetry as _;
+use crate::service::throttle::set_rate_limit;
+use crate::{Console, Result};
+
+lazy_static! {
+    static ref API_BASE_URL: Url = Url::parse("https://kenkoooo.com/atcoder/").unwrap();
+}
+
+/// Interval between requests asked by AtCoder Problems.
+const API_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+/// Max number of submissions returned by a request of AtCoder Problems API.
+const SUBMISSIONS_PER_PAGE: usize = 500;
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+struct ApiProblem {
+    id: String,
+    contest_id: ContestId,
+    problem_index: ProblemId,
+    name: String,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct ApiProblemModel {
+    difficulty: Option<f64>,
+    #[serde(default)]
+    is_experimental: bool,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+struct ApiSubmission {
+    epoch_second: i64,
+    problem_id: String,
+    result: String,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "PascalCase")]
+struct RatingHistory {
+    is_rated: bool,
+    new_rating: i64,
+}
+
+/// Problem of AtCoder listed by AtCoder Problems.
+#[derive(Serialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PracticeProblem {
+    #[get = "pub"]
+    contest_id: ContestId,
+    #[get = "pub"]
+    problem_id: ProblemId,
+    /// Id of problem in url (e.g. `abc100_c`)
+    #[get = "pub"]
+    url_name: String,
+    #[get = "pub"]
+    name: String,
+    /// Difficulty estimated by AtCoder Problems, which is comparable to rating
+    #[get_copy = "pub"]
+    difficulty: Option<i64>,
+    #[get_copy = "pub"]
+    is_experimental: bool,
+    #[get_copy = "pub"]
+    is_solved: bool,
+}
+
+/// Client of AtCoder Problems API (https://github.com/kenkoooo/AtCoderProblems).
+#[derive(Debug)]
+pub(crate) struct ProblemsApi<'a> {
+    client: &'a Client,
+    session: &'a SessionConfig,
+}
+
+impl<'a> ProblemsApi<'a> {
+    pub fn new(client: &'a Client, session: &'a SessionConfig) -> Self {
+        if let Some(host) = API_BASE_URL.host_str() {
+            set_rate_limit(host, API_REQUEST_INTERVAL);
+        }
+        Self { client, session }
+    }
+
+    /// Fetches problems with difficulties, marking the ones accepted by `user` as solved.
+    pub async fn fetch_problems(
+        &self,
+        user: Option<&str>,
+        cnsl: &mut Console,
+    ) -> Result<Vec<PracticeProblem>> {
+        let problems: Vec<ApiProblem> = self
+            .get_json(API_BASE_URL.join("resources/problems.json")?, cnsl)
+            .await
+            .context("Could not fetch problems from AtCoder Problems")?;
+        let models: HashMap<String, ApiProblemModel> = self
+            .get_json(API_BASE_URL.join("resources/problem-models.json")?, cnsl)
+            .await
+            .context("Could not fetch difficulties from AtCoder Problems")?;
+        let solved = match user {
+            Some(user) => self.fetch_solved(user, cnsl).await?,
+            None => HashSet::new(),
+        };
+
+        let problems = problems
+            .into_iter()
+            .map(|problem| {
+                let model = models.get(&problem.id);
+                PracticeProblem {
+                    is_solved: solved.contains(&problem.id),
+                    difficulty: model.and_then(|m| m.difficulty).map(clip_difficulty),
+                    is_experimental: model.map_or(false, |m| m.is_experimental),
+                    contest_id: problem.contest_id,
+                    problem_id: problem.problem_index,
+                    url_name: problem.id,
+                    name: problem.name,
+                }
+            })
+            .collect();
+        Ok(problems)
+    }
+
+    /// Fetches difficulties of problems in the contest, omitting problems without estimates.
+    pub async fn fetch_difficulties(
+        &self,
+        contest_id: &ContestId,
+        cnsl: &mut Console,
+    ) -> Result<BTreeMap<ProblemId, i64>> {
+        let difficulties = self
+            .fetch_problems(None, cnsl)
+            .await?
+            .into_iter()
+            .filter(|problem| &problem.contest_id == contest_id)
+            .filter_map(|problem| Some((problem.problem_id, problem.difficulty?)))
+            .collect();
+        Ok(difficulties)
+    }
+
+    /// Fetches ids of problems accepted by the user.
+    async fn fetch_solved(&self, user: &str, cnsl: &mut Console) -> Result<HashSet<String>> {
+        let mut solved = HashSet::new();
+        let mut from_second = 0;
+        loop {
+            let mut url = API_BASE_URL.join("atcoder-api/v3/user/submissions")?;
+            url.query_pairs_mut()
+                .append_pair("user", user)
+                .append_pair("from_second", &from_second.to_string());
+            let submissions: Vec<ApiSubmission> = self
+                .get_json(url, cnsl)
+                .await
+                .context("Could not fetch submissions from AtCoder Problems")?;
+            for submission in &submissions {
+                if submission.result == "AC" {
+                    solved.insert(submission.problem_id.clone());
+                }
+            }
+            match submissions.iter().map(|s| s.epoch_second).max() {
+                Some(last) if submissions.len() >= SUBMISSIONS_PER_PAGE => from_second = last + 1,
+                _ => return Ok(solved),
+            }
+        }
+    }
+
+    /// Fetches the latest rating of the user from AtCoder, or None if the user has not been rated.
+    pub async fn fetch_rating(&self, user: &str, cnsl: &mut Console) -> Result<Option<i64>> {
+        let url = BASE_URL.join(&format!("/users/{}/history/json", user))?;
+        let history: Vec<RatingHistory> = self
+            .get_json(url, cnsl)
+            .await
+            .context("Could not fetch rating history")?;
+        Ok(history
+            .iter()
+            .rev()
+            .find(|history| history.is_rated)
+            .map(|history| history.new_rating))
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: Url, cnsl: &mut Console) -> Result<T> {
+        let res = self
+            .client
+            .get(url)
+            .with_retry(
+                self.client,
+                self.session.cookies_path(),
+                self.session.retry_limit(),
+                self.session.retry_interval(),
+            )
+            .without_cookies()
+            .retry_send(cnsl)
+            .await?;
+        match res.status() {
+            StatusCode::OK => {
+                let text = res.text().await.context("Could not read response")?;
+                Ok(serde_json::from_str(&text).context("Could not parse response")?)
+            }
+            status => Err(anyhow!("Received invalid response code : {}", status)),
+        }
+    }
+}
+
+/// Clips difficulty below 400 into positive values in the same way as AtCoder Problems shows it.
+fn clip_difficulty(difficulty: f64) -> i64 {
+    let difficulty = if difficulty >= 400.0 {
+        difficulty
+    } else {
+        400.0 / (1.0 - difficulty / 400.0).exp()
+    };
+    difficulty.round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clip_difficulty() {
+        assert_eq!(clip_difficulty(1234.4), 1234);
+        assert_eq!(clip_difficulty(400.0), 400);
+        assert_eq!(clip_difficulty(0.0), 147);
+        assert!(clip_difficulty(-1000.0) > 0);
+    }
+
+    #[test]
+    fn deserialize_problem() -> anyhow::Result<()> {
+        let problem: ApiProblem = serde_json::from_str(
+            r#"{"id":"abc100_c","contest_id":"abc100","problem_index":"C","name":"*3 or /2","title":"C. *3 or /2"}"#,
+        )?;
+        assert_eq!(problem.contest_id, ContestId::from("abc100"));
+        assert_eq!(problem.problem_index, ProblemId::from("C"));
+        Ok(())
+    }
+}
@@ -0,0 +1,319 @@
+//! Storages from which full testcases are downloaded.
+
+use std::fs::{self, read_dir};
+
+use anyhow::{anyhow, Context as _};
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use futures::stream::{self, StreamExt as _, TryStreamExt as _};
+use reqwest::{Client, Proxy};
+use strum::IntoEnumIterator as _;
+
+use crate::abs_path::AbsPathBuf;
+use crate::config::Config;
+use crate::dropbox::{Downloader, Dropbox};
+use crate::full::InOut;
+use crate::model::{ContestId, Problem};
+use crate::service::throttle::Throttle;
+use crate::Result;
+
+static DBX_TESTCASES_URL: &str =
+    "https://www.dropbox.com/sh/arnpe0ef5wds8cv/AAAk_SECQ2Nc6SVGii3rHX6Fa?dl=0";
+
+/// Testcase file in a storage.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TestcaseFile {
+    pub inout: InOut,
+    /// File name (e.g. `sample_01.txt`)
+    pub name: String,
+    pub size: u64,
+    /// Path that locates the file in the storage
+    pub path: String,
+}
+
+/// Storage that provides testcase files of contests.
+///
+/// Testcases of a problem are expected to be in `<contest>/<problem>/{in,out}/` of the storage.
+#[async_trait(?Send)]
+pub trait StorageProvider {
+    /// Name of the storage shown to user.
+    fn name(&self) -> &str;
+
+    /// Lists testcase files of each problem of the contest.
+    async fn list_testcase_files(
+        &self,
+        contest_id: &ContestId,
+        problems: &[Problem],
+    ) -> Result<Vec<Vec<TestcaseFile>>>;
+
+    /// Saves content of the testcase file to `abs_path`,
+    /// calling `on_progress` with the number of bytes received.
+    async fn download_file(
+        &self,
+        file: &TestcaseFile,
+        abs_path: &AbsPathBuf,
+        throttle: Option<&Throttle>,
+        on_progress: &(dyn Fn(u64) + Sync),
+    ) -> Result<()>;
+}
+
+/// Shared folder of testcases on Dropbox published by AtCoder.
+#[derive(Debug)]
+pub struct DropboxStorage<'a> {
+    dropbox: &'a Dropbox,
+    downloader: Downloader,
+    jobs: Option<usize>,
+}
+
+impl<'a> DropboxStorage<'a> {
+    /// Creates storage that lists files of up to `jobs` problems concurrently.
+    pub fn new(dropbox: &'a Dropbox, jobs: Option<usize>) -> Self {
+        Self {
+            dropbox,
+            downloader: dropbox.downloader(),
+            jobs,
+        }
+    }
+
+    async fn list_problem_files(
+        &self,
+        folder_name: &str,
+        problem: &Problem,
+    ) -> Result<Vec<TestcaseFile>> {
+        let files_arr = try_join_all(InOut::iter().map(|inout| async move {
+            let dir = format!("/{}/{}/{}", folder_name, problem.id(), inout.as_ref());
+            let files = self
+                .dropbox
+                .list_all_files(&dir, Some(DBX_TESTCASES_URL))
+                .await
+                .context("Could not list testcase files on Dropbox")?
+                .into_iter()
+                .map(|file| TestcaseFile {
+                    inout,
+                    path: format!("{}/{}", dir, file.name),
+                    name: file.name,
+                    size: file.size,
+                })
+                .collect::<Vec<_>>();
+            Ok::<_, anyhow::Error>(files)
+        }))
+        .await?;
+        Ok(files_arr.into_iter().flatten().collect())
+    }
+}
+
+#[async_trait(?Send)]
+impl StorageProvider for DropboxStorage<'_> {
+    fn name(&self) -> &str {
+        "Dropbox"
+    }
+
+    async fn list_testcase_files(
+        &self,
+        contest_id: &ContestId,
+        problems: &[Problem],
+    ) -> Result<Vec<Vec<TestcaseFile>>> {
+        // find dropbox folder that corresponds to the contest
+        let folders = self
+            .dropbox
+            .list_all_folders("", Some(DBX_TESTCASES_URL))
+            .await?;
+        let folder = folders
+            .iter()
+            .find(|folder| &ContestId::from(&folder.name) == contest_id)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Could not find folder for the contest on Dropbox : {}",
+                    contest_id
+                )
+            })?;
+
+        let jobs = self.jobs.unwrap_or_else(rayon::current_num_threads);
+        stream::iter(problems)
+            .map(|problem| self.list_problem_files(&folder.name, problem))
+            .buffered(jobs)
+            .try_collect()
+            .await
+    }
+
+    async fn download_file(
+        &self,
+        file: &TestcaseFile,
+        abs_path: &AbsPathBuf,
+        throttle: Option<&Throttle>,
+        on_progress: &(dyn Fn(u64) + Sync),
+    ) -> Result<()> {
+        self.downloader
+            .download_shared_link_file(
+                DBX_TESTCASES_URL,
+                &file.path,
+                file.size,
+                abs_path,
+                throttle,
+                on_progress,
+            )
+            .await
+    }
+}
+
+/// Builds client for Dropbox configured by session config (e.g. proxy).
+pub(crate) fn build_client(conf: &Config) -> Result<Client> {
+    let session = conf.session();
+    let mut builder = Client::builder().connect_timeout(session.timeout());
+    if let Some(proxy) = session.proxy() {
+        let proxy =
+            Proxy::all(&proxy).with_context(|| format!("Could not parse proxy url : {}", proxy))?;
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .context("Could not setup client for Dropbox")
+}
+
+/// Local directory that mirrors the shared folder of testcases (e.g. synced by Dropbox client).
+///
+/// Transfer rate is not limited because files are only copied.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LocalStorage {
+    dir: AbsPathBuf,
+    name: String,
+}
+
+impl LocalStorage {
+    pub fn new(dir: AbsPathBuf) -> Self {
+        Self {
+            name: format!("local directory {}", dir),
+            dir,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl StorageProvider for LocalStorage {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn list_testcase_files(
+        &self,
+        contest_id: &ContestId,
+        problems: &[Problem],
+    ) -> Result<Vec<Vec<TestcaseFile>>> {
+        // find directory that corresponds to the contest
+        let contest_dir = read_dir(self.dir.as_ref())
+            .with_context(|| format!("Could not read directory : {}", self.dir))?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .find(|entry| &ContestId::from(entry.file_name().to_string_lossy()) == contest_id)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Could not find directory for the contest in {} : {}",
+                    self.dir,
+                    contest_id
+                )
+            })?
+            .path();
+
+        let mut files_per_problem = Vec::with_capacity(problems.len());
+        for problem in problems {
+            let mut files = Vec::new();
+            for inout in InOut::iter() {
+                let dir = contest_dir.join(problem.id().as_ref()).join(inout.as_ref());
+                if !dir.is_dir() {
+                    continue;
+                }
+                for entry in read_dir(&dir)? {
+                    let entry = entry?;
+                    let metadata = entry.metadata()?;
+                    if !metadata.is_file() {
+                        continue;
+                    }
+                    files.push(TestcaseFile {
+                        inout,
+                        name: entry.file_name().to_string_lossy().into_owned(),
+                        size: metadata.len(),
+                        path: entry.path().to_string_lossy().into_owned(),
+                    });
+                }
+            }
+            files.sort_by(|a, b| (a.inout, &a.name).cmp(&(b.inout, &b.name)));
+            files_per_problem.push(files);
+        }
+        Ok(files_per_problem)
+    }
+
+    async fn download_file(
+        &self,
+        file: &TestcaseFile,
+        abs_path: &AbsPathBuf,
+        _throttle: Option<&Throttle>,
+        on_progress: &(dyn Fn(u64) + Sync),
+    ) -> Result<()> {
+        if let Some(parent) = abs_path.parent() {
+            parent.create_dir_all()?;
+        }
+        fs::copy(&file.path, abs_path.as_ref())
+            .with_context(|| format!("Could not copy testcase file : {}", file.path))?;
+        on_progress(file.size);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::model::Compare;
+
+    #[tokio::test]
+    async fn test_local_storage() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let dir = AbsPathBuf::try_new(test_dir.path())?;
+        for (path, content) in &[
+            ("ARC100/C/in/01.txt", "1\n"),
+            ("ARC100/C/out/01.txt", "2\n"),
+            ("ARC100/C/in/00.txt", "0\n"),
+            ("ARC101/C/in/01.txt", "3\n"),
+        ] {
+            let abs_path = dir.join(path);
+            abs_path.parent().unwrap().create_dir_all()?;
+            fs::write(abs_path.as_ref(), content)?;
+        }
+        let storage = LocalStorage::new(dir.clone());
+        let problems = vec![
+            Problem::new("C", "", "arc100_a", None, None, Compare::Default, vec![]),
+            Problem::new("D", "", "arc100_b", None, None, Compare::Default, vec![]),
+        ];
+
+        let files = storage
+            .list_testcase_files(&"arc100".into(), &problems)
+            .await?;
+        assert_eq!(files.len(), 2);
+        let names: Vec<_> = files[0]
+            .iter()
+            .map(|file| (file.inout, file.name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (InOut::In, "00.txt"),
+                (InOut::In, "01.txt"),
+                (InOut::Out, "01.txt")
+            ]
+        );
+        assert!(files[1].is_empty());
+
+        let abs_path = dir.join("saved/in/01.txt");
+        storage
+            .download_file(&files[0][1], &abs_path, None, &|_| {})
+            .await?;
+        assert_eq!(fs::read_to_string(abs_path.as_ref())?, "1\n");
+
+        assert!(storage
+            .list_testcase_files(&"abc100".into(), &problems)
+            .await
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash as _, Hasher as _};
+
+use anyhow::Context as _;
+
+use crate::abs_path::AbsPathBuf;
+use crate::model::ProblemId;
+use crate::store::StateStore;
+use crate::{Config, Result};
+
+static COMPILE_CACHE_FILE_NAME: &str = ".acick-compile.jsonl";
+
+/// Cache that tells whether the artifact is built from the current source code
+/// with the current compile command.
+///
+/// The cache is stored in working_dir of the problem.
+/// Removing the artifact makes the compile command run again.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompileCache {
+    store: StateStore,
+    key: String,
+    fingerprint: String,
+    artifact: AbsPathBuf,
+}
+
+impl CompileCache {
+    /// Returns true if the artifact exists and was built with the same fingerprint.
+    pub fn is_up_to_date(&self) -> Result<bool> {
+        if !self.artifact.as_ref().is_file() {
+            return Ok(false);
+        }
+        let saved: Option<String> = self.store.get(&self.key)?;
+        Ok(saved.as_ref() == Some(&self.fingerprint))
+    }
+
+    /// Records that the artifact was built with the fingerprint.
+    ///
+    /// Fingerprint is computed before compile, so that changes of source code during compile
+    /// are detected next time.
+    pub fn save(&self) -> Result<()> {
+        self.store.set(&self.key, &self.fingerprint)
+    }
+}
+
+impl Config {
+    /// Returns compile cache of the problem with fingerprint of the current source code
+    /// and compile command, which is compile_debug if `debug` is true.
+    pub fn compile_cache(&self, problem_id: &ProblemId, debug: bool) -> Result<CompileCache> {
+        let source_abs_path = self.source_abs_path(problem_id)?;
+        let source = fs::read(source_abs_path.as_ref())
+            .with_context(|| format!("Could not read source file : {}", source_abs_path))?;
+
+        // DefaultHasher may change between versions of Rust, which only causes extra compile
+        let mut hasher = DefaultHasher::new();
+        let service = self.service();
+        self.service_id.hash(&mut hasher);
+        self.contest_id.to_string().hash(&mut hasher);
+        problem_id.to_string().hash(&mut hasher);
+        self.body.shell.hash(&mut hasher);
+        // artifact built for debug is not up to date for the regular build, and vice versa
+        if debug {
+            service.compile_debug.hash(&mut hasher);
+        } else {
+            service.compile.hash(&mut hasher);
+        }
+        debug.hash(&mut hasher);
+        service.workspace_dir.hash(&mut hasher);
+        service.artifact_path.hash(&mut hasher);
+        service.env.hash(&mut hasher);
+        source.hash(&mut hasher);
+        // compile may read the other files of the project (e.g. Cargo.toml in workspace_dir)
+        for template_file in &service.template_files {
+            let abs_path = self.expand_to_abs(&template_file.path, problem_id)?;
+            hash_file(&abs_path, &mut hasher)?;
+        }
+        let artifacts = self.artifact_abs_paths(problem_id)?;
+        if service.workspace_dir.is_some() {
+            let workspace_abs_dir = self.compile_abs_dir(problem_id)?;
+            for abs_path in workspace_files(&workspace_abs_dir, &artifacts)? {
+                hash_file(&abs_path, &mut hasher)?;
+            }
+        }
+
+        let store = StateStore::new(
+            self.working_abs_dir(problem_id)?
+                .join(COMPILE_CACHE_FILE_NAME),
+        );
+        let artifact = artifacts
+            .into_iter()
+            .next()
+            .context("Could not get path of artifact")?;
+        Ok(CompileCache {
+            store,
+            key: format!("{}/{}/{}", self.service_id, self.contest_id, problem_id),
+            fingerprint: format!("{:016x}", hasher.finish()),
+            artifact,
+        })
+    }
+}
+
+/// Hashes path and content of the file, or only the path if the file does not exist.
+fn hash_file(abs_path: &AbsPathBuf, hasher: &mut DefaultHasher) -> Result<()> {
+    abs_path.hash(hasher);
+    if abs_path.as_ref().is_file() {
+        let content = fs::read(abs_path.as_ref())
+            .with_context(|| format!("Could not read file : {}", abs_path))?;
+        content.hash(hasher);
+    }
+    Ok(())
+}
+
+/// Lists files directly in workspace_dir (e.g. manifest and lock file) in a stable order.
+///
+/// Artifacts and subdirectories are not listed because they are (or may contain) build outputs.
+fn workspace_files(
+    workspace_abs_dir: &AbsPathBuf,
+    artifacts: &[AbsPathBuf],
+) -> Result<Vec<AbsPathBuf>> {
+    if !workspace_abs_dir.as_ref().is_dir() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(workspace_abs_dir.as_ref())
+        .with_context(|| format!("Could not read workspace dir : {}", workspace_abs_dir))?;
+    let mut file_names = Vec::new();
+    for entry in entries {
+        let entry = entry
+            .with_context(|| format!("Could not read workspace dir : {}", workspace_abs_dir))?;
+        if entry.file_type()?.is_file() && entry.file_name() != COMPILE_CACHE_FILE_NAME {
+            file_names.push(entry.file_name());
+        }
+    }
+    file_names.sort();
+    let artifacts: Vec<_> = artifacts.iter().map(AbsPathBuf::normalize).collect();
+    Ok(file_names
+        .into_iter()
+        .map(|file_name| workspace_abs_dir.join(file_name))
+        .filter(|abs_path| !artifacts.contains(&abs_path.normalize()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_is_up_to_date() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let conf = Config::default_in_dir(AbsPathBuf::try_new(test_dir.path())?);
+        let problem_id = ProblemId::from("C");
+        let source_abs_path = conf.source_abs_path(&problem_id)?;
+        source_abs_path.parent().unwrap().create_dir_all()?;
+        fs::write(source_abs_path.as_ref(), "int main() {}\n")?;
+
+        let cache = conf.compile_cache(&problem_id, false)?;
+        assert!(!cache.is_up_to_date()?);
+        cache.save()?;
+        // artifact does not exist yet
+        assert!(!cache.is_up_to_date()?);
+        let artifact = &conf.artifact_abs_paths(&problem_id)?[0];
+        fs::write(artifact.as_ref(), "")?;
+        assert!(conf.compile_cache(&problem_id, false)?.is_up_to_date()?);
+        assert!(!conf.compile_cache(&problem_id, true)?.is_up_to_date()?);
+
+        fs::write(source_abs_path.as_ref(), "int main() { return 0; }\n")?;
+        assert!(!conf.compile_cache(&problem_id, false)?.is_up_to_date()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_up_to_date_with_project_files() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let mut conf = Config::default_in_dir(AbsPathBuf::try_new(test_dir.path())?);
+        conf.body.services.atcoder.workspace_dir = Some("atcoder/{{ contest }}".into());
+        conf.body.services.atcoder.template_files = serde_yaml::from_str(
+            r#"
+- path: atcoder/{{ contest }}/manifest.txt
+  template: "{{ contest.id }}"
+"#,
+        )?;
+        let problem_id = ProblemId::from("C");
+        let source_abs_path = conf.source_abs_path(&problem_id)?;
+        source_abs_path.parent().unwrap().create_dir_all()?;
+        fs::write(source_abs_path.as_ref(), "int main() {}\n")?;
+        let workspace_abs_dir = conf.compile_abs_dir(&problem_id)?;
+        workspace_abs_dir.create_dir_all()?;
+        let manifest_abs_path = workspace_abs_dir.join("manifest.txt");
+        fs::write(manifest_abs_path.as_ref(), "arc100")?;
+        let artifact = &conf.artifact_abs_paths(&problem_id)?[0];
+        artifact.parent().unwrap().create_dir_all()?;
+        fs::write(artifact.as_ref(), "")?;
+
+        conf.compile_cache(&problem_id, false)?.save()?;
+        assert!(conf.compile_cache(&problem_id, false)?.is_up_to_date()?);
+
+        // changes of template files make the artifact outdated
+        fs::write(manifest_abs_path.as_ref(), "arc100\n[dependencies]")?;
+        assert!(!conf.compile_cache(&problem_id, false)?.is_up_to_date()?);
+        conf.compile_cache(&problem_id, false)?.save()?;
+        assert!(conf.compile_cache(&problem_id, false)?.is_up_to_date()?);
+
+        // so do changes of the other files in workspace_dir
+        fs::write(workspace_abs_dir.join("lock.txt").as_ref(), "")?;
+        assert!(!conf.compile_cache(&problem_id, false)?.is_up_to_date()?);
+        Ok(())
+    }
+}
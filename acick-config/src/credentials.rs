@@ -0,0 +1,409 @@
+use std::env;
+use std::fmt;
+use std::io::Write as _;
+use std::process::Command;
+
+use anyhow::{anyhow, Context as _};
+use keyring::{Keyring, KeyringError};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+use crate::console::Console;
+use crate::model::ServiceKind;
+use crate::Result;
+
+lazy_static! {
+    static ref DEFAULT_SOURCES: Vec<CredentialSource> = vec![
+        CredentialSource::Env(EnvProvider::default()),
+        CredentialSource::Prompt(PromptProvider {}),
+    ];
+}
+
+/// Username and password of a service.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &"********")
+            .finish()
+    }
+}
+
+/// Source from which credentials of a service are read.
+///
+/// Username and password are read separately, so that they can be provided by different sources
+/// (e.g. username from env and password from keyring).
+pub trait CredentialProvider {
+    /// Returns `None` if the provider does not know the username.
+    fn username(&self, service_id: ServiceKind, cnsl: &mut Console) -> Result<Option<String>>;
+
+    /// Returns `None` if the provider does not know the password.
+    fn password(
+        &self,
+        service_id: ServiceKind,
+        username: &str,
+        cnsl: &mut Console,
+    ) -> Result<Option<String>>;
+
+    /// Returns true if the provider asks user, which is not allowed while running other commands.
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+/// Reads credentials from environment variables (e.g. `ACICK_ATCODER_USERNAME` and `ACICK_ATCODER_PASSWORD`).
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EnvProvider {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    username_env: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password_env: Option<String>,
+}
+
+impl EnvProvider {
+    fn read(
+        env_name: &str,
+        prompt: &str,
+        is_password: bool,
+        cnsl: &mut Console,
+    ) -> Result<Option<String>> {
+        let val = match env::var(env_name) {
+            Ok(val) => val,
+            Err(_) => return Ok(None),
+        };
+        writeln!(
+            cnsl,
+            "{}{:16} (read from env {})",
+            prompt,
+            if is_password { "********" } else { &val },
+            env_name
+        )?;
+        Ok(Some(val))
+    }
+}
+
+impl CredentialProvider for EnvProvider {
+    fn username(&self, service_id: ServiceKind, cnsl: &mut Console) -> Result<Option<String>> {
+        let default_env = service_id.to_user_pass_env_names().0;
+        let env_name = self.username_env.as_deref().unwrap_or(default_env);
+        Self::read(env_name, "username: ", false, cnsl)
+    }
+
+    fn password(
+        &self,
+        service_id: ServiceKind,
+        _username: &str,
+        cnsl: &mut Console,
+    ) -> Result<Option<String>> {
+        let default_env = service_id.to_user_pass_env_names().1;
+        let env_name = self.password_env.as_deref().unwrap_or(default_env);
+        Self::read(env_name, "password: ", true, cnsl)
+    }
+}
+
+/// Asks user to input credentials.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PromptProvider {}
+
+impl CredentialProvider for PromptProvider {
+    fn username(&self, _service_id: ServiceKind, cnsl: &mut Console) -> Result<Option<String>> {
+        let username = cnsl
+            .prompt_and_read("username: ", false)
+            .context("Could not read username")?;
+        Ok(Some(username))
+    }
+
+    fn password(
+        &self,
+        _service_id: ServiceKind,
+        _username: &str,
+        cnsl: &mut Console,
+    ) -> Result<Option<String>> {
+        let password = cnsl
+            .prompt_and_read("password: ", true)
+            .context("Could not read password")?;
+        Ok(Some(password))
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+
+/// Reads password from keyring of OS, stored under service `acick-<service>` (e.g. `acick-atcoder`).
+///
+/// Password not stored in keyring is treated as unknown, while the other errors are returned.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyringProvider {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+}
+
+impl CredentialProvider for KeyringProvider {
+    fn username(&self, _service_id: ServiceKind, _cnsl: &mut Console) -> Result<Option<String>> {
+        Ok(self.username.clone())
+    }
+
+    fn password(
+        &self,
+        service_id: ServiceKind,
+        username: &str,
+        cnsl: &mut Console,
+    ) -> Result<Option<String>> {
+        let service = format!("acick-{}", <&str>::from(service_id));
+        let password = match Keyring::new(&service, username).get_password() {
+            Ok(password) => password,
+            // try the next provider if the password is not stored
+            Err(KeyringError::NoPasswordFound) => return Ok(None),
+            Err(err) => {
+                return Err(anyhow!("{}", err)).with_context(|| {
+                    format!(
+                        "Could not read password from keyring : {} ({})",
+                        service, username
+                    )
+                })
+            }
+        };
+        writeln!(cnsl, "password: ******** (read from keyring {})", service)?;
+        Ok(Some(password))
+    }
+}
+
+/// Reads credentials written in config file.
+///
+/// Password in config file is stored as plain text, so prefer other providers for password.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConfigProvider {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+}
+
+impl CredentialProvider for ConfigProvider {
+    fn username(&self, _service_id: ServiceKind, _cnsl: &mut Console) -> Result<Option<String>> {
+        Ok(self.username.clone())
+    }
+
+    fn password(
+        &self,
+        _service_id: ServiceKind,
+        _username: &str,
+        _cnsl: &mut Console,
+    ) -> Result<Option<String>> {
+        Ok(self.password.clone())
+    }
+}
+
+/// Reads password from the first line of output of external command (e.g. `pass show atcoder`).
+///
+/// Empty output means the command does not know the password.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommandProvider {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+    command: Vec<String>,
+}
+
+impl CredentialProvider for CommandProvider {
+    fn username(&self, _service_id: ServiceKind, _cnsl: &mut Console) -> Result<Option<String>> {
+        Ok(self.username.clone())
+    }
+
+    fn password(
+        &self,
+        _service_id: ServiceKind,
+        _username: &str,
+        cnsl: &mut Console,
+    ) -> Result<Option<String>> {
+        let (program, args) = self
+            .command
+            .split_first()
+            .context("Found empty command of credentials")?;
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .with_context(|| format!("Could not run command : {}", program))?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Command returned non-zero status : {}\n{}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            ));
+        }
+        let stdout = String::from_utf8(output.stdout)
+            .context("Found invalid password in output of command")?;
+        let password = stdout.lines().next().unwrap_or("");
+        if password.is_empty() {
+            // try the next provider if the command knows nothing
+            return Ok(None);
+        }
+        let password = password.to_owned();
+        writeln!(cnsl, "password: ******** (read from command {})", program)?;
+        Ok(Some(password))
+    }
+}
+
+/// Config of a provider of credentials.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CredentialSource {
+    Env(EnvProvider),
+    Prompt(PromptProvider),
+    Keyring(KeyringProvider),
+    Config(ConfigProvider),
+    Command(CommandProvider),
+}
+
+impl CredentialSource {
+    pub fn provider(&self) -> &dyn CredentialProvider {
+        match self {
+            Self::Env(provider) => provider,
+            Self::Prompt(provider) => provider,
+            Self::Keyring(provider) => provider,
+            Self::Config(provider) => provider,
+            Self::Command(provider) => provider,
+        }
+    }
+}
+
+/// Providers of credentials for each service, tried in order.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct CredentialsConfig {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    atcoder: Vec<CredentialSource>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    mock: Vec<CredentialSource>,
+}
+
+impl CredentialsConfig {
+    /// Returns providers for the service.
+    ///
+    /// Defaults to env and prompt if no providers are specified.
+    pub fn get(&self, service_id: ServiceKind) -> &[CredentialSource] {
+        let sources = match service_id {
+            ServiceKind::Atcoder => &self.atcoder,
+            ServiceKind::Mock => &self.mock,
+        };
+        if sources.is_empty() {
+            DEFAULT_SOURCES.as_slice()
+        } else {
+            sources.as_slice()
+        }
+    }
+
+    /// Reads username and password from the first provider that knows each of them.
+    ///
+    /// Providers that ask user are skipped unless `interactive` is true.
+    pub fn read(
+        &self,
+        service_id: ServiceKind,
+        interactive: bool,
+        cnsl: &mut Console,
+    ) -> Result<Credentials> {
+        let providers: Vec<&dyn CredentialProvider> = self
+            .get(service_id)
+            .iter()
+            .map(CredentialSource::provider)
+            .filter(|provider| interactive || !provider.is_interactive())
+            .collect();
+
+        let mut username = None;
+        for provider in &providers {
+            username = provider.username(service_id, cnsl)?;
+            if username.is_some() {
+                break;
+            }
+        }
+        let username =
+            username.with_context(|| format!("Could not find username of {}", service_id))?;
+
+        let mut password = None;
+        for provider in &providers {
+            password = provider.password(service_id, &username, cnsl)?;
+            if password.is_some() {
+                break;
+            }
+        }
+        let password =
+            password.with_context(|| format!("Could not find password of {}", service_id))?;
+
+        Ok(Credentials { username, password })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_credentials() -> anyhow::Result<()> {
+        let mut cnsl = Console::sink(Default::default());
+        let conf: CredentialsConfig = serde_yaml::from_str(
+            r#"
+atcoder:
+  - type: env
+    username_env: ACICK_TEST_CREDENTIALS_USERNAME
+    password_env: ACICK_TEST_CREDENTIALS_UNKNOWN
+  - type: prompt
+  - type: config
+    password: config_password
+"#,
+        )?;
+        env::set_var("ACICK_TEST_CREDENTIALS_USERNAME", "env_user");
+        let credentials = conf.read(ServiceKind::Atcoder, false, &mut cnsl)?;
+        assert_eq!(credentials.username, "env_user");
+        assert_eq!(credentials.password, "config_password");
+        assert!(!format!("{:?}", credentials).contains("config_password"));
+
+        // defaults to env and prompt, which is skipped if not interactive
+        assert_eq!(
+            CredentialsConfig::default().get(ServiceKind::Atcoder),
+            DEFAULT_SOURCES.as_slice()
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_credentials_fallback_chain() -> anyhow::Result<()> {
+        let mut cnsl = Console::sink(Default::default());
+        let conf: CredentialsConfig = serde_yaml::from_str(
+            r#"
+atcoder:
+  - type: command
+    username: command_user
+    command: ["true"]
+  - type: command
+    command: ["echo", "command_password"]
+  - type: config
+    password: config_password
+"#,
+        )?;
+        // empty output falls back to the next provider
+        let credentials = conf.read(ServiceKind::Atcoder, false, &mut cnsl)?;
+        assert_eq!(credentials.username, "command_user");
+        assert_eq!(credentials.password, "command_password");
+
+        // failure of command is not treated as unknown password
+        let conf: CredentialsConfig = serde_yaml::from_str(
+            r#"
+atcoder:
+  - type: command
+    username: command_user
+    command: ["false"]
+  - type: config
+    password: config_password
+"#,
+        )?;
+        assert!(conf.read(ServiceKind::Atcoder, false, &mut cnsl).is_err());
+        Ok(())
+    }
+}
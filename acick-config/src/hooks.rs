@@ -0,0 +1,83 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::template::TargetTempl;
+
+/// Timing at which hook scripts are executed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HookKind {
+    /// Executed after problems are fetched and before they are saved
+    PreSave,
+    PostFetch,
+    PreTest,
+    PostTest,
+    PreSubmit,
+    PostSubmit,
+}
+
+impl HookKind {
+    /// Returns true if failure of the hook aborts the command.
+    pub fn is_pre(self) -> bool {
+        matches!(self, Self::PreSave | Self::PreTest | Self::PreSubmit)
+    }
+}
+
+impl fmt::Display for HookKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::PreSave => "pre_save",
+            Self::PostFetch => "post_fetch",
+            Self::PreTest => "pre_test",
+            Self::PostTest => "post_test",
+            Self::PreSubmit => "pre_submit",
+            Self::PostSubmit => "post_submit",
+        })
+    }
+}
+
+/// Shell scripts executed before and after fetch, test and submit commands.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Also read from `pre_fetch`, the former name of the hook
+    #[serde(alias = "pre_fetch", skip_serializing_if = "Vec::is_empty")]
+    pre_save: Vec<TargetTempl>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    post_fetch: Vec<TargetTempl>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pre_test: Vec<TargetTempl>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    post_test: Vec<TargetTempl>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pre_submit: Vec<TargetTempl>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    post_submit: Vec<TargetTempl>,
+}
+
+impl HooksConfig {
+    pub fn get(&self, kind: HookKind) -> &[TargetTempl] {
+        match kind {
+            HookKind::PreSave => &self.pre_save,
+            HookKind::PostFetch => &self.post_fetch,
+            HookKind::PreTest => &self.pre_test,
+            HookKind::PostTest => &self.post_test,
+            HookKind::PreSubmit => &self.pre_submit,
+            HookKind::PostSubmit => &self.post_submit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_pre_fetch_as_pre_save() -> anyhow::Result<()> {
+        let hooks: HooksConfig = serde_yaml::from_str("{ pre_fetch: [a] }")?;
+        assert_eq!(hooks.get(HookKind::PreSave).len(), 1);
+        let yaml = serde_yaml::to_string(&hooks)?;
+        assert!(yaml.contains("pre_save") && !yaml.contains("pre_fetch"));
+        Ok(())
+    }
+}
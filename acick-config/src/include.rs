@@ -0,0 +1,145 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context as _};
+use serde_yaml::{Mapping, Value};
+
+use crate::abs_path::AbsPathBuf;
+use crate::console::Console;
+use crate::Result;
+
+/// Key of the field that lists config files to be included.
+pub static INCLUDE_KEY: &str = "include";
+
+/// Loads config file as yaml and merges config files included by it.
+///
+/// Included files are merged in the order listed, and then the including file overrides them.
+pub fn load_with_includes(
+    path: &AbsPathBuf,
+    base_dir: &AbsPathBuf,
+    cnsl: &mut Console,
+) -> Result<Value> {
+    let mut stack = Vec::new();
+    load_rec(path, base_dir, &mut stack, cnsl)
+}
+
+fn load_rec(
+    path: &AbsPathBuf,
+    base_dir: &AbsPathBuf,
+    stack: &mut Vec<PathBuf>,
+    cnsl: &mut Console,
+) -> Result<Value> {
+    let id = fs::canonicalize(path.as_ref()).unwrap_or_else(|_| path.as_ref().to_owned());
+    if stack.contains(&id) {
+        return Err(anyhow!("Found cyclic include of config file : {}", path));
+    }
+    let mut value: Value = path.load_pretty(
+        |file| serde_yaml::from_reader(file).context("Could not read config file as yaml"),
+        Some(base_dir),
+        cnsl,
+    )?;
+    let includes = take_includes(&mut value)?;
+    if includes.is_empty() {
+        return Ok(value);
+    }
+
+    let dir = path
+        .parent()
+        .ok_or_else(|| anyhow!("Could not get directory of config file : {}", path))?;
+    stack.push(id);
+    let mut merged = Value::Mapping(Mapping::new());
+    for include in includes {
+        let include_path = dir.join_expand(&include)?;
+        let included = load_rec(&include_path, base_dir, stack, cnsl)
+            .with_context(|| format!("Could not include config file : {}", include))?;
+        merge(&mut merged, included);
+    }
+    stack.pop();
+    merge(&mut merged, value);
+    Ok(merged)
+}
+
+/// Removes include field from yaml and returns paths listed in it.
+fn take_includes(value: &mut Value) -> Result<Vec<String>> {
+    let mapping = match value {
+        Value::Mapping(mapping) => mapping,
+        _ => return Ok(Vec::new()),
+    };
+    match mapping.remove(&Value::from(INCLUDE_KEY)) {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::String(path)) => Ok(vec![path]),
+        Some(paths) => serde_yaml::from_value(paths)
+            .context("Field include must be a path or an array of paths"),
+    }
+}
+
+/// Merges `value` into `base`.
+///
+/// Mappings are merged recursively, while the other values in `base` are replaced.
+fn merge(base: &mut Value, value: Value) {
+    match (base, value) {
+        (Value::Mapping(base), Value::Mapping(value)) => {
+            for (key, value) in value {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, value) => *base = value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_merge() -> anyhow::Result<()> {
+        let mut base: Value = serde_yaml::from_str("{ a: 1, b: { c: 2, d: [3] } }")?;
+        let value: Value = serde_yaml::from_str("{ b: { d: [4], e: 5 }, f: 6 }")?;
+        merge(&mut base, value);
+        let expected: Value = serde_yaml::from_str("{ a: 1, b: { c: 2, d: [4], e: 5 }, f: 6 }")?;
+        assert_eq!(base, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn load_includes() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let mut cnsl = Console::sink(Default::default());
+        fs::create_dir(test_dir.path().join("shared"))?;
+        fs::write(
+            test_dir.path().join("shared/base.yaml"),
+            "include: common.yaml\nsession: { timeout: 10s }\n",
+        )?;
+        fs::write(
+            test_dir.path().join("shared/common.yaml"),
+            "session: { timeout: 5s, retry_limit: 1 }\n",
+        )?;
+        fs::write(
+            test_dir.path().join("main.yaml"),
+            "include: [shared/base.yaml]\nsession: { retry_limit: 2 }\n",
+        )?;
+
+        let value = load_with_includes(&base_dir.join("main.yaml"), &base_dir, &mut cnsl)?;
+        let expected: Value =
+            serde_yaml::from_str("{ session: { timeout: 10s, retry_limit: 2 } }")?;
+        assert_eq!(value, expected);
+
+        // cyclic include
+        fs::write(
+            test_dir.path().join("shared/common.yaml"),
+            "include: ../main.yaml\n",
+        )?;
+        let err =
+            load_with_includes(&base_dir.join("main.yaml"), &base_dir, &mut cnsl).unwrap_err();
+        assert!(format!("{:#}", err).contains("Found cyclic include"));
+        Ok(())
+    }
+}
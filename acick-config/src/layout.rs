@@ -0,0 +1,99 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumString, EnumVariantNames, IntoStaticStr};
+
+/// Arrangement of files of problems for which default paths are generated by `acick init`.
+#[derive(
+    Serialize,
+    Deserialize,
+    EnumString,
+    EnumVariantNames,
+    IntoStaticStr,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Layout {
+    /// Each problem has its own directory in the directory of its contest
+    /// (e.g. `atcoder/abc100/c/Main.cpp`).
+    Nested,
+    /// Files of all problems are put side by side in the directory of their contest
+    /// (e.g. `atcoder/abc100/c.cpp`).
+    Flat,
+}
+
+impl Layout {
+    pub(crate) fn problem_path(self) -> &'static str {
+        match self {
+            Self::Nested => "{{ service }}/{{ contest }}/{{ problem | lower }}/problem.yaml",
+            Self::Flat => "{{ service }}/{{ contest }}/{{ problem | lower }}.yaml",
+        }
+    }
+
+    pub(crate) fn testcases_dir(self) -> &'static str {
+        match self {
+            Self::Nested => "{{ service }}/{{ contest }}/{{ problem | lower }}/testcases",
+            Self::Flat => "{{ service }}/{{ contest }}/testcases/{{ problem | lower }}",
+        }
+    }
+
+    pub(crate) fn working_dir(self) -> &'static str {
+        match self {
+            Self::Nested => "{{ service }}/{{ contest }}/{{ problem | lower }}",
+            Self::Flat => "{{ service }}/{{ contest }}",
+        }
+    }
+
+    /// Returns name of source file in working_dir (e.g. `Main.cpp` or `c.cpp`).
+    pub(crate) fn source_file(self, name: &str, ext: &str) -> String {
+        match self {
+            Self::Nested => format!("{}.{}", name, ext),
+            Self::Flat => format!("{{{{ problem | lower }}}}.{}", ext),
+        }
+    }
+
+    /// Returns path of file generated from source file (e.g. bundled with its dependencies),
+    /// relative to the directory of contest.
+    pub(crate) fn bundled_file(self, ext: &str) -> String {
+        match self {
+            Self::Nested => format!("{{{{ problem | lower }}}}/bundled.{}", ext),
+            Self::Flat => format!("bundled/{{{{ problem | lower }}}}.{}", ext),
+        }
+    }
+
+    pub(crate) fn notes_path(self) -> &'static str {
+        match self {
+            Self::Nested => "{{ service }}/{{ contest }}/{{ problem | lower }}/NOTES.md",
+            Self::Flat => "{{ service }}/{{ contest }}/{{ problem | lower }}.md",
+        }
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::Nested
+    }
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Layout::default().to_string(), "nested");
+        assert_eq!(Layout::Flat.to_string(), "flat");
+    }
+}
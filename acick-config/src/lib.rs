@@ -14,6 +14,12 @@
 //! - `snake` : converts string to `snake_case`
 //! - `kebab` : converts string to `kebab-case`
 //!
+//! Custom filters and functions can be defined as shell commands
+//! in `filters` and `functions` fields (e.g. `filters: { upperhex: "xxd -p" }`).
+//! Filters read the value from stdin and functions read nothing.
+//! Both return stdout without trailing newlines,
+//! and receive arguments as environment variables (e.g. `width=4` as `ACICK_ARG_WIDTH`).
+//!
 //! Available variables depend on fields.
 //! See [Field features](#field-features) section for details.
 //!
@@ -27,6 +33,13 @@
 //! with the following variables available:
 //! - `command` (str): command to be executed on shell
 //!
+//! `shell` field also accepts the name of a preset shell (`bash`, `powershell` or `cmd`),
+//! which runs the command with quoting appropriate for the shell.
+//! `powershell` and `cmd` work on Windows without MSYS2 or Git Bash.
+//! Unlike `bash -e`, PowerShell older than 7.3 goes on after a native command (e.g. compiler)
+//! exits with non-zero status, and `cmd` always does,
+//! so chain such commands with `if ($LASTEXITCODE) { exit $LASTEXITCODE }` or `&&` respectively.
+//!
 //! ### `[t]` Target template field
 //!
 //! The field is recognized as a Tera template
@@ -35,6 +48,13 @@
 //! - `contest` (str): id of contest (e.g.: `arc100`)
 //! - `problem` (str): id of problem (e.g.: `C`)
 //!
+//! In `compile` and `run` fields, the following variable is also available:
+//! - `artifact` (str): path of binary expanded from `artifact_path` field (e.g.: `./c.out`)
+//!   , which is made absolute if `workspace_dir` field is specified
+//!
+//! In `hooks` fields, `artifact` is available as well as the following variable:
+//! - `verdict` (str): result of submission or test (e.g.: `AC`), only in `post_test` and `post_submit`
+//!
 //! ### `[p]` Problem template field
 //!
 //! The field is recognized as a Tera template
@@ -43,6 +63,15 @@
 //! - `contest` (object): object that describes contest
 //! - `problem` (object): object that describes problem
 //!
+//! ### `[m]` Commit message template field
+//!
+//! The field is recognized as a Tera template
+//! with the following variables available:
+//! - `service` (str): id of service (e.g.: `atcoder`)
+//! - `contest` (str): id of contest (e.g.: `arc100`)
+//! - `problem` (object): object that describes problem
+//! - `verdict` (str): result of submission or test (e.g.: `AC`)
+//!
 //! ### `[s]` Shell-expanded field
 //!
 //! The field is processed with shell-like expansions.
@@ -51,32 +80,115 @@
 //!
 //! When combined with Tera template,
 //! the field is first processed as a template and then expanded.
+//!
+//! ## Including other config files
+//!
+//! `include` field takes a path or an array of paths of config files (e.g. `~/.config/acick/base.yaml`)
+//! that are merged into the config file at load time.
+//! Relative paths are resolved from the directory of the including file,
+//! and the paths are shell-expanded.
+//! Included files are merged in the order listed, and then the including file overrides them.
+//! Mappings are merged recursively, while arrays and the other values are replaced.
+//! Included files may include other files, but cyclic includes are rejected.
+//!
+//! ## Languages
+//!
+//! `acick init --profile <profile>` generates service configs for a language
+//! (`cpp`, `rust` or `python`).
+//! For other languages, edit `source_path`, `compile` and `run` fields of the service.
+//!
+//! `compile` field is optional. If it is omitted or empty, the compile phase is skipped,
+//! which suits interpreted languages. The following are examples of `run` field
+//! for such languages, executed in `working_dir`.
+//! - Python: `python3 ./Main.py`
+//! - PyPy: `pypy3 ./Main.py`
+//! - Ruby: `ruby ./Main.rb`
+//! - JavaScript: `node ./Main.js`
+//!
+//! Compiled languages build `artifact` in `compile` field and run it in `run` field.
+//! - C++: `g++ -std=gnu++17 -O2 -o {{ artifact }} ./Main.cpp` and `{{ artifact }}`
+//! - Rust: `cargo build --release` and `{{ artifact }}`
+//! - Java: `javac -d . ./Main.java` and `java Main`
+//!
+//! ## Layouts
+//!
+//! `acick init --layout <layout>` generates paths of files of problems in one of the layouts.
+//! - `nested` (default): each problem has its own directory (e.g. `atcoder/abc100/c/Main.cpp`).
+//! - `flat`: all problems of a contest are put side by side in the directory of the contest
+//!   (e.g. `atcoder/abc100/c.cpp`), with problem files `c.yaml` and testcases in `testcases/c/`.
 
+use std::collections::BTreeMap;
 use std::fmt;
+use std::fs;
+use std::hash::{Hash, Hasher};
 use std::io::{Read as _, Write};
+use std::path::PathBuf;
+use std::process::{self, ExitStatus};
+use std::time::Duration;
 
 use anyhow::{anyhow, Context as _};
 use lazy_static::lazy_static;
+use notify_rust::Notification;
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use tokio::process::Command;
 
-use acick_util::{abs_path, console, model, DATA_LOCAL_DIR};
-
+use acick_judge::set_new_process_group;
+use acick_util::{abs_path, console, metrics, model, service, store, DATA_LOCAL_DIR};
+
+mod compile_cache;
+mod credentials;
+mod hooks;
+mod include;
+mod layout;
+mod migrate;
+mod problem_index;
+mod profile;
+mod schema;
 mod session_config;
+mod submit_checks;
 mod template;
+mod test_cache;
+mod testcases_source;
 
 use crate::abs_path::AbsPathBuf;
 use crate::console::Console;
-use crate::model::{Contest, ContestId, LangName, Problem, ProblemId, Service, ServiceKind};
+use crate::metrics::{Metrics, Phase};
+use crate::model::{
+    Byte, Contest, ContestId, LangName, Problem, ProblemId, Service, ServiceKind, Statement,
+    TestSet,
+};
+use crate::service::scrape::set_save_html;
+pub use acick_judge::{DiffConfig, IoFiles, OutputFilter};
+pub use compile_cache::CompileCache;
+pub use credentials::{
+    CommandProvider, ConfigProvider, CredentialProvider, CredentialSource, Credentials,
+    CredentialsConfig, EnvProvider, KeyringProvider, PromptProvider,
+};
+pub use hooks::{HookKind, HooksConfig};
+pub use layout::Layout;
+pub use migrate::MigrationReport;
+pub use problem_index::ProblemIndex;
+pub use profile::Profile;
 pub use session_config::SessionConfig;
-use template::{Expand, ProblemTempl, Shell, TargetContext, TargetTempl};
+pub use submit_checks::SubmitChecks;
+use template::{
+    register_plugins, CommitTempl, Expand, ProblemTempl, Shell, TargetContext, TargetTempl,
+    TemplateFile, TemplateRule,
+};
+pub use test_cache::TestCache;
+pub use testcases_source::TestcasesSource;
 
 pub type Error = anyhow::Error;
 pub type Result<T> = anyhow::Result<T>;
 
+static HISTORY_FILE_NAME: &str = "history.jsonl";
+static VIRTUAL_FILE_NAME: &str = "virtual.json";
+static PENDING_SUBMISSIONS_FILE_NAME: &str = "pending-submissions.jsonl";
+
 lazy_static! {
     static ref VERSION: Version = Version::parse(env!("CARGO_PKG_VERSION")).unwrap();
+    static ref DEFAULT_HISTORY_PATH: AbsPathBuf = DATA_LOCAL_DIR.join(HISTORY_FILE_NAME);
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -84,26 +196,35 @@ pub struct Config {
     pub service_id: ServiceKind,
     pub contest_id: ContestId,
     pub base_dir: AbsPathBuf,
+    /// If true, risky operations during contest are disabled.
+    pub contest_mode: bool,
     body: ConfigBody,
 }
 
 impl Config {
+    const STATEMENT_FILE_NAME: &'static str = "statement.html";
+
     pub fn load(
         service_id: ServiceKind,
         contest_id: ContestId,
         base_dir: Option<AbsPathBuf>,
         cnsl: &mut Console,
     ) -> Result<Self> {
-        let base_dir = match base_dir {
-            Some(base_dir) => base_dir,
-            None => ConfigBody::search(cnsl)?,
-        };
-        let body = ConfigBody::load(&base_dir, cnsl)?;
-        Ok(Self {
-            service_id,
-            contest_id,
-            base_dir,
-            body,
+        Metrics::measure(Phase::Config, || {
+            let base_dir = match base_dir {
+                Some(base_dir) => base_dir,
+                None => ConfigBody::search(cnsl)?,
+            };
+            let body = ConfigBody::load(&base_dir, cnsl)?;
+            set_save_html(body.debug.save_html);
+            register_plugins(&body.shell, &body.filters, &body.functions);
+            Ok(Self {
+                service_id,
+                contest_id,
+                base_dir,
+                contest_mode: false,
+                body,
+            })
         })
     }
 
@@ -111,10 +232,32 @@ impl Config {
         &self.body.session
     }
 
+    pub fn defaults(&self) -> &DefaultsConfig {
+        &self.body.defaults
+    }
+
     pub fn service(&self) -> &ServiceConfig {
         self.body.services.get(self.service_id)
     }
 
+    pub fn test(&self) -> &TestConfig {
+        &self.body.test
+    }
+
+    pub fn normalize_testcases(&self) -> bool {
+        self.body.normalize_testcases
+    }
+
+    pub fn diff(&self) -> &DiffConfig {
+        &self.body.diff
+    }
+
+    pub fn git(&self) -> Option<&GitConfig> {
+        self.body.git.as_ref()
+    }
+
+    /// Replaces full testcases of the problem with the files in `from`,
+    /// keeping testcases of the other test sets stored in subdirectories of testcases_dir.
     pub fn move_testcases_dir(
         &self,
         problem: &Problem,
@@ -122,24 +265,55 @@ impl Config {
         cnsl: &mut Console,
     ) -> Result<bool> {
         let testcases_abs_dir = self.testcases_abs_dir(problem.id())?;
-        if testcases_abs_dir.as_ref().exists() {
-            let message = format!(
-                "remove existing testcases dir {}?",
-                testcases_abs_dir.strip_prefix(&self.base_dir).display()
-            );
-            if !cnsl.confirm(&message, false)? {
-                return Ok(false);
+        if !testcases_abs_dir.as_ref().exists() {
+            if let Some(parent) = testcases_abs_dir.parent() {
+                parent.create_dir_all()?;
             }
-            testcases_abs_dir.remove_dir_all_pretty(Some(&self.base_dir), cnsl)?;
-        } else if let Some(parent) = testcases_abs_dir.parent() {
-            parent.create_dir_all()?;
+            testcases_abs_dir.move_from_pretty(from, Some(&self.base_dir), cnsl)?;
+            return Ok(true);
         }
 
-        testcases_abs_dir.move_from_pretty(from, Some(&self.base_dir), cnsl)?;
-
+        let message = format!(
+            "remove existing testcases in {}?",
+            testcases_abs_dir.strip_prefix(&self.base_dir).display()
+        );
+        if !cnsl.confirm(&message, false)? {
+            return Ok(false);
+        }
+        self.remove_full_testcases(problem.id(), cnsl)?;
+        for entry in read_dir_names(from)? {
+            testcases_abs_dir.join(&entry).move_from_pretty(
+                &from.join(&entry),
+                Some(&self.base_dir),
+                cnsl,
+            )?;
+        }
         Ok(true)
     }
 
+    /// Removes files of full testcases in testcases_dir except the dirs of the other test sets
+    /// (e.g. hand-written stress tests), returning true if any file is removed.
+    fn remove_full_testcases(&self, problem_id: &ProblemId, cnsl: &mut Console) -> Result<bool> {
+        let testcases_abs_dir = self.testcases_abs_dir(problem_id)?;
+        let mut kept_dirs = Vec::new();
+        for &test_set in &[TestSet::Pretests, TestSet::Stress] {
+            kept_dirs.extend(self.test_set_abs_dir(problem_id, test_set)?);
+        }
+        let mut is_removed = false;
+        for entry in read_dir_names(&testcases_abs_dir)? {
+            let path = testcases_abs_dir.join(&entry);
+            if kept_dirs.contains(&path) {
+                continue;
+            }
+            is_removed |= if path.as_ref().is_dir() {
+                path.remove_dir_all_pretty(Some(&self.base_dir), cnsl)?
+            } else {
+                path.remove_file_pretty(Some(&self.base_dir), cnsl)?
+            };
+        }
+        Ok(is_removed)
+    }
+
     pub fn save_problem(
         &self,
         problem: &Problem,
@@ -147,12 +321,19 @@ impl Config {
         cnsl: &mut Console,
     ) -> Result<Option<bool>> {
         let problem_abs_path = self.problem_abs_path(problem.id())?;
-        problem_abs_path.save_pretty(
+        let is_saved = problem_abs_path.save_pretty(
             |file| serde_yaml::to_writer(file, &problem).context("Could not save problem as yaml"),
             overwrite,
             Some(&self.base_dir),
             cnsl,
-        )
+        )?;
+        // index describes the problem files, so it is kept as is if the file was not written
+        if is_saved.is_some() {
+            self.problem_index()
+                .insert(problem)
+                .context("Could not update index of problems")?;
+        }
+        Ok(is_saved)
     }
 
     pub fn load_problem(&self, problem_id: &ProblemId, cnsl: &mut Console) -> Result<Problem> {
@@ -177,6 +358,35 @@ impl Config {
         }
     }
 
+    pub fn save_statement(
+        &self,
+        problem: &Problem,
+        statement: &Statement,
+        overwrite: bool,
+        cnsl: &mut Console,
+    ) -> Result<Option<bool>> {
+        let problem_abs_dir = self
+            .problem_abs_path(problem.id())?
+            .parent()
+            .context("Could not get directory of problem file")?;
+        let assets_abs_dir = problem_abs_dir.join(Statement::ASSETS_DIR_NAME);
+        for asset in statement.assets() {
+            assets_abs_dir.join(asset.file_name()).save_pretty(
+                |mut file| Ok(file.write_all(asset.content())?),
+                overwrite,
+                Some(&self.base_dir),
+                cnsl,
+            )?;
+        }
+        let title = format!("{} - {}", problem.id(), problem.name());
+        problem_abs_dir.join(Self::STATEMENT_FILE_NAME).save_pretty(
+            |mut file| Ok(file.write_all(statement.to_document(&title).as_bytes())?),
+            overwrite,
+            Some(&self.base_dir),
+            cnsl,
+        )
+    }
+
     pub fn expand_and_save_source(
         &self,
         service: &Service,
@@ -189,21 +399,73 @@ impl Config {
             return Err(anyhow!("Found mismatching service id or contest id"));
         }
         let source_abs_path = self.source_abs_path(problem.id())?;
-        let template = match &self.service().template {
+        let mut rule_template = None;
+        for rule in &self.service().template_rules {
+            rule_template = rule.select(service, contest, problem)?;
+            if rule_template.is_some() {
+                break;
+            }
+        }
+        let template = match rule_template.or_else(|| self.service().template.as_ref()) {
             Some(template) => template,
             None => return Ok(None), // skip if template is empty
         };
         let template_expanded = template.expand_with(service, contest, problem)?;
-        source_abs_path.save_pretty(
+        let is_saved = source_abs_path.save_pretty(
             |mut file| Ok(file.write_all(template_expanded.as_bytes())?),
             overwrite,
             Some(&self.base_dir),
             cnsl,
+        )?;
+
+        // scaffold the other files of the project,
+        // which are never overwritten because they may have been edited (e.g. dependencies)
+        for template_file in &self.service().template_files {
+            let abs_path = self.expand_to_abs(&template_file.path, problem.id())?;
+            let expanded = template_file
+                .template
+                .expand_with(service, contest, problem)?;
+            abs_path.save_pretty(
+                |mut file| Ok(file.write_all(expanded.as_bytes())?),
+                false,
+                Some(&self.base_dir),
+                cnsl,
+            )?;
+        }
+        Ok(is_saved)
+    }
+
+    pub fn expand_and_save_notes(
+        &self,
+        service: &Service,
+        contest: &Contest,
+        problem: &Problem,
+        overwrite: bool,
+        cnsl: &mut Console,
+    ) -> Result<Option<bool>> {
+        if service.id() != self.service_id || contest.id() != &self.contest_id {
+            return Err(anyhow!("Found mismatching service id or contest id"));
+        }
+        let notes_template = match &self.service().notes_template {
+            Some(notes_template) => notes_template,
+            None => return Ok(None), // skip if notes template is not specified
+        };
+        let notes_abs_path = self.notes_abs_path(problem.id())?;
+        let notes_expanded = notes_template.expand_with(service, contest, problem)?;
+        notes_abs_path.save_pretty(
+            |mut file| Ok(file.write_all(notes_expanded.as_bytes())?),
+            overwrite,
+            Some(&self.base_dir),
+            cnsl,
         )
     }
 
+    /// Loads source code to be submitted, which is the file at `submit_path` if specified.
     pub fn load_source(&self, problem_id: &ProblemId, cnsl: &mut Console) -> Result<String> {
-        let source_abs_path = self.source_abs_path(problem_id)?;
+        let source_abs_path = self.submit_abs_path(problem_id)?;
+        if source_abs_path != self.source_abs_path(problem_id)? {
+            self.warn_if_stale(problem_id, &source_abs_path, cnsl)?;
+        }
         source_abs_path.load_pretty(
             |mut file| {
                 let mut buf = String::new();
@@ -215,17 +477,258 @@ impl Config {
         )
     }
 
-    pub fn exec_compile(&self, problem_id: &ProblemId) -> Result<Command> {
-        let compile = &self.service().compile;
-        self.exec_templ(compile, problem_id)
+    /// Returns path of the file where history of test and submit commands is recorded.
+    pub fn history_abs_path(&self) -> &AbsPathBuf {
+        self.body
+            .history_path
+            .as_ref()
+            .unwrap_or(&DEFAULT_HISTORY_PATH)
+    }
+
+    /// Path of the file that stores virtual participations, placed next to history file.
+    pub fn virtual_abs_path(&self) -> AbsPathBuf {
+        match self.history_abs_path().parent() {
+            Some(dir) => dir.join(VIRTUAL_FILE_NAME),
+            None => DATA_LOCAL_DIR.join(VIRTUAL_FILE_NAME),
+        }
+    }
+
+    /// Path of the file that queues submissions failed because of network errors,
+    /// placed next to history file.
+    pub fn pending_submissions_abs_path(&self) -> AbsPathBuf {
+        match self.history_abs_path().parent() {
+            Some(dir) => dir.join(PENDING_SUBMISSIONS_FILE_NAME),
+            None => DATA_LOCAL_DIR.join(PENDING_SUBMISSIONS_FILE_NAME),
+        }
+    }
+
+    /// Checks source code before submission.
+    pub fn check_source(&self, source: &str) -> Result<()> {
+        self.service().submit_checks.check(source)
+    }
+
+    /// Returns period in which submitting the same source code again requires `--force`.
+    pub fn duplicate_window(&self) -> Duration {
+        self.service().submit_checks.duplicate_window()
+    }
+
+    /// Executes command on shell in base_dir.
+    pub fn exec_shell(&self, command: &str) -> Result<Command> {
+        let mut command = self.body.shell.exec(command)?;
+        command.current_dir(self.base_dir.as_ref());
+        Ok(command)
+    }
+
+    /// Returns compile command, or None if compile is not needed (e.g. for interpreted languages).
+    pub fn exec_compile(&self, problem_id: &ProblemId) -> Result<Option<Command>> {
+        let compile = match &self.service().compile {
+            Some(compile) if !compile.is_empty() => compile,
+            _ => return Ok(None),
+        };
+        let compile_abs_dir = self.compile_abs_dir(problem_id)?;
+        self.exec_templ(compile, problem_id, &compile_abs_dir)
+            .map(Some)
+    }
+
+    /// Returns compile command of debug build (e.g. with sanitizers), used by `acick test --debug`.
+    pub fn exec_compile_debug(&self, problem_id: &ProblemId) -> Result<Command> {
+        let compile_debug = self
+            .service()
+            .compile_debug
+            .as_ref()
+            .filter(|compile_debug| !compile_debug.is_empty())
+            .context(
+                "Could not find compile_debug in config file. Specify it to build for debug.",
+            )?;
+        let compile_abs_dir = self.compile_abs_dir(problem_id)?;
+        self.exec_templ(compile_debug, problem_id, &compile_abs_dir)
+    }
+
+    /// Returns files through which binary reads input and writes output instead of standard streams.
+    pub fn io_files(&self, problem_id: &ProblemId) -> Result<IoFiles> {
+        let working_abs_dir = self.working_abs_dir(problem_id)?;
+        let expand = |templ: &Option<TargetTempl>| -> Result<Option<PathBuf>> {
+            match templ {
+                Some(templ) if !templ.is_empty() => {
+                    let path = templ.expand_with(self.service_id, &self.contest_id, problem_id)?;
+                    Ok(Some(working_abs_dir.join_expand(path)?.as_ref().to_owned()))
+                }
+                _ => Ok(None),
+            }
+        };
+        let service = self.service();
+        Ok(IoFiles::new(
+            expand(&service.stdin_file).context("Could not expand stdin_file")?,
+            expand(&service.stdout_file).context("Could not expand stdout_file")?,
+        ))
     }
 
+    /// Returns run command, which starts in a new process group
+    /// so that processes spawned by it are killed together when it exceeds time limit.
     pub fn exec_run(&self, problem_id: &ProblemId) -> Result<Command> {
         let run = &self.service().run;
-        self.exec_templ(run, problem_id)
+        let mut command =
+            self.exec_templ_std(run, problem_id, &self.working_abs_dir(problem_id)?)?;
+        set_new_process_group(&mut command);
+        Ok(Shell::into_async(command))
+    }
+
+    /// Runs hook scripts in base_dir.
+    ///
+    /// Returns error if any pre hook fails. Failures of post hooks are only warned.
+    pub async fn exec_hooks(
+        &self,
+        kind: HookKind,
+        problem_id: &ProblemId,
+        verdict: Option<&str>,
+        cnsl: &mut Console,
+    ) -> Result<()> {
+        for hook in self.body.hooks.get(kind) {
+            let context = TargetContext::new(self.service_id, &self.contest_id, problem_id)
+                .with_artifact(self.artifact_for_exec(problem_id)?)
+                .with_verdict(verdict);
+            let mut command = self.body.shell.exec_templ_std(hook, &context)?;
+            command.current_dir(self.base_dir.as_ref());
+            self.set_env(&mut command, &context)?;
+            let command = Shell::into_async(command);
+            writeln!(cnsl, "Running {} hook for {} ...", kind, problem_id)?;
+            let result = wait_hook(command).await.and_then(|status| {
+                if status.success() {
+                    Ok(())
+                } else {
+                    Err(anyhow!(
+                        "{} hook returned non-zero status : {}",
+                        kind,
+                        status
+                    ))
+                }
+            });
+            match result {
+                Err(err) if kind.is_pre() => return Err(err),
+                Err(err) => cnsl.warn(&format!("{:#}", err))?,
+                Ok(()) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes full testcases of the problem after confirmation if `testcases.cleanup_on_ac` is enabled.
+    ///
+    /// Call this only for accepted submissions of the logged-in user.
+    /// Returns true if testcases are removed.
+    pub fn cleanup_testcases_on_ac(
+        &self,
+        problem_id: &ProblemId,
+        cnsl: &mut Console,
+    ) -> Result<bool> {
+        if !self.body.testcases.cleanup_on_ac {
+            return Ok(false);
+        }
+        let testcases_abs_dir = self.testcases_abs_dir(problem_id)?;
+        if !testcases_abs_dir.as_ref().exists() {
+            return Ok(false);
+        }
+        let message = format!("remove full testcases of accepted problem {}?", problem_id);
+        if !cnsl.confirm(&message, false)? {
+            return Ok(false);
+        }
+        let is_removed = self.remove_full_testcases(problem_id, cnsl)?;
+        // remove testcases_dir itself unless testcases of the other test sets remain
+        if read_dir_names(&testcases_abs_dir)?.is_empty() {
+            fs::remove_dir(testcases_abs_dir.as_ref()).with_context(|| {
+                format!("Could not remove testcases dir : {}", testcases_abs_dir)
+            })?;
+        }
+        Ok(is_removed)
+    }
+
+    /// Shows desktop notification if `console.notify` is enabled.
+    ///
+    /// Failure to show notification is only warned.
+    pub fn notify(&self, summary: &str, body: &str, cnsl: &mut Console) -> Result<()> {
+        if !self.body.console.notify {
+            return Ok(());
+        }
+        let result = Notification::new()
+            .appname("acick")
+            .summary(summary)
+            .body(body)
+            .show();
+        if let Err(err) = result {
+            cnsl.warn(&format!("Could not show desktop notification : {}", err))?;
+        }
+        Ok(())
+    }
+
+    /// Searches base_dir for problem files of all contests of the service.
+    pub fn search_problems(&self) -> Result<Vec<(ContestId, ProblemId)>> {
+        self.body
+            .problem_path
+            .to_pattern(self.service_id, &self.base_dir)?
+            .search()
+    }
+
+    /// Returns config that targets the other service.
+    pub fn with_service(&self, service_id: ServiceKind) -> Self {
+        Self {
+            service_id,
+            ..self.clone()
+        }
+    }
+
+    /// Returns config that targets the other contest.
+    pub fn with_contest(&self, contest_id: ContestId) -> Self {
+        Self {
+            contest_id,
+            ..self.clone()
+        }
+    }
+
+    /// Returns config whose paths are relative to the other directory.
+    pub fn with_base_dir(&self, base_dir: AbsPathBuf) -> Self {
+        Self {
+            base_dir,
+            ..self.clone()
+        }
+    }
+
+    /// Returns config whose session forbids network access.
+    pub fn with_offline(&self, offline: bool) -> Self {
+        let mut conf = self.clone();
+        conf.body.session = self.body.session.with_offline(offline);
+        conf
+    }
+
+    /// Returns paths of the binary and the other files generated by compile command.
+    pub fn artifact_abs_paths(&self, problem_id: &ProblemId) -> Result<Vec<AbsPathBuf>> {
+        let artifact = self.expand_artifact_path(problem_id)?;
+        let mut paths = vec![self.compile_abs_dir(problem_id)?.join_expand(artifact)?];
+        for artifact in &self.service().artifacts {
+            paths.push(self.expand_to_abs(artifact, problem_id)?);
+        }
+        Ok(paths)
+    }
+
+    fn expand_artifact_path(&self, problem_id: &ProblemId) -> Result<String> {
+        self.service()
+            .artifact_path
+            .expand_with(self.service_id, &self.contest_id, problem_id)
+    }
+
+    /// Returns path of the binary passed to scripts as `artifact` variable.
+    ///
+    /// The path is made absolute if workspace_dir is specified,
+    /// because scripts are executed in different directories.
+    fn artifact_for_exec(&self, problem_id: &ProblemId) -> Result<String> {
+        let artifact = self.expand_artifact_path(problem_id)?;
+        if self.service().workspace_dir.is_none() {
+            return Ok(artifact);
+        }
+        let artifact_abs_path = self.compile_abs_dir(problem_id)?.join_expand(artifact)?;
+        Ok(artifact_abs_path.to_string())
     }
 
-    fn problem_abs_path(&self, problem_id: &ProblemId) -> Result<AbsPathBuf> {
+    pub fn problem_abs_path(&self, problem_id: &ProblemId) -> Result<AbsPathBuf> {
         let problem_path = &self.body.problem_path;
         self.expand_to_abs(problem_path, problem_id)
     }
@@ -235,16 +738,82 @@ impl Config {
         self.expand_to_abs(testcases_dir, problem_id)
     }
 
-    fn working_abs_dir(&self, problem_id: &ProblemId) -> Result<AbsPathBuf> {
+    /// Returns dir of testcase files of the test set, or None for samples stored in problem file.
+    ///
+    /// Full testcases of system tests are stored directly in `testcases_dir`
+    /// and the other sets are stored in its subdirectories (e.g. `testcases/pretests`).
+    pub fn test_set_abs_dir(
+        &self,
+        problem_id: &ProblemId,
+        test_set: TestSet,
+    ) -> Result<Option<AbsPathBuf>> {
+        let testcases_abs_dir = self.testcases_abs_dir(problem_id)?;
+        let dir = match test_set {
+            TestSet::Samples => None,
+            TestSet::System => Some(testcases_abs_dir),
+            TestSet::Pretests | TestSet::Stress => {
+                let dir_name: &str = test_set.into();
+                Some(testcases_abs_dir.join(dir_name))
+            }
+        };
+        Ok(dir)
+    }
+
+    pub fn working_abs_dir(&self, problem_id: &ProblemId) -> Result<AbsPathBuf> {
         let working_dir = &self.service().working_dir;
         self.expand_to_abs(working_dir, problem_id)
     }
 
-    fn source_abs_path(&self, problem_id: &ProblemId) -> Result<AbsPathBuf> {
+    /// Returns dir where compile command is executed, which is workspace_dir if specified.
+    fn compile_abs_dir(&self, problem_id: &ProblemId) -> Result<AbsPathBuf> {
+        match &self.service().workspace_dir {
+            Some(workspace_dir) => self.expand_to_abs(workspace_dir, problem_id),
+            None => self.working_abs_dir(problem_id),
+        }
+    }
+
+    pub fn source_abs_path(&self, problem_id: &ProblemId) -> Result<AbsPathBuf> {
         let source_path = &self.service().source_path;
         self.expand_to_abs(source_path, problem_id)
     }
 
+    /// Returns path of the file to be submitted, which may be bundled from source code.
+    pub fn submit_abs_path(&self, problem_id: &ProblemId) -> Result<AbsPathBuf> {
+        match &self.service().submit_path {
+            Some(submit_path) => self.expand_to_abs(submit_path, problem_id),
+            None => self.source_abs_path(problem_id),
+        }
+    }
+
+    /// Warns if the file to be submitted is older than source code,
+    /// which means the compile command has not bundled the latest source code.
+    fn warn_if_stale(
+        &self,
+        problem_id: &ProblemId,
+        submit_abs_path: &AbsPathBuf,
+        cnsl: &mut Console,
+    ) -> Result<()> {
+        let modified = |path: &AbsPathBuf| path.as_ref().metadata().and_then(|m| m.modified());
+        let source_abs_path = self.source_abs_path(problem_id)?;
+        if let (Ok(submit_modified), Ok(source_modified)) =
+            (modified(submit_abs_path), modified(&source_abs_path))
+        {
+            if submit_modified < source_modified {
+                cnsl.warn(&format!(
+                    "{} is older than {}. Run `acick test` to build it again.",
+                    submit_abs_path.strip_prefix(&self.base_dir).display(),
+                    source_abs_path.strip_prefix(&self.base_dir).display(),
+                ))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn notes_abs_path(&self, problem_id: &ProblemId) -> Result<AbsPathBuf> {
+        let notes_path = &self.service().notes_path;
+        self.expand_to_abs(notes_path, problem_id)
+    }
+
     fn expand_to_abs(&self, path: &TargetTempl, problem_id: &ProblemId) -> Result<AbsPathBuf> {
         path.expand_with(self.service_id, &self.contest_id, problem_id)
             .and_then(|path_expanded| self.base_dir.join_expand(path_expanded))
@@ -254,17 +823,43 @@ impl Config {
         &'a self,
         templ: &T,
         problem_id: &'a ProblemId,
+        current_dir: &AbsPathBuf,
     ) -> Result<Command>
     where
         T: Expand<'a, Context = TargetContext<'a>>,
     {
-        let target_context = TargetContext::new(self.service_id, &self.contest_id, problem_id);
-        let working_abs_dir = self.working_abs_dir(problem_id)?;
-        let mut command = self.body.shell.exec_templ(templ, &target_context)?;
-        command.current_dir(working_abs_dir.as_ref());
+        let command = self.exec_templ_std(templ, problem_id, current_dir)?;
+        Ok(Shell::into_async(command))
+    }
+
+    fn exec_templ_std<'a, T: Expand<'a>>(
+        &'a self,
+        templ: &T,
+        problem_id: &'a ProblemId,
+        current_dir: &AbsPathBuf,
+    ) -> Result<process::Command>
+    where
+        T: Expand<'a, Context = TargetContext<'a>>,
+    {
+        let artifact = self.artifact_for_exec(problem_id)?;
+        let target_context = TargetContext::new(self.service_id, &self.contest_id, problem_id)
+            .with_artifact(artifact);
+        let mut command = self.body.shell.exec_templ_std(templ, &target_context)?;
+        command.current_dir(current_dir.as_ref());
+        self.set_env(&mut command, &target_context)?;
         Ok(command)
     }
 
+    fn set_env(&self, command: &mut process::Command, context: &TargetContext) -> Result<()> {
+        for (key, value) in &self.service().env {
+            let value = value
+                .expand(context)
+                .with_context(|| format!("Could not expand environment variable : {}", key))?;
+            command.env(key, value);
+        }
+        Ok(())
+    }
+
     pub fn default_in_dir(base_dir: AbsPathBuf) -> Self {
         let body = ConfigBody::default_in_dir(&base_dir);
 
@@ -272,6 +867,7 @@ impl Config {
             service_id: ServiceKind::default(),
             contest_id: Contest::default().id().clone(),
             base_dir,
+            contest_mode: false,
             body,
         }
     }
@@ -288,33 +884,73 @@ impl fmt::Display for Config {
 pub struct ConfigBody {
     #[serde(with = "string_serde")]
     version: Version,
+    #[serde(default, skip_serializing_if = "DefaultsConfig::is_empty")]
+    defaults: DefaultsConfig,
     #[serde(default)]
     shell: Shell,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    filters: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    functions: BTreeMap<String, String>,
     #[serde(default = "ConfigBody::default_problem_path")]
     problem_path: TargetTempl,
     #[serde(default = "ConfigBody::default_testcases_dir")]
     testcases_dir: TargetTempl,
     #[serde(default)]
+    normalize_testcases: bool,
+    #[serde(default)]
+    testcases: TestcasesConfig,
+    #[serde(default)]
+    test: TestConfig,
+    #[serde(default)]
+    diff: DiffConfig,
+    #[serde(default)]
     session: SessionConfig,
     #[serde(default)]
     services: ServicesConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    git: Option<GitConfig>,
+    #[serde(default)]
+    hooks: HooksConfig,
+    #[serde(default)]
+    console: ConsoleSettings,
+    #[serde(default)]
+    debug: DebugConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    history_path: Option<AbsPathBuf>,
 }
 
 impl ConfigBody {
     pub const FILE_NAME: &'static str = ".acick.yaml";
 
-    const DEFAULT_PROBLEM_PATH: &'static str =
-        "{{ service }}/{{ contest }}/{{ problem | lower }}/problem.yaml";
-
-    const DEFAULT_TESTCASES_DIR: &'static str =
-        "{{ service }}/{{ contest }}/{{ problem | lower }}/testcases";
-
     pub fn generate_to(writer: &mut dyn Write) -> Result<()> {
+        Self::generate_with_defaults_to(
+            writer,
+            &DefaultsConfig::default(),
+            Profile::default(),
+            Layout::default(),
+        )
+    }
+
+    /// Writes config with the given default service and contest,
+    /// and with service configs for the language of the profile,
+    /// whose paths arrange files of problems in the layout.
+    pub fn generate_with_defaults_to(
+        writer: &mut dyn Write,
+        defaults: &DefaultsConfig,
+        profile: Profile,
+        layout: Layout,
+    ) -> Result<()> {
         writeln!(
             writer,
             include_str!("../resources/.acick.yaml.txt"),
             version = &*VERSION,
-            bash = Shell::find_bash().display()
+            defaults = defaults,
+            shell = Shell::default(),
+            problem_path = layout.problem_path(),
+            testcases_dir = layout.testcases_dir(),
+            service = profile.service_yaml(layout).trim_end(),
+            notes_path = layout.notes_path()
         )
         .context("Could not write config")
     }
@@ -322,20 +958,32 @@ impl ConfigBody {
     fn default_in_dir(base_dir: &AbsPathBuf) -> Self {
         Self {
             version: VERSION.clone(),
+            defaults: DefaultsConfig::default(),
             shell: Shell::default(),
+            filters: BTreeMap::new(),
+            functions: BTreeMap::new(),
             problem_path: Self::default_problem_path(),
             testcases_dir: Self::default_testcases_dir(),
+            normalize_testcases: false,
+            diff: DiffConfig::default(),
             session: SessionConfig::default_in_dir(base_dir),
             services: ServicesConfig::default(),
+            git: None,
+            hooks: HooksConfig::default(),
+            testcases: TestcasesConfig::default(),
+            test: TestConfig::default(),
+            console: ConsoleSettings::default(),
+            debug: DebugConfig::default(),
+            history_path: Some(base_dir.join(HISTORY_FILE_NAME)),
         }
     }
 
     fn default_problem_path() -> TargetTempl {
-        Self::DEFAULT_PROBLEM_PATH.into()
+        Layout::default().problem_path().into()
     }
 
     fn default_testcases_dir() -> TargetTempl {
-        Self::DEFAULT_TESTCASES_DIR.into()
+        Layout::default().testcases_dir().into()
     }
 
     fn search(cnsl: &mut Console) -> Result<AbsPathBuf> {
@@ -353,11 +1001,8 @@ impl ConfigBody {
     }
 
     fn load(base_dir: &AbsPathBuf, cnsl: &mut Console) -> Result<Self> {
-        let body: Self = base_dir.join(Self::FILE_NAME).load_pretty(
-            |file| serde_yaml::from_reader(file).context("Could not read config file as yaml"),
-            Some(base_dir),
-            cnsl,
-        )?;
+        let value = include::load_with_includes(&base_dir.join(Self::FILE_NAME), base_dir, cnsl)?;
+        let body: Self = serde_yaml::from_value(value).context("Could not read config file")?;
         body.validate()?;
         Ok(body)
     }
@@ -371,12 +1016,15 @@ impl ConfigBody {
                 r#"Found mismatched version in config file.
     config version: {}
     acick version : {}
-Fix the config file so that it is compatible with the current version of acick."#,
+Run `acick migrate` or fix the config file so that it is compatible with the current version of acick."#,
                 self.version,
                 &*VERSION
             ));
         }
 
+        // check time limit scale
+        self.test.validate()?;
+
         Ok(())
     }
 }
@@ -385,34 +1033,100 @@ impl Default for ConfigBody {
     fn default() -> Self {
         Self {
             version: VERSION.clone(),
+            defaults: DefaultsConfig::default(),
             shell: Shell::default(),
+            filters: BTreeMap::new(),
+            functions: BTreeMap::new(),
             problem_path: Self::default_problem_path(),
             testcases_dir: Self::default_testcases_dir(),
+            normalize_testcases: false,
+            diff: DiffConfig::default(),
             session: SessionConfig::default(),
             services: ServicesConfig::default(),
+            git: None,
+            hooks: HooksConfig::default(),
+            testcases: TestcasesConfig::default(),
+            test: TestConfig::default(),
+            console: ConsoleSettings::default(),
+            debug: DebugConfig::default(),
+            history_path: None,
         }
     }
 }
 
+/// Service and contest used when they are not specified by command line options.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct DefaultsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<ServiceKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contest: Option<ContestId>,
+}
+
+impl DefaultsConfig {
+    pub fn new(service: ServiceKind, contest: ContestId) -> Self {
+        Self {
+            service: Some(service),
+            contest: Some(contest),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.service.is_none() && self.contest.is_none()
+    }
+}
+
+impl fmt::Display for DefaultsConfig {
+    /// Formats as yaml in config file, which is commented out if empty.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return write!(
+                f,
+                "# defaults:\n#   service: {}\n#   contest: {}",
+                ServiceKind::default(),
+                ContestId::default()
+            );
+        }
+        f.write_str("defaults:")?;
+        if let Some(service) = self.service {
+            write!(f, "\n  service: {}", service)?;
+        }
+        if let Some(contest) = &self.contest {
+            write!(f, "\n  contest: {}", contest)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(default)]
 pub struct ServicesConfig {
     atcoder: ServiceConfig,
+    /// Config for the mock service, which falls back to config for AtCoder
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mock: Option<ServiceConfig>,
 }
 
 impl ServicesConfig {
     fn get(&self, service_id: ServiceKind) -> &ServiceConfig {
         match service_id {
             ServiceKind::Atcoder => &self.atcoder,
+            ServiceKind::Mock => self.mock.as_ref().unwrap_or(&self.atcoder),
+        }
+    }
+
+    fn default_for(profile: Profile) -> Self {
+        Self {
+            atcoder: ServiceConfig::default_for(ServiceKind::Atcoder, profile),
+            mock: None,
         }
     }
 }
 
 impl Default for ServicesConfig {
     fn default() -> Self {
-        Self {
-            atcoder: ServiceConfig::default_for(ServiceKind::Atcoder),
-        }
+        Self::default_for(Profile::default())
     }
 }
 
@@ -420,11 +1134,43 @@ impl Default for ServicesConfig {
 pub struct ServiceConfig {
     lang_names: Vec<LangName>,
     working_dir: TargetTempl,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    workspace_dir: Option<TargetTempl>,
     source_path: TargetTempl,
-    compile: TargetTempl,
+    #[serde(default = "ServiceConfig::default_artifact_path")]
+    artifact_path: TargetTempl,
+    #[serde(default)]
+    compile: Option<TargetTempl>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    compile_debug: Option<TargetTempl>,
     run: TargetTempl,
     #[serde(default)]
+    env: BTreeMap<String, TargetTempl>,
+    #[serde(default)]
+    artifacts: Vec<TargetTempl>,
+    #[serde(default)]
     template: Option<ProblemTempl>,
+    #[serde(default)]
+    template_rules: Vec<TemplateRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    template_files: Vec<TemplateFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    submit_path: Option<TargetTempl>,
+    #[serde(default = "ServiceConfig::default_notes_path")]
+    notes_path: TargetTempl,
+    #[serde(default)]
+    notes_template: Option<ProblemTempl>,
+    #[serde(default)]
+    submit_checks: SubmitChecks,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stdin_file: Option<TargetTempl>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stdout_file: Option<TargetTempl>,
+    /// If true, annotates fetched problems with difficulties estimated by AtCoder Problems.
+    #[serde(default)]
+    show_difficulty: bool,
+    #[serde(default)]
+    testcases_source: TestcasesSource,
 }
 
 impl ServiceConfig {
@@ -440,24 +1186,315 @@ int main() {
 }
 "#;
 
-    fn default_for(service_id: ServiceKind) -> Self {
-        match service_id {
-            ServiceKind::Atcoder => Self {
+    const DEFAULT_PYTHON_TEMPLATE: &'static str = r#"# [{{ contest.id }}] {{ problem.id }} - {{ problem.name }}
+
+import sys
+
+input = sys.stdin.readline
+
+
+def main():
+    pass
+
+
+if __name__ == "__main__":
+    main()
+"#;
+
+    const DEFAULT_RUST_TEMPLATE: &'static str = r#"// [{{ contest.id }}] {{ problem.id }} - {{ problem.name }}
+
+use std::io::{self, Read as _};
+
+fn main() {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input).unwrap();
+}
+"#;
+
+    const DEFAULT_RUST_PACKAGE_MANIFEST: &'static str = r#"[package]
+name = "{{ contest.id }}-{{ problem.id | lower }}"
+version = "0.1.0"
+edition = "2018"
+
+[dependencies]
+"#;
+
+    const DEFAULT_RUST_WORKSPACE_MANIFEST: &'static str = r#"[workspace]
+members = ["*"]
+exclude = ["target"]
+"#;
+
+    const DEFAULT_ARTIFACT_PATH: &'static str = "./{{ problem | lower }}.out";
+
+    fn default_artifact_path() -> TargetTempl {
+        Self::DEFAULT_ARTIFACT_PATH.into()
+    }
+
+    fn default_notes_path() -> TargetTempl {
+        Layout::default().notes_path().into()
+    }
+
+    fn default_for(service_id: ServiceKind, profile: Profile) -> Self {
+        match (service_id, profile) {
+            (ServiceKind::Atcoder, Profile::Cpp) => Self {
                 lang_names: vec!["C++ (GCC 9.2.1)".into(), "C++14 (GCC 5.4.1)".into()],
                 working_dir: "{{ service }}/{{ contest }}/{{ problem | lower }}".into(),
+                workspace_dir: None,
                 source_path: "{{ service }}/{{ contest }}/{{ problem | lower }}/Main.cpp".into(),
-                compile: "set -x && g++ -std=gnu++17 -Wall -Wextra -O2 -o ./a.out ./Main.cpp"
-                    .into(),
-                // compile: "set -x && g++ -std=gnu++17 -Wall -Wextra -O2 -DONLINE_JUDGE -I/opt/boost/gcc/include -L/opt/boost/gcc/lib -o ./a.out ./Main.cpp".into(),
-                run: "./a.out".into(),
+                artifact_path: Self::default_artifact_path(),
+                compile: Some(
+                    "set -x && g++ -std=gnu++17 -Wall -Wextra -O2 -o {{ artifact }} ./Main.cpp"
+                        .into(),
+                ),
+                compile_debug: None,
+                // compile: "set -x && g++ -std=gnu++17 -Wall -Wextra -O2 -DONLINE_JUDGE -I/opt/boost/gcc/include -L/opt/boost/gcc/lib -o {{ artifact }} ./Main.cpp".into(),
+                run: "{{ artifact }}".into(),
+                env: BTreeMap::new(),
+                artifacts: Vec::new(),
                 template: Some(Self::DEFAULT_TEMPLATE.into()),
+                template_rules: Vec::new(),
+                template_files: Vec::new(),
+                submit_path: None,
+                notes_path: Self::default_notes_path(),
+                notes_template: None,
+                submit_checks: SubmitChecks::default(),
+                stdin_file: None,
+                stdout_file: None,
+                show_difficulty: false,
+                testcases_source: TestcasesSource::default(),
+            },
+            (ServiceKind::Atcoder, Profile::Python) => Self {
+                lang_names: vec!["Python (3.8.2)".into(), "PyPy3 (7.3.0)".into()],
+                working_dir: "{{ service }}/{{ contest }}/{{ problem | lower }}".into(),
+                workspace_dir: None,
+                source_path: "{{ service }}/{{ contest }}/{{ problem | lower }}/Main.py".into(),
+                artifact_path: Self::default_artifact_path(),
+                compile: None,
+                compile_debug: None,
+                run: "python3 ./Main.py".into(),
+                env: BTreeMap::new(),
+                artifacts: Vec::new(),
+                template: Some(Self::DEFAULT_PYTHON_TEMPLATE.into()),
+                template_rules: Vec::new(),
+                template_files: Vec::new(),
+                submit_path: None,
+                notes_path: Self::default_notes_path(),
+                notes_template: None,
+                submit_checks: SubmitChecks::default(),
+                stdin_file: None,
+                stdout_file: None,
+                show_difficulty: false,
+                testcases_source: TestcasesSource::default(),
+            },
+            (ServiceKind::Atcoder, Profile::Rust) => Self {
+                lang_names: vec!["Rust (1.42.0)".into()],
+                working_dir: "{{ service }}/{{ contest }}/{{ problem | lower }}".into(),
+                workspace_dir: Some("{{ service }}/{{ contest }}".into()),
+                source_path: "{{ service }}/{{ contest }}/{{ problem | lower }}/src/main.rs"
+                    .into(),
+                artifact_path: "./target/release/{{ contest }}-{{ problem | lower }}".into(),
+                compile: Some(
+                    "set -x && cargo build --release --bin {{ contest }}-{{ problem | lower }} \
+                     && (cargo equip --bin {{ contest }}-{{ problem | lower }} > ./{{ problem | lower }}/bundled.rs \
+                     || cp ./{{ problem | lower }}/src/main.rs ./{{ problem | lower }}/bundled.rs)"
+                        .into(),
+                ),
+                compile_debug: None,
+                run: "{{ artifact }}".into(),
+                env: BTreeMap::new(),
+                artifacts: vec!["{{ service }}/{{ contest }}/{{ problem | lower }}/bundled.rs".into()],
+                template: Some(Self::DEFAULT_RUST_TEMPLATE.into()),
+                template_rules: Vec::new(),
+                template_files: vec![
+                    TemplateFile {
+                        path: "{{ service }}/{{ contest }}/{{ problem | lower }}/Cargo.toml".into(),
+                        template: Self::DEFAULT_RUST_PACKAGE_MANIFEST.into(),
+                    },
+                    TemplateFile {
+                        path: "{{ service }}/{{ contest }}/Cargo.toml".into(),
+                        template: Self::DEFAULT_RUST_WORKSPACE_MANIFEST.into(),
+                    },
+                ],
+                submit_path: Some(
+                    "{{ service }}/{{ contest }}/{{ problem | lower }}/bundled.rs".into(),
+                ),
+                notes_path: Self::default_notes_path(),
+                notes_template: None,
+                submit_checks: SubmitChecks::default(),
+                stdin_file: None,
+                stdout_file: None,
+                show_difficulty: false,
+                testcases_source: TestcasesSource::default(),
             },
+            // mock service accepts the same languages as AtCoder
+            (ServiceKind::Mock, profile) => Self::default_for(ServiceKind::Atcoder, profile),
         }
     }
 
     pub fn lang_names(&self) -> &[LangName] {
         &self.lang_names
     }
+
+    pub fn show_difficulty(&self) -> bool {
+        self.show_difficulty
+    }
+
+    pub fn testcases_source(&self) -> &TestcasesSource {
+        &self.testcases_source
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct TestcasesConfig {
+    /// If true, removes full testcases of a problem after its submission is accepted.
+    cleanup_on_ac: bool,
+}
+
+/// Adjusts time limit of problems for the difference in speed between local machine and judge,
+/// and limits output of binary.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct TestConfig {
+    time_limit_scale: f64,
+    extra_time_ms: u64,
+    /// Output of binary after this size is dropped and replaced with a marker.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_bytes: Option<Byte>,
+    /// If true, removes ANSI escape sequences (e.g. colors) from output of binary.
+    strip_ansi: bool,
+}
+
+impl TestConfig {
+    /// Returns filter applied to output of binary before it is compared and shown.
+    pub fn output_filter(&self) -> OutputFilter {
+        OutputFilter::new(self.max_output_bytes.map(Byte::as_u64), self.strip_ansi)
+    }
+
+    /// Upper limit of `time_limit_scale`, beyond which time limit means nothing.
+    const MAX_TIME_LIMIT_SCALE: f64 = 100.0;
+
+    /// Returns time limit used in `acick test` for time limit of problem.
+    pub fn scale_time_limit(&self, time_limit: Duration) -> Duration {
+        // scale is validated on load, but is clamped here too because Duration panics on overflow
+        let scale = self
+            .time_limit_scale
+            .max(0.0)
+            .min(Self::MAX_TIME_LIMIT_SCALE);
+        let secs = (time_limit.as_secs_f64() * scale).min(f64::from(u32::MAX));
+        let scaled = Duration::from_secs_f64(secs);
+        let extra_time = Duration::from_millis(self.extra_time_ms);
+        scaled.checked_add(extra_time).unwrap_or(scaled)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let scale = self.time_limit_scale;
+        if !(scale.is_finite() && scale > 0.0 && scale <= Self::MAX_TIME_LIMIT_SCALE) {
+            return Err(anyhow!(
+                "Found invalid time_limit_scale in config file : {}. \
+                 It must be a positive number up to {}.",
+                scale,
+                Self::MAX_TIME_LIMIT_SCALE
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for TestConfig {
+    fn default() -> Self {
+        Self {
+            time_limit_scale: 1.0,
+            extra_time_ms: 0,
+            max_output_bytes: None,
+            strip_ansi: true,
+        }
+    }
+}
+
+// f64 is compared by its bits so that config can be used as a key
+impl PartialEq for TestConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.time_limit_scale.to_bits() == other.time_limit_scale.to_bits()
+            && self.extra_time_ms == other.extra_time_ms
+            && self.max_output_bytes == other.max_output_bytes
+            && self.strip_ansi == other.strip_ansi
+    }
+}
+
+impl Eq for TestConfig {}
+
+impl Hash for TestConfig {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.time_limit_scale.to_bits().hash(state);
+        self.extra_time_ms.hash(state);
+        self.max_output_bytes.hash(state);
+        self.strip_ansi.hash(state);
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct ConsoleSettings {
+    /// If true, shows desktop notification when long operations finish.
+    notify: bool,
+}
+
+/// Options for debugging acick.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct DebugConfig {
+    /// If true, saves html of pages from which data could not be extracted.
+    save_html: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GitConfig {
+    #[serde(default = "GitConfig::default_message")]
+    message: CommitTempl,
+}
+
+impl GitConfig {
+    const DEFAULT_MESSAGE: &'static str =
+        "[{{ contest }}] {{ problem.id }} - {{ problem.name }} ({{ verdict }})";
+
+    fn default_message() -> CommitTempl {
+        Self::DEFAULT_MESSAGE.into()
+    }
+
+    pub fn expand_message(
+        &self,
+        service_id: ServiceKind,
+        contest_id: &ContestId,
+        problem: &Problem,
+        verdict: &str,
+    ) -> Result<String> {
+        self.message
+            .expand_with(service_id, contest_id, problem, verdict)
+    }
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        Self {
+            message: Self::default_message(),
+        }
+    }
+}
+
+/// Returns names of entries in the dir.
+fn read_dir_names(dir: &AbsPathBuf) -> Result<Vec<std::ffi::OsString>> {
+    fs::read_dir(dir.as_ref())
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.file_name()))
+                .collect()
+        })
+        .with_context(|| format!("Could not read directory : {}", dir))
+}
+
+async fn wait_hook(mut command: Command) -> Result<ExitStatus> {
+    command.status().await.context("Could not start hook")
 }
 
 mod string_serde {
@@ -493,6 +1530,7 @@ mod tests {
     use tempfile::tempdir;
 
     use super::*;
+    use crate::model::Compare;
     use crate::template::TargetContext;
 
     #[test]
@@ -509,6 +1547,383 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn generate_and_deserialize_profiles() -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        ConfigBody::generate_with_defaults_to(
+            &mut buf,
+            &DefaultsConfig::default(),
+            Profile::Rust,
+            Layout::default(),
+        )?;
+        let body_generated: ConfigBody = serde_yaml::from_slice(&buf)?;
+
+        assert_eq!(
+            body_generated.services,
+            ServicesConfig::default_for(Profile::Rust)
+        );
+
+        let mut buf = Vec::new();
+        ConfigBody::generate_with_defaults_to(
+            &mut buf,
+            &DefaultsConfig::default(),
+            Profile::Python,
+            Layout::default(),
+        )?;
+        let body_generated: ConfigBody = serde_yaml::from_slice(&buf)?;
+        assert_eq!(
+            body_generated.services,
+            ServicesConfig::default_for(Profile::Python)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn generate_and_deserialize_flat_layout() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let problem_id = ProblemId::from("C");
+        let tests = &[
+            (Profile::Cpp, "c.cpp"),
+            (Profile::Rust, "src/bin/c.rs"),
+            (Profile::Python, "c.py"),
+        ];
+        for &(profile, source_path) in tests {
+            let mut buf = Vec::new();
+            ConfigBody::generate_with_defaults_to(
+                &mut buf,
+                &DefaultsConfig::default(),
+                profile,
+                Layout::Flat,
+            )?;
+            let body: ConfigBody = serde_yaml::from_slice(&buf)?;
+            body.validate()?;
+            let conf = Config {
+                body,
+                ..Config::default_in_dir(base_dir.clone())
+            };
+
+            let contest_dir = base_dir.join("atcoder").join("arc100");
+            assert_eq!(conf.working_abs_dir(&problem_id)?, contest_dir);
+            assert_eq!(
+                conf.source_abs_path(&problem_id)?,
+                contest_dir.join(source_path)
+            );
+            assert_eq!(
+                conf.problem_abs_path(&problem_id)?,
+                contest_dir.join("c.yaml")
+            );
+            assert_eq!(
+                conf.testcases_abs_dir(&problem_id)?,
+                contest_dir.join("testcases").join("c")
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn expand_and_save_notes() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let mut conf = Config::default_in_dir(base_dir);
+        let service = Service::default();
+        let contest = Contest::default();
+        let problem = Problem::default();
+        let mut cnsl = Console::sink(Default::default());
+
+        // skip if notes template is not specified
+        let saved = conf.expand_and_save_notes(&service, &contest, &problem, false, &mut cnsl)?;
+        assert_eq!(saved, None);
+
+        conf.body.services.atcoder.notes_template =
+            Some("# {{ problem.id }} - {{ problem.name }}".into());
+        let saved = conf.expand_and_save_notes(&service, &contest, &problem, false, &mut cnsl)?;
+        assert_eq!(saved, Some(false));
+        let notes = std::fs::read_to_string(test_dir.path().join("atcoder/arc100/c/NOTES.md"))?;
+        assert_eq!(notes, "# C - Linear Approximation");
+        Ok(())
+    }
+
+    #[test]
+    fn expand_and_save_source_with_rules() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let mut conf = Config::default_in_dir(base_dir);
+        let service = Service::default();
+        let contest = Contest::default();
+        let mut cnsl = Console::sink(Default::default());
+
+        conf.body.services.atcoder.template_rules = serde_yaml::from_str(
+            r#"
+- when: problem.id in ["E", "F"]
+  template: heavy {{ problem.id }}
+- when: problem.id == "D"
+  template: medium {{ problem.id }}
+"#,
+        )?;
+        conf.body.services.atcoder.template = Some("light {{ problem.id }}".into());
+        for (id, expected) in &[("C", "light C"), ("D", "medium D"), ("F", "heavy F")] {
+            let problem = Problem::new(*id, "", "", None, None, Compare::Default, vec![]);
+            conf.expand_and_save_source(&service, &contest, &problem, false, &mut cnsl)?;
+            let path = format!("atcoder/arc100/{}/Main.cpp", id.to_lowercase());
+            assert_eq!(
+                std::fs::read_to_string(test_dir.path().join(path))?,
+                *expected
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn expand_and_save_source_with_files() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let mut conf = Config::default_in_dir(base_dir);
+        let service = Service::default();
+        let contest = Contest::default();
+        let problem = Problem::new("C", "", "", None, None, Compare::Default, vec![]);
+        let mut cnsl = Console::sink(Default::default());
+
+        conf.body.services.atcoder.source_path =
+            "{{ service }}/{{ contest }}/{{ problem | lower }}/src/main.rs".into();
+        conf.body.services.atcoder.template = Some("fn main() {}".into());
+        conf.body.services.atcoder.template_files = serde_yaml::from_str(
+            r#"
+- path: "{{ service }}/{{ contest }}/{{ problem | lower }}/Cargo.toml"
+  template: name = "{{ contest.id }}-{{ problem.id | lower }}"
+"#,
+        )?;
+        conf.expand_and_save_source(&service, &contest, &problem, false, &mut cnsl)?;
+        let cargo_toml =
+            std::fs::read_to_string(test_dir.path().join("atcoder/arc100/c/Cargo.toml"))?;
+        assert_eq!(cargo_toml, r#"name = "arc100-c""#);
+
+        // files of the project are kept even if source file is overwritten
+        let cargo_toml_path = test_dir.path().join("atcoder/arc100/c/Cargo.toml");
+        std::fs::write(&cargo_toml_path, "edited")?;
+        conf.expand_and_save_source(&service, &contest, &problem, true, &mut cnsl)?;
+        assert_eq!(std::fs::read_to_string(&cargo_toml_path)?, "edited");
+
+        // submits bundled file instead of source code
+        assert_eq!(conf.load_source(problem.id(), &mut cnsl)?, "fn main() {}");
+        conf.body.services.atcoder.submit_path =
+            Some("{{ service }}/{{ contest }}/{{ problem | lower }}/bundled.rs".into());
+        std::fs::write(
+            test_dir.path().join("atcoder/arc100/c/bundled.rs"),
+            "bundled",
+        )?;
+        assert_eq!(conf.load_source(problem.id(), &mut cnsl)?, "bundled");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn exec_hooks() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let mut conf = Config::default_in_dir(base_dir);
+        let problem_id = ProblemId::from("C");
+        let mut cnsl = Console::sink(Default::default());
+
+        conf.body.hooks = serde_yaml::from_str(
+            r#"
+pre_test: ["false"]
+post_test: ["echo {{ problem }} {{ verdict }} > hook.txt", "false"]
+"#,
+        )?;
+        assert!(conf
+            .exec_hooks(HookKind::PreTest, &problem_id, None, &mut cnsl)
+            .await
+            .is_err());
+        conf.exec_hooks(HookKind::PostTest, &problem_id, Some("AC"), &mut cnsl)
+            .await?;
+        let output = std::fs::read_to_string(test_dir.path().join("hook.txt"))?;
+        assert_eq!(output.trim(), "C AC");
+        // no hooks
+        conf.exec_hooks(HookKind::PreSave, &problem_id, None, &mut cnsl)
+            .await?;
+        Ok(())
+    }
+
+    #[test]
+    fn exec_compile_empty() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let mut conf = Config::default_in_dir(base_dir);
+        let problem_id = ProblemId::from("C");
+
+        conf.body.services.atcoder.compile = None;
+        assert!(conf.exec_compile(&problem_id)?.is_none());
+        conf.body.services.atcoder.compile = Some("".into());
+        assert!(conf.exec_compile(&problem_id)?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn exec_with_workspace_dir() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let mut conf = Config::default_in_dir(base_dir.clone());
+        conf.body.services = ServicesConfig::default_for(Profile::Rust);
+        let problem_id = ProblemId::from("C");
+        base_dir.join("atcoder/arc100/c").create_dir_all()?;
+
+        let artifact = base_dir.join("atcoder/arc100/target/release/arc100-c");
+        assert_eq!(conf.artifact_abs_paths(&problem_id)?[0], artifact);
+
+        // compile runs in workspace_dir and run runs in working_dir with absolute artifact path
+        conf.body.services.atcoder.compile = Some("pwd".into());
+        conf.body.services.atcoder.run = "pwd && echo {{ artifact }}".into();
+        let output = conf.exec_compile(&problem_id)?.unwrap().output().await?;
+        assert!(String::from_utf8(output.stdout)?
+            .trim_end()
+            .ends_with("arc100"));
+        let output = conf.exec_run(&problem_id)?.output().await?;
+        let stdout = String::from_utf8(output.stdout)?;
+        let lines: Vec<_> = stdout.lines().collect();
+        assert!(lines[0].ends_with("c"));
+        assert_eq!(lines[1], artifact.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_abs_dir() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let conf = Config::default_in_dir(base_dir);
+        let problem_id = ProblemId::from("C");
+        let testcases_dir = test_dir.path().join("atcoder/arc100/c/testcases");
+        let dir = |test_set| -> anyhow::Result<_> {
+            Ok(conf
+                .test_set_abs_dir(&problem_id, test_set)?
+                .map(|dir| dir.as_ref().to_owned()))
+        };
+        assert_eq!(dir(TestSet::Samples)?, None);
+        assert_eq!(dir(TestSet::System)?, Some(testcases_dir.clone()));
+        assert_eq!(
+            dir(TestSet::Pretests)?,
+            Some(testcases_dir.join("pretests"))
+        );
+        assert_eq!(dir(TestSet::Stress)?, Some(testcases_dir.join("stress")));
+        Ok(())
+    }
+
+    #[test]
+    fn scale_time_limit() -> anyhow::Result<()> {
+        let time_limit = Duration::from_secs(2);
+        assert_eq!(
+            TestConfig::default().scale_time_limit(time_limit),
+            time_limit
+        );
+        let test_conf: TestConfig =
+            serde_yaml::from_str("{ time_limit_scale: 1.5, extra_time_ms: 200 }")?;
+        assert_eq!(
+            test_conf.scale_time_limit(time_limit),
+            Duration::from_millis(3200)
+        );
+        let test_conf: TestConfig = serde_yaml::from_str("{ time_limit_scale: 0 }")?;
+        assert!(test_conf.validate().is_err());
+
+        // huge scale is rejected on load and does not panic even if not validated
+        let test_conf: TestConfig = serde_yaml::from_str("{ time_limit_scale: 1e300 }")?;
+        assert!(test_conf.validate().is_err());
+        assert_eq!(
+            test_conf.scale_time_limit(time_limit),
+            Duration::from_secs(200)
+        );
+        let test_conf: TestConfig = serde_yaml::from_str("{ time_limit_scale: .nan }")?;
+        assert!(test_conf.validate().is_err());
+        test_conf.scale_time_limit(time_limit);
+        Ok(())
+    }
+
+    #[test]
+    fn cleanup_testcases_on_ac() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let mut conf = Config::default_in_dir(base_dir);
+        let problem_id = ProblemId::from("C");
+        let mut cnsl = Console::sink(Default::default());
+        let testcases_dir = test_dir.path().join("atcoder/arc100/c/testcases");
+        std::fs::create_dir_all(testcases_dir.join("in"))?;
+
+        assert!(!conf.cleanup_testcases_on_ac(&problem_id, &mut cnsl)?);
+        assert!(testcases_dir.exists());
+
+        conf.body.testcases.cleanup_on_ac = true;
+        // not confirmed
+        assert!(!conf.cleanup_testcases_on_ac(&problem_id, &mut cnsl)?);
+        assert!(testcases_dir.exists());
+
+        let mut cnsl = Console::sink(crate::console::ConsoleConfig {
+            assume_yes: true,
+            ..Default::default()
+        });
+        assert!(conf.cleanup_testcases_on_ac(&problem_id, &mut cnsl)?);
+        assert!(!testcases_dir.exists());
+        assert!(!conf.cleanup_testcases_on_ac(&problem_id, &mut cnsl)?);
+
+        // testcases of the other test sets are kept
+        std::fs::create_dir_all(testcases_dir.join("in"))?;
+        std::fs::create_dir_all(testcases_dir.join("stress/in"))?;
+        assert!(conf.cleanup_testcases_on_ac(&problem_id, &mut cnsl)?);
+        assert!(!testcases_dir.join("in").exists());
+        assert!(testcases_dir.join("stress/in").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn move_testcases_dir_keeps_other_test_sets() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let conf = Config::default_in_dir(base_dir.clone());
+        let problem = Problem::new("C", "", "arc100_a", None, None, Compare::Default, vec![]);
+        let mut cnsl = Console::sink(crate::console::ConsoleConfig {
+            assume_yes: true,
+            ..Default::default()
+        });
+        let testcases_dir = test_dir.path().join("atcoder/arc100/c/testcases");
+        for path in &["in/old.txt", "stress/in/01.txt", "pretests/in/01.txt"] {
+            let path = testcases_dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::fs::write(path, "1\n")?;
+        }
+        let tmp_dir = base_dir.join("tmp");
+        std::fs::create_dir_all(tmp_dir.join("in").as_ref())?;
+        std::fs::write(tmp_dir.join("in/new.txt").as_ref(), "2\n")?;
+
+        assert!(conf.move_testcases_dir(&problem, &tmp_dir, &mut cnsl)?);
+        assert!(!testcases_dir.join("in/old.txt").exists());
+        assert!(testcases_dir.join("in/new.txt").exists());
+        assert!(testcases_dir.join("stress/in/01.txt").exists());
+        assert!(testcases_dir.join("pretests/in/01.txt").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn exec_run_with_env() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let mut conf = Config::default_in_dir(base_dir.clone());
+        let problem = Problem::default();
+        base_dir.join("atcoder/arc100/c").create_dir_all()?;
+
+        conf.body.services.atcoder.run = "echo $CONTEST-$PROBLEM".into();
+        conf.body
+            .services
+            .atcoder
+            .env
+            .insert("CONTEST".into(), "{{ contest }}".into());
+        conf.body
+            .services
+            .atcoder
+            .env
+            .insert("PROBLEM".into(), "{{ problem | lower }}".into());
+        let output = conf.exec_run(problem.id())?.output().await?;
+        assert_eq!(String::from_utf8(output.stdout)?, "arc100-c\n");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn exec_default_atcoder_compile() -> anyhow::Result<()> {
         let test_dir = tempdir()?;
@@ -534,8 +1949,11 @@ int main() {{
         let contest = Contest::default();
         let problem = Problem::default();
         let shell = Shell::default();
-        let compile = ServiceConfig::default_for(ServiceKind::Atcoder).compile;
-        let context = TargetContext::new(ServiceKind::default(), contest.id(), problem.id());
+        let compile = ServiceConfig::default_for(ServiceKind::Atcoder, Profile::Cpp)
+            .compile
+            .unwrap();
+        let context = TargetContext::new(ServiceKind::default(), contest.id(), problem.id())
+            .with_artifact("./c.out".into());
         let output = shell
             .exec_templ(&compile, &context)?
             .current_dir(test_dir.path())
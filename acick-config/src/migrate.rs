@@ -0,0 +1,195 @@
+use std::fs;
+use std::io::Write as _;
+
+use anyhow::{anyhow, Context as _};
+use semver::{Version, VersionReq};
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::abs_path::AbsPathBuf;
+use crate::console::Console;
+use crate::include::INCLUDE_KEY;
+use crate::{ConfigBody, Error, Result, VERSION};
+
+/// Transformation of config file needed by acick of `version` and later.
+struct Migration {
+    version: &'static str,
+    description: &'static str,
+    /// Returns true if the config is changed.
+    apply: fn(&mut Mapping) -> bool,
+}
+
+/// Known transformations in ascending order of version.
+static MIGRATIONS: &[Migration] = &[Migration {
+    version: "0.2.0",
+    description: "Renamed services.*.lang_name to lang_names, which is a list of languages",
+    apply: rename_lang_name,
+}];
+
+fn rename_lang_name(body: &mut Mapping) -> bool {
+    let services = match body.get_mut(&Value::from("services")) {
+        Some(Value::Mapping(services)) => services,
+        _ => return false,
+    };
+    let mut is_changed = false;
+    for (_, service) in services.iter_mut() {
+        let service = match service {
+            Value::Mapping(service) => service,
+            _ => continue,
+        };
+        if service.contains_key(&Value::from("lang_names")) {
+            continue;
+        }
+        if let Some(lang_name) = service.remove(&Value::from("lang_name")) {
+            service.insert(Value::from("lang_names"), Value::Sequence(vec![lang_name]));
+            is_changed = true;
+        }
+    }
+    is_changed
+}
+
+/// Result of migration of config file.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MigrationReport {
+    pub config_path: AbsPathBuf,
+    /// Copy of the original config file, or `None` if the file is not changed
+    pub backup_path: Option<AbsPathBuf>,
+    pub from_version: String,
+    pub to_version: String,
+    /// Descriptions of applied transformations
+    pub changes: Vec<String>,
+}
+
+impl ConfigBody {
+    /// Rewrites config file in base_dir so that it is valid for the current version of acick,
+    /// saving the original file as backup.
+    ///
+    /// Comments in the config file are not kept. Included config files are not migrated.
+    pub fn migrate(base_dir: Option<AbsPathBuf>, cnsl: &mut Console) -> Result<MigrationReport> {
+        let base_dir = match base_dir {
+            Some(base_dir) => base_dir,
+            None => Self::search(cnsl)?,
+        };
+        let config_path = base_dir.join(Self::FILE_NAME);
+        let mut value: Value = config_path.load_pretty(
+            |file| serde_yaml::from_reader(file).context("Could not read config file as yaml"),
+            Some(&base_dir),
+            cnsl,
+        )?;
+        let body = match &mut value {
+            Value::Mapping(body) => body,
+            _ => return Err(Error::msg("Config file must be a mapping")),
+        };
+        let from_version = body
+            .get(&Value::from("version"))
+            .and_then(Value::as_str)
+            .context("Could not find version in config file")?
+            .to_owned();
+        let version = Version::parse(&from_version)
+            .with_context(|| format!("Could not parse version : {}", from_version))?;
+        let mut report = MigrationReport {
+            config_path: config_path.clone(),
+            backup_path: None,
+            from_version: from_version.clone(),
+            to_version: VERSION.to_string(),
+            changes: Vec::new(),
+        };
+
+        let version_req =
+            VersionReq::parse(&from_version).context("Could not parse version requirement")?;
+        if version_req.matches(&VERSION) {
+            return Ok(report);
+        }
+        if version > *VERSION {
+            return Err(anyhow!(
+                "Config file is for newer version of acick : {}. Update acick instead.",
+                from_version
+            ));
+        }
+
+        for migration in MIGRATIONS {
+            let migration_version = Version::parse(migration.version)?;
+            if version < migration_version
+                && migration_version <= *VERSION
+                && (migration.apply)(body)
+            {
+                report.changes.push(migration.description.to_owned());
+            }
+        }
+        body.insert(Value::from("version"), Value::from(VERSION.to_string()));
+        report.changes.push(format!(
+            "Updated version from {} to {}",
+            from_version, &*VERSION
+        ));
+
+        // fields may be given by included files, which are checked when config is loaded
+        if !body.contains_key(&Value::from(INCLUDE_KEY)) {
+            let migrated: Self = serde_yaml::from_value(value.clone())
+                .context("Could not migrate config file. Fix it by hand.")?;
+            migrated
+                .validate()
+                .context("Could not migrate config file. Fix it by hand.")?;
+        }
+
+        for change in &report.changes {
+            writeln!(cnsl, "{}", change)?;
+        }
+        let message = "overwrite config file with migrated one? (comments in it are removed)";
+        if !cnsl.confirm(message, false)? {
+            return Err(Error::msg("Not migrated"));
+        }
+        let backup_path = base_dir.join(format!("{}.{}.bak", Self::FILE_NAME, from_version));
+        fs::copy(config_path.as_ref(), backup_path.as_ref())
+            .with_context(|| format!("Could not save backup : {}", backup_path))?;
+        config_path.save_pretty(
+            |file| serde_yaml::to_writer(file, &value).context("Could not save config file"),
+            true,
+            Some(&base_dir),
+            cnsl,
+        )?;
+        report.backup_path = Some(backup_path);
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::console::ConsoleConfig;
+
+    #[test]
+    fn test_migrate() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let config_path = base_dir.join(ConfigBody::FILE_NAME);
+        let original = r#"
+version: 0.1.0
+services:
+  atcoder:
+    lang_name: C++ (GCC 9.2.1)
+    working_dir: "{{ service }}/{{ contest }}/{{ problem | lower }}"
+    source_path: "{{ service }}/{{ contest }}/{{ problem | lower }}/Main.cpp"
+    run: ./a.out
+"#;
+        fs::write(config_path.as_ref(), original)?;
+        let mut cnsl = Console::buf(ConsoleConfig {
+            assume_yes: true,
+            ..Default::default()
+        });
+
+        let report = ConfigBody::migrate(Some(base_dir.clone()), &mut cnsl)?;
+        assert_eq!(report.changes.len(), 2);
+        let backup_path = report.backup_path.unwrap();
+        assert_eq!(fs::read_to_string(backup_path.as_ref())?, original);
+        let body = ConfigBody::load(&base_dir, &mut cnsl)?;
+        assert_eq!(body.services.atcoder.lang_names().len(), 1);
+
+        // config file of the current version is not changed
+        let report = ConfigBody::migrate(Some(base_dir), &mut cnsl)?;
+        assert!(report.changes.is_empty());
+        assert_eq!(report.backup_path, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,314 @@
+use std::io::Write as _;
+
+use anyhow::{anyhow, Context as _};
+
+use crate::console::Console;
+use crate::model::{Problem, ProblemId};
+use crate::store::StateStore;
+use crate::{Config, Result};
+
+static PROBLEM_INDEX_FILE_NAME: &str = ".acick-problems.jsonl";
+
+/// Max number of problems suggested for a problem id that matches no problems.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Index of problems fetched into base_dir, which has one entry for each contest.
+///
+/// Problems in the index have no samples, so that commands can look up problems
+/// without loading all problem files of the contest.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProblemIndex {
+    store: StateStore,
+    key: String,
+}
+
+impl ProblemIndex {
+    /// Returns problems of the contest sorted by id, or `None` if the contest has not been indexed.
+    pub fn get(&self) -> Result<Option<Vec<Problem>>> {
+        self.store.get(&self.key)
+    }
+
+    /// Adds the problem to the index, replacing the one of the same id.
+    pub fn insert(&self, problem: &Problem) -> Result<()> {
+        let mut problem = problem.clone();
+        problem.set_samples(Vec::new());
+        self.store
+            .update(&self.key, |problems: Option<Vec<Problem>>| {
+                let mut problems = problems.unwrap_or_default();
+                problems.retain(|p| p.id() != problem.id());
+                problems.push(problem);
+                problems.sort_by(|a, b| a.id().cmp(b.id()));
+                Some(problems)
+            })
+    }
+}
+
+impl Config {
+    /// Returns index of problems fetched for the current contest.
+    pub fn problem_index(&self) -> ProblemIndex {
+        ProblemIndex {
+            store: StateStore::new(self.base_dir.join(PROBLEM_INDEX_FILE_NAME)),
+            key: format!("{}/{}", self.service_id, self.contest_id),
+        }
+    }
+
+    /// Returns problems fetched for the current contest.
+    ///
+    /// Problem files are searched instead if the contest has not been indexed
+    /// (e.g. fetched before the index was introduced)
+    /// or if some of the indexed problem files have been removed.
+    pub fn fetched_problems(&self) -> Result<Vec<Problem>> {
+        if let Some(problems) = self.problem_index().get()? {
+            if self.has_problem_files(&problems)? {
+                return Ok(problems);
+            }
+        }
+        self.scan_problems()
+    }
+
+    fn has_problem_files(&self, problems: &[Problem]) -> Result<bool> {
+        for problem in problems {
+            if !self.problem_abs_path(problem.id())?.as_ref().is_file() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Loads problems of the current contest from problem files.
+    fn scan_problems(&self) -> Result<Vec<Problem>> {
+        let mut problems = Vec::new();
+        for (contest_id, id) in self.search_problems()? {
+            if contest_id != self.contest_id {
+                continue;
+            }
+            match self
+                .problem_abs_path(&id)?
+                .load(|file| serde_yaml::from_reader(file).context("Could not read problem"))
+            {
+                Ok(problem) => problems.push(problem),
+                // broken problem files are reported when they are loaded by id
+                Err(_) => continue,
+            }
+        }
+        Ok(problems)
+    }
+
+    /// Resolves problem given by user into id of fetched problem of the contest.
+    ///
+    /// Problem can be given by its id (e.g. `Ex`), url name (e.g. `abc234_h`)
+    /// or index in url name (e.g. `h`). Id is preferred if problem file of it exists.
+    /// Returns the given id as it is if no problems of the contest have been fetched,
+    /// and suggests similar problems if the fetched problems do not match.
+    pub fn resolve_problem_id(
+        &self,
+        problem_id: &ProblemId,
+        cnsl: &mut Console,
+    ) -> Result<ProblemId> {
+        if self.problem_abs_path(problem_id)?.as_ref().is_file() {
+            return Ok(problem_id.clone());
+        }
+        let problems = self.fetched_problems()?;
+        let candidates: Vec<&ProblemId> = problems
+            .iter()
+            .filter(|problem| problem.is_addressed_by(problem_id.as_ref()))
+            .map(Problem::id)
+            .collect();
+        match candidates.as_slice() {
+            [] if problems.is_empty() => Ok(problem_id.clone()),
+            [] => {
+                let suggestions = suggest(problem_id.as_ref(), &problems);
+                let hint = if suggestions.is_empty() {
+                    format!(
+                        "Fetched problems are {}.",
+                        join_ids(problems.iter().map(Problem::id))
+                    )
+                } else {
+                    format!("Did you mean {}?", join_ids(suggestions.into_iter()))
+                };
+                Err(anyhow!(
+                    "Could not find problem {} in contest {}. {}",
+                    problem_id.as_ref(),
+                    self.contest_id,
+                    hint
+                ))
+            }
+            [id] => {
+                if *id != problem_id {
+                    writeln!(cnsl, "Resolved problem {} as {}", problem_id.as_ref(), id)?;
+                }
+                Ok((*id).clone())
+            }
+            ids => Err(anyhow!(
+                "Found multiple problems matching {} : {}. Specify id of problem instead.",
+                problem_id.as_ref(),
+                join_ids(ids.iter().copied())
+            )),
+        }
+    }
+}
+
+fn join_ids<'a>(ids: impl Iterator<Item = &'a ProblemId>) -> String {
+    ids.map(ToString::to_string).collect::<Vec<_>>().join(", ")
+}
+
+/// Returns ids of problems whose id, url name or index in url name is close to `name`,
+/// most similar first.
+fn suggest<'a>(name: &str, problems: &'a [Problem]) -> Vec<&'a ProblemId> {
+    let name = name.to_lowercase();
+    // allow a typo for each 3 chars, e.g. `abc100_d` for `abc100_c`
+    let max_distance = (name.chars().count() / 3).max(1);
+    let mut suggestions: Vec<(usize, &ProblemId)> = problems
+        .iter()
+        .filter_map(|problem| {
+            let url_name = problem.url_name();
+            let index = url_name.rsplit('_').next().unwrap_or(url_name);
+            let distance = [problem.id().as_ref(), url_name, index]
+                .iter()
+                .map(|target| edit_distance(&name, &target.to_lowercase()))
+                .min()?;
+            if distance <= max_distance {
+                Some((distance, problem.id()))
+            } else {
+                None
+            }
+        })
+        .collect();
+    suggestions.sort();
+    suggestions
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, id)| id)
+        .collect()
+}
+
+/// Levenshtein distance between two strings counted in chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev_diag = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substituted = prev_diag + if ca == cb { 0 } else { 1 };
+            prev_diag = row[j + 1];
+            row[j + 1] = substituted.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::abs_path::AbsPathBuf;
+    use crate::model::Compare;
+
+    fn problem(id: &str, url_name: &str) -> Problem {
+        Problem::new(id, "", url_name, None, None, Compare::Default, vec![])
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("arc100_a", "arc100_b"), 1);
+    }
+
+    #[test]
+    fn test_insert() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let conf = Config::default_in_dir(AbsPathBuf::try_new(test_dir.path())?);
+        let index = conf.problem_index();
+        assert_eq!(index.get()?, None);
+
+        index.insert(&Problem::default())?;
+        index.insert(&problem("A", "arc100_x"))?;
+        index.insert(&problem("C", "arc100_a"))?;
+        let problems = index.get()?.unwrap();
+        assert_eq!(
+            problems,
+            vec![problem("A", "arc100_x"), problem("C", "arc100_a")]
+        );
+
+        // other contests have their own entries
+        let other = conf.with_contest("arc101".into()).problem_index();
+        assert_eq!(other.get()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_problem_id() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let conf = Config::default_in_dir(base_dir);
+        let mut cnsl = Console::sink(Default::default());
+        let resolve = |id: &str, cnsl: &mut Console| conf.resolve_problem_id(&id.into(), cnsl);
+
+        // nothing to resolve before fetch
+        assert_eq!(resolve("z", &mut cnsl)?, ProblemId::from("z"));
+
+        conf.save_problem(&problem("C", "arc100_a"), false, &mut cnsl)?;
+        conf.save_problem(&problem("D", "arc100_b"), false, &mut cnsl)?;
+        assert_eq!(resolve("c", &mut cnsl)?, ProblemId::from("C"));
+        assert_eq!(resolve("a", &mut cnsl)?, ProblemId::from("C"));
+        assert_eq!(resolve("ARC100_B", &mut cnsl)?, ProblemId::from("D"));
+
+        let err = resolve("arc100_d", &mut cnsl).unwrap_err();
+        assert!(err.to_string().ends_with("Did you mean C, D?"));
+        let err = resolve("xyz", &mut cnsl).unwrap_err();
+        assert!(err.to_string().ends_with("Fetched problems are C, D."));
+
+        conf.save_problem(&problem("Ex", "arc100_extra_a"), false, &mut cnsl)?;
+        assert!(resolve("a", &mut cnsl).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_problem_id_without_index() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let conf = Config::default_in_dir(base_dir.clone());
+        let mut cnsl = Console::sink(Default::default());
+        conf.save_problem(&problem("C", "arc100_a"), false, &mut cnsl)?;
+        std::fs::remove_file(base_dir.join(PROBLEM_INDEX_FILE_NAME).as_ref())?;
+
+        assert_eq!(
+            conf.resolve_problem_id(&"a".into(), &mut cnsl)?,
+            ProblemId::from("C")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetched_problems_follow_problem_files() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let conf = Config::default_in_dir(base_dir);
+        let mut cnsl = Console::sink(Default::default());
+        conf.save_problem(&problem("C", "arc100_a"), false, &mut cnsl)?;
+        conf.save_problem(&problem("D", "arc100_b"), false, &mut cnsl)?;
+
+        // index is not updated if the problem file is kept
+        conf.save_problem(&problem("C", "arc100_x"), false, &mut cnsl)?;
+        let url_names: Vec<_> = conf
+            .fetched_problems()?
+            .iter()
+            .map(|problem| problem.url_name().to_owned())
+            .collect();
+        assert_eq!(url_names, vec!["arc100_a", "arc100_b"]);
+
+        // removed problem files are not listed even if they are in the index
+        std::fs::remove_file(conf.problem_abs_path(&"D".into())?.as_ref())?;
+        let ids: Vec<_> = conf
+            .fetched_problems()?
+            .iter()
+            .map(|problem| problem.id().clone())
+            .collect();
+        assert_eq!(ids, vec![ProblemId::from("C")]);
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumString, EnumVariantNames, IntoStaticStr};
+
+use crate::Layout;
+
+/// Language for which default service configs are generated by `acick init`.
+#[derive(
+    Serialize,
+    Deserialize,
+    EnumString,
+    EnumVariantNames,
+    IntoStaticStr,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Profile {
+    Cpp,
+    Rust,
+    Python,
+}
+
+impl Profile {
+    /// Returns lines of service config in config file with paths arranged in the layout,
+    /// which is indented to be put under the key of a service.
+    pub(crate) fn service_yaml(self, layout: Layout) -> String {
+        let working_dir = layout.working_dir();
+        match self {
+            Self::Cpp => format!(
+                include_str!("../resources/profiles/cpp.yaml.txt"),
+                working_dir = working_dir,
+                source_file = layout.source_file("Main", "cpp"),
+                bundled_file = layout.bundled_file("cpp"),
+            ),
+            Self::Rust => {
+                let cargo = CargoLayout::new(layout);
+                format!(
+                    include_str!("../resources/profiles/rust.yaml.txt"),
+                    working_dir = working_dir,
+                    workspace_dir = cargo.workspace_dir,
+                    source_file = cargo.source_file,
+                    bin = cargo.bin,
+                    mkdir_bundled = cargo.mkdir_bundled,
+                    bundled_file = layout.bundled_file("rs"),
+                    package_dir = cargo.package_dir,
+                    package_name = cargo.package_name,
+                    workspace_manifest = cargo.workspace_manifest,
+                )
+            }
+            Self::Python => format!(
+                include_str!("../resources/profiles/python.yaml.txt"),
+                working_dir = working_dir,
+                source_file = layout.source_file("Main", "py"),
+            ),
+        }
+    }
+}
+
+/// Lines of Rust profile that depend on layout, relative to the directory of contest.
+///
+/// Problems of a contest are packages of one cargo workspace in nested layout,
+/// and binaries of one cargo package in flat layout.
+struct CargoLayout {
+    workspace_dir: &'static str,
+    source_file: &'static str,
+    bin: &'static str,
+    mkdir_bundled: &'static str,
+    package_dir: &'static str,
+    package_name: &'static str,
+    workspace_manifest: &'static str,
+}
+
+impl CargoLayout {
+    fn new(layout: Layout) -> Self {
+        match layout {
+            Layout::Nested => Self {
+                workspace_dir: r#"    # Directory of workspace shared by problems, where compile command is executed instead. [t, s]
+    # Problems of a contest are built as packages of one cargo workspace sharing the target directory.
+    workspace_dir: "{{ service }}/{{ contest }}"
+"#,
+                source_file: "{{ problem | lower }}/src/main.rs",
+                bin: "{{ contest }}-{{ problem | lower }}",
+                mkdir_bundled: "",
+                package_dir: "{{ problem | lower }}/",
+                package_name: "{{ contest.id }}-{{ problem.id | lower }}",
+                workspace_manifest: r#"      - path: "{{ service }}/{{ contest }}/Cargo.toml"
+        template: |
+          [workspace]
+          members = ["*"]
+          exclude = ["target"]
+"#,
+            },
+            Layout::Flat => Self {
+                workspace_dir:
+                    "    # Problems of a contest are built as binaries of one cargo package.\n",
+                source_file: "src/bin/{{ problem | lower }}.rs",
+                bin: "{{ problem | lower }}",
+                mkdir_bundled: " && mkdir -p ./bundled",
+                package_dir: "",
+                package_name: "{{ contest.id }}",
+                workspace_manifest: "",
+            },
+        }
+    }
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self::Cpp
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Profile::default().to_string(), "cpp");
+        assert_eq!(Profile::Rust.to_string(), "rust");
+    }
+}
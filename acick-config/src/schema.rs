@@ -0,0 +1,565 @@
+//! JSON Schema of config file.
+//!
+//! The schema is written by hand and kept in sync with `ConfigBody` by tests,
+//! so that editors can validate and complete config files.
+
+use serde_json::{json, Map, Value};
+
+use crate::ConfigBody;
+
+static SCHEMA_DRAFT: &str = "http://json-schema.org/draft-07/schema#";
+
+fn string(description: &str) -> Value {
+    json!({ "type": "string", "description": description })
+}
+
+fn boolean(description: &str) -> Value {
+    json!({ "type": "boolean", "description": description })
+}
+
+fn integer(description: &str) -> Value {
+    json!({ "type": "integer", "minimum": 0, "description": description })
+}
+
+fn strings(description: &str) -> Value {
+    json!({ "type": "array", "items": { "type": "string" }, "description": description })
+}
+
+/// Duration in human-readable format (e.g. `30s`).
+fn duration(description: &str) -> Value {
+    string(description)
+}
+
+/// Size in bytes with unit (e.g. `16MB`).
+fn byte(description: &str) -> Value {
+    string(description)
+}
+
+fn object(description: &str, properties: Vec<(&str, Value)>, required: &[&str]) -> Value {
+    let properties: Map<String, Value> = properties
+        .into_iter()
+        .map(|(key, value)| (key.to_owned(), value))
+        .collect();
+    json!({
+        "type": "object",
+        "description": description,
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+fn shell() -> Value {
+    json!({
+        "description": "Shell used to compile the source code or to run the binary. [c]",
+        "oneOf": [
+            { "type": "string", "enum": ["bash", "powershell", "cmd"] },
+            { "type": "array", "items": { "type": "string" }, "minItems": 1 },
+        ],
+    })
+}
+
+fn defaults() -> Value {
+    object(
+        "Service and contest used when `--service` and `--contest` options are not specified.",
+        vec![
+            (
+                "service",
+                json!({ "type": "string", "enum": ["atcoder", "mock"], "description": "Id of service." }),
+            ),
+            ("contest", string("Id of contest.")),
+        ],
+        &[],
+    )
+}
+
+fn testcases() -> Value {
+    object(
+        "Full testcases downloaded from AtCoder.",
+        vec![(
+            "cleanup_on_ac",
+            boolean("If true, removes full testcases of a problem after confirmation once `acick submit --wait` sees it accepted."),
+        )],
+        &[],
+    )
+}
+
+fn test() -> Value {
+    object(
+        "Time limit of problems and limits of output of your binary in `acick test`.",
+        vec![
+            (
+                "time_limit_scale",
+                json!({
+                    "type": "number",
+                    "exclusiveMinimum": 0,
+                    "maximum": 100,
+                    "description": "Factor by which time limit of problem is multiplied.",
+                }),
+            ),
+            (
+                "extra_time_ms",
+                integer("Time in milliseconds added to the scaled time limit."),
+            ),
+            (
+                "max_output_bytes",
+                byte("Output of your binary after this size is dropped and replaced with a marker (e.g. 1MB)."),
+            ),
+            (
+                "strip_ansi",
+                boolean("If true, removes ANSI escape sequences (e.g. colors) from output of your binary."),
+            ),
+        ],
+        &[],
+    )
+}
+
+fn diff() -> Value {
+    object(
+        "Limits of outputs of your binary compared with expected outputs in `acick test`.",
+        vec![
+            (
+                "spill_threshold",
+                byte("Outputs larger than this are written to temporary files instead of memory."),
+            ),
+            ("max_lines", integer("Max number of lines shown in diff.")),
+            (
+                "max_line_width",
+                integer("Max number of chars shown in each line of diff."),
+            ),
+            (
+                "view",
+                json!({
+                    "type": "string",
+                    "enum": ["table", "side-by-side"],
+                    "description": "Layout of diff.",
+                }),
+            ),
+            (
+                "width",
+                integer("Width of diff in chars in side-by-side view."),
+            ),
+        ],
+        &[],
+    )
+}
+
+fn session() -> Value {
+    object(
+        "Session that communicates with service.",
+        vec![
+            ("timeout", duration("Timeout of each request.")),
+            (
+                "cookies_path",
+                string("Path of the file that stores cookies. [s]"),
+            ),
+            ("retry_limit", integer("Max number of retries of each request.")),
+            ("retry_interval", duration("Interval between retries.")),
+            (
+                "limit_rate",
+                string("Limits transfer rate of downloading full testcases (e.g. 2MB/s)."),
+            ),
+            (
+                "proxy",
+                string("Proxy server through which requests are sent (e.g. http://proxy.example.com:8080)."),
+            ),
+            (
+                "anonymous",
+                boolean("Sends requests to pages of contests without cookies and retries with cookies only if the service requires login."),
+            ),
+            (
+                "auto_relogin",
+                boolean("Logs in again automatically when the session has expired."),
+            ),
+            (
+                "credentials",
+                object(
+                    "Providers of username and password for each service, tried in order.",
+                    vec![
+                        ("atcoder", credential_sources()),
+                        ("mock", credential_sources()),
+                    ],
+                    &[],
+                ),
+            ),
+            (
+                "max_clock_skew",
+                duration("Warns if local clock differs from clock of service by more than this."),
+            ),
+            (
+                "request_interval",
+                duration("Minimum interval between requests to each service (e.g. 1s)."),
+            ),
+            (
+                "mock_dir",
+                string("Directory from which the mock service reads contests. [s]"),
+            ),
+        ],
+        &[],
+    )
+}
+
+fn credential_sources() -> Value {
+    json!({
+        "type": "array",
+        "description": "Providers of credentials. Defaults to env and prompt.",
+        "items": {
+            "type": "object",
+            "properties": {
+                "type": {
+                    "type": "string",
+                    "enum": ["env", "prompt", "keyring", "config", "command"],
+                },
+                "username_env": string("Env var of username. (env)"),
+                "password_env": string("Env var of password. (env)"),
+                "username": string("Username. (keyring, config, command)"),
+                "password": string("Password stored as plain text. (config)"),
+                "command": strings("Command whose first line of output is the password, or empty if unknown. (command)"),
+            },
+            "required": ["type"],
+            "additionalProperties": false,
+        },
+    })
+}
+
+fn testcases_source() -> Value {
+    json!({
+        "type": "object",
+        "description": "Storage from which `acick fetch --full` downloads testcases. Defaults to dropbox.",
+        "properties": {
+            "type": { "type": "string", "enum": ["dropbox", "local"] },
+            "dir": string("Directory that has testcases in `<contest>/<problem>/{in,out}/`. (local)"),
+        },
+        "required": ["type"],
+        "additionalProperties": false,
+    })
+}
+
+fn git() -> Value {
+    object(
+        "Commits source code to git repository after `acick submit` succeeds or after all testcases pass in `acick test --full`.",
+        vec![("message", string("Commit message. [m]"))],
+        &[],
+    )
+}
+
+fn hooks() -> Value {
+    object(
+        "Shell scripts executed in base_dir before and after fetch, test and submit. [t]",
+        vec![
+            (
+                "pre_save",
+                strings("Scripts executed before fetched problems are saved."),
+            ),
+            ("post_fetch", strings("Scripts executed after fetch.")),
+            ("pre_test", strings("Scripts executed before test.")),
+            ("post_test", strings("Scripts executed after test.")),
+            ("pre_submit", strings("Scripts executed before submit.")),
+            ("post_submit", strings("Scripts executed after submit.")),
+        ],
+        &[],
+    )
+}
+
+fn console() -> Value {
+    object(
+        "Console of acick.",
+        vec![(
+            "notify",
+            boolean("If true, shows a desktop notification when `acick test --full` or `acick fetch --full` finishes."),
+        )],
+        &[],
+    )
+}
+
+fn debug() -> Value {
+    object(
+        "Options for debugging acick.",
+        vec![(
+            "save_html",
+            boolean("If true, saves html of pages from which data could not be extracted under the data directory of acick (e.g. ~/.local/share/acick/debug/)."),
+        )],
+        &[],
+    )
+}
+
+fn submit_checks() -> Value {
+    object(
+        "Checks of source code before submission. Submission is blocked if any check fails.",
+        vec![
+            ("max_source_size", byte("Maximum size of source code.")),
+            (
+                "deny_patterns",
+                strings("Regular expressions that must not appear in source code."),
+            ),
+            (
+                "required_header",
+                string("Comment that source code must begin with."),
+            ),
+            (
+                "duplicate_window",
+                duration(
+                    "Submitting the same source code again within this period requires `--force`, if the latest submission could not be fetched from service.",
+                ),
+            ),
+        ],
+        &[],
+    )
+}
+
+fn service() -> Value {
+    let template_rule = object(
+        "Rule to choose template by problem.",
+        vec![
+            (
+                "when",
+                string("Tera expression evaluated with problem context (e.g. `problem.id in [\"E\", \"F\"]`)."),
+            ),
+            ("template", string("Template for source code. [p]")),
+        ],
+        &["when", "template"],
+    );
+    let template_file = object(
+        "File scaffolded together with source code.",
+        vec![
+            ("path", string("Path of the file. [t, s]")),
+            ("template", string("Template for the file. [p]")),
+        ],
+        &["path", "template"],
+    );
+    object(
+        "Config for service.",
+        vec![
+            (
+                "lang_names",
+                strings("Names of language as which your source code is submitted to the service, in order of preference."),
+            ),
+            (
+                "working_dir",
+                string("Directory where compile and run commands are executed. [t, s]"),
+            ),
+            (
+                "workspace_dir",
+                string("Directory of workspace shared by problems, where compile command is executed instead. [t, s]"),
+            ),
+            ("source_path", string("Path of your source code. [t, s]")),
+            (
+                "artifact_path",
+                string("Path of binary built from your source code, relative to workspace_dir or working_dir. [t, s]"),
+            ),
+            (
+                "compile",
+                string("Shell script to compile your source code. Compile phase is skipped if omitted or empty. [t]"),
+            ),
+            (
+                "compile_debug",
+                string("Shell script to compile your source code for debug, used instead of compile by `acick test --debug`. [t]"),
+            ),
+            (
+                "run",
+                string("Shell script to run the binary built with the compile script. [t]"),
+            ),
+            (
+                "env",
+                json!({
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Environment variables set when running the compile and run scripts. [t]",
+                }),
+            ),
+            (
+                "artifacts",
+                strings("Paths of other files generated by the compile script. [t, s]"),
+            ),
+            (
+                "template",
+                json!({ "type": ["string", "null"], "description": "Template for source code. [p]" }),
+            ),
+            (
+                "template_rules",
+                json!({
+                    "type": "array",
+                    "items": template_rule,
+                    "description": "Rules to choose template by problem, evaluated in order before `template`.",
+                }),
+            ),
+            (
+                "template_files",
+                json!({
+                    "type": "array",
+                    "items": template_file,
+                    "description": "Other files scaffolded together with source code, such as project files. They are never overwritten.",
+                }),
+            ),
+            (
+                "submit_path",
+                string("Path of the file submitted instead of source_path, such as a single file bundled by the compile script. [t, s]"),
+            ),
+            (
+                "stdin_file",
+                string("File from which your binary reads input instead of stdin, relative to working_dir. [t, s]"),
+            ),
+            (
+                "stdout_file",
+                string("File to which your binary writes output instead of stdout, relative to working_dir. [t, s]"),
+            ),
+            (
+                "notes_path",
+                string("Path of notes generated for each problem. [t, s]"),
+            ),
+            (
+                "notes_template",
+                json!({
+                    "type": ["string", "null"],
+                    "description": "Template for notes. Notes are generated only if this field is specified. [p]",
+                }),
+            ),
+            ("submit_checks", submit_checks()),
+            (
+                "show_difficulty",
+                boolean("If true, `acick fetch` annotates problems with difficulties estimated by AtCoder Problems (only available for AtCoder)."),
+            ),
+            ("testcases_source", testcases_source()),
+        ],
+        &[
+            "lang_names",
+            "working_dir",
+            "source_path",
+            "run",
+        ],
+    )
+}
+
+impl ConfigBody {
+    /// Returns JSON Schema of config file.
+    pub fn json_schema() -> Value {
+        let services = object(
+            "Configs for each service.",
+            vec![("atcoder", service()), ("mock", service())],
+            &[],
+        );
+        let mut schema = object(
+            "acick config file",
+            vec![
+                (
+                    "version",
+                    string("Version of acick that generated this config file."),
+                ),
+                (
+                    crate::include::INCLUDE_KEY,
+                    json!({
+                        "description": "Config files merged into this file, which overrides them. [s]",
+                        "oneOf": [
+                            { "type": "string" },
+                            { "type": "array", "items": { "type": "string" } },
+                        ],
+                    }),
+                ),
+                ("defaults", defaults()),
+                ("shell", shell()),
+                (
+                    "filters",
+                    json!({
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                        "description": "Custom filters of templates defined as shell commands, which read the value from stdin.",
+                    }),
+                ),
+                (
+                    "functions",
+                    json!({
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                        "description": "Custom functions of templates defined as shell commands.",
+                    }),
+                ),
+                (
+                    "problem_path",
+                    string("Path at which the problems fetched from service are saved. [t, s]"),
+                ),
+                (
+                    "testcases_dir",
+                    string("Directory in which the testcase files downloaded from AtCoder are saved. [t, s]"),
+                ),
+                (
+                    "normalize_testcases",
+                    boolean("If true, converts CRLF to LF and appends missing trailing newlines in downloaded testcase files."),
+                ),
+                ("testcases", testcases()),
+                ("test", test()),
+                ("diff", diff()),
+                ("session", session()),
+                ("services", services),
+                ("git", git()),
+                ("hooks", hooks()),
+                ("console", console()),
+                ("debug", debug()),
+                (
+                    "history_path",
+                    string("Path of the file that stores history of test and submit commands. [s]"),
+                ),
+            ],
+            &["version"],
+        );
+        schema["$schema"] = SCHEMA_DRAFT.into();
+        schema["title"] = Self::FILE_NAME.into();
+        schema
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{ContestId, ServiceKind};
+    use crate::DefaultsConfig;
+
+    /// Asserts that schema has all the keys of the value and vice versa.
+    fn assert_keys(schema: &Value, value: &Value, path: &str) {
+        let properties = schema["properties"].as_object().unwrap();
+        let value = value.as_object().unwrap();
+        for key in value.keys() {
+            assert!(
+                properties.contains_key(key),
+                "{}.{} is not in schema",
+                path,
+                key
+            );
+        }
+        for (key, property) in properties {
+            if let (Some(Value::Object(_)), Some("object")) =
+                (value.get(key), property["type"].as_str())
+            {
+                if property.get("properties").is_some() {
+                    assert_keys(property, &value[key], &format!("{}.{}", path, key));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn schema_has_all_fields() -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        ConfigBody::generate_to(&mut buf)?;
+        let mut body: ConfigBody = serde_yaml::from_slice(&buf)?;
+        body.defaults = DefaultsConfig::new(ServiceKind::Atcoder, ContestId::default());
+        body.git = Some(Default::default());
+        body.filters = serde_yaml::from_str("{ a: cat }")?;
+        body.functions = serde_yaml::from_str("{ a: echo }")?;
+        body.hooks = serde_yaml::from_str("{ pre_save: [a], post_fetch: [a], pre_test: [a], post_test: [a], pre_submit: [a], post_submit: [a] }")?;
+        body.services.atcoder.submit_checks = serde_yaml::from_str(
+            "{ max_source_size: 512KB, deny_patterns: [a], required_header: a }",
+        )?;
+        body.services.atcoder.template_rules =
+            serde_yaml::from_str("[{ when: 'true', template: a }]")?;
+        body.services.atcoder.template_files =
+            serde_yaml::from_str("[{ path: Cargo.toml, template: a }]")?;
+        body.services.atcoder.submit_path = Some("bundled.rs".into());
+        body.services.atcoder.stdin_file = Some("input.txt".into());
+        body.services.atcoder.stdout_file = Some("output.txt".into());
+        body.services.atcoder.workspace_dir = Some("atcoder".into());
+        body.services.atcoder.compile_debug = Some("g++ -g".into());
+        let value = serde_json::to_value(&body)?;
+        assert_keys(&ConfigBody::json_schema(), &value, "");
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use std::env;
 use std::time::Duration;
 
 use getset::{CopyGetters, Getters};
@@ -5,17 +6,23 @@ use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 
 use crate::abs_path::AbsPathBuf;
+use crate::credentials::CredentialsConfig;
+use crate::service::throttle::ByteRate;
 use crate::DATA_LOCAL_DIR;
 
 static COOKIES_FILE_NAME: &str = "cookies.json";
+static MOCK_DIR_NAME: &str = "mock";
+static PROXY_ENV_NAME: &str = "ACICK_PROXY";
 
 lazy_static! {
     static ref DEFAULT_COOKIES_PATH: AbsPathBuf = DATA_LOCAL_DIR.join(COOKIES_FILE_NAME);
+    static ref DEFAULT_MOCK_DIR: AbsPathBuf = DATA_LOCAL_DIR.join(MOCK_DIR_NAME);
 }
 
 const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 const DEFAULT_RETRY_LIMIT: usize = 4;
 const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+const DEFAULT_MAX_CLOCK_SKEW: Duration = Duration::from_secs(10);
 
 #[derive(Serialize, Deserialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(default)]
@@ -30,6 +37,34 @@ pub struct SessionConfig {
     #[serde(with = "humantime_serde")]
     #[get_copy = "pub"]
     retry_interval: Duration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[get_copy = "pub"]
+    limit_rate: Option<ByteRate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proxy: Option<String>,
+    #[get_copy = "pub"]
+    anonymous: bool,
+    #[get_copy = "pub"]
+    auto_relogin: bool,
+    /// Guesses samples in unknown layouts, which is enabled only by fetch command
+    #[serde(skip)]
+    #[get_copy = "pub"]
+    lax_samples: bool,
+    /// Forbids network access, which is enabled only by `--offline` option
+    #[serde(skip)]
+    #[get_copy = "pub"]
+    offline: bool,
+    #[get = "pub"]
+    credentials: CredentialsConfig,
+    #[serde(with = "humantime_serde")]
+    #[get_copy = "pub"]
+    max_clock_skew: Duration,
+    #[serde(with = "humantime_serde", skip_serializing_if = "Option::is_none")]
+    #[get_copy = "pub"]
+    request_interval: Option<Duration>,
+    /// Directory from which the mock service reads contests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mock_dir: Option<AbsPathBuf>,
 }
 
 impl SessionConfig {
@@ -39,12 +74,61 @@ impl SessionConfig {
             cookies_path: Some(base_dir.join(COOKIES_FILE_NAME)),
             retry_limit: DEFAULT_RETRY_LIMIT,
             retry_interval: DEFAULT_RETRY_INTERVAL,
+            limit_rate: None,
+            proxy: None,
+            anonymous: false,
+            auto_relogin: false,
+            lax_samples: false,
+            offline: false,
+            credentials: CredentialsConfig::default(),
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            request_interval: None,
+            mock_dir: Some(base_dir.join(MOCK_DIR_NAME)),
         }
     }
 
     pub fn cookies_path(&self) -> &AbsPathBuf {
         self.cookies_path.as_ref().unwrap_or(&DEFAULT_COOKIES_PATH)
     }
+
+    pub fn mock_dir(&self) -> &AbsPathBuf {
+        self.mock_dir.as_ref().unwrap_or(&DEFAULT_MOCK_DIR)
+    }
+
+    /// Returns config that sends requests without cookies until login is required.
+    pub fn with_anonymous(&self, anonymous: bool) -> Self {
+        Self {
+            anonymous,
+            ..self.clone()
+        }
+    }
+
+    /// Returns config that guesses samples of problems in unknown layouts.
+    pub fn with_lax_samples(&self, lax_samples: bool) -> Self {
+        Self {
+            lax_samples,
+            ..self.clone()
+        }
+    }
+
+    /// Returns config that forbids network access.
+    pub fn with_offline(&self, offline: bool) -> Self {
+        Self {
+            offline,
+            ..self.clone()
+        }
+    }
+
+    /// Returns url of proxy server.
+    ///
+    /// Falls back to the value of `ACICK_PROXY` environment variable if not specified in config.
+    pub fn proxy(&self) -> Option<String> {
+        self.proxy.clone().or_else(|| {
+            env::var(PROXY_ENV_NAME)
+                .ok()
+                .filter(|proxy| !proxy.is_empty())
+        })
+    }
 }
 
 impl Default for SessionConfig {
@@ -54,6 +138,16 @@ impl Default for SessionConfig {
             cookies_path: None,
             retry_limit: DEFAULT_RETRY_LIMIT,
             retry_interval: DEFAULT_RETRY_INTERVAL,
+            limit_rate: None,
+            proxy: None,
+            anonymous: false,
+            auto_relogin: false,
+            lax_samples: false,
+            offline: false,
+            credentials: CredentialsConfig::default(),
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            request_interval: None,
+            mock_dir: None,
         }
     }
 }
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::model::Byte;
+use crate::Result;
+
+const DEFAULT_DUPLICATE_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct SubmitChecks {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_source_size: Option<Byte>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    deny_patterns: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    required_header: Option<String>,
+    #[serde(with = "humantime_serde", skip_serializing_if = "Option::is_none")]
+    duplicate_window: Option<Duration>,
+}
+
+impl SubmitChecks {
+    /// Returns period in which submitting the same source code again requires `--force`.
+    pub fn duplicate_window(&self) -> Duration {
+        self.duplicate_window.unwrap_or(DEFAULT_DUPLICATE_WINDOW)
+    }
+
+    /// Checks if source code is ready to be submitted.
+    pub fn check(&self, source: &str) -> Result<()> {
+        if let Some(max_source_size) = self.max_source_size {
+            if source.len() as u64 > max_source_size.as_u64() {
+                return Err(anyhow!(
+                    "Found source code larger than {} ({} Bytes)",
+                    max_source_size,
+                    source.len()
+                ));
+            }
+        }
+        for pattern in &self.deny_patterns {
+            let re = Regex::new(pattern)
+                .with_context(|| format!("Could not parse deny pattern : {}", pattern))?;
+            if let Some(found) = re.find(source) {
+                let line = source[..found.start()].matches('\n').count() + 1;
+                return Err(anyhow!(
+                    "Found denied pattern `{}` at line {} : {}",
+                    pattern,
+                    line,
+                    found.as_str()
+                ));
+            }
+        }
+        if let Some(header) = &self.required_header {
+            if !source.trim_start().starts_with(header.trim()) {
+                return Err(anyhow!(
+                    "Could not find required header at the beginning of source code : {}",
+                    header.trim()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() -> anyhow::Result<()> {
+        let checks: SubmitChecks = serde_yaml::from_str(
+            r#"
+max_source_size: 64 B
+deny_patterns:
+  - system\(
+  - '#define DEBUG'
+required_header: "// acick"
+duplicate_window: 5m
+"#,
+        )?;
+        assert_eq!(checks.duplicate_window(), Duration::from_secs(300));
+        assert!(checks.check("// acick\nint main() {}\n").is_ok());
+        assert!(checks.check("int main() {}\n").is_err());
+        assert!(checks
+            .check("// acick\nint main() { system(\"pause\"); }\n")
+            .is_err());
+        assert!(checks
+            .check(&format!("// acick\n{}", "a".repeat(64)))
+            .is_err());
+        assert!(SubmitChecks::default().check("").is_ok());
+        Ok(())
+    }
+}
@@ -1,15 +1,20 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write as _;
+use std::path::{Component, Path, PathBuf};
+use std::process::{self, Stdio};
 use std::sync::Mutex;
 use std::{env, fmt};
 
-use anyhow::Context as _;
+use anyhow::{anyhow, Context as _};
 use heck::{CamelCase as _, KebabCase as _, MixedCase as _, SnakeCase as _};
 use lazy_static::lazy_static;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use tera::Tera;
 use tokio::process::Command;
 
+use crate::abs_path::AbsPathBuf;
 use crate::model::{Contest, ContestId, Problem, ProblemId, Service, ServiceKind};
 use crate::Result;
 
@@ -44,6 +49,97 @@ lazy_static! {
     };
 }
 
+/// Registers filters and functions defined as shell commands in config file.
+///
+/// Filters read the value from stdin and functions read nothing.
+/// Both return stdout without trailing newlines,
+/// and receive arguments as environment variables (e.g. `width=4` as `ACICK_ARG_WIDTH`).
+pub fn register_plugins(
+    shell: &Shell,
+    filters: &BTreeMap<String, String>,
+    functions: &BTreeMap<String, String>,
+) {
+    let mut renderer = RENDERER.lock().unwrap();
+    for (name, command) in filters {
+        let plugin = ShellPlugin::new(shell, name, command);
+        renderer.register_filter(
+            name,
+            move |value: &tera::Value, args: &HashMap<String, tera::Value>| {
+                plugin.run(&value_to_string(value), args)
+            },
+        );
+    }
+    for (name, command) in functions {
+        let plugin = ShellPlugin::new(shell, name, command);
+        renderer.register_function(name, move |args: &HashMap<String, tera::Value>| {
+            plugin.run("", args)
+        });
+    }
+}
+
+fn value_to_string(value: &tera::Value) -> String {
+    match value {
+        tera::Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Filter or function of template that runs shell command.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ShellPlugin {
+    shell: Shell,
+    name: String,
+    command: String,
+}
+
+impl ShellPlugin {
+    fn new(shell: &Shell, name: &str, command: &str) -> Self {
+        Self {
+            shell: shell.clone(),
+            name: name.to_owned(),
+            command: command.to_owned(),
+        }
+    }
+
+    fn run(&self, input: &str, args: &HashMap<String, tera::Value>) -> tera::Result<tera::Value> {
+        self.run_inner(input, args)
+            .map_err(|err| tera::Error::msg(format!("Could not run {} : {:#}", self.name, err)))
+    }
+
+    fn run_inner(&self, input: &str, args: &HashMap<String, tera::Value>) -> Result<tera::Value> {
+        let mut command = self.shell.exec_std(&self.command)?;
+        for (key, value) in args {
+            command.env(
+                format!("ACICK_ARG_{}", key.to_uppercase()),
+                value_to_string(value),
+            );
+        }
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        // drop stdin to close it after writing input
+        child
+            .stdin
+            .take()
+            .expect("Failed to open stdin")
+            .write_all(input.as_bytes())?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Command returned non-zero status : {}\n{}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            ));
+        }
+        let stdout = String::from_utf8(output.stdout).context("Found non UTF-8 output")?;
+        Ok(tera::Value::String(
+            stdout.trim_end_matches(&['\r', '\n'][..]).to_owned(),
+        ))
+    }
+}
+
 pub trait Expand<'a> {
     type Context: Serialize + 'a;
 
@@ -60,16 +156,20 @@ pub trait Expand<'a> {
         let ctx =
             tera::Context::from_serialize(context).context("Could not create template context")?;
 
-        let mut renderer = RENDERER.lock().unwrap();
-        if let Err(err) = renderer.get_template(template_name) {
-            if let tera::ErrorKind::TemplateNotFound(_) = err.kind {
-                // need to register template because this is the first time to use it
-                renderer
-                    .add_raw_template(template_name, template)
-                    .context("Could not build template inheritance chain")?;
-            } else {
-                return Err(err).context("Could not expand template")?;
-            }
+        let renderer = {
+            let mut renderer = RENDERER.lock().unwrap();
+            if let Err(err) = renderer.get_template(template_name) {
+                if let tera::ErrorKind::TemplateNotFound(_) = err.kind {
+                    // need to register template because this is the first time to use it
+                    renderer
+                        .add_raw_template(template_name, template)
+                        .context("Could not build template inheritance chain")?;
+                } else {
+                    return Err(err).context("Could not expand template")?;
+                }
+            };
+            // render without lock because plugins expand shell template while rendering
+            renderer.clone()
         };
         renderer.render(template_name, &ctx).context(format!(
             "Could not expand template with context\n    template: {}\n    context: {}",
@@ -121,6 +221,10 @@ pub struct TargetContext<'a> {
     contest_id: &'a ContestId,
     #[serde(rename = "problem")]
     problem_id: &'a ProblemId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    artifact: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verdict: Option<&'a str>,
 }
 
 impl<'a> TargetContext<'a> {
@@ -133,8 +237,21 @@ impl<'a> TargetContext<'a> {
             service_id,
             contest_id,
             problem_id,
+            artifact: None,
+            verdict: None,
         }
     }
+
+    pub fn with_artifact(self, artifact: String) -> Self {
+        Self {
+            artifact: Some(artifact),
+            ..self
+        }
+    }
+
+    pub fn with_verdict(self, verdict: Option<&'a str>) -> Self {
+        Self { verdict, ..self }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -147,11 +264,57 @@ impl TargetTempl {
         contest_id: &ContestId,
         problem_id: &ProblemId,
     ) -> Result<String> {
-        self.expand(&TargetContext {
+        self.expand(&TargetContext::new(service_id, contest_id, problem_id))
+    }
+}
+
+impl TargetTempl {
+    const CONTEST_PLACEHOLDER: &'static str = "acickcontestplaceholder";
+    const PROBLEM_PLACEHOLDER: &'static str = "acickproblemplaceholder";
+
+    /// Builds a pattern that matches paths expanded with any contest and any problem.
+    pub fn to_pattern(
+        &self,
+        service_id: ServiceKind,
+        base_dir: &AbsPathBuf,
+    ) -> Result<TargetPattern> {
+        let expanded = self.expand_with(
             service_id,
-            contest_id,
-            problem_id,
-        })
+            &Self::CONTEST_PLACEHOLDER.into(),
+            &Self::PROBLEM_PLACEHOLDER.into(),
+        )?;
+        let abs_path = base_dir.join_expand(expanded)?;
+
+        let mut root = PathBuf::new();
+        let mut components = Vec::new();
+        for component in abs_path.as_ref().components() {
+            let component = match component {
+                Component::Normal(component) => component.to_string_lossy(),
+                _ if components.is_empty() => {
+                    root.push(component);
+                    continue;
+                }
+                _ => return Err(anyhow!("Found unsupported path component in {}", self)),
+            };
+            if components.is_empty()
+                && !component.contains(Self::CONTEST_PLACEHOLDER)
+                && !component.contains(Self::PROBLEM_PLACEHOLDER)
+            {
+                root.push(component.as_ref());
+                continue;
+            }
+            // capture only the first occurrence of each placeholder in the component
+            let pattern = format!(
+                "^{}$",
+                regex::escape(&component)
+                    .replacen(Self::CONTEST_PLACEHOLDER, "(?P<contest>.+)", 1)
+                    .replace(Self::CONTEST_PLACEHOLDER, ".+")
+                    .replacen(Self::PROBLEM_PLACEHOLDER, "(?P<problem>.+)", 1)
+                    .replace(Self::PROBLEM_PLACEHOLDER, ".+")
+            );
+            components.push(Regex::new(&pattern).context("Could not build path pattern")?);
+        }
+        Ok(TargetPattern { root, components })
     }
 }
 
@@ -175,6 +338,64 @@ impl fmt::Display for TargetTempl {
     }
 }
 
+/// Pattern of paths expanded from `TargetTempl`.
+#[derive(Debug, Clone)]
+pub struct TargetPattern {
+    root: PathBuf,
+    components: Vec<Regex>,
+}
+
+impl TargetPattern {
+    /// Searches for existing paths that match the pattern
+    /// and returns contest ids and problem ids found in the paths.
+    pub fn search(&self) -> Result<Vec<(ContestId, ProblemId)>> {
+        let mut found = Vec::new();
+        self.search_in(&self.root, 0, (None, None), &mut found)?;
+        found.sort();
+        found.dedup();
+        Ok(found)
+    }
+
+    fn search_in(
+        &self,
+        dir: &Path,
+        depth: usize,
+        ids: (Option<String>, Option<String>),
+        found: &mut Vec<(ContestId, ProblemId)>,
+    ) -> Result<()> {
+        let regex = match self.components.get(depth) {
+            Some(regex) => regex,
+            None => {
+                if let (Some(contest), Some(problem)) = ids {
+                    found.push((contest.into(), problem.into()));
+                }
+                return Ok(());
+            }
+        };
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(()), // skip if not a directory or not readable
+        };
+        for entry in entries {
+            let path = entry?.path();
+            let name = match path.file_name() {
+                Some(name) => name.to_string_lossy(),
+                None => continue,
+            };
+            if let Some(caps) = regex.captures(&name) {
+                let contest = caps.name("contest").map(|m| m.as_str().to_owned());
+                let problem = caps.name("problem").map(|m| m.as_str().to_owned());
+                let ids = (
+                    contest.or_else(|| ids.0.clone()),
+                    problem.or_else(|| ids.1.clone()),
+                );
+                self.search_in(&path, depth + 1, ids, found)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ProblemContext<'a> {
     service: &'a Service,
@@ -220,6 +441,99 @@ impl fmt::Display for ProblemTempl {
     }
 }
 
+/// Rule to choose template of source code for problems that match condition.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TemplateRule {
+    /// Tera expression evaluated with problem context (e.g. `problem.id in ["E", "F"]`)
+    when: String,
+    template: ProblemTempl,
+}
+
+impl TemplateRule {
+    /// Returns template of this rule if the condition holds for the problem.
+    pub fn select(
+        &self,
+        service: &Service,
+        contest: &Contest,
+        problem: &Problem,
+    ) -> Result<Option<&ProblemTempl>> {
+        let cond = ProblemTempl::from(format!("{{% if {} %}}true{{% endif %}}", self.when));
+        let result = cond
+            .expand_with(service, contest, problem)
+            .with_context(|| {
+                format!(
+                    "Could not evaluate condition of template rule : {}",
+                    self.when
+                )
+            })?;
+        Ok(if result == "true" {
+            Some(&self.template)
+        } else {
+            None
+        })
+    }
+}
+
+/// Additional file scaffolded together with source code (e.g. `Cargo.toml` of a Rust project).
+///
+/// It is never overwritten, even by `acick fetch --overwrite`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TemplateFile {
+    /// Path of the file, relative to base_dir
+    pub path: TargetTempl,
+    pub template: ProblemTempl,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommitContext<'a> {
+    #[serde(rename = "service")]
+    service_id: ServiceKind,
+    #[serde(rename = "contest")]
+    contest_id: &'a ContestId,
+    problem: &'a Problem,
+    verdict: &'a str,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommitTempl(String);
+
+impl CommitTempl {
+    pub fn expand_with(
+        &self,
+        service_id: ServiceKind,
+        contest_id: &ContestId,
+        problem: &Problem,
+        verdict: &str,
+    ) -> Result<String> {
+        self.expand(&CommitContext {
+            service_id,
+            contest_id,
+            problem,
+            verdict,
+        })
+    }
+}
+
+impl<'a> Expand<'a> for CommitTempl {
+    type Context = CommitContext<'a>;
+
+    fn get_template(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<T: Into<String>> From<T> for CommitTempl {
+    fn from(s: T) -> Self {
+        Self(s.into())
+    }
+}
+
+impl fmt::Display for CommitTempl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(transparent)]
 pub struct TemplArray<T>(Vec<T>);
@@ -254,16 +568,42 @@ impl<T: fmt::Display> fmt::Display for TemplArray<T> {
     }
 }
 
-pub type Shell = TemplArray<CmdTempl>;
+/// Shell used to run commands.
+///
+/// Either name of a preset shell or an array of templates of program and its arguments.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(untagged)]
+pub enum Shell {
+    Preset(ShellKind),
+    Custom(TemplArray<CmdTempl>),
+}
 
 impl Shell {
     pub fn exec(&self, cmd: &str) -> Result<Command> {
-        let cmd_context = CmdContext::new(cmd);
-        let cmd_expanded = self
-            .expand_all(&cmd_context)
-            .context("Could not expand shell template")?;
-        let mut command = Command::new(&cmd_expanded[0]);
-        command.args(&cmd_expanded[1..]).kill_on_drop(true);
+        Ok(Self::into_async(self.exec_std(cmd)?))
+    }
+
+    /// Converts blocking command into async one, which is killed when it is dropped.
+    pub fn into_async(command: process::Command) -> Command {
+        let mut command = Command::from(command);
+        command.kill_on_drop(true);
+        command
+    }
+
+    /// Returns blocking command, which is used where async runtime is not available.
+    pub fn exec_std(&self, cmd: &str) -> Result<process::Command> {
+        let command = match self {
+            Self::Preset(kind) => kind.command(cmd),
+            Self::Custom(templs) => {
+                let cmd_context = CmdContext::new(cmd);
+                let cmd_expanded = templs
+                    .expand_all(&cmd_context)
+                    .context("Could not expand shell template")?;
+                let mut command = process::Command::new(&cmd_expanded[0]);
+                command.args(&cmd_expanded[1..]);
+                command
+            }
+        };
         Ok(command)
     }
 
@@ -272,13 +612,26 @@ impl Shell {
         templ: &T,
         context: &<T as Expand<'a>>::Context,
     ) -> Result<Command> {
+        Ok(Self::into_async(self.exec_templ_std(templ, context)?))
+    }
+
+    pub fn exec_templ_std<'a, T: Expand<'a>>(
+        &self,
+        templ: &T,
+        context: &<T as Expand<'a>>::Context,
+    ) -> Result<process::Command> {
         let cmd = templ
             .expand(context)
             .context("Could not expand command template")?;
-        self.exec(&cmd)
+        self.exec_std(&cmd)
     }
 
     pub fn find_bash() -> PathBuf {
+        Self::search_bash().unwrap_or_else(|| PathBuf::from("bash"))
+    }
+
+    /// Returns path of bash found in PATH (or in the default install dirs on Windows).
+    pub fn search_bash() -> Option<PathBuf> {
         let env_path = env::var_os("PATH").unwrap_or_default();
         env::split_paths(&env_path)
             .chain(if cfg!(windows) {
@@ -298,17 +651,99 @@ impl Shell {
                 }
             })
             .find(|p| p.is_file() && p.to_str().is_some())
-            .unwrap_or_else(|| PathBuf::from("bash"))
     }
 }
 
 impl Default for Shell {
+    /// Uses bash if found, otherwise PowerShell on Windows.
     fn default() -> Self {
-        let bash = Self::find_bash();
-        (&[bash.to_str().unwrap(), "-eu", "-c", "{{ command }}"]).into()
+        match Self::search_bash() {
+            None if cfg!(windows) => Self::Preset(ShellKind::Powershell),
+            bash => {
+                let bash = bash.unwrap_or_else(|| PathBuf::from("bash"));
+                Self::Custom((&[bash.to_str().unwrap(), "-eu", "-c", "{{ command }}"]).into())
+            }
+        }
     }
 }
 
+impl fmt::Display for Shell {
+    /// Formats in yaml flow style.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Preset(kind) => serde_json::to_string(kind),
+            Self::Custom(templs) => serde_json::to_string(templs),
+        };
+        f.write_str(&s.map_err(|_| fmt::Error)?)
+    }
+}
+
+/// Preset shells that take care of quoting commands.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ShellKind {
+    Bash,
+    Powershell,
+    Cmd,
+}
+
+impl ShellKind {
+    fn command(self, cmd: &str) -> process::Command {
+        match self {
+            Self::Bash => {
+                let mut command = process::Command::new(Shell::find_bash());
+                command.args(&["-eu", "-c", cmd]);
+                command
+            }
+            Self::Powershell => {
+                // encoded command does not need any quoting
+                // (native commands that fail stop the script only on PowerShell 7.3 or later)
+                let script = format!(
+                    "$ErrorActionPreference = 'Stop'\n\
+                     $PSNativeCommandUseErrorActionPreference = $true\n\
+                     {}\n\
+                     exit $LASTEXITCODE",
+                    cmd
+                );
+                let program = if cfg!(windows) { "powershell" } else { "pwsh" };
+                let mut command = process::Command::new(program);
+                command.args(&[
+                    "-NoLogo",
+                    "-NoProfile",
+                    "-NonInteractive",
+                    "-EncodedCommand",
+                ]);
+                command.arg(encode_powershell_command(&script));
+                command
+            }
+            Self::Cmd => cmd_command(cmd),
+        }
+    }
+}
+
+/// Encodes script as the argument of `-EncodedCommand` (base64 of UTF-16LE).
+fn encode_powershell_command(script: &str) -> String {
+    let bytes: Vec<u8> = script.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    base64::encode(&bytes)
+}
+
+#[cfg(windows)]
+fn cmd_command(cmd: &str) -> process::Command {
+    use std::os::windows::process::CommandExt as _;
+
+    // cmd does not follow the quoting rules of msvcrt, so pass command line as is
+    let mut command = process::Command::new("cmd");
+    command.raw_arg(format!("/D /S /C \"{}\"", cmd));
+    command
+}
+
+#[cfg(not(windows))]
+fn cmd_command(cmd: &str) -> process::Command {
+    let mut command = process::Command::new("cmd");
+    command.args(&["/D", "/S", "/C", cmd]);
+    command
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -333,19 +768,93 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn expand_with_plugins() -> anyhow::Result<()> {
+        let shell = Shell::Preset(ShellKind::Bash);
+        let filters = vec![("test_upper".to_owned(), "tr a-z A-Z".to_owned())];
+        let functions = vec![(
+            "test_repeat".to_owned(),
+            "printf '%s%s\\n' $ACICK_ARG_S $ACICK_ARG_S".to_owned(),
+        )];
+        register_plugins(
+            &shell,
+            &filters.into_iter().collect(),
+            &functions.into_iter().collect(),
+        );
+        let templ = CmdTempl::from("{{ command | test_upper }}-{{ test_repeat(s=\"ab\") }}");
+        assert_eq!(templ.expand(&CmdContext::new("abc"))?, "ABC-abab");
+        Ok(())
+    }
+
+    #[test]
+    fn search_target_pattern() -> anyhow::Result<()> {
+        let test_dir = tempfile::tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        for path in &["atcoder/arc100/c", "atcoder/abc100/a", "atcoder/abc100/b"] {
+            let dir = base_dir.join(path);
+            dir.create_dir_all()?;
+            fs::write(dir.join("problem.yaml").as_ref(), "")?;
+        }
+        base_dir.join("atcoder/abc101/a").create_dir_all()?;
+
+        let templ =
+            TargetTempl::from("{{ service }}/{{ contest }}/{{ problem | lower }}/problem.yaml");
+        let actual = templ
+            .to_pattern(ServiceKind::Atcoder, &base_dir)?
+            .search()?;
+        let expected: Vec<(ContestId, ProblemId)> = vec![
+            ("abc100".into(), "a".into()),
+            ("abc100".into(), "b".into()),
+            ("arc100".into(), "c".into()),
+        ];
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+
     #[test]
     fn expand_default_shell() -> anyhow::Result<()> {
-        let shell = Shell::default();
-        let cmd_context = CmdContext::new("echo hello");
-        shell.expand_all(&cmd_context)?;
+        Shell::default().exec("echo hello")?;
         Ok(())
     }
 
     #[test]
     fn expand_shell_failure() -> anyhow::Result<()> {
-        let shell = Shell::from(&["/bin/bash", "-c", "{{ some_undefined_variable }}"]);
-        let cmd_context = CmdContext::new("echo hello");
-        assert!(shell.expand_all(&cmd_context).is_err());
+        let shell = Shell::Custom((&["/bin/bash", "-c", "{{ some_undefined_variable }}"]).into());
+        assert!(shell.exec("echo hello").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_shell() -> anyhow::Result<()> {
+        let shell: Shell = serde_yaml::from_str("powershell")?;
+        assert_eq!(shell, Shell::Preset(ShellKind::Powershell));
+        let shell: Shell = serde_yaml::from_str(&Shell::default().to_string())?;
+        assert_eq!(shell, Shell::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_powershell_command() {
+        assert_eq!(encode_powershell_command("echo 1"), "ZQBjAGgAbwAgADEA");
+    }
+
+    #[cfg(windows)]
+    #[tokio::test]
+    async fn exec_preset_shells() -> anyhow::Result<()> {
+        let cases = [
+            (
+                ShellKind::Powershell,
+                r#"Write-Output "hello world"; exit 3"#,
+            ),
+            (ShellKind::Cmd, r#"echo "hello world" & exit 3"#),
+        ];
+        for (kind, cmd) in &cases {
+            let mut command = Shell::Preset(*kind).exec(cmd)?;
+            let output = command.output().await?;
+            assert_eq!(output.status.code(), Some(3));
+            assert!(String::from_utf8_lossy(&output.stdout).contains("hello world"));
+        }
         Ok(())
     }
 
@@ -0,0 +1,175 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash as _, Hasher as _};
+use std::time::Duration;
+
+use acick_judge::{Status, StatusKind};
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Compare, ProblemId, Sample};
+use crate::store::StateStore;
+use crate::{Config, Result};
+
+static TEST_CACHE_FILE_NAME: &str = ".acick-test.jsonl";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+struct CachedStatus {
+    fingerprint: String,
+    status: Status,
+}
+
+/// Cache of results of testcases, which tells whether a testcase has been tested
+/// with the current binary, run command and settings of judge.
+///
+/// The cache is stored in working_dir of the problem and has one entry for each testcase.
+#[derive(Debug, Clone)]
+pub struct TestCache {
+    store: StateStore,
+    key_prefix: String,
+    hasher: DefaultHasher,
+}
+
+impl TestCache {
+    /// Returns the status saved for the same input and expected output of the testcase.
+    pub fn get(&self, sample: &Sample) -> Result<Option<Status>> {
+        let cached: Option<CachedStatus> = self.store.get(&self.key(sample))?;
+        Ok(cached
+            .filter(|cached| cached.fingerprint == self.fingerprint(sample))
+            .map(|cached| cached.status))
+    }
+
+    /// Saves the status of the testcase.
+    ///
+    /// TLE is not saved because it depends on load of the machine.
+    pub fn save(&self, sample: &Sample, status: &Status) -> Result<()> {
+        if status.kind() == StatusKind::Tle {
+            return self.store.remove(&self.key(sample));
+        }
+        let cached = CachedStatus {
+            fingerprint: self.fingerprint(sample),
+            status: status.clone(),
+        };
+        self.store.set(&self.key(sample), &cached)
+    }
+
+    fn key(&self, sample: &Sample) -> String {
+        format!("{}/{}", self.key_prefix, sample.name())
+    }
+
+    fn fingerprint(&self, sample: &Sample) -> String {
+        let mut hasher = self.hasher.clone();
+        sample.input().hash(&mut hasher);
+        sample.output().hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+}
+
+impl Config {
+    /// Returns test cache of the problem with fingerprint of the current source code, artifact,
+    /// run command and settings of judge.
+    pub fn test_cache(
+        &self,
+        problem_id: &ProblemId,
+        time_limit: Duration,
+        compare: Compare,
+    ) -> Result<TestCache> {
+        let (source, artifact) = self.read_binary(problem_id)?;
+
+        // DefaultHasher may change between versions of Rust, which only causes extra tests
+        let mut hasher = DefaultHasher::new();
+        let service = self.service();
+        self.body.shell.hash(&mut hasher);
+        service.run.hash(&mut hasher);
+        service.env.hash(&mut hasher);
+        service.stdin_file.hash(&mut hasher);
+        service.stdout_file.hash(&mut hasher);
+        self.test().output_filter().hash(&mut hasher);
+        time_limit.hash(&mut hasher);
+        compare.hash(&mut hasher);
+        source.hash(&mut hasher);
+        artifact.hash(&mut hasher);
+
+        let store = StateStore::new(self.working_abs_dir(problem_id)?.join(TEST_CACHE_FILE_NAME));
+        Ok(TestCache {
+            store,
+            key_prefix: format!("{}/{}/{}", self.service_id, self.contest_id, problem_id),
+            hasher,
+        })
+    }
+
+    /// Returns hash of the current source code and artifact,
+    /// which tells whether outcomes of tests are of the same binary.
+    pub fn binary_hash(&self, problem_id: &ProblemId) -> Result<String> {
+        let (source, artifact) = self.read_binary(problem_id)?;
+        // DefaultHasher has fixed keys, so the hash is the same across machines
+        // as long as acick is built with the same version of Rust
+        let mut hasher = DefaultHasher::new();
+        source.hash(&mut hasher);
+        artifact.hash(&mut hasher);
+        Ok(format!("{:016x}", hasher.finish()))
+    }
+
+    /// Reads the source code and the artifact, which does not exist for interpreted languages.
+    fn read_binary(&self, problem_id: &ProblemId) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
+        let source_abs_path = self.source_abs_path(problem_id)?;
+        let source = fs::read(source_abs_path.as_ref())
+            .with_context(|| format!("Could not read source file : {}", source_abs_path))?;
+        let artifact_abs_path = self
+            .artifact_abs_paths(problem_id)?
+            .into_iter()
+            .next()
+            .context("Could not get path of artifact")?;
+        // interpreted languages have no artifact
+        let artifact = if artifact_abs_path.as_ref().is_file() {
+            Some(
+                fs::read(artifact_abs_path.as_ref())
+                    .with_context(|| format!("Could not read artifact : {}", artifact_abs_path))?,
+            )
+        } else {
+            None
+        };
+        Ok((source, artifact))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::abs_path::AbsPathBuf;
+
+    #[test]
+    fn test_get_and_save() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let conf = Config::default_in_dir(AbsPathBuf::try_new(test_dir.path())?);
+        let problem_id = ProblemId::from("C");
+        let time_limit = Duration::from_secs(2);
+        let source_abs_path = conf.source_abs_path(&problem_id)?;
+        source_abs_path.parent().unwrap().create_dir_all()?;
+        fs::write(source_abs_path.as_ref(), "int main() {}\n")?;
+
+        let sample = Sample::new("01", "1\n", "2\n");
+        let status = Status::ac(String::from("01"), Duration::from_millis(10));
+        let cache = conf.test_cache(&problem_id, time_limit, Compare::Default)?;
+        assert_eq!(cache.get(&sample)?, None);
+        cache.save(&sample, &status)?;
+        assert_eq!(cache.get(&sample)?, Some(status.clone()));
+
+        // changes of testcase, settings of judge or binary invalidate the result
+        assert_eq!(cache.get(&Sample::new("01", "1\n", "3\n"))?, None);
+        let cache = conf.test_cache(&problem_id, time_limit * 2, Compare::Default)?;
+        assert_eq!(cache.get(&sample)?, None);
+        let binary_hash = conf.binary_hash(&problem_id)?;
+        let artifact = &conf.artifact_abs_paths(&problem_id)?[0];
+        fs::write(artifact.as_ref(), "binary")?;
+        let cache = conf.test_cache(&problem_id, time_limit, Compare::Default)?;
+        assert_eq!(cache.get(&sample)?, None);
+        assert_ne!(conf.binary_hash(&problem_id)?, binary_hash);
+
+        cache.save(&sample, &Status::tle(String::from("01"), time_limit))?;
+        assert_eq!(cache.get(&sample)?, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::abs_path::AbsPathBuf;
+
+/// Storage from which `acick fetch --full` downloads testcases.
+///
+/// Storages are expected to have the same structure as the shared folder on Dropbox,
+/// i.e. testcases of a problem in `<contest>/<problem>/{in,out}/`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TestcasesSource {
+    /// Shared folder on Dropbox published by the service
+    Dropbox,
+    /// Local directory that mirrors the shared folder (e.g. synced by Dropbox client)
+    Local { dir: AbsPathBuf },
+}
+
+impl Default for TestcasesSource {
+    fn default() -> Self {
+        Self::Dropbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() -> anyhow::Result<()> {
+        let source: TestcasesSource = serde_yaml::from_str("type: dropbox")?;
+        assert_eq!(source, TestcasesSource::Dropbox);
+
+        let source: TestcasesSource = serde_yaml::from_str("{ type: local, dir: /tmp/testcases }")?;
+        assert_eq!(
+            source,
+            TestcasesSource::Local {
+                dir: AbsPathBuf::try_new("/tmp/testcases")?
+            }
+        );
+
+        assert!(serde_yaml::from_str::<TestcasesSource>("type: s3").is_err());
+        Ok(())
+    }
+}
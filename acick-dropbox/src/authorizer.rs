@@ -3,24 +3,46 @@ use std::convert::Infallible;
 use std::io::{Read, Write};
 use std::net::SocketAddr;
 
-use anyhow::Context as _;
-use dropbox_sdk::default_client::NoauthDefaultClient;
-use dropbox_sdk::oauth2::{Authorization, AuthorizeUrlBuilder, Oauth2Type, PkceCode};
+use anyhow::{anyhow, Context as _};
 use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Method, Request, Response, Server, StatusCode, Uri};
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng as _};
+use ring::digest::{digest, SHA256};
+use serde::Deserialize;
 use tokio::sync::broadcast::{self, Sender};
-use url::form_urlencoded;
+use url::{form_urlencoded, Url};
 
-use crate::abs_path::AbsPathBuf;
+use crate::abs_path::{restrict_to_owner, AbsPathBuf};
 use crate::web::open_in_browser;
-use crate::{Dropbox, Result};
+use crate::{is_auth_error, AuthError, Dropbox, Error, Result};
 
 static STATE_LEN: usize = 16;
+// length of PKCE code verifier, which must be between 43 and 128 (RFC 7636)
+static CODE_VERIFIER_LEN: usize = 128;
 static DBX_CODE_PARAM: &str = "code";
 static DBX_STATE_PARAM: &str = "state";
+static DBX_AUTHORIZE_URL: &str = "https://www.dropbox.com/oauth2/authorize";
+static DBX_TOKEN_URL: &str = "https://api.dropboxapi.com/oauth2/token";
+
+/// Response of OAuth token endpoint.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+struct TokenResponse {
+    access_token: String,
+    /// Returned only when authorization code is exchanged
+    refresh_token: Option<String>,
+}
+
+/// Error response of OAuth token endpoint (RFC 6749).
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+struct TokenError {
+    error: String,
+}
 
+/// Authorizes acick on Dropbox with OAuth code flow with PKCE.
+///
+/// Refresh token is saved in `token_path`,
+/// and all requests are sent through `client` so that its settings (e.g. proxy) apply.
 #[derive(Debug, Clone)]
 pub struct DbxAuthorizer<'a> {
     client_id: &'a str,
@@ -28,7 +50,8 @@ pub struct DbxAuthorizer<'a> {
     redirect_path: &'a str,
     redirect_uri: String,
     token_path: &'a AbsPathBuf,
-    oauth2_flow: Oauth2Type,
+    client: reqwest::Client,
+    code_verifier: String,
 }
 
 impl<'a> DbxAuthorizer<'a> {
@@ -37,6 +60,7 @@ impl<'a> DbxAuthorizer<'a> {
         redirect_port: u16,
         redirect_path: &'a str,
         token_path: &'a AbsPathBuf,
+        client: reqwest::Client,
     ) -> Self {
         Self {
             client_id,
@@ -44,63 +68,121 @@ impl<'a> DbxAuthorizer<'a> {
             redirect_path,
             redirect_uri: format!("http://localhost:{}{}", redirect_port, redirect_path),
             token_path,
-            oauth2_flow: Oauth2Type::PKCE(PkceCode::new()),
+            client,
+            code_verifier: gen_random_string(CODE_VERIFIER_LEN),
         }
     }
 
-    pub fn load_or_request(
+    pub async fn load_or_request(
         &self,
         access_token: Option<String>,
         cnsl: &mut dyn Write,
     ) -> Result<Dropbox> {
-        let load_result = self.load_token(access_token, cnsl)?;
-        let (mut auth, is_updated) = match load_result {
-            Some(auth) => (auth, false),
-            _ => (self.request_token(cnsl)?, true),
+        if let Some(access_token) = access_token {
+            return Ok(Dropbox::new(self.client.clone(), access_token));
+        }
+        let refresh_token = match self.load_token(cnsl)? {
+            Some(refresh_token) => refresh_token,
+            None => return self.request_and_save(cnsl).await,
         };
 
-        let client = NoauthDefaultClient::default();
-        auth.obtain_access_token(client)
-            .context("Failed to obtain dropbox access token")?;
-
-        if is_updated {
-            self.save_token(&auth, cnsl)?;
+        let params = [
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token.as_str()),
+            ("client_id", self.client_id),
+        ];
+        match self.request_token(&params).await {
+            Ok(token) => Ok(Dropbox::new(self.client.clone(), token.access_token)),
+            // stored refresh token may have been revoked
+            Err(err) if is_auth_error(&err) => self.reauthorize(cnsl).await,
+            Err(err) => Err(err).context("Failed to obtain dropbox access token"),
         }
+    }
 
-        Ok(Dropbox::new(auth))
+    /// Removes stale token file and authorizes acick on Dropbox again in web browser.
+    pub async fn reauthorize(&self, cnsl: &mut dyn Write) -> Result<Dropbox> {
+        writeln!(
+            cnsl,
+            "Stored Dropbox authorization is expired or revoked. Authorizing again ..."
+        )?;
+        self.remove_token()?;
+        self.request_and_save(cnsl).await
     }
 
-    fn load_token(
-        &self,
-        access_token: Option<String>,
-        cnsl: &mut dyn Write,
-    ) -> Result<Option<Authorization>> {
-        if let Some(access_token) = access_token {
-            return Ok(Some(Authorization::from_access_token(access_token)));
+    async fn request_and_save(&self, cnsl: &mut dyn Write) -> Result<Dropbox> {
+        let state = gen_random_string(STATE_LEN);
+        let auth_code = self
+            .authorize(state, cnsl)
+            .await
+            .context("Could not authorize acick on Dropbox")?;
+
+        let params = [
+            ("grant_type", "authorization_code"),
+            ("code", auth_code.trim()),
+            ("client_id", self.client_id),
+            ("code_verifier", self.code_verifier.as_str()),
+            ("redirect_uri", self.redirect_uri.as_str()),
+        ];
+        let token = self
+            .request_token(&params)
+            .await
+            .context("Failed to obtain dropbox access token")?;
+        let refresh_token = token
+            .refresh_token
+            .context("Could not find refresh token in response from Dropbox")?;
+        self.save_token(&refresh_token, cnsl)?;
+        Ok(Dropbox::new(self.client.clone(), token.access_token))
+    }
+
+    /// Sends request to OAuth token endpoint.
+    async fn request_token(&self, params: &[(&str, &str)]) -> Result<TokenResponse> {
+        let res = self
+            .client
+            .post(DBX_TOKEN_URL)
+            .form(params)
+            .send()
+            .await
+            .context("Could not send request to Dropbox")?;
+        let status = res.status();
+        let body = res
+            .bytes()
+            .await
+            .context("Could not receive response from Dropbox")?;
+        if !status.is_success() {
+            return Err(token_error(status, &body));
         }
+        serde_json::from_slice(&body).context("Could not parse token from Dropbox")
+    }
 
+    /// Loads refresh token, or `None` if no valid token is saved.
+    fn load_token(&self, cnsl: &mut dyn Write) -> Result<Option<String>> {
         if !self.token_path.as_ref().exists() {
             return Ok(None);
         }
 
-        let auth = self.token_path.load_pretty(
+        let token = self.token_path.load_pretty(
             |mut file| {
                 let mut buf = String::new();
                 file.read_to_string(&mut buf)
                     .context("Could not load token from file")?;
-                Ok(Authorization::load(self.client_id.to_string(), &buf))
+                Ok(buf.trim().to_owned())
             },
             None,
             cnsl,
         )?;
 
-        Ok(auth)
+        // token saved by older versions (e.g. `1&<access token>`) is no longer used
+        if token.is_empty() || token.contains('&') {
+            return Ok(None);
+        }
+        Ok(Some(token))
     }
 
-    fn save_token(&self, auth: &Authorization, cnsl: &mut dyn Write) -> Result<()> {
+    fn save_token(&self, refresh_token: &str, cnsl: &mut dyn Write) -> Result<()> {
         self.token_path.save_pretty(
             |mut file| {
-                file.write_all(auth.save().unwrap_or_default().as_bytes())
+                restrict_to_owner(&file).context("Could not restrict permissions of token file")?;
+                file.write_all(refresh_token.as_bytes())
                     .context("Could not save token as file")
             },
             true,
@@ -111,22 +193,12 @@ impl<'a> DbxAuthorizer<'a> {
         Ok(())
     }
 
-    #[tokio::main]
-    async fn request_token(&self, cnsl: &mut dyn Write) -> Result<Authorization> {
-        let state = gen_random_state();
-        let auth_code = self
-            .authorize(state, cnsl)
-            .await
-            .context("Could not authorize acick on Dropbox")?;
-
-        let auth = Authorization::from_auth_code(
-            self.client_id.to_string(),
-            self.oauth2_flow.clone(),
-            auth_code.trim().to_owned(),
-            Some(self.redirect_uri.to_owned()),
-        );
-
-        Ok(auth)
+    fn remove_token(&self) -> Result<()> {
+        if self.token_path.as_ref().exists() {
+            std::fs::remove_file(self.token_path.as_ref())
+                .with_context(|| format!("Could not remove token file : {}", self.token_path))?;
+        }
+        Ok(())
     }
 
     async fn authorize(&self, state: String, cnsl: &mut dyn Write) -> Result<String> {
@@ -147,10 +219,7 @@ impl<'a> DbxAuthorizer<'a> {
         let server = Server::bind(&addr).serve(make_service);
 
         // open auth url in browser
-        let auth_url = AuthorizeUrlBuilder::new(self.client_id, &self.oauth2_flow)
-            .redirect_uri(&self.redirect_uri)
-            .state(&state)
-            .build();
+        let auth_url = self.authorize_url(&state);
         open_in_browser(auth_url.as_str())
             .context("Could not open a url in browser")
             // coerce error
@@ -166,13 +235,45 @@ impl<'a> DbxAuthorizer<'a> {
 
         Ok(rx.recv().await?)
     }
+
+    fn authorize_url(&self, state: &str) -> Url {
+        let mut url = Url::parse(DBX_AUTHORIZE_URL).expect("Authorize url should be valid");
+        url.query_pairs_mut()
+            .append_pair("client_id", self.client_id)
+            .append_pair("response_type", "code")
+            // issue refresh token
+            .append_pair("token_access_type", "offline")
+            .append_pair("code_challenge", &code_challenge(&self.code_verifier))
+            .append_pair("code_challenge_method", "S256")
+            .append_pair("redirect_uri", &self.redirect_uri)
+            .append_pair("state", state);
+        url
+    }
+}
+
+/// Returns error from error response of token endpoint.
+///
+/// `invalid_grant` means that authorization code or refresh token is revoked or expired.
+fn token_error(status: reqwest::StatusCode, body: &[u8]) -> Error {
+    let msg = format!(
+        "Dropbox returned {} : {}",
+        status,
+        String::from_utf8_lossy(body)
+    );
+    match serde_json::from_slice::<TokenError>(body) {
+        Ok(TokenError { error }) if error == "invalid_grant" => Error::new(AuthError(msg)),
+        _ => anyhow!(msg),
+    }
 }
 
-fn gen_random_state() -> String {
-    thread_rng()
-        .sample_iter(&Alphanumeric)
-        .take(STATE_LEN)
-        .collect()
+fn gen_random_string(len: usize) -> String {
+    thread_rng().sample_iter(&Alphanumeric).take(len).collect()
+}
+
+/// Returns S256 code challenge of PKCE (RFC 7636).
+fn code_challenge(code_verifier: &str) -> String {
+    let hash = digest(&SHA256, code_verifier.as_bytes());
+    base64::encode_config(hash.as_ref(), base64::URL_SAFE_NO_PAD)
 }
 
 fn get_params(uri: &Uri) -> HashMap<String, String> {
@@ -261,34 +362,32 @@ mod tests {
     fn run_test(f: fn(test_dir: &TempDir, authorizer: DbxAuthorizer) -> anyhow::Result<()>) {
         let test_dir = tempdir().unwrap();
         let token_path = AbsPathBuf::try_new(test_dir.path().join("dbx_token.txt")).unwrap();
-        let authorizer = DbxAuthorizer::new("test_id", 4100, "/path", &token_path);
+        let authorizer = DbxAuthorizer::new(
+            "test_id",
+            4100,
+            "/path",
+            &token_path,
+            reqwest::Client::new(),
+        );
         f(&test_dir, authorizer).unwrap();
     }
 
     #[test]
     fn test_load_token() {
         run_test(|_, authorizer| {
-            let access_token = "test_token".to_string();
-            let auth = Authorization::from_access_token(access_token.to_owned());
             let mut buf = Vec::new();
-
-            let actual = authorizer
-                .load_token(Some(access_token), &mut buf)?
-                .and_then(|auth| auth.save());
-            let expected = auth.save();
-            assert_eq!(actual, expected);
-
-            assert!(authorizer.load_token(None, &mut buf)?.is_none());
+            assert_eq!(authorizer.load_token(&mut buf)?, None);
 
             let token_path = authorizer.token_path.as_ref();
-            let mut file = std::fs::File::create(token_path)?;
-            file.write_all(b"1&test_token")?;
-
-            let actual = authorizer
-                .load_token(None, &mut buf)?
-                .and_then(|auth| auth.save());
-            assert_eq!(actual, expected);
-
+            std::fs::write(token_path, "test_token\n")?;
+            assert_eq!(
+                authorizer.load_token(&mut buf)?,
+                Some(String::from("test_token"))
+            );
+
+            // token saved by older versions
+            std::fs::write(token_path, "1&test_token")?;
+            assert_eq!(authorizer.load_token(&mut buf)?, None);
             Ok(())
         })
     }
@@ -296,21 +395,77 @@ mod tests {
     #[test]
     fn test_save_token() {
         run_test(|_, authorizer| {
-            let access_token = "test_token".to_string();
-            let auth = Authorization::from_access_token(access_token);
             let mut buf = Vec::<u8>::new();
-            authorizer.save_token(&auth, &mut buf)?;
+            authorizer.save_token("test_token", &mut buf)?;
             let token_str = std::fs::read_to_string(authorizer.token_path.as_ref())?;
-            assert_eq!(token_str, "1&test_token");
+            assert_eq!(token_str, "test_token");
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_remove_token() {
+        run_test(|_, authorizer| {
+            std::fs::write(authorizer.token_path.as_ref(), "test_token")?;
+            authorizer.remove_token()?;
+            assert!(!authorizer.token_path.as_ref().exists());
+            // removing missing token is not an error
+            authorizer.remove_token()?;
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_authorize_url() {
+        run_test(|_, authorizer| {
+            let url = authorizer.authorize_url("test_state");
+            let params = get_params(&url.as_str().parse()?);
+            assert_eq!(params["client_id"], "test_id");
+            assert_eq!(params["redirect_uri"], "http://localhost:4100/path");
+            assert_eq!(params["state"], "test_state");
+            assert_eq!(
+                params["code_challenge"],
+                code_challenge(&authorizer.code_verifier)
+            );
             Ok(())
         })
     }
 
+    #[test]
+    fn test_code_challenge() {
+        // example in RFC 7636
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCQaoeKvLo3Rvwmn2y1OkcEM"
+        );
+    }
+
+    #[test]
+    fn test_token_error() {
+        let body =
+            br#"{"error": "invalid_grant", "error_description": "refresh token is invalid"}"#;
+        assert!(is_auth_error(&token_error(
+            reqwest::StatusCode::BAD_REQUEST,
+            body
+        )));
+        let body = br#"{"error": "invalid_request"}"#;
+        assert!(!is_auth_error(&token_error(
+            reqwest::StatusCode::BAD_REQUEST,
+            body
+        )));
+    }
+
     #[tokio::test]
     async fn test_authorize() -> anyhow::Result<()> {
         let test_dir = tempdir().unwrap();
         let token_path = AbsPathBuf::try_new(test_dir.path().join("dbx_token.txt")).unwrap();
-        let authorizer = DbxAuthorizer::new("test_id", 4100, "/path", &token_path);
+        let authorizer = DbxAuthorizer::new(
+            "test_id",
+            4100,
+            "/path",
+            &token_path,
+            reqwest::Client::new(),
+        );
         let mut buf = Vec::<u8>::new();
         let future = authorizer.authorize("test_state".to_string(), &mut buf);
 
@@ -327,9 +482,9 @@ mod tests {
     }
 
     #[test]
-    fn test_gen_random_state() {
-        assert_eq!(gen_random_state().len(), STATE_LEN);
-        assert_ne!(gen_random_state(), gen_random_state());
+    fn test_gen_random_string() {
+        assert_eq!(gen_random_string(STATE_LEN).len(), STATE_LEN);
+        assert_ne!(gen_random_string(STATE_LEN), gen_random_string(STATE_LEN));
     }
 
     #[test]
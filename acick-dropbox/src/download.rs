@@ -0,0 +1,185 @@
+//! Async downloads of files in shared links of Dropbox.
+
+use std::fs;
+use std::io::{Seek as _, SeekFrom, Write as _};
+use std::ops::Range;
+
+use anyhow::Context as _;
+use futures::future::try_join_all;
+use reqwest::header::{AUTHORIZATION, RANGE};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+
+use crate::abs_path::AbsPathBuf;
+use crate::throttle::Throttle;
+use crate::{check_status, Result};
+
+static SHARED_LINK_FILE_URL: &str = "https://content.dropboxapi.com/2/sharing/get_shared_link_file";
+static API_ARG_HEADER: &str = "Dropbox-API-Arg";
+
+/// Files larger than this are downloaded as ranges in parallel.
+const RANGE_THRESHOLD: u64 = 8 * 1024 * 1024;
+/// Size of each range of a large file.
+const RANGE_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Downloader of files in shared links of Dropbox.
+///
+/// Requests are sent through one client, which reuses connections to Dropbox.
+#[derive(Debug, Clone)]
+pub struct Downloader {
+    client: Client,
+    access_token: String,
+}
+
+impl Downloader {
+    /// Creates downloader that sends requests through `client` (e.g. with proxy).
+    pub fn new(client: Client, access_token: String) -> Self {
+        Self {
+            client,
+            access_token,
+        }
+    }
+
+    /// Downloads the file at `path` in the shared link to `abs_path`,
+    /// calling `on_progress` with the number of bytes received.
+    ///
+    /// `size` is the size of the file listed by Dropbox,
+    /// which decides whether the file is downloaded as ranges in parallel.
+    pub async fn download_shared_link_file(
+        &self,
+        url: &str,
+        path: &str,
+        size: u64,
+        abs_path: &AbsPathBuf,
+        throttle: Option<&Throttle>,
+        on_progress: &(dyn Fn(u64) + Sync),
+    ) -> Result<()> {
+        let ranges = if size > RANGE_THRESHOLD {
+            split_ranges(size, RANGE_SIZE)
+        } else {
+            vec![0..size]
+        };
+        let is_partial = ranges.len() > 1;
+
+        // chunks are written to the file as soon as received, at their offset in the file
+        let mut file = None;
+        abs_path.save(
+            |opened| {
+                if is_partial {
+                    opened
+                        .set_len(size)
+                        .context("Could not allocate downloaded file")?;
+                }
+                file = Some(opened);
+                Ok(())
+            },
+            true,
+        )?;
+        let file = file.context("Could not open downloaded file")?;
+        let result = try_join_all(ranges.into_iter().map(|range| {
+            let range = if is_partial { Some(range) } else { None };
+            self.download_range(url, path, range, &file, throttle, on_progress)
+        }))
+        .await;
+        if result.is_err() {
+            // remove incomplete file, ignoring error because the download error matters more
+            drop(file);
+            let _ = fs::remove_file(abs_path.as_ref());
+        }
+        result.map(|_| ())
+    }
+
+    /// Downloads the range of the file (or the whole file if `range` is None),
+    /// writing it to `file` at the same position.
+    async fn download_range(
+        &self,
+        url: &str,
+        path: &str,
+        range: Option<Range<u64>>,
+        mut file: &fs::File,
+        throttle: Option<&Throttle>,
+        on_progress: &(dyn Fn(u64) + Sync),
+    ) -> Result<()> {
+        let api_arg = json!({ "url": url, "path": path });
+        let mut req = self
+            .client
+            .post(SHARED_LINK_FILE_URL)
+            .header(AUTHORIZATION, format!("Bearer {}", self.access_token))
+            .header(API_ARG_HEADER, escape_non_ascii(&api_arg.to_string()));
+        if let Some(range) = &range {
+            req = req.header(RANGE, format!("bytes={}-{}", range.start, range.end - 1));
+        }
+        let res = req
+            .send()
+            .await
+            .with_context(|| format!("Could not send request to Dropbox : {}", path))?;
+        let expected = if range.is_some() {
+            StatusCode::PARTIAL_CONTENT
+        } else {
+            StatusCode::OK
+        };
+        let mut res = check_status(res, expected).await?;
+
+        let mut pos = range.map_or(0, |range| range.start);
+        while let Some(chunk) = res
+            .chunk()
+            .await
+            .with_context(|| format!("Could not receive file from Dropbox : {}", path))?
+        {
+            if let Some(throttle) = throttle {
+                throttle.consume_async(chunk.len()).await;
+            }
+            // seek and write without await between, so that writes of the other ranges
+            // do not move the shared cursor in the meantime
+            file.seek(SeekFrom::Start(pos))
+                .and_then(|_| file.write_all(&chunk))
+                .context("Could not save downloaded file")?;
+            pos += chunk.len() as u64;
+            on_progress(chunk.len() as u64);
+        }
+        Ok(())
+    }
+}
+
+/// Splits `0..size` into ranges of at most `range_size` bytes.
+fn split_ranges(size: u64, range_size: u64) -> Vec<Range<u64>> {
+    (0..size)
+        .step_by(range_size as usize)
+        .map(|start| start..(start + range_size).min(size))
+        .collect()
+}
+
+/// Escapes non-ASCII chars in json, which is required in headers of Dropbox API.
+fn escape_non_ascii(json: &str) -> String {
+    let mut escaped = String::with_capacity(json.len());
+    for c in json.chars() {
+        if c.is_ascii() {
+            escaped.push(c);
+        } else {
+            let mut buf = [0; 2];
+            for unit in c.encode_utf16(&mut buf) {
+                escaped.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_ranges() {
+        assert_eq!(split_ranges(10, 4), vec![0..4, 4..8, 8..10]);
+        assert_eq!(split_ranges(8, 4), vec![0..4, 4..8]);
+        assert!(split_ranges(0, 4).is_empty());
+    }
+
+    #[test]
+    fn test_escape_non_ascii() {
+        assert_eq!(escape_non_ascii(r#"{"path":"/a"}"#), r#"{"path":"/a"}"#);
+        assert_eq!(escape_non_ascii("é"), "\\u00e9");
+        assert_eq!(escape_non_ascii("😀"), "\\ud83d\\ude00");
+    }
+}
@@ -1,47 +1,59 @@
 use std::fmt;
-use std::io::Read;
+use std::mem;
 
-use anyhow::anyhow;
-use dropbox_sdk::default_client::UserAuthDefaultClient;
-use dropbox_sdk::files::{
-    self, FileMetadata, FolderMetadata, ListFolderArg, ListFolderContinueArg, ListFolderCursor,
-    Metadata, PathROrId, SharedLink,
-};
-use dropbox_sdk::oauth2::Authorization;
-use dropbox_sdk::sharing::{self, GetSharedLinkFileArg, Path};
+use anyhow::Context as _;
+use dropbox_sdk::files::{FileMetadata, FolderMetadata, ListFolderResult, Metadata};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
 
-use crate::convert_dbx_err;
-use crate::Result;
+use crate::download::Downloader;
+use crate::{check_status, Result};
 
+static LIST_FOLDER_URL: &str = "https://api.dropboxapi.com/2/files/list_folder";
+static LIST_FOLDER_CONTINUE_URL: &str = "https://api.dropboxapi.com/2/files/list_folder/continue";
+
+/// Client of Dropbox API on behalf of an account.
+///
+/// Requests are sent through `client`, so that its settings (e.g. proxy) apply to all of them.
+#[derive(Clone)]
 pub struct Dropbox {
-    client: UserAuthDefaultClient,
+    client: Client,
+    access_token: String,
 }
 
 impl fmt::Debug for Dropbox {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Dropbox")
-            .field("client", &"client")
+            .field("client", &self.client)
+            .field("access_token", &"********")
             .finish()
     }
 }
 
 impl Dropbox {
-    pub fn new(auth: Authorization) -> Self {
-        let client = UserAuthDefaultClient::new(auth);
-        Self { client }
+    /// Creates client that sends requests through `client` with the access token.
+    pub fn new(client: Client, access_token: String) -> Self {
+        Self {
+            client,
+            access_token,
+        }
     }
 
-    pub fn from_access_token(access_token: String) -> Self {
-        Self::new(Authorization::from_access_token(access_token))
+    /// Returns downloader of files that shares client with this.
+    pub fn downloader(&self) -> Downloader {
+        Downloader::new(self.client.clone(), self.access_token.clone())
     }
 
-    pub fn list_all_folders<P: Into<PathROrId>>(
+    pub async fn list_all_folders(
         &self,
-        path: P,
+        path: &str,
         shared_link_url: Option<&str>,
     ) -> Result<Vec<FolderMetadata>> {
         let folders = self
-            .list_folder(path, shared_link_url)?
+            .list_folder(path, shared_link_url)
+            .await?
             .into_iter()
             .filter_map(|meta| match meta {
                 Metadata::Folder(folder_meta) => Some(folder_meta),
@@ -52,13 +64,14 @@ impl Dropbox {
         Ok(folders)
     }
 
-    pub fn list_all_files<P: Into<PathROrId>>(
+    pub async fn list_all_files(
         &self,
-        path: P,
+        path: &str,
         shared_link_url: Option<&str>,
     ) -> Result<Vec<FileMetadata>> {
         let files = self
-            .list_folder(path, shared_link_url)?
+            .list_folder(path, shared_link_url)
+            .await?
             .into_iter()
             .filter_map(|meta| match meta {
                 Metadata::File(file_meta) => Some(file_meta),
@@ -69,52 +82,44 @@ impl Dropbox {
         Ok(files)
     }
 
-    fn list_folder<P: Into<PathROrId>>(
+    async fn list_folder(
         &self,
-        path: P,
+        path: &str,
         shared_link_url: Option<&str>,
     ) -> Result<Vec<Metadata>> {
-        let mut arg = ListFolderArg::new(path.into());
+        let mut arg = json!({ "path": path });
         if let Some(shared_link_url) = shared_link_url {
-            let shared_link = SharedLink::new(shared_link_url.to_owned());
-            arg = arg.with_shared_link(shared_link);
+            arg["shared_link"] = json!({ "url": shared_link_url });
         }
-        let res = files::list_folder(&self.client, &arg).map_err(convert_dbx_err)??;
+        let mut res: ListFolderResult = self.call(LIST_FOLDER_URL, &arg).await?;
 
-        let mut folders = res.entries;
-        if res.has_more {
-            self.list_folder_continue(res.cursor, &mut folders)?;
+        let mut entries = mem::take(&mut res.entries);
+        while res.has_more {
+            let arg = json!({ "cursor": res.cursor });
+            res = self.call(LIST_FOLDER_CONTINUE_URL, &arg).await?;
+            entries.append(&mut res.entries);
         }
 
-        Ok(folders)
-    }
-
-    fn list_folder_continue(
-        &self,
-        cursor: ListFolderCursor,
-        folders: &mut Vec<Metadata>,
-    ) -> Result<()> {
-        let arg = ListFolderContinueArg::new(cursor);
-        let res = files::list_folder_continue(&self.client, &arg).map_err(convert_dbx_err)??;
-        folders.extend(res.entries.into_iter());
-        if res.has_more {
-            self.list_folder_continue(res.cursor, folders)?;
-        }
-        Ok(())
+        Ok(entries)
     }
 
-    pub fn get_shared_link_file<T: Into<String>>(
-        &self,
-        url: T,
-        path: Path,
-    ) -> Result<Box<dyn Read>> {
-        let arg = GetSharedLinkFileArg::new(url.into()).with_path(path.clone());
-        let res = sharing::get_shared_link_file(&self.client, &arg, None, None)
-            .map_err(convert_dbx_err)??;
-
-        match res.body {
-            Some(body) => Ok(body),
-            _ => Err(anyhow!("Found empty body : {}", path)),
-        }
+    /// Calls RPC endpoint of Dropbox API.
+    async fn call<T: DeserializeOwned>(&self, url: &str, arg: &Value) -> Result<T> {
+        let res = self
+            .client
+            .post(url)
+            .bearer_auth(&self.access_token)
+            .header(CONTENT_TYPE, "application/json")
+            .body(arg.to_string())
+            .send()
+            .await
+            .with_context(|| format!("Could not send request to Dropbox : {}", url))?;
+        let res = check_status(res, StatusCode::OK).await?;
+        let body = res
+            .bytes()
+            .await
+            .with_context(|| format!("Could not receive response from Dropbox : {}", url))?;
+        serde_json::from_slice(&body)
+            .with_context(|| format!("Could not parse response from Dropbox : {}", url))
     }
 }
@@ -1,20 +1,77 @@
 #![warn(clippy::all)]
 #![cfg_attr(coverage, feature(no_coverage))]
 
+use std::fmt;
+
+use reqwest::{Response, StatusCode};
+
 mod authorizer;
+mod download;
 mod dropbox;
 
 use acick_util::abs_path;
+use acick_util::service::throttle;
 use acick_util::web;
 
-pub use dropbox_sdk::files::FileMetadata;
+pub use dropbox_sdk::files::{FileMetadata, FolderMetadata};
 
 pub use authorizer::DbxAuthorizer;
+pub use download::Downloader;
 pub use dropbox::Dropbox;
 
 pub type Error = anyhow::Error;
 pub type Result<T> = anyhow::Result<T>;
 
-fn convert_dbx_err(err: dropbox_sdk::Error) -> Error {
-    Error::msg(err.to_string())
+/// Error indicating that stored authorization of Dropbox is revoked or expired.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AuthError(String);
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Dropbox authorization is invalid : {}", self.0)
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// Returns true if the error is caused by invalid authorization of Dropbox.
+pub fn is_auth_error(err: &Error) -> bool {
+    err.chain().any(|cause| cause.is::<AuthError>())
+}
+
+/// Returns the response if it has `expected` status, otherwise error that describes the response.
+async fn check_status(res: Response, expected: StatusCode) -> Result<Response> {
+    let status = res.status();
+    if status == expected {
+        return Ok(res);
+    }
+    // body of error responses describes the error (e.g. `expired_access_token`)
+    let body = res.text().await.unwrap_or_default();
+    Err(status_error(status, body))
+}
+
+fn status_error(status: StatusCode, body: String) -> Error {
+    let msg = format!("Dropbox API returned {} : {}", status, body);
+    if status == StatusCode::UNAUTHORIZED {
+        Error::new(AuthError(msg))
+    } else {
+        Error::msg(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_auth_error() {
+        let err = status_error(
+            StatusCode::UNAUTHORIZED,
+            String::from("expired_access_token"),
+        )
+        .context("Could not list folders");
+        assert!(is_auth_error(&err));
+        let err = status_error(StatusCode::INTERNAL_SERVER_ERROR, String::new());
+        assert!(!is_auth_error(&err));
+    }
 }
@@ -0,0 +1,135 @@
+#![warn(clippy::all)]
+
+//! Git integration for acick.
+//!
+//! Commits source code with git command executed in the base directory of config.
+
+use std::io::Write as _;
+use std::process::Stdio;
+
+use anyhow::{anyhow, Context as _};
+use tokio::process::Command;
+
+use acick_config as config;
+use acick_util::{abs_path, console, model};
+
+use crate::abs_path::AbsPathBuf;
+use crate::config::Config;
+use crate::console::Console;
+use crate::model::Problem;
+
+pub type Error = anyhow::Error;
+pub type Result<T> = anyhow::Result<T>;
+
+/// Commits source code of the problem if `git` section is specified in config.
+///
+/// Returns `false` if git integration is disabled or the source code has no changes.
+pub async fn commit_source(
+    conf: &Config,
+    problem: &Problem,
+    verdict: &str,
+    cnsl: &mut Console,
+) -> Result<bool> {
+    let git_conf = match conf.git() {
+        Some(git_conf) => git_conf,
+        None => return Ok(false),
+    };
+    let source_abs_path = conf.source_abs_path(problem.id())?;
+    let source_path = source_path_str(&source_abs_path, conf);
+    let message = git_conf.expand_message(conf.service_id, &conf.contest_id, problem, verdict)?;
+
+    git(conf, &["add", "--", &source_path]).await?;
+    if git(conf, &["diff", "--cached", "--quiet", "--", &source_path])
+        .await
+        .is_ok()
+    {
+        writeln!(cnsl, "Skipped git commit (no changes)")?;
+        return Ok(false);
+    }
+    git(conf, &["commit", "-m", &message, "--", &source_path]).await?;
+    writeln!(cnsl, "Committed to git : {}", message)?;
+    Ok(true)
+}
+
+async fn git(conf: &Config, args: &[&str]) -> Result<()> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(conf.base_dir.as_ref())
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .context("Could not execute git command. Make sure git is installed.")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git command returned non-zero status : git {}\n{}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ));
+    }
+    Ok(())
+}
+
+fn source_path_str(source_abs_path: &AbsPathBuf, conf: &Config) -> String {
+    source_abs_path
+        .strip_prefix(&conf.base_dir)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::config::ConfigBody;
+    use crate::model::ServiceKind;
+
+    #[tokio::test]
+    async fn test_commit_source() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        // identity is configured in the repository, which does not affect other tests
+        for args in &[
+            &["init"][..],
+            &["config", "user.name", "acick"],
+            &["config", "user.email", "acick@example.com"],
+        ] {
+            assert!(std::process::Command::new("git")
+                .args(*args)
+                .current_dir(test_dir.path())
+                .status()?
+                .success());
+        }
+
+        // enable git integration
+        let mut conf_buf = Vec::new();
+        ConfigBody::generate_to(&mut conf_buf)?;
+        conf_buf.extend_from_slice(b"git:\n  message: \"{{ problem.id }} {{ verdict }}\"\n");
+        fs::write(base_dir.join(ConfigBody::FILE_NAME).as_ref(), conf_buf)?;
+        let mut cnsl = Console::sink(Default::default());
+        let conf = Config::load(
+            ServiceKind::Atcoder,
+            "arc100".into(),
+            Some(base_dir.clone()),
+            &mut cnsl,
+        )?;
+
+        let problem = Problem::default();
+        let source_abs_path = conf.source_abs_path(problem.id())?;
+        source_abs_path.create_dir_all_and_open(false, true)?;
+        fs::write(source_abs_path.as_ref(), "int main() {}\n")?;
+
+        assert!(commit_source(&conf, &problem, "AC", &mut cnsl).await?);
+        // nothing to commit
+        assert!(!commit_source(&conf, &problem, "AC", &mut cnsl).await?);
+
+        let log = std::process::Command::new("git")
+            .args(&["log", "--format=%s"])
+            .current_dir(test_dir.path())
+            .output()?;
+        assert_eq!(String::from_utf8(log.stdout)?, "C AC\n");
+        Ok(())
+    }
+}
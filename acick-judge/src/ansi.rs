@@ -0,0 +1,86 @@
+/// State of `AnsiStripper` kept between chunks.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum State {
+    Normal,
+    /// After ESC
+    Esc,
+    /// Inside control sequence (e.g. `ESC [ 31 m`), which ends with a byte in `@` to `~`
+    Csi,
+    /// Inside operating system command (e.g. `ESC ] 0 ; title BEL`), which ends with BEL or ST
+    Osc,
+    /// After ESC inside operating system command
+    OscEsc,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+/// Removes ANSI escape sequences (e.g. colors) from bytes read in chunks,
+/// keeping sequences that are split across chunks.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct AnsiStripper {
+    state: State,
+}
+
+impl AnsiStripper {
+    const ESC: u8 = 0x1b;
+    const BEL: u8 = 0x07;
+
+    pub fn strip(&mut self, chunk: &[u8]) -> Vec<u8> {
+        let mut stripped = Vec::with_capacity(chunk.len());
+        for &byte in chunk {
+            self.state = match (self.state, byte) {
+                (State::Normal, Self::ESC) => State::Esc,
+                (State::Normal, _) => {
+                    stripped.push(byte);
+                    State::Normal
+                }
+                (State::Esc, b'[') => State::Csi,
+                (State::Esc, b']') => State::Osc,
+                // two-byte sequence (e.g. `ESC c`)
+                (State::Esc, _) => State::Normal,
+                (State::Csi, 0x40..=0x7e) => State::Normal,
+                (State::Csi, _) => State::Csi,
+                (State::Osc, Self::BEL) => State::Normal,
+                (State::Osc, Self::ESC) => State::OscEsc,
+                (State::Osc, _) => State::Osc,
+                (State::OscEsc, b'\\') => State::Normal,
+                (State::OscEsc, _) => State::Osc,
+            };
+        }
+        stripped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip() {
+        let tests: &[(&[&str], &str)] = &[
+            (&["plain\n"], "plain\n"),
+            (&["\x1b[1;31mred\x1b[0m\n"], "red\n"),
+            (&["\x1b[1;3", "1mred\x1b", "[0m"], "red"),
+            (&["\x1b]0;title\x07text"], "text"),
+            (&["\x1b]0;title\x1b\\text"], "text"),
+            (&["\x1bcreset"], "reset"),
+        ];
+        for (chunks, expected) in tests {
+            let mut stripper = AnsiStripper::default();
+            let actual: Vec<u8> = chunks
+                .iter()
+                .flat_map(|chunk| stripper.strip(chunk.as_bytes()))
+                .collect();
+            assert_eq!(
+                String::from_utf8(actual).unwrap(),
+                *expected,
+                "{:?}",
+                chunks
+            );
+        }
+    }
+}
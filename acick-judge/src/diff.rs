@@ -0,0 +1,428 @@
+use std::cmp::{max, min};
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str;
+
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+use crate::model::Compare;
+use crate::style::{sty_g, sty_r};
+use crate::{DiffConfig, DiffView};
+
+// max bytes of utf-8 char
+const MAX_CHAR_BYTES: usize = 4;
+// min width of each column in side-by-side view
+const MIN_COLUMN_WIDTH: usize = 8;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+struct DiffLine {
+    left: String,
+    right: String,
+    is_same: bool,
+}
+
+#[derive(Serialize, Deserialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextDiff {
+    #[get = "pub"]
+    l_title: String,
+    #[get = "pub"]
+    r_title: String,
+    #[get_copy = "pub"]
+    l_width: usize,
+    #[get_copy = "pub"]
+    r_width: usize,
+    #[get_copy = "pub"]
+    is_any: bool,
+    lines: Vec<DiffLine>,
+    #[serde(default)]
+    n_omitted_lines: usize,
+    #[serde(default)]
+    has_invalid_utf8: bool,
+    /// Byte offset of the first invalid UTF-8 in the right text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[get_copy = "pub"]
+    invalid_utf8_at: Option<u64>,
+    /// True if texts differ only in line endings or final newline
+    #[serde(default)]
+    #[get_copy = "pub"]
+    differs_only_in_line_endings: bool,
+    #[serde(default)]
+    view: DiffView,
+    #[serde(default)]
+    width: usize,
+}
+
+impl TextDiff {
+    /// Compares texts line by line without loading whole texts into memory.
+    ///
+    /// Only the first lines are kept for display as specified in `conf`.
+    /// Lines of invalid UTF-8 are compared as bytes.
+    ///
+    /// If `cmp` ignores order of lines or tokens, whole texts are loaded
+    /// and compared after canonicalized.
+    /// In that case, the right text of invalid UTF-8 is never regarded as the same,
+    /// because lossy conversion may hide differences.
+    pub fn new(
+        l_title: impl Into<String>,
+        r_title: impl Into<String>,
+        mut left: impl BufRead,
+        mut right: impl BufRead,
+        cmp: Compare,
+        conf: &DiffConfig,
+    ) -> io::Result<Self> {
+        if !cmp.is_order_insensitive() {
+            return Self::compare_lines(l_title, r_title, left, right, cmp, conf);
+        }
+        let (mut l_buf, mut r_buf) = (Vec::new(), Vec::new());
+        left.read_to_end(&mut l_buf)?;
+        right.read_to_end(&mut r_buf)?;
+        let (l_text, r_text) = (
+            String::from_utf8_lossy(&l_buf),
+            String::from_utf8_lossy(&r_buf),
+        );
+        let (l_text, r_text) = (cmp.canonicalize(&l_text), cmp.canonicalize(&r_text));
+        let mut diff = Self::compare_lines(
+            l_title,
+            r_title,
+            l_text.as_bytes(),
+            r_text.as_bytes(),
+            cmp,
+            conf,
+        )?;
+        if let Err(err) = str::from_utf8(&r_buf) {
+            diff.is_any = true;
+            diff.invalid_utf8_at = Some(err.valid_up_to() as u64);
+        }
+        Ok(diff)
+    }
+
+    fn compare_lines(
+        l_title: impl Into<String>,
+        r_title: impl Into<String>,
+        mut left: impl BufRead,
+        mut right: impl BufRead,
+        cmp: Compare,
+        conf: &DiffConfig,
+    ) -> io::Result<Self> {
+        let (l_title, r_title) = (l_title.into(), r_title.into());
+        let (mut l_width, mut r_width) = (l_title.len(), r_title.len());
+        let mut lines = Vec::new();
+        let mut n_omitted_lines = 0;
+        let mut is_any = false;
+        let mut has_invalid_utf8 = false;
+        let mut invalid_utf8_at = None;
+        let mut differs_only_in_line_endings = true;
+        // line endings matter only when outputs are compared as bytes
+        let keeps_line_ending = cmp == Compare::Bytes;
+
+        let (mut l_buf, mut r_buf) = (Vec::new(), Vec::new());
+        let mut r_offset = 0;
+        loop {
+            let l_len = read_line(&mut left, &mut l_buf, keeps_line_ending)?;
+            let r_len = read_line(&mut right, &mut r_buf, keeps_line_ending)?;
+            if l_len == 0 && r_len == 0 {
+                break;
+            }
+            let (l_str, r_str) = (str::from_utf8(&l_buf), str::from_utf8(&r_buf));
+            if let (Err(err), None) = (&r_str, invalid_utf8_at) {
+                invalid_utf8_at = Some(r_offset + err.valid_up_to() as u64);
+            }
+            r_offset += r_len as u64;
+            let is_same = match (l_str, r_str) {
+                (Ok(l), Ok(r)) => cmp.compare(l, r),
+                _ => {
+                    has_invalid_utf8 = true;
+                    l_buf == r_buf
+                }
+            };
+            is_any = is_any || !is_same;
+            if !is_same && strip_line_ending(&l_buf) != strip_line_ending(&r_buf) {
+                differs_only_in_line_endings = false;
+            }
+            if lines.len() < conf.max_lines() {
+                let show = |buf: &[u8]| {
+                    let line = truncate(buf, conf.max_line_width());
+                    if keeps_line_ending {
+                        line.replace('\r', "\\r").replace('\n', "\\n")
+                    } else {
+                        line
+                    }
+                };
+                let line = DiffLine {
+                    left: show(&l_buf),
+                    right: show(&r_buf),
+                    is_same,
+                };
+                l_width = max(l_width, line.left.len());
+                r_width = max(r_width, line.right.len());
+                lines.push(line);
+            } else {
+                n_omitted_lines += 1;
+            }
+        }
+
+        Ok(Self {
+            l_title,
+            r_title,
+            l_width,
+            r_width,
+            is_any,
+            lines,
+            n_omitted_lines,
+            has_invalid_utf8,
+            invalid_utf8_at,
+            differs_only_in_line_endings: is_any && differs_only_in_line_endings,
+            view: conf.view(),
+            width: conf.width(),
+        })
+    }
+}
+
+fn strip_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Reads a line into `buf`, removing its line ending unless `keeps_line_ending` is true.
+///
+/// Returns the number of bytes read, which is 0 if reached EOF.
+fn read_line(
+    reader: &mut impl BufRead,
+    buf: &mut Vec<u8>,
+    keeps_line_ending: bool,
+) -> io::Result<usize> {
+    buf.clear();
+    let len = reader.read_until(b'\n', buf)?;
+    if keeps_line_ending {
+        return Ok(len);
+    }
+    if buf.last() == Some(&b'\n') {
+        buf.pop();
+        if buf.last() == Some(&b'\r') {
+            buf.pop();
+        }
+    }
+    Ok(len)
+}
+
+fn truncate(line: &[u8], max_width: usize) -> String {
+    let head = &line[..min(line.len(), (max_width + 1) * MAX_CHAR_BYTES)];
+    let head = String::from_utf8_lossy(head);
+    if head.chars().count() > max_width || head.len() < line.len() {
+        let mut head: String = head.chars().take(max_width).collect();
+        head.push_str("...");
+        head
+    } else {
+        head.into_owned()
+    }
+}
+
+impl TextDiff {
+    fn fmt_table(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "  | {:l_width$} | {:r_width$} ",
+            self.l_title,
+            self.r_title,
+            l_width = self.l_width,
+            r_width = self.r_width
+        )?;
+        writeln!(
+            f,
+            "--+-{:-<l_width$}-+-{:-<r_width$}-",
+            "",
+            "",
+            l_width = self.l_width,
+            r_width = self.r_width
+        )?;
+        for line in &self.lines {
+            writeln!(
+                f,
+                "{} | {:l_width$} | {:r_width$} ",
+                if line.is_same { " " } else { ">" },
+                line.left,
+                line.right,
+                l_width = self.l_width,
+                r_width = self.r_width
+            )?;
+        }
+        Ok(())
+    }
+
+    fn fmt_side_by_side(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // each row is laid out as `> left | right`
+        let col = max(self.width.saturating_sub(5) / 2, MIN_COLUMN_WIDTH);
+        writeln!(
+            f,
+            "  {} | {}",
+            pad(&wrap(&self.l_title, col)[0], col),
+            wrap(&self.r_title, col)[0]
+        )?;
+        writeln!(f, "--{:-<col$}-+-{:-<col$}", "", "", col = col)?;
+        for line in &self.lines {
+            let (left, right) = (wrap(&line.left, col), wrap(&line.right, col));
+            for i in 0..max(left.len(), right.len()) {
+                let marker = if i == 0 && !line.is_same { ">" } else { " " };
+                // pad before styling so that escape sequences do not break alignment
+                let l = pad(left.get(i).map_or("", String::as_str), col);
+                let r = right.get(i).map_or("", String::as_str);
+                if line.is_same {
+                    writeln!(f, "{} {} | {}", marker, l, r)?;
+                } else {
+                    writeln!(f, "{} {} | {}", marker, sty_g(l), sty_r(r))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn fmt_notes(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.n_omitted_lines > 0 {
+            writeln!(f, "  ... ({} more lines)", self.n_omitted_lines)?;
+        }
+        if let Some(pos) = self.invalid_utf8_at {
+            writeln!(
+                f,
+                "  ({} output contained invalid UTF-8 at byte {})",
+                self.r_title, pos
+            )?;
+        }
+        if self.has_invalid_utf8 {
+            writeln!(f, "  (found invalid UTF-8, compared as bytes)")?;
+        }
+        if self.differs_only_in_line_endings {
+            writeln!(
+                f,
+                "  (outputs differ only in line endings (CRLF / LF) or final newline)"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits text into chunks of at most `width` chars. Empty text results in one empty chunk.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars
+        .chunks(width)
+        .map(|chunk| chunk.iter().collect())
+        .collect()
+}
+
+/// Pads text with spaces so that it has `width` chars.
+fn pad(text: &str, width: usize) -> String {
+    let n_chars = text.chars().count();
+    format!("{}{}", text, " ".repeat(width.saturating_sub(n_chars)))
+}
+
+impl fmt::Display for TextDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.view {
+            DiffView::Table => self.fmt_table(f)?,
+            DiffView::SideBySide => self.fmt_side_by_side(f)?,
+        }
+        self.fmt_notes(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(left: &[u8], right: &[u8]) -> io::Result<TextDiff> {
+        let conf: DiffConfig = serde_yaml::from_str("{max_lines: 2, max_line_width: 4}").unwrap();
+        TextDiff::new("expected", "actual", left, right, Compare::Default, &conf)
+    }
+
+    #[test]
+    fn test_new() -> anyhow::Result<()> {
+        let d = diff(b"1\n2\n3\n", b"1\r\n2\n3")?;
+        assert!(!d.is_any());
+        assert_eq!(d.lines.len(), 2);
+        assert_eq!(d.n_omitted_lines, 1);
+
+        let d = diff(b"1\n2\n3\n4\n", b"1\n2\n3\n5\n")?;
+        assert!(d.is_any());
+        assert_eq!(d.n_omitted_lines, 2);
+
+        let d = diff(b"abcdefgh\n", b"abcdefgh\n")?;
+        assert!(!d.is_any());
+        assert_eq!(d.lines[0].left, "abcd...");
+
+        let d = diff(b"a\xff\n", b"a\xff\n")?;
+        assert!(!d.is_any());
+        assert!(d.has_invalid_utf8);
+        let d = diff(b"a\xff\n", b"a\xfe\n")?;
+        assert!(d.is_any());
+        assert_eq!(d.lines[0].left, "a\u{fffd}");
+        assert_eq!(d.invalid_utf8_at(), Some(1));
+        let d = diff(b"1\n2\n", b"1\r\n2\xff\n")?;
+        assert_eq!(d.invalid_utf8_at(), Some(4));
+        assert!(d
+            .to_string()
+            .contains("actual output contained invalid UTF-8 at byte 4"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_bytes() -> anyhow::Result<()> {
+        let conf = DiffConfig::default();
+        let diff = |left: &[u8], right: &[u8]| {
+            TextDiff::new("expected", "actual", left, right, Compare::Bytes, &conf)
+        };
+        assert!(!diff(b"1 2\n3\n", b"1 2\n3\n")?.is_any());
+        assert!(diff(b"1 2\n3\n", b"1 2 \n3\n")?.is_any());
+        assert!(diff(b"1 2\n3\n", b"1 2\n3")?.is_any());
+        let d = diff(b"1\n", b"1\r\n")?;
+        assert!(d.is_any());
+        assert_eq!(d.lines[0].right, "1\\r\\n");
+        assert!(d.differs_only_in_line_endings());
+        assert!(d.to_string().contains("differ only in line endings"));
+        assert!(diff(b"1\n2\n", b"1\n2")?.differs_only_in_line_endings());
+        assert!(diff(b"1\n", b"1\n\n")?.differs_only_in_line_endings());
+        assert!(!diff(b"1\n2\n", b"1\r\n3\n")?.differs_only_in_line_endings());
+        assert!(!diff(b"1\n", b"1\n")?.differs_only_in_line_endings());
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_order_insensitive() -> anyhow::Result<()> {
+        let conf = DiffConfig::default();
+        let diff = |left: &[u8], right: &[u8], cmp| {
+            TextDiff::new("expected", "actual", left, right, cmp, &conf)
+        };
+        assert!(!diff(b"1\n2\n", b"2\n1\n", Compare::SortedLines)?.is_any());
+        assert!(diff(b"1 2\n", b"2 1\n", Compare::SortedLines)?.is_any());
+        assert!(!diff(b"1 2\n", b"2\n1\n", Compare::UnorderedTokens)?.is_any());
+        assert!(diff(b"1 2\n", b"2 2\n", Compare::UnorderedTokens)?.is_any());
+        let d = diff(b"1 \xff\n", b"\xfe 1\n", Compare::UnorderedTokens)?;
+        assert!(d.is_any());
+        assert_eq!(d.invalid_utf8_at(), Some(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fmt_side_by_side() -> anyhow::Result<()> {
+        let conf: DiffConfig = serde_yaml::from_str("{view: side-by-side, width: 25}")?;
+        let d = TextDiff::new(
+            "expected",
+            "actual",
+            &b"1\nabcdefghijklmn\n"[..],
+            &b"1\nabcdefghijklmnopq\n"[..],
+            Compare::Default,
+            &conf,
+        )?;
+        let s = console::strip_ansi_codes(&d.to_string()).into_owned();
+        let lines: Vec<&str> = s.lines().collect();
+        assert_eq!(lines[0], "  expected   | actual");
+        assert_eq!(lines[2], "  1          | 1");
+        assert_eq!(lines[3], "> abcdefghij | abcdefghij");
+        assert_eq!(lines[4], "  klmn       | klmnopq");
+        assert_eq!(lines.len(), 5);
+        Ok(())
+    }
+}
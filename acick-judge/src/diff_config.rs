@@ -0,0 +1,78 @@
+use getset::CopyGetters;
+use serde::{Deserialize, Serialize};
+
+use crate::model::Byte;
+
+const DEFAULT_SPILL_THRESHOLD: u64 = 16_000_000;
+const DEFAULT_MAX_LINES: usize = 100;
+const DEFAULT_MAX_LINE_WIDTH: usize = 200;
+const DEFAULT_WIDTH: usize = 120;
+
+/// Layout of diff of outputs.
+#[derive(
+    Serialize,
+    Deserialize,
+    EnumString,
+    EnumVariantNames,
+    IntoStaticStr,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum DiffView {
+    /// Table of expected and actual lines, whose columns grow with the longest line
+    Table,
+    /// Colored columns of expected and actual lines that fit in `width`, wrapping long lines
+    SideBySide,
+}
+
+impl Default for DiffView {
+    fn default() -> Self {
+        Self::Table
+    }
+}
+
+/// Config for comparing outputs of binary with expected outputs.
+#[derive(Serialize, Deserialize, CopyGetters, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct DiffConfig {
+    /// Outputs larger than this are written to temporary files instead of memory.
+    #[get_copy = "pub"]
+    spill_threshold: Byte,
+    /// Max number of lines shown in diff.
+    #[get_copy = "pub"]
+    max_lines: usize,
+    /// Max number of chars shown in each line of diff.
+    #[get_copy = "pub"]
+    max_line_width: usize,
+    /// Layout of diff.
+    #[get_copy = "pub"]
+    view: DiffView,
+    /// Width of diff in chars in side-by-side view.
+    #[get_copy = "pub"]
+    width: usize,
+}
+
+impl DiffConfig {
+    /// Returns config with the other layout of diff.
+    pub fn with_view(self, view: DiffView) -> Self {
+        Self { view, ..self }
+    }
+}
+
+impl Default for DiffConfig {
+    fn default() -> Self {
+        Self {
+            spill_threshold: DEFAULT_SPILL_THRESHOLD.into(),
+            max_lines: DEFAULT_MAX_LINES,
+            max_line_width: DEFAULT_MAX_LINE_WIDTH,
+            view: DiffView::default(),
+            width: DEFAULT_WIDTH,
+        }
+    }
+}
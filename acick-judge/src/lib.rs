@@ -0,0 +1,555 @@
+#![warn(clippy::all)]
+
+//! Judge engine for acick.
+//!
+//! Runs a command with input of a sample and judges its output.
+//! This crate does not depend on other crates of acick,
+//! so that other tools can reuse the runner and the verdict model.
+
+#[macro_use]
+extern crate strum;
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read as _, Seek as _, SeekFrom, Write as _};
+use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _};
+use tokio::io::{AsyncRead, AsyncReadExt as _, AsyncWriteExt as _, BufWriter};
+use tokio::process::{Child, Command};
+use tokio::time::{timeout, Instant};
+
+use crate::ansi::AnsiStripper;
+use crate::model::{Compare, Sample};
+use crate::process_tree::ProcessTree;
+
+mod ansi;
+mod diff;
+mod diff_config;
+mod model;
+mod process_tree;
+mod status;
+mod style;
+
+pub use diff::TextDiff;
+pub use diff_config::{DiffConfig, DiffView};
+pub use model::{Byte, Compare, Sample};
+pub use process_tree::set_new_process_group;
+pub use status::{Status, StatusKind, TotalStatus};
+
+pub type Error = anyhow::Error;
+pub type Result<T> = anyhow::Result<T>;
+
+const READ_CHUNK_SIZE: usize = 8192;
+/// Time given to processes to terminate before they are killed when binary exceeds time limit.
+const KILL_GRACE: Duration = Duration::from_millis(100);
+
+/// Filters applied to output of binary before it is compared and shown.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct OutputFilter {
+    /// Output after this number of bytes is dropped and replaced with a marker
+    max_bytes: Option<u64>,
+    /// Removes ANSI escape sequences (e.g. colors)
+    strip_ansi: bool,
+}
+
+impl OutputFilter {
+    pub fn new(max_bytes: Option<u64>, strip_ansi: bool) -> Self {
+        Self {
+            max_bytes,
+            strip_ansi,
+        }
+    }
+}
+
+impl Default for OutputFilter {
+    fn default() -> Self {
+        Self::new(None, true)
+    }
+}
+
+/// Files through which binary reads input and writes output instead of standard streams.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IoFiles {
+    input: Option<PathBuf>,
+    output: Option<PathBuf>,
+}
+
+impl IoFiles {
+    pub fn new(input: Option<PathBuf>, output: Option<PathBuf>) -> Self {
+        Self { input, output }
+    }
+}
+
+#[derive(Debug)]
+pub struct Judge {
+    sample: Sample,
+    time_limit: Duration,
+    cmp: Compare,
+    diff_conf: DiffConfig,
+    filter: OutputFilter,
+    io_files: IoFiles,
+}
+
+impl Judge {
+    pub fn new(sample: Sample, time_limit: Duration, cmp: Compare, diff_conf: DiffConfig) -> Self {
+        Self {
+            sample,
+            time_limit,
+            cmp,
+            diff_conf,
+            filter: OutputFilter::default(),
+            io_files: IoFiles::default(),
+        }
+    }
+
+    /// Returns judge that filters output of binary with `filter`.
+    pub fn with_output_filter(self, filter: OutputFilter) -> Self {
+        Self { filter, ..self }
+    }
+
+    /// Returns judge that passes input and output of binary through files.
+    pub fn with_io_files(self, io_files: IoFiles) -> Self {
+        Self { io_files, ..self }
+    }
+
+    pub async fn test(self, command: Command) -> Result<Status> {
+        let (status, _) = self.judge(command, false).await?;
+        Ok(status)
+    }
+
+    /// Judges output like `test`, and also returns the output
+    /// if the command exited successfully (i.e. the status is AC or WA).
+    ///
+    /// Output is kept in memory, so this is meant for small samples (e.g. to bless them).
+    pub async fn test_with_output(self, command: Command) -> Result<(Status, Option<String>)> {
+        self.judge(command, true).await
+    }
+
+    async fn judge(self, command: Command, keep_output: bool) -> Result<(Status, Option<String>)> {
+        let Self {
+            sample,
+            time_limit,
+            cmp,
+            diff_conf,
+            filter,
+            io_files,
+        } = self;
+        let (sample_name, sample_in, sample_out) = sample.take();
+
+        let spill_threshold = diff_conf.spill_threshold().as_u64();
+        let (elapsed, result) = Self::exec_child(
+            command,
+            sample_in,
+            time_limit,
+            spill_threshold,
+            filter,
+            &io_files,
+        )
+        .await?;
+
+        match result {
+            None => Ok((Status::tle(sample_name, elapsed), None)),
+            Some((status, output)) if status.success() => {
+                let (actual, kept): (Box<dyn BufRead>, _) = if keep_output {
+                    let kept = read_to_string(output)?;
+                    (
+                        Box::new(io::Cursor::new(kept.clone().into_bytes())),
+                        Some(kept),
+                    )
+                } else {
+                    (output.into_reader()?, None)
+                };
+                let diff = TextDiff::new(
+                    "expected",
+                    "actual",
+                    sample_out.as_bytes(),
+                    actual,
+                    cmp,
+                    &diff_conf,
+                )
+                .context("Could not compare output")?;
+                if diff.is_any() {
+                    Ok((Status::wa(sample_name, elapsed, diff), kept))
+                } else {
+                    Ok((Status::ac(sample_name, elapsed), kept))
+                }
+            }
+            Some((status, _)) => Ok((
+                Status::re(sample_name, elapsed, anyhow!("{}", status)),
+                None,
+            )),
+        }
+    }
+
+    /// Runs command with input of the sample and returns its output without judging it.
+    pub async fn run(self, command: Command) -> Result<String> {
+        let (_, sample_in, _) = self.sample.take();
+        let spill_threshold = self.diff_conf.spill_threshold().as_u64();
+        let (_, result) = Self::exec_child(
+            command,
+            sample_in,
+            self.time_limit,
+            spill_threshold,
+            self.filter,
+            &self.io_files,
+        )
+        .await?;
+        let (status, output) = result.context("Run command exceeded time limit")?;
+        if !status.success() {
+            return Err(anyhow!("Run command returned non-zero status : {}", status));
+        }
+        read_to_string(output)
+    }
+
+    /// Runs command with input, returning elapsed time and its exit status and output,
+    /// or `None` if it exceeds time limit.
+    ///
+    /// When the command exceeds time limit, processes spawned by it are killed as well,
+    /// if the command was created with `set_new_process_group`.
+    async fn exec_child(
+        mut command: Command,
+        mut input: String,
+        time_limit: Duration,
+        spill_threshold: u64,
+        filter: OutputFilter,
+        io_files: &IoFiles,
+    ) -> Result<(Duration, Option<(ExitStatus, CapturedOutput)>)> {
+        if let Some(input_path) = &io_files.input {
+            fs::write(input_path, &input).with_context(|| {
+                format!("Could not write input to file : {}", input_path.display())
+            })?;
+            input = String::new();
+        }
+        if let Some(output_path) = &io_files.output {
+            // output of the previous run must not be judged
+            if let Err(err) = fs::remove_file(output_path) {
+                if err.kind() != io::ErrorKind::NotFound {
+                    return Err(Error::new(err).context(format!(
+                        "Could not remove output file : {}",
+                        output_path.display()
+                    )));
+                }
+            }
+        }
+
+        let started_at = Instant::now();
+        let (child, tree) = ProcessTree::spawn(
+            command
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .kill_on_drop(true),
+        )?;
+        let result = timeout(
+            time_limit,
+            Self::wait_child(child, input, spill_threshold, filter, io_files),
+        )
+        .await;
+        let elapsed = started_at.elapsed();
+        match result {
+            Ok(result) => Ok((elapsed, Some(result?))),
+            Err(_) => {
+                tree.kill(KILL_GRACE).await;
+                Ok((elapsed, None))
+            }
+        }
+    }
+
+    async fn wait_child(
+        mut child: Child,
+        input: String,
+        spill_threshold: u64,
+        filter: OutputFilter,
+        io_files: &IoFiles,
+    ) -> Result<(ExitStatus, CapturedOutput)> {
+        let stdin = child.stdin.take().unwrap();
+        let mut stdout = child.stdout.take().unwrap();
+
+        // write to stdin and read from stdout at the same time
+        // so that the child is not blocked by full pipe
+        let write_input = async move {
+            let mut stdin = BufWriter::new(stdin);
+            // async write to stdin may cause broken pipe error
+            // when write is performed after the child exited
+            Self::ignore_broken_pipe(
+                tokio::io::copy(&mut input.as_bytes(), &mut stdin)
+                    .await
+                    .map(|_| ()),
+            )
+            .context("Could not write input to stdin")?;
+            Self::ignore_broken_pipe(stdin.flush().await).context("Could not flush stdin")
+        };
+        let read_output = CapturedOutput::capture(&mut stdout, spill_threshold, filter);
+        let (written, output) = tokio::join!(write_input, read_output);
+        written?;
+        let mut output = output.context("Could not read output from stdout")?;
+
+        let status = child.await.context("Failed to run")?;
+        if let (Some(output_path), true) = (&io_files.output, status.success()) {
+            let mut file = tokio::fs::File::open(output_path).await.with_context(|| {
+                format!("Could not open output file : {}", output_path.display())
+            })?;
+            output = CapturedOutput::capture(&mut file, spill_threshold, filter)
+                .await
+                .with_context(|| {
+                    format!("Could not read output file : {}", output_path.display())
+                })?;
+        }
+        Ok((status, output))
+    }
+
+    fn ignore_broken_pipe(
+        result: std::result::Result<(), io::Error>,
+    ) -> std::result::Result<(), io::Error> {
+        result.or_else(|err| match err.kind() {
+            io::ErrorKind::BrokenPipe => Ok(()),
+            _ => Err(err),
+        })
+    }
+}
+
+/// Output of binary kept in memory, or in temporary file if it is large.
+#[derive(Debug)]
+enum CapturedOutput {
+    Memory(Vec<u8>),
+    File(File),
+}
+
+impl CapturedOutput {
+    /// Reads output until EOF while filtering it.
+    ///
+    /// Output after `filter.max_bytes` is still read so that the child is not blocked,
+    /// but it is dropped and a marker with the number of dropped bytes is appended instead.
+    async fn capture(
+        reader: &mut (impl AsyncRead + Unpin),
+        spill_threshold: u64,
+        filter: OutputFilter,
+    ) -> io::Result<Self> {
+        let mut writer = OutputWriter::new(spill_threshold);
+        let mut stripper = AnsiStripper::default();
+        let mut chunk = vec![0; READ_CHUNK_SIZE];
+        let mut dropped = 0;
+        loop {
+            let n = reader.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            let stripped;
+            let mut data = &chunk[..n];
+            if filter.strip_ansi {
+                stripped = stripper.strip(data);
+                data = &stripped;
+            }
+            if let Some(max_bytes) = filter.max_bytes {
+                let room = max_bytes.saturating_sub(writer.len()) as usize;
+                if data.len() > room {
+                    dropped += (data.len() - room) as u64;
+                    data = &data[..room];
+                }
+            }
+            writer.write(data)?;
+        }
+        if dropped > 0 {
+            writer.write(
+                format!("\n[acick: output truncated, {} bytes omitted]\n", dropped).as_bytes(),
+            )?;
+        }
+        Ok(writer.finish())
+    }
+
+    fn into_reader(self) -> io::Result<Box<dyn BufRead>> {
+        match self {
+            Self::Memory(buf) => Ok(Box::new(io::Cursor::new(buf))),
+            Self::File(mut file) => {
+                file.seek(SeekFrom::Start(0))?;
+                Ok(Box::new(BufReader::new(file)))
+            }
+        }
+    }
+}
+
+/// Reads whole output as UTF-8 string.
+fn read_to_string(output: CapturedOutput) -> Result<String> {
+    let mut actual = Vec::new();
+    output.into_reader()?.read_to_end(&mut actual)?;
+    String::from_utf8(actual).map_err(|err| {
+        anyhow!(
+            "Output contained invalid UTF-8 at byte {}",
+            err.utf8_error().valid_up_to()
+        )
+    })
+}
+
+/// Writes output into memory, or into temporary file once it gets larger than threshold.
+struct OutputWriter {
+    buf: Vec<u8>,
+    file: Option<File>,
+    len: u64,
+    spill_threshold: u64,
+}
+
+impl OutputWriter {
+    fn new(spill_threshold: u64) -> Self {
+        Self {
+            buf: Vec::new(),
+            file: None,
+            len: 0,
+            spill_threshold,
+        }
+    }
+
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.len += data.len() as u64;
+        match &mut self.file {
+            Some(file) => file.write_all(data),
+            None => {
+                self.buf.extend_from_slice(data);
+                if self.buf.len() as u64 > self.spill_threshold {
+                    let mut spilled = tempfile::tempfile()?;
+                    spilled.write_all(&self.buf)?;
+                    self.buf = Vec::new();
+                    self.file = Some(spilled);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(self) -> CapturedOutput {
+        match self.file {
+            Some(file) => CapturedOutput::File(file),
+            None => CapturedOutput::Memory(self.buf),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_capture() -> anyhow::Result<()> {
+        let input = "1234567890".repeat(10);
+
+        let filter = OutputFilter::default();
+        let output = CapturedOutput::capture(&mut input.as_bytes(), 1000, filter).await?;
+        assert!(matches!(output, CapturedOutput::Memory(_)));
+        let mut actual = String::new();
+        output.into_reader()?.read_to_string(&mut actual)?;
+        assert_eq!(actual, input);
+
+        let output = CapturedOutput::capture(&mut input.as_bytes(), 10, filter).await?;
+        assert!(matches!(output, CapturedOutput::File(_)));
+        let mut actual = String::new();
+        output.into_reader()?.read_to_string(&mut actual)?;
+        assert_eq!(actual, input);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_capture_filtered() -> anyhow::Result<()> {
+        let input = format!("\x1b[32m{}\x1b[0m", "1234567890".repeat(10));
+
+        let filter = OutputFilter::new(Some(15), true);
+        let output = CapturedOutput::capture(&mut input.as_bytes(), 1000, filter).await?;
+        let mut actual = String::new();
+        output.into_reader()?.read_to_string(&mut actual)?;
+        assert_eq!(
+            actual,
+            "123456789012345\n[acick: output truncated, 85 bytes omitted]\n"
+        );
+
+        let filter = OutputFilter::new(None, false);
+        let output = CapturedOutput::capture(&mut input.as_bytes(), 1000, filter).await?;
+        let mut actual = String::new();
+        output.into_reader()?.read_to_string(&mut actual)?;
+        assert_eq!(actual, input);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_io_files() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let io_files = IoFiles::new(
+            Some(dir.path().join("input.txt")),
+            Some(dir.path().join("output.txt")),
+        );
+        let mut command = Command::new("sh");
+        command
+            .args(&["-c", "cat input.txt > output.txt"])
+            .current_dir(dir.path());
+        let status = Judge::new(
+            Sample::new("sample1", "1 2\n", "1 2\n"),
+            Duration::from_secs(2),
+            Compare::Default,
+            DiffConfig::default(),
+        )
+        .with_io_files(io_files)
+        .test(command)
+        .await?;
+        assert_eq!(status.kind(), StatusKind::Ac);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_with_output() -> anyhow::Result<()> {
+        let judge = |sample_out| {
+            Judge::new(
+                Sample::new("sample1", "1 2\n", sample_out),
+                Duration::from_secs(2),
+                Compare::Default,
+                DiffConfig::default(),
+            )
+        };
+        let mut command = Command::new("cat");
+        let (status, output) = judge("3\n").test_with_output(command).await?;
+        assert_eq!(status.kind(), StatusKind::Wa);
+        assert_eq!(output.as_deref(), Some("1 2\n"));
+
+        command = Command::new("false");
+        let (status, output) = judge("1 2\n").test_with_output(command).await?;
+        assert_eq!(status.kind(), StatusKind::Re);
+        assert_eq!(output, None);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_kill_process_tree() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut command = std::process::Command::new("sh");
+        command
+            .args(&["-c", "sleep 10 & echo $! > pid.txt; wait"])
+            .current_dir(dir.path());
+        set_new_process_group(&mut command);
+        let status = Judge::new(
+            Sample::new("sample1", "", ""),
+            Duration::from_millis(500),
+            Compare::Default,
+            DiffConfig::default(),
+        )
+        .test(Command::from(command))
+        .await?;
+        assert_eq!(status.kind(), StatusKind::Tle);
+
+        // grandchild may remain for a while until it is reaped
+        let pid: libc::pid_t = fs::read_to_string(dir.path().join("pid.txt"))?
+            .trim()
+            .parse()?;
+        let started_at = Instant::now();
+        while unsafe { libc::kill(pid, 0) } == 0 {
+            assert!(started_at.elapsed() < Duration::from_secs(5));
+            tokio::time::delay_for(Duration::from_millis(50)).await;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+//! Models that the judge needs, which are also re-exported by `acick_util::model`.
+
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Getters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Sample {
+    #[get = "pub"]
+    name: String,
+    #[get = "pub"]
+    input: String,
+    #[get = "pub"]
+    output: String,
+}
+
+impl Sample {
+    /// Prefix of names of samples added by hand, which are kept when problem is fetched again.
+    pub const CUSTOM_PREFIX: &'static str = "custom-";
+
+    pub fn new(
+        name: impl Into<String>,
+        input: impl Into<String>,
+        output: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            input: input.into(),
+            output: output.into(),
+        }
+    }
+
+    pub fn is_custom(&self) -> bool {
+        self.name.starts_with(Self::CUSTOM_PREFIX)
+    }
+
+    pub fn take(self) -> (String, String, String) {
+        (self.name, self.input, self.output)
+    }
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    EnumString,
+    EnumVariantNames,
+    IntoStaticStr,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Compare {
+    Default,
+    /// Accepts any order of lines
+    SortedLines,
+    /// Accepts any order of whitespace-separated tokens
+    UnorderedTokens,
+    /// Accepts only exactly the same bytes including spaces and line endings
+    Bytes,
+    // TODO: support float
+    // Float {
+    //     relative_error: Option<f64>,
+    //     absolute_error: Option<f64>,
+    // },
+}
+
+impl Compare {
+    /// Compares a line of expected output with a line of actual output.
+    ///
+    /// Whole outputs should be canonicalized with `canonicalize` before comparison.
+    pub fn compare(self, a: &str, b: &str) -> bool {
+        match self {
+            Self::Default | Self::SortedLines | Self::UnorderedTokens => {
+                Self::compare_default(a, b)
+            }
+            Self::Bytes => a == b,
+        }
+    }
+
+    /// Returns true if whole output is needed to compare outputs.
+    pub fn is_order_insensitive(self) -> bool {
+        matches!(self, Self::SortedLines | Self::UnorderedTokens)
+    }
+
+    /// Rearranges whole output so that it can be compared line by line.
+    pub fn canonicalize(self, text: &str) -> Cow<str> {
+        match self {
+            Self::Default | Self::Bytes => Cow::Borrowed(text),
+            Self::SortedLines => {
+                let mut lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+                while lines.last() == Some(&"") {
+                    lines.pop();
+                }
+                lines.sort_unstable();
+                Cow::Owned(lines.join("\n"))
+            }
+            Self::UnorderedTokens => {
+                let mut tokens: Vec<&str> = text.split_whitespace().collect();
+                tokens.sort_unstable();
+                Cow::Owned(tokens.join("\n"))
+            }
+        }
+    }
+
+    fn compare_default(a: &str, b: &str) -> bool {
+        a.trim_end() == b.trim_end() // ignore spaces at the end of lines
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct Byte(u64);
+
+impl Byte {
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for Byte {
+    fn from(bytes: u64) -> Self {
+        Self(bytes)
+    }
+}
+
+impl FromStr for Byte {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self(bytefmt::parse(s)?))
+    }
+}
+
+impl TryFrom<String> for Byte {
+    type Error = &'static str;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+impl From<Byte> for String {
+    fn from(byte: Byte) -> Self {
+        bytefmt::format_to(byte.0, bytefmt::Unit::MB)
+    }
+}
+
+impl fmt::Display for Byte {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&String::from(*self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        let tests = &[
+            (Compare::Default, "hoge", "hoge", true),
+            (Compare::Default, "hoge", "hoge  ", true),
+            (Compare::Default, "hoge", "hoge\n", true),
+            (Compare::Default, "hoge", "  hoge", false),
+            (Compare::Default, "hoge", "\nhoge", false),
+            (Compare::Bytes, "hoge\n", "hoge\n", true),
+            (Compare::Bytes, "hoge\n", "hoge \n", false),
+            (Compare::Bytes, "hoge\n", "hoge\r\n", false),
+        ];
+
+        for (compare, a, b, expected) in tests {
+            let actual = compare.compare(a, b);
+            assert_eq!(actual, *expected);
+        }
+    }
+
+    #[test]
+    fn test_compare_canonicalize() {
+        let tests = &[
+            (Compare::Default, "b\na\n", "b\na\n"),
+            (Compare::SortedLines, "b \na\n\n", "a\nb"),
+            (Compare::UnorderedTokens, "3 1\n2\n", "1\n2\n3"),
+        ];
+
+        for (compare, text, expected) in tests {
+            assert_eq!(compare.canonicalize(text), *expected);
+        }
+    }
+
+    #[test]
+    fn test_byte_try_from() -> anyhow::Result<()> {
+        assert_eq!(
+            Byte::try_from(String::from("1024KB")).unwrap(),
+            Byte(1024 * 1000)
+        );
+        assert_eq!(
+            Byte::try_from(String::from("1.2MB")).unwrap(),
+            Byte(1200 * 1000)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_byte_display() {
+        assert_eq!(&Byte(1024 * 1000).to_string(), "1.02 MB");
+        assert_eq!(&Byte(2000 * 1000).to_string(), "2 MB");
+        assert_eq!(&Byte(10 * 1000 * 1000).to_string(), "10 MB");
+    }
+}
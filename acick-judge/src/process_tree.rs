@@ -0,0 +1,292 @@
+//! Kills a child process together with the processes it spawned (e.g. binary run by shell).
+
+use std::io;
+use std::process;
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::process::{Child, Command};
+
+use crate::Result;
+
+/// Makes the command start in a new process group,
+/// so that `ProcessTree` can kill the processes spawned by it.
+///
+/// This is no-op on Windows, where processes are tracked with a job object instead.
+///
+/// The new group does not receive SIGINT from terminal,
+/// so the processes are killed by acick when it is interrupted.
+pub fn set_new_process_group(command: &mut process::Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt as _;
+
+        // safety: setpgid is async-signal-safe
+        unsafe {
+            command.pre_exec(|| {
+                if libc::setpgid(0, 0) == 0 {
+                    Ok(())
+                } else {
+                    Err(io::Error::last_os_error())
+                }
+            });
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = command;
+}
+
+/// Child process and its descendants.
+#[derive(Debug)]
+pub(crate) struct ProcessTree {
+    inner: imp::ProcessTree,
+}
+
+impl ProcessTree {
+    /// Spawns the command, tracking the processes spawned by it.
+    ///
+    /// The processes are killed if acick is interrupted (on Unix)
+    /// or exits in any way (on Windows) while they are running.
+    pub fn spawn(command: &mut Command) -> Result<(Child, Self)> {
+        imp::prepare(command);
+        let child = command.spawn().context("Failed to start run command")?;
+        let inner = imp::ProcessTree::new(child.id())
+            .context("Could not track processes of run command")?;
+        Ok((child, Self { inner }))
+    }
+
+    /// Asks all processes in the tree to terminate, and kills the ones still alive after `grace`.
+    pub async fn kill(self, grace: Duration) {
+        self.inner.kill(grace).await
+    }
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::io;
+    use std::sync::atomic::{AtomicI32, Ordering};
+    use std::sync::Once;
+    use std::time::Duration;
+
+    use lazy_static::lazy_static;
+    use tokio::process::Command;
+    use tokio::time::delay_for;
+
+    /// Maximum number of process groups killed when acick is interrupted.
+    const MAX_LIVE_GROUPS: usize = 64;
+
+    lazy_static! {
+        /// Ids of process groups of running commands, where 0 means an empty slot.
+        ///
+        /// Atomics are used because they are read in the signal handler.
+        static ref LIVE_GROUPS: Vec<AtomicI32> =
+            (0..MAX_LIVE_GROUPS).map(|_| AtomicI32::new(0)).collect();
+    }
+
+    static INSTALL_HANDLER: Once = Once::new();
+
+    pub fn prepare(_command: &mut Command) {}
+
+    #[derive(Debug)]
+    pub struct ProcessTree {
+        pid: libc::pid_t,
+        /// True if the child leads its own process group created by `set_new_process_group`
+        is_group_leader: bool,
+        /// Index of the slot in `LIVE_GROUPS` if the group is registered
+        slot: Option<usize>,
+    }
+
+    impl ProcessTree {
+        pub fn new(pid: u32) -> io::Result<Self> {
+            let pid = pid as libc::pid_t;
+            let pgid = unsafe { libc::getpgid(pid) };
+            if pgid < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let is_group_leader = pgid == pid;
+            // the group does not receive SIGINT from terminal because it is not in foreground
+            let slot = if is_group_leader { register(pid) } else { None };
+            Ok(Self {
+                pid,
+                is_group_leader,
+                slot,
+            })
+        }
+
+        pub async fn kill(self, grace: Duration) {
+            // errors are ignored because processes may have exited already
+            self.signal(libc::SIGTERM);
+            delay_for(grace).await;
+            self.signal(libc::SIGKILL);
+        }
+
+        fn signal(&self, sig: libc::c_int) {
+            unsafe {
+                if self.is_group_leader {
+                    libc::killpg(self.pid, sig);
+                } else {
+                    libc::kill(self.pid, sig);
+                }
+            }
+        }
+    }
+
+    impl Drop for ProcessTree {
+        fn drop(&mut self) {
+            if let Some(slot) = self.slot {
+                LIVE_GROUPS[slot].store(0, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Registers the process group to be killed on SIGINT or SIGTERM, returning its slot.
+    fn register(pgid: libc::pid_t) -> Option<usize> {
+        INSTALL_HANDLER.call_once(|| {
+            lazy_static::initialize(&LIVE_GROUPS);
+            for &sig in &[libc::SIGINT, libc::SIGTERM] {
+                unsafe {
+                    libc::signal(sig, kill_live_groups as libc::sighandler_t);
+                }
+            }
+        });
+        LIVE_GROUPS.iter().position(|slot| {
+            slot.compare_exchange(0, pgid, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+        })
+    }
+
+    /// Kills the registered process groups and terminates acick by the default action of `sig`.
+    ///
+    /// Only async-signal-safe functions are called.
+    extern "C" fn kill_live_groups(sig: libc::c_int) {
+        for slot in LIVE_GROUPS.iter() {
+            let pgid = slot.load(Ordering::SeqCst);
+            if pgid > 0 {
+                unsafe {
+                    libc::killpg(pgid, libc::SIGKILL);
+                }
+            }
+        }
+        unsafe {
+            libc::signal(sig, libc::SIG_DFL);
+            libc::raise(sig);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::io;
+    use std::mem;
+    use std::ptr;
+    use std::time::Duration;
+
+    use tokio::process::Command;
+    use winapi::shared::minwindef::{DWORD, FALSE, LPVOID};
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+    use winapi::um::jobapi2::{
+        AssignProcessToJobObject, CreateJobObjectW, SetInformationJobObject, TerminateJobObject,
+    };
+    use winapi::um::processthreadsapi::{OpenProcess, OpenThread, ResumeThread};
+    use winapi::um::tlhelp32::{
+        CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+    };
+    use winapi::um::winbase::CREATE_SUSPENDED;
+    use winapi::um::winnt::{
+        JobObjectExtendedLimitInformation, HANDLE, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE, PROCESS_SET_QUOTA, PROCESS_TERMINATE,
+        THREAD_SUSPEND_RESUME,
+    };
+
+    /// Starts the process suspended, so that it cannot spawn processes before assigned to the job.
+    pub fn prepare(command: &mut Command) {
+        command.creation_flags(CREATE_SUSPENDED);
+    }
+
+    /// Job object to which the child is assigned.
+    ///
+    /// Processes spawned by the child belong to the job as well,
+    /// and all of them are killed when the job is closed (e.g. acick exits).
+    #[derive(Debug)]
+    pub struct ProcessTree {
+        job: HANDLE,
+    }
+
+    impl ProcessTree {
+        /// Assigns the suspended process to a new job and resumes it.
+        pub fn new(pid: u32) -> io::Result<Self> {
+            unsafe {
+                let job = CreateJobObjectW(ptr::null_mut(), ptr::null());
+                if job.is_null() {
+                    return Err(io::Error::last_os_error());
+                }
+                let tree = Self { job };
+                let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = mem::zeroed();
+                info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+                let is_set = SetInformationJobObject(
+                    tree.job,
+                    JobObjectExtendedLimitInformation,
+                    &mut info as *mut _ as LPVOID,
+                    mem::size_of_val(&info) as DWORD,
+                );
+                if is_set == FALSE {
+                    return Err(io::Error::last_os_error());
+                }
+                let process = OpenProcess(PROCESS_SET_QUOTA | PROCESS_TERMINATE, FALSE, pid);
+                if process.is_null() {
+                    return Err(io::Error::last_os_error());
+                }
+                let is_assigned = AssignProcessToJobObject(tree.job, process);
+                CloseHandle(process);
+                if is_assigned == FALSE {
+                    return Err(io::Error::last_os_error());
+                }
+                resume_threads(pid)?;
+                Ok(tree)
+            }
+        }
+
+        /// Processes on Windows cannot be asked to terminate, so they are killed without grace.
+        pub async fn kill(self, _grace: Duration) {
+            unsafe {
+                TerminateJobObject(self.job, 1);
+            }
+        }
+    }
+
+    impl Drop for ProcessTree {
+        fn drop(&mut self) {
+            unsafe {
+                CloseHandle(self.job);
+            }
+        }
+    }
+
+    /// Resumes threads of the process created with `CREATE_SUSPENDED`.
+    unsafe fn resume_threads(pid: u32) -> io::Result<()> {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+        let mut entry: THREADENTRY32 = mem::zeroed();
+        entry.dwSize = mem::size_of::<THREADENTRY32>() as DWORD;
+        let mut result = Ok(());
+        let mut has_entry = Thread32First(snapshot, &mut entry) != FALSE;
+        while has_entry {
+            if entry.th32OwnerProcessID == pid {
+                let thread = OpenThread(THREAD_SUSPEND_RESUME, FALSE, entry.th32ThreadID);
+                if thread.is_null() {
+                    result = Err(io::Error::last_os_error());
+                } else {
+                    if ResumeThread(thread) == DWORD::MAX {
+                        result = Err(io::Error::last_os_error());
+                    }
+                    CloseHandle(thread);
+                }
+            }
+            has_entry = Thread32Next(snapshot, &mut entry) != FALSE;
+        }
+        CloseHandle(snapshot);
+        result
+    }
+}
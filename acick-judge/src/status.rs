@@ -1,18 +1,18 @@
 use std::cmp::max;
 use std::fmt;
-use std::io::Write as _;
+use std::io::Write;
 use std::time::Duration;
 
 use console::StyledObject;
 use getset::CopyGetters;
 use serde::{Deserialize, Serialize};
 
-use crate::console::{
+use crate::diff::TextDiff;
+use crate::style::{
     sty_dim, sty_g, sty_g_rev, sty_g_under, sty_none, sty_r, sty_r_rev, sty_r_under, sty_y,
     sty_y_rev, sty_y_under,
 };
-use crate::judge::diff::TextDiff;
-use crate::{Console, Error, Result};
+use crate::{Error, Result};
 
 #[derive(
     Serialize, Deserialize, AsRefStr, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash,
@@ -82,12 +82,12 @@ enum StatusInner {
 }
 
 impl StatusInner {
-    fn describe(&self, cnsl: &mut Console) -> Result<()> {
+    fn describe(&self, writer: &mut dyn Write) -> Result<()> {
         match self {
             Self::Ac => {}
-            Self::Wa { diff } => writeln!(cnsl, "{}", diff)?,
+            Self::Wa { diff } => writeln!(writer, "{}", diff)?,
             Self::Tle => {}
-            Self::Re { reason } => writeln!(cnsl, "{}", reason)?,
+            Self::Re { reason } => writeln!(writer, "{}", reason)?,
         }
         Ok(())
     }
@@ -146,12 +146,21 @@ impl Status {
         }
     }
 
+    pub fn sample_name(&self) -> &str {
+        &self.sample_name
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
     pub fn kind(&self) -> StatusKind {
         self.inner.to_kind()
     }
 
-    pub fn describe(&self, cnsl: &mut Console) -> Result<()> {
-        self.inner.describe(cnsl)
+    /// Writes details of the status (e.g. diff of outputs).
+    pub fn describe(&self, writer: &mut dyn Write) -> Result<()> {
+        self.inner.describe(writer)
     }
 }
 
@@ -228,6 +237,10 @@ impl TotalStatus {
     pub fn count(&self) -> usize {
         self.count.total()
     }
+
+    pub fn into_statuses(self) -> Vec<Status> {
+        self.statuses
+    }
 }
 
 impl fmt::Display for TotalStatus {
@@ -0,0 +1,25 @@
+//! Styles of verdicts and diffs.
+//!
+//! Whether colors are shown follows the global setting of `console` crate.
+
+use console::{Style, StyledObject};
+
+macro_rules! def_style {
+    ($name:ident, $style:expr) => {
+        pub fn $name<D>(val: D) -> StyledObject<D> {
+            $style.apply_to(val)
+        }
+    };
+}
+
+def_style!(sty_none, Style::new());
+def_style!(sty_r, Style::new().red());
+def_style!(sty_g, Style::new().green());
+def_style!(sty_y, Style::new().yellow());
+def_style!(sty_dim, Style::new().dim());
+def_style!(sty_r_under, Style::new().underlined().red());
+def_style!(sty_g_under, Style::new().underlined().green());
+def_style!(sty_y_under, Style::new().underlined().yellow());
+def_style!(sty_r_rev, Style::new().bold().reverse().red());
+def_style!(sty_g_rev, Style::new().bold().reverse().green());
+def_style!(sty_y_rev, Style::new().bold().reverse().yellow());
@@ -2,12 +2,14 @@ use std::env::current_dir;
 use std::fmt;
 use std::fs;
 use std::io::{self, Seek as _, SeekFrom, Write};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
+use std::time::SystemTime;
 
 use anyhow::{anyhow, Context as _};
 use serde::{de, Deserialize, Deserializer, Serialize};
 
+use crate::metrics::{Metrics, Phase};
 use crate::{Error, Result};
 
 /// Wraps `shellexpand::full` method.
@@ -61,6 +63,26 @@ impl AbsPathBuf {
         self.0.push(path)
     }
 
+    /// Resolves `.` and `..` lexically without accessing file system.
+    pub fn normalize(&self) -> Self {
+        let mut path = PathBuf::new();
+        for component in self.0.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    path.pop();
+                }
+                component => path.push(component),
+            }
+        }
+        Self(path)
+    }
+
+    /// Returns true if the path is `base` or inside `base` after normalized.
+    pub fn starts_with(&self, base: &AbsPathBuf) -> bool {
+        self.normalize().0.starts_with(&base.normalize().0)
+    }
+
     /// Returns parent path.
     pub fn parent(&self) -> Option<Self> {
         self.0.parent().map(|parent| Self(parent.to_owned()))
@@ -106,20 +128,22 @@ impl AbsPathBuf {
         save: impl FnOnce(fs::File) -> Result<()>,
         overwrite: bool,
     ) -> Result<Option<bool>> {
-        let is_existed = self.as_ref().is_file();
-        if !overwrite && is_existed {
-            return Ok(None);
-        }
-        self.create_dir_all_and_open(false, true)
-            .with_context(|| format!("Could not open file : {}", self))
-            .and_then(|mut file| {
-                // truncate file before write
-                file.seek(SeekFrom::Start(0))?;
-                file.set_len(0)?;
-                Ok(file)
-            })
-            .and_then(save)?;
-        Ok(Some(is_existed))
+        Metrics::measure(Phase::DiskIo, || {
+            let is_existed = self.as_ref().is_file();
+            if !overwrite && is_existed {
+                return Ok(None);
+            }
+            self.create_dir_all_and_open(false, true)
+                .with_context(|| format!("Could not open file : {}", self))
+                .and_then(|mut file| {
+                    // truncate file before write
+                    file.seek(SeekFrom::Start(0))?;
+                    file.set_len(0)?;
+                    Ok(file)
+                })
+                .and_then(save)?;
+            Ok(Some(is_existed))
+        })
     }
 
     pub fn load_pretty<T>(
@@ -143,11 +167,13 @@ impl AbsPathBuf {
     }
 
     pub fn load<T>(&self, load: impl FnOnce(fs::File) -> Result<T>) -> Result<T> {
-        fs::OpenOptions::new()
-            .read(true)
-            .open(&self.0)
-            .with_context(|| format!("Could not open file : {}", self))
-            .and_then(load)
+        Metrics::measure(Phase::DiskIo, || {
+            fs::OpenOptions::new()
+                .read(true)
+                .open(&self.0)
+                .with_context(|| format!("Could not open file : {}", self))
+                .and_then(load)
+        })
     }
 
     pub fn remove_dir_all_pretty(
@@ -252,6 +278,28 @@ impl AbsPathBuf {
             .open(&self.0)
     }
 
+    /// Returns total size in bytes of the file or of all files under the directory.
+    ///
+    /// Returns 0 if the path does not exist.
+    pub fn disk_usage(&self) -> io::Result<u64> {
+        let mut total = 0;
+        walk_files(&self.0, &mut |meta| total += meta.len())?;
+        Ok(total)
+    }
+
+    /// Returns the latest modification time of the file or of all files under the directory.
+    ///
+    /// Returns `None` if the path does not exist.
+    pub fn last_modified(&self) -> io::Result<Option<SystemTime>> {
+        let mut latest = None;
+        walk_files(&self.0, &mut |meta| {
+            if let Ok(modified) = meta.modified() {
+                latest = latest.max(Some(modified));
+            }
+        })?;
+        Ok(latest)
+    }
+
     pub fn strip_prefix(&self, base: &AbsPathBuf) -> &Path {
         self.0
             .strip_prefix(&base.0)
@@ -267,6 +315,35 @@ impl AbsPathBuf {
     }
 }
 
+/// Makes the file readable and writable only by its owner (mode 0600),
+/// because it holds secrets (e.g. cookies and tokens). Does nothing on platforms other than unix.
+pub fn restrict_to_owner(file: &fs::File) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = file;
+    Ok(())
+}
+
+fn walk_files(path: &Path, f: &mut dyn FnMut(&fs::Metadata)) -> io::Result<()> {
+    let meta = match fs::symlink_metadata(path) {
+        Ok(meta) => meta,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    if meta.is_dir() {
+        for entry in fs::read_dir(path)? {
+            walk_files(&entry?.path(), f)?;
+        }
+    } else {
+        f(&meta);
+    }
+    Ok(())
+}
+
 impl AsRef<PathBuf> for AbsPathBuf {
     fn as_ref(&self) -> &PathBuf {
         &self.0
@@ -407,6 +484,20 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_normalize() -> anyhow::Result<()> {
+        let base = AbsPathBuf::try_new(prefix("/a/b"))?;
+        assert_eq!(base.join("c/../d").normalize(), base.join("d"));
+        assert_eq!(
+            base.join("../../../..").normalize(),
+            AbsPathBuf::try_new(prefix("/"))?
+        );
+        assert!(base.join("c/./d").starts_with(&base));
+        assert!(!base.join("../c").starts_with(&base));
+        assert!(!AbsPathBuf::try_new(prefix("/a/bc"))?.starts_with(&base));
+        Ok(())
+    }
+
     #[test]
     fn test_from_str_success() -> anyhow::Result<()> {
         for (actual, expected) in SHELL_PATH_SUCCESS_TESTS.iter() {
@@ -493,4 +584,38 @@ abs_path: {}
         assert_eq!(format!("{}", actual), format!("{}", expected.display()));
         Ok(())
     }
+
+    #[test]
+    fn test_disk_usage() -> anyhow::Result<()> {
+        let test_dir = tempfile::tempdir()?;
+        let dir = AbsPathBuf::try_new(test_dir.path())?;
+        dir.join("a/b").create_dir_all()?;
+        fs::write(dir.join("a/x.txt").as_ref(), b"12345")?;
+        fs::write(dir.join("a/b/y.txt").as_ref(), b"123")?;
+        assert_eq!(dir.join("a").disk_usage()?, 8);
+        assert_eq!(dir.join("a/b/y.txt").disk_usage()?, 3);
+        assert_eq!(dir.join("c").disk_usage()?, 0);
+        assert!(dir.join("a").last_modified()?.is_some());
+        assert_eq!(dir.join("c").last_modified()?, None);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_restrict_to_owner() -> anyhow::Result<()> {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let test_dir = tempfile::tempdir()?;
+        let path = AbsPathBuf::try_new(test_dir.path())?.join("secret.txt");
+        path.save(
+            |mut file| {
+                restrict_to_owner(&file)?;
+                Ok(file.write_all(b"secret")?)
+            },
+            false,
+        )?;
+        let mode = fs::metadata(path.as_ref())?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        Ok(())
+    }
 }
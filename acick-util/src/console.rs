@@ -3,7 +3,9 @@ use std::io::{self, BufRead as _, Write};
 
 use anyhow::Context as _;
 use console::Term;
-use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use indicatif::{ProgressDrawTarget, ProgressStyle};
+
+pub use indicatif::ProgressBar;
 
 static PB_TICK_INTERVAL_MS: u64 = 50;
 static PB_TEMPL_COUNT: &str =
@@ -28,6 +30,8 @@ enum Inner {
 pub struct ConsoleConfig {
     /// If true, assumes yes and skips any confirmation.
     pub assume_yes: bool,
+    /// If true, shows detailed diagnostics of commands.
+    pub verbose: bool,
 }
 
 #[derive(Debug)]
@@ -92,15 +96,41 @@ impl Console {
         }
     }
 
+    pub fn is_verbose(&self) -> bool {
+        self.conf.verbose
+    }
+
     pub fn warn(&mut self, message: &str) -> io::Result<()> {
         writeln!(self, "WARN: {}", message)
     }
 
+    /// Shows message in the last line, which is replaced by the next output.
+    pub fn set_status_line(&mut self, message: &str) -> io::Result<()> {
+        match self.inner {
+            Inner::Term(ref term) => {
+                term.clear_line()?;
+                term.write_str(message)
+            }
+            _ => writeln!(self, "{}", message),
+        }
+    }
+
+    pub fn clear_status_line(&mut self) -> io::Result<()> {
+        match self.inner {
+            Inner::Term(ref term) => term.clear_line(),
+            _ => Ok(()),
+        }
+    }
+
     pub fn confirm(&mut self, message: &str, default: bool) -> io::Result<bool> {
         if self.conf.assume_yes {
             return Ok(true);
         }
+        self.confirm_always(message, default)
+    }
 
+    /// Asks for confirmation even if `assume_yes` is enabled.
+    pub fn confirm_always(&mut self, message: &str, default: bool) -> io::Result<bool> {
         let prompt = format!("{} ({}) ", message, if default { "Y/n" } else { "y/N" });
         let input = self.prompt_and_read(&prompt, false)?;
         match input.to_lowercase().as_str() {
@@ -153,7 +183,7 @@ impl Console {
         Ok(())
     }
 
-    fn prompt_and_read(&mut self, prompt: &str, is_password: bool) -> io::Result<String> {
+    pub fn prompt_and_read(&mut self, prompt: &str, is_password: bool) -> io::Result<String> {
         self.prompt(prompt)?;
         self.read_user(is_password)
     }
@@ -236,7 +266,10 @@ mod tests {
 
     #[test]
     fn test_warn() -> anyhow::Result<()> {
-        let conf = ConsoleConfig { assume_yes: true };
+        let conf = ConsoleConfig {
+            assume_yes: true,
+            ..Default::default()
+        };
         let mut cnsl = Console::buf(conf);
         cnsl.warn("message")?;
         let output_str = cnsl.take_output()?;
@@ -264,6 +297,7 @@ mod tests {
         for (assume_yes, input, default, expected) in tests {
             let conf = ConsoleConfig {
                 assume_yes: *assume_yes,
+                ..Default::default()
             };
             let mut cnsl = Console::buf(conf);
             cnsl.write_input(input);
@@ -273,6 +307,18 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_confirm_always() -> anyhow::Result<()> {
+        let conf = ConsoleConfig {
+            assume_yes: true,
+            ..Default::default()
+        };
+        let mut cnsl = Console::buf(conf);
+        cnsl.write_input("n");
+        assert!(!cnsl.confirm_always("message", true)?);
+        Ok(())
+    }
+
     #[test]
     fn test_get_env_or_prompt_and_read() -> anyhow::Result<()> {
         let cnsl_term = Console::term(ConsoleConfig::default());
@@ -0,0 +1,102 @@
+//! Passphrase-based encryption of data moved between machines (e.g. exported sessions).
+
+use std::num::NonZeroU32;
+
+use anyhow::{anyhow, Context as _};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom as _, SystemRandom};
+
+use crate::Result;
+
+/// Prefix of encrypted data, which also identifies the format.
+pub static ENCRYPTED_PREFIX: &str = "acick-encrypted-v1:";
+
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+const PBKDF2_ITERATIONS: u32 = 100_000;
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<LessSafeKey> {
+    let mut key = [0; KEY_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        NonZeroU32::new(PBKDF2_ITERATIONS).unwrap(),
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    let key = UnboundKey::new(&AES_256_GCM, &key).map_err(|_| anyhow!("Could not create key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+/// Returns true if data is encrypted by `encrypt`.
+pub fn is_encrypted(data: &str) -> bool {
+    data.trim_start().starts_with(ENCRYPTED_PREFIX)
+}
+
+/// Encrypts data with AES-256-GCM by key derived from passphrase.
+///
+/// Returns base64-encoded salt, nonce and ciphertext with `ENCRYPTED_PREFIX`.
+pub fn encrypt(plain: &[u8], passphrase: &str) -> Result<String> {
+    let rng = SystemRandom::new();
+    let mut salt = [0; SALT_LEN];
+    let mut nonce = [0; NONCE_LEN];
+    rng.fill(&mut salt)
+        .and_then(|_| rng.fill(&mut nonce))
+        .map_err(|_| anyhow!("Could not generate random bytes"))?;
+
+    let key = derive_key(passphrase, &salt)?;
+    let mut in_out = plain.to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::empty(),
+        &mut in_out,
+    )
+    .map_err(|_| anyhow!("Could not encrypt data"))?;
+
+    let mut sealed = Vec::with_capacity(SALT_LEN + NONCE_LEN + in_out.len());
+    sealed.extend_from_slice(&salt);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&in_out);
+    Ok(format!("{}{}", ENCRYPTED_PREFIX, base64::encode(&sealed)))
+}
+
+/// Decrypts data encrypted by `encrypt`.
+pub fn decrypt(data: &str, passphrase: &str) -> Result<Vec<u8>> {
+    let encoded = data
+        .trim()
+        .strip_prefix(ENCRYPTED_PREFIX)
+        .context("Found data in unknown format")?;
+    let sealed = base64::decode(encoded).context("Could not decode encrypted data")?;
+    if sealed.len() < SALT_LEN + NONCE_LEN {
+        return Err(anyhow!("Found too short encrypted data"));
+    }
+    let (salt, rest) = sealed.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let key = derive_key(passphrase, salt)?;
+    let nonce =
+        Nonce::try_assume_unique_for_key(nonce).map_err(|_| anyhow!("Found invalid nonce"))?;
+    let mut in_out = ciphertext.to_vec();
+    let plain = key
+        .open_in_place(nonce, Aad::empty(), &mut in_out)
+        .map_err(|_| anyhow!("Could not decrypt data. The passphrase may be wrong."))?;
+    Ok(plain.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_and_decrypt() -> anyhow::Result<()> {
+        let plain = b"{\"name\":\"REVEL_SESSION\"}\n";
+        let encrypted = encrypt(plain, "passphrase")?;
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains("REVEL_SESSION"));
+        assert_eq!(decrypt(&encrypted, "passphrase")?, plain);
+        assert!(decrypt(&encrypted, "wrong").is_err());
+        assert!(decrypt("plain text", "passphrase").is_err());
+        Ok(())
+    }
+}
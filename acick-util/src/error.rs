@@ -0,0 +1,159 @@
+//! Kinds of errors surfaced as exit statuses of process.
+
+use std::error::Error as StdError;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::Error;
+
+#[derive(
+    Serialize, AsRefStr, EnumIter, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum ErrorKind {
+    /// Error not classified into any other kinds
+    Unknown,
+    /// Config file is missing or invalid
+    Config,
+    /// Could not communicate with service
+    Network,
+    /// Not logged in or login rejected by service
+    Auth,
+    /// Source code could not be compiled
+    Compile,
+    /// Outputs did not match expected outputs
+    WrongAnswer,
+    /// Run exceeded time limit
+    TimeLimitExceeded,
+    /// Run exited with non-zero status
+    RuntimeError,
+    /// Command finished but reported failure in its outcome
+    Failed,
+}
+
+impl ErrorKind {
+    /// Returns the kind tagged to the innermost cause of `err`.
+    pub fn of(err: &Error) -> Self {
+        err.chain()
+            .filter_map(|cause| cause.downcast_ref::<Tagged>())
+            .last()
+            .map_or(Self::Unknown, |tagged| tagged.kind)
+    }
+
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::Unknown => 1,
+            Self::Config => 3,
+            Self::Network => 4,
+            Self::Auth => 5,
+            Self::Compile => 10,
+            Self::WrongAnswer => 11,
+            Self::TimeLimitExceeded => 12,
+            Self::RuntimeError => 13,
+            Self::Failed => 20,
+        }
+    }
+
+    /// Tags `err` with the kind without changing its message.
+    pub fn tag(self, err: impl Into<Error>) -> Error {
+        Error::new(Tagged {
+            kind: self,
+            inner: err.into(),
+        })
+    }
+}
+
+/// Error that displays and chains as the inner error.
+struct Tagged {
+    kind: ErrorKind,
+    inner: Error,
+}
+
+impl fmt::Debug for Tagged {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.inner, f)
+    }
+}
+
+impl fmt::Display for Tagged {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl StdError for Tagged {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.inner.chain().nth(1)
+    }
+}
+
+/// Returns the outermost cause of `err` that has type `E`, seeing through kinds tagged to causes.
+pub fn find_cause<E>(err: &Error) -> Option<&E>
+where
+    E: fmt::Display + fmt::Debug + Send + Sync + 'static,
+{
+    err.chain()
+        .find_map(|cause| match cause.downcast_ref::<Tagged>() {
+            Some(tagged) => tagged.inner.downcast_ref::<E>(),
+            None => cause.downcast_ref::<E>(),
+        })
+}
+
+pub trait WithErrorKind<T> {
+    fn with_kind(self, kind: ErrorKind) -> Result<T, Error>;
+}
+
+impl<T, E: Into<Error>> WithErrorKind<T> for Result<T, E> {
+    fn with_kind(self, kind: ErrorKind) -> Result<T, Error> {
+        self.map_err(|err| kind.tag(err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::io;
+
+    use anyhow::{anyhow, Context as _};
+    use strum::IntoEnumIterator as _;
+
+    use super::*;
+
+    #[test]
+    fn test_of() {
+        let err = anyhow!("connection refused");
+        assert_eq!(ErrorKind::of(&err), ErrorKind::Unknown);
+
+        let result: Result<(), _> = Err(err);
+        let err = result
+            .with_kind(ErrorKind::Network)
+            .context("Could not send request")
+            .with_kind(ErrorKind::Auth)
+            .context("Could not log in")
+            .unwrap_err();
+        assert_eq!(ErrorKind::of(&err), ErrorKind::Network);
+        assert_eq!(
+            format!("{:#}", err),
+            "Could not log in: Could not send request: connection refused"
+        );
+    }
+
+    #[test]
+    fn test_find_cause() {
+        let err = ErrorKind::Network
+            .tag(io::Error::from(io::ErrorKind::ConnectionRefused))
+            .context("Could not send request");
+        let cause = find_cause::<io::Error>(&err).unwrap();
+        assert_eq!(cause.kind(), io::ErrorKind::ConnectionRefused);
+        assert!(find_cause::<fmt::Error>(&err).is_none());
+    }
+
+    #[test]
+    fn test_exit_code_unique() {
+        let codes: HashSet<_> = ErrorKind::iter().map(ErrorKind::exit_code).collect();
+        assert_eq!(codes.len(), ErrorKind::iter().count());
+        assert!(!codes.contains(&0));
+    }
+}
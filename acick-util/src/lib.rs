@@ -9,9 +9,13 @@ use lazy_static::lazy_static;
 
 pub mod abs_path;
 pub mod console;
+pub mod crypto;
+pub mod error;
 mod macros;
+pub mod metrics;
 pub mod model;
 pub mod service;
+pub mod store;
 pub mod web;
 
 use crate::abs_path::AbsPathBuf;
@@ -0,0 +1,146 @@
+//! Metrics of commands, i.e. requests sent to services and time spent in each phase.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use serde::Serialize;
+
+lazy_static! {
+    static ref METRICS: Mutex<Metrics> = Mutex::new(Metrics::default());
+}
+
+#[derive(Serialize, AsRefStr, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Phase {
+    Config,
+    Network,
+    Parse,
+    DiskIo,
+    Dropbox,
+    Compile,
+    Judge,
+}
+
+/// Accumulated time of each phase.
+///
+/// Phases may overlap (e.g. `dropbox` includes `disk-io` of downloaded files).
+#[derive(Serialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Timings(BTreeMap<Phase, Duration>);
+
+impl Timings {
+    pub fn get(&self, phase: Phase) -> Duration {
+        self.0.get(&phase).copied().unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (phase, elapsed)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {:.2}s", phase.as_ref(), elapsed.as_secs_f32())?;
+        }
+        Ok(())
+    }
+}
+
+/// Metrics accumulated in the current process.
+#[derive(Serialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Metrics {
+    /// Number of requests sent to services, including retries
+    requests: usize,
+    retries: usize,
+    /// Number of requests that failed after all retries
+    failures: usize,
+    timings: Timings,
+}
+
+impl Metrics {
+    pub fn current() -> Self {
+        METRICS.lock().unwrap().clone()
+    }
+
+    pub fn requests(&self) -> usize {
+        self.requests
+    }
+
+    pub fn timings(&self) -> &Timings {
+        &self.timings
+    }
+
+    pub fn record(phase: Phase, elapsed: Duration) {
+        Self::update(|metrics| *metrics.timings.0.entry(phase).or_default() += elapsed);
+    }
+
+    /// Runs `f` and adds the elapsed time to `phase`.
+    pub fn measure<R>(phase: Phase, f: impl FnOnce() -> R) -> R {
+        let started_at = Instant::now();
+        let result = f();
+        Self::record(phase, started_at.elapsed());
+        result
+    }
+
+    /// Records a request that took `elapsed` until response.
+    pub(crate) fn record_request(elapsed: Duration) {
+        Self::update(|metrics| metrics.requests += 1);
+        Self::record(Phase::Network, elapsed);
+    }
+
+    pub(crate) fn record_retry() {
+        Self::update(|metrics| metrics.retries += 1);
+    }
+
+    pub(crate) fn record_failure() {
+        Self::update(|metrics| metrics.failures += 1);
+    }
+
+    fn update(f: impl FnOnce(&mut Self)) {
+        f(&mut METRICS.lock().unwrap())
+    }
+}
+
+// shows metrics of requests, while timings are shown by `Timings`
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} requests, {} retries, {} failures, network time: {:.2}s",
+            self.requests,
+            self.retries,
+            self.failures,
+            self.timings.get(Phase::Network).as_secs_f32()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let mut metrics = Metrics {
+            requests: 3,
+            retries: 1,
+            ..Default::default()
+        };
+        metrics
+            .timings
+            .0
+            .insert(Phase::Judge, Duration::from_millis(1500));
+        metrics
+            .timings
+            .0
+            .insert(Phase::Network, Duration::from_millis(20));
+        assert_eq!(metrics.timings.to_string(), "network: 0.02s, judge: 1.50s");
+        assert_eq!(
+            metrics.to_string(),
+            "3 requests, 1 retries, 0 failures, network time: 0.02s"
+        );
+    }
+}
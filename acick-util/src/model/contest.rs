@@ -3,8 +3,10 @@ use std::convert::Infallible;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::str::FromStr;
+use std::time::Duration;
 
-use getset::Getters;
+use chrono::{DateTime, FixedOffset, Local};
+use getset::{CopyGetters, Getters};
 use serde::{Deserialize, Serialize};
 
 use crate::regex;
@@ -34,6 +36,75 @@ impl Default for Contest {
     }
 }
 
+/// Schedule of a contest.
+#[derive(Serialize, Deserialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContestInfo {
+    #[get = "pub"]
+    contest: Contest,
+    #[get_copy = "pub"]
+    start_at: DateTime<FixedOffset>,
+    #[get_copy = "pub"]
+    end_at: DateTime<FixedOffset>,
+}
+
+impl ContestInfo {
+    pub fn new(
+        contest: Contest,
+        start_at: DateTime<FixedOffset>,
+        end_at: DateTime<FixedOffset>,
+    ) -> Self {
+        Self {
+            contest,
+            start_at,
+            end_at,
+        }
+    }
+
+    pub fn phase_at(&self, now: DateTime<Local>) -> ContestPhase {
+        let now = now.naive_utc();
+        if now < self.start_at.naive_utc() {
+            ContestPhase::NotStarted
+        } else if now < self.end_at.naive_utc() {
+            ContestPhase::Running
+        } else {
+            ContestPhase::Ended
+        }
+    }
+
+    /// Returns the time until the next event (start or end) of the contest.
+    ///
+    /// Returns `None` if the contest has already ended.
+    pub fn remaining_at(&self, now: DateTime<Local>) -> Option<Duration> {
+        let next = match self.phase_at(now) {
+            ContestPhase::NotStarted => self.start_at,
+            ContestPhase::Running => self.end_at,
+            ContestPhase::Ended => return None,
+        };
+        (next.with_timezone(&Local) - now).to_std().ok()
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, AsRefStr, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum ContestPhase {
+    NotStarted,
+    Running,
+    Ended,
+}
+
+impl fmt::Display for ContestPhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::NotStarted => "not started",
+            Self::Running => "running",
+            Self::Ended => "ended",
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Eq)]
 pub struct ContestId(String);
 
@@ -113,6 +184,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_contest_info_phase() {
+        let start_at = DateTime::parse_from_rfc3339("2018-07-01T21:00:00+09:00").unwrap();
+        let end_at = DateTime::parse_from_rfc3339("2018-07-01T22:40:00+09:00").unwrap();
+        let info = ContestInfo::new(Contest::default(), start_at, end_at);
+        let at = |s| {
+            DateTime::parse_from_rfc3339(s)
+                .unwrap()
+                .with_timezone(&Local)
+        };
+
+        let now = at("2018-07-01T20:00:00+09:00");
+        assert_eq!(info.phase_at(now), ContestPhase::NotStarted);
+        assert_eq!(info.remaining_at(now), Some(Duration::from_secs(3600)));
+        let now = at("2018-07-01T22:00:00+09:00");
+        assert_eq!(info.phase_at(now), ContestPhase::Running);
+        assert_eq!(info.remaining_at(now), Some(Duration::from_secs(2400)));
+        let now = at("2018-07-01T22:40:00+09:00");
+        assert_eq!(info.phase_at(now), ContestPhase::Ended);
+        assert_eq!(info.remaining_at(now), None);
+    }
+
     #[test]
     fn test_contest_id_display() {
         assert_eq!(&ContestId::from("arc100").to_string(), "arc100");
@@ -0,0 +1,78 @@
+use crate::model::LangName;
+
+/// Prefixes of names of languages for extensions of source files,
+/// matched with names of languages listed by services (e.g. `C++ (GCC 9.2.1)`).
+static LANG_PREFIXES: &[(&str, &[&str])] = &[
+    ("c", &["C ("]),
+    ("cc", &["C++"]),
+    ("cpp", &["C++"]),
+    ("cxx", &["C++"]),
+    ("cr", &["Crystal"]),
+    ("cs", &["C#"]),
+    ("d", &["D ("]),
+    ("go", &["Go"]),
+    ("hs", &["Haskell"]),
+    ("java", &["Java ("]),
+    ("jl", &["Julia"]),
+    ("js", &["JavaScript"]),
+    ("kt", &["Kotlin"]),
+    ("ml", &["OCaml"]),
+    ("nim", &["Nim"]),
+    ("php", &["PHP"]),
+    ("pl", &["Perl"]),
+    ("py", &["Python", "PyPy"]),
+    ("rb", &["Ruby"]),
+    ("rs", &["Rust"]),
+    ("scala", &["Scala"]),
+    ("swift", &["Swift"]),
+    ("ts", &["TypeScript"]),
+    ("zig", &["Zig"]),
+];
+
+/// Returns languages in `lang_names` in which source file with the extension is probably written,
+/// in the order of `lang_names`.
+pub fn detect_lang_names<'a>(extension: &str, lang_names: &'a [LangName]) -> Vec<&'a LangName> {
+    let extension = extension.to_lowercase();
+    let prefixes = match LANG_PREFIXES.iter().find(|(ext, _)| *ext == extension) {
+        Some((_, prefixes)) => prefixes,
+        None => return Vec::new(),
+    };
+    lang_names
+        .iter()
+        .filter(|lang_name| prefixes.iter().any(|prefix| lang_name.starts_with(prefix)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_lang_names() {
+        let lang_names: Vec<LangName> = vec![
+            "C (GCC 9.2.1)".into(),
+            "C++ (GCC 9.2.1)".into(),
+            "C# (.NET Core 3.1.201)".into(),
+            "Python (3.8.2)".into(),
+            "PyPy3 (7.3.0)".into(),
+            "Java (OpenJDK 11.0.6)".into(),
+            "JavaScript (Node.js 12.16.1)".into(),
+        ];
+        let tests: &[(&str, &[&str])] = &[
+            ("c", &["C (GCC 9.2.1)"]),
+            ("CPP", &["C++ (GCC 9.2.1)"]),
+            ("py", &["Python (3.8.2)", "PyPy3 (7.3.0)"]),
+            ("java", &["Java (OpenJDK 11.0.6)"]),
+            ("rs", &[]),
+            ("txt", &[]),
+        ];
+        for (extension, expected) in tests {
+            assert_eq!(
+                detect_lang_names(extension, &lang_names),
+                expected.to_vec(),
+                "{}",
+                extension
+            );
+        }
+    }
+}
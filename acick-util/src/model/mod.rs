@@ -1,12 +1,20 @@
 mod contest;
+mod lang;
 mod problem;
 mod sample;
 mod service;
+mod standings;
+mod statement;
+mod submission;
 
 pub use contest::*;
+pub use lang::*;
 pub use problem::*;
 pub use sample::*;
 pub use service::*;
+pub use standings::*;
+pub use statement::*;
+pub use submission::*;
 
 pub type LangId = String;
 
@@ -1,5 +1,5 @@
 use std::cmp::Ordering;
-use std::convert::{Infallible, TryFrom};
+use std::convert::Infallible;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::str::FromStr;
@@ -8,6 +8,8 @@ use std::time::Duration;
 use getset::{CopyGetters, Getters, Setters};
 use serde::{Deserialize, Serialize};
 
+pub use acick_judge::{Byte, Compare};
+
 use crate::model::sample::{Sample, SampleIter};
 
 #[derive(
@@ -27,6 +29,17 @@ pub struct Problem {
     memory_limit: Option<Byte>,
     #[get_copy = "pub"]
     compare: Compare,
+    /// Points of problem shown in statement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[get_copy = "pub"]
+    #[set = "pub"]
+    points: Option<u64>,
+    /// Difficulty estimated by AtCoder Problems, which is comparable to rating
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[get_copy = "pub"]
+    #[set = "pub"]
+    difficulty: Option<i64>,
+    #[get = "pub"]
     #[set = "pub"]
     samples: Vec<Sample>,
 }
@@ -48,10 +61,21 @@ impl Problem {
             time_limit,
             memory_limit,
             compare,
+            points: None,
+            difficulty: None,
             samples,
         }
     }
 
+    /// Returns true if `name` is the id (e.g. `Ex`), the url name (e.g. `abc234_h`)
+    /// or the index in the url name (e.g. `h`) of the problem.
+    pub fn is_addressed_by(&self, name: &str) -> bool {
+        let index = self.url_name.rsplit('_').next().unwrap_or(&self.url_name);
+        self.id == ProblemId::from(name)
+            || self.url_name.eq_ignore_ascii_case(name)
+            || index.eq_ignore_ascii_case(name)
+    }
+
     pub fn take_samples(self, sample_name: &Option<String>) -> SampleIter {
         if let Some(sample_name) = sample_name {
             self.samples
@@ -138,80 +162,29 @@ impl fmt::Display for ProblemId {
     }
 }
 
-#[derive(
-    Serialize,
-    Deserialize,
-    EnumString,
-    EnumVariantNames,
-    IntoStaticStr,
-    Debug,
-    Copy,
-    Clone,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-)]
-#[serde(rename_all = "kebab-case")]
-#[strum(serialize_all = "kebab-case")]
-pub enum Compare {
-    Default,
-    // TODO: support float
-    // Float {
-    //     relative_error: Option<f64>,
-    //     absolute_error: Option<f64>,
-    // },
-}
-
-impl Compare {
-    pub fn compare(self, a: &str, b: &str) -> bool {
-        match self {
-            Self::Default => Self::compare_default(a, b),
-        }
-    }
-
-    fn compare_default(a: &str, b: &str) -> bool {
-        a.trim_end() == b.trim_end() // ignore spaces at the end of lines
-    }
-}
-
-#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
-#[serde(try_from = "String", into = "String")]
-pub struct Byte(u64);
-
-impl FromStr for Byte {
-    type Err = &'static str;
-
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        Ok(Self(bytefmt::parse(s)?))
-    }
-}
-
-impl TryFrom<String> for Byte {
-    type Error = &'static str;
-
-    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
-        Self::from_str(&s)
-    }
-}
-
-impl From<Byte> for String {
-    fn from(byte: Byte) -> Self {
-        bytefmt::format_to(byte.0, bytefmt::Unit::MB)
-    }
-}
-
-impl fmt::Display for Byte {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(&String::from(*self))
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_addressed_by() {
+        let problem = Problem::new(
+            "Ex",
+            "Problem Ex",
+            "abc234_h",
+            None,
+            None,
+            Compare::Default,
+            vec![],
+        );
+        for name in &["Ex", "ex", "abc234_h", "ABC234_H", "h", "H"] {
+            assert!(problem.is_addressed_by(name), "{}", name);
+        }
+        for name in &["e", "abc234", "234_h", "x"] {
+            assert!(!problem.is_addressed_by(name), "{}", name);
+        }
+    }
+
     #[test]
     fn test_problem_take_sapmles() {
         let samples = vec![
@@ -225,6 +198,7 @@ mod tests {
             time_limit: Some(Duration::from_secs(2)),
             memory_limit: Some("1024 KB".parse().unwrap()),
             compare: Compare::Default,
+            points: None,
             samples: samples.clone(),
         };
         let tests = &[
@@ -257,40 +231,4 @@ mod tests {
         assert_eq!(&ProblemId::from("A").to_string(), "A");
         assert_eq!(&ProblemId::from("a").to_string(), "A");
     }
-
-    #[test]
-    fn test_compare() {
-        let tests = &[
-            (Compare::Default, "hoge", "hoge", true),
-            (Compare::Default, "hoge", "hoge  ", true),
-            (Compare::Default, "hoge", "hoge\n", true),
-            (Compare::Default, "hoge", "  hoge", false),
-            (Compare::Default, "hoge", "\nhoge", false),
-        ];
-
-        for (compare, a, b, expected) in tests {
-            let actual = compare.compare(a, b);
-            assert_eq!(actual, *expected);
-        }
-    }
-
-    #[test]
-    fn test_byte_try_from() -> anyhow::Result<()> {
-        assert_eq!(
-            Byte::try_from(String::from("1024KB")).unwrap(),
-            Byte(1024 * 1000)
-        );
-        assert_eq!(
-            Byte::try_from(String::from("1.2MB")).unwrap(),
-            Byte(1200 * 1000)
-        );
-        Ok(())
-    }
-
-    #[test]
-    fn test_byte_display() {
-        assert_eq!(&Byte(1024 * 1000).to_string(), "1.02 MB");
-        assert_eq!(&Byte(2000 * 1000).to_string(), "2 MB");
-        assert_eq!(&Byte(10 * 1000 * 1000).to_string(), "10 MB");
-    }
 }
@@ -1,36 +1,42 @@
 use std::hash::Hash;
 use std::vec::IntoIter;
 
-use getset::Getters;
 use serde::{Deserialize, Serialize};
 
+pub use acick_judge::Sample;
+
 use crate::Result;
 
-#[derive(Serialize, Deserialize, Getters, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Sample {
-    #[get = "pub"]
-    name: String,
-    #[get = "pub"]
-    input: String,
-    #[get = "pub"]
-    output: String,
+/// Set of testcases to be tested.
+#[derive(
+    Serialize,
+    Deserialize,
+    EnumString,
+    EnumVariantNames,
+    IntoStaticStr,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum TestSet {
+    /// Samples stored in problem file
+    Samples,
+    /// Pretests judged during contest (e.g. on Codeforces)
+    Pretests,
+    /// Full testcases of system tests (e.g. downloaded from AtCoder)
+    System,
+    /// Testcases added by hand (e.g. for stress tests)
+    Stress,
 }
 
-impl Sample {
-    pub fn new(
-        name: impl Into<String>,
-        input: impl Into<String>,
-        output: impl Into<String>,
-    ) -> Self {
-        Self {
-            name: name.into(),
-            input: input.into(),
-            output: output.into(),
-        }
-    }
-
-    pub fn take(self) -> (String, String, String) {
-        (self.name, self.input, self.output)
+impl Default for TestSet {
+    fn default() -> Self {
+        Self::Samples
     }
 }
 
@@ -73,7 +79,7 @@ impl From<Vec<Sample>> for SampleIter {
     fn from(samples: Vec<Sample>) -> Self {
         Self {
             len: samples.len(),
-            max_name_len: samples.iter().map(|s| s.name.len()).max().unwrap_or(0),
+            max_name_len: samples.iter().map(|s| s.name().len()).max().unwrap_or(0),
             iter: samples.into_iter(),
         }
     }
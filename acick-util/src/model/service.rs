@@ -2,8 +2,11 @@ use std::fmt;
 use std::hash::Hash;
 
 use getset::CopyGetters;
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
 
+use crate::model::ContestId;
+
 #[derive(Serialize, Deserialize, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Service {
     #[get_copy = "pub"]
@@ -27,6 +30,7 @@ impl Default for Service {
     Deserialize,
     EnumString,
     EnumVariantNames,
+    EnumIter,
     IntoStaticStr,
     Debug,
     Copy,
@@ -41,12 +45,32 @@ impl Default for Service {
 #[strum(serialize_all = "kebab-case")]
 pub enum ServiceKind {
     Atcoder,
+    /// Serves contests from local files without network
+    Mock,
 }
 
 impl ServiceKind {
     pub fn to_user_pass_env_names(self) -> (&'static str, &'static str) {
         match self {
             Self::Atcoder => ("ACICK_ATCODER_USERNAME", "ACICK_ATCODER_PASSWORD"),
+            Self::Mock => ("ACICK_MOCK_USERNAME", "ACICK_MOCK_PASSWORD"),
+        }
+    }
+
+    /// Infers service and contest from url of contest page or its subpages.
+    pub fn parse_contest_url(url: &str) -> Option<(Self, ContestId)> {
+        let url = Url::parse(url).ok()?;
+        match url.host_str()? {
+            "atcoder.jp" | "www.atcoder.jp" => {
+                let mut segments = url.path_segments()?;
+                match (segments.next(), segments.next()) {
+                    (Some("contests"), Some(contest_id)) if !contest_id.is_empty() => {
+                        Some((Self::Atcoder, ContestId::from(contest_id)))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
         }
     }
 }
@@ -71,4 +95,24 @@ mod tests {
     fn test_service_kind_default_display() {
         assert_eq!(ServiceKind::default().to_string(), "atcoder");
     }
+
+    #[test]
+    fn test_parse_contest_url() {
+        let tests = &[
+            ("https://atcoder.jp/contests/arc100", Some("arc100")),
+            (
+                "https://atcoder.jp/contests/abc150/tasks/abc150_a",
+                Some("abc150"),
+            ),
+            ("https://atcoder.jp/contests/", None),
+            ("https://atcoder.jp/home", None),
+            ("https://example.com/contests/arc100", None),
+            ("arc100", None),
+        ];
+        for (url, expected) in tests {
+            let actual = ServiceKind::parse_contest_url(url);
+            let expected = expected.map(|id| (ServiceKind::Atcoder, ContestId::from(id)));
+            assert_eq!(actual, expected, "{}", url);
+        }
+    }
 }
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+/// Standings of a contest in order of rank.
+#[derive(Serialize, Deserialize, Getters, Debug, Clone, PartialEq, Eq, Hash)]
+#[get = "pub"]
+pub struct Standings {
+    rows: Vec<StandingsRow>,
+}
+
+impl Standings {
+    pub fn new(rows: Vec<StandingsRow>) -> Self {
+        Self { rows }
+    }
+
+    /// Finds the row of user by username, ignoring case as service does.
+    pub fn find_user(&self, user: &str) -> Option<&StandingsRow> {
+        self.rows
+            .iter()
+            .find(|row| row.user.eq_ignore_ascii_case(user))
+    }
+}
+
+/// Rank, score and penalty of a user in standings.
+#[derive(Serialize, Deserialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StandingsRow {
+    #[get_copy = "pub"]
+    rank: usize,
+    #[get = "pub"]
+    user: String,
+    /// Score formatted as shown on service (e.g. `1200` or `1200.5`)
+    #[get = "pub"]
+    score: String,
+    /// Number of wrong submissions counted as penalty
+    #[get_copy = "pub"]
+    penalty: usize,
+    /// Time of the last submission that updated score
+    #[serde(with = "humantime_serde")]
+    #[get_copy = "pub"]
+    elapsed: Duration,
+}
+
+impl StandingsRow {
+    pub fn new(
+        rank: usize,
+        user: impl Into<String>,
+        score: impl Into<String>,
+        penalty: usize,
+        elapsed: Duration,
+    ) -> Self {
+        Self {
+            rank,
+            user: user.into(),
+            score: score.into(),
+            penalty,
+            elapsed,
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::hash::Hash;
+
+use getset::Getters;
+
+use crate::model::ProblemId;
+
+/// Problem statement with the files it refers to.
+#[derive(Getters, Debug, Clone, PartialEq, Eq, Hash)]
+#[get = "pub"]
+pub struct Statement {
+    problem_id: ProblemId,
+    html: String,
+    assets: Vec<Asset>,
+}
+
+impl Statement {
+    /// Directory name relative to the statement file in which assets are saved.
+    pub const ASSETS_DIR_NAME: &'static str = "assets";
+
+    /// Creates a statement while rewriting links to the assets so that they refer to local files.
+    pub fn new(
+        problem_id: impl Into<ProblemId>,
+        html: impl Into<String>,
+        assets: Vec<Asset>,
+    ) -> Self {
+        let mut html = html.into();
+        for asset in &assets {
+            html = html.replace(
+                &format!("=\"{}\"", asset.url),
+                &format!("=\"{}/{}\"", Self::ASSETS_DIR_NAME, asset.file_name),
+            );
+        }
+        Self {
+            problem_id: problem_id.into(),
+            html,
+            assets,
+        }
+    }
+
+    /// Wraps the statement into a standalone html document.
+    pub fn to_document(&self, title: &str) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+            title, self.html
+        )
+    }
+}
+
+/// File referred from a statement, such as an image or an attached archive.
+#[derive(Getters, Debug, Clone, PartialEq, Eq, Hash)]
+#[get = "pub"]
+pub struct Asset {
+    /// Url as written in the statement
+    url: String,
+    file_name: String,
+    content: Vec<u8>,
+}
+
+impl Asset {
+    pub fn new(url: impl Into<String>, file_name: impl Into<String>, content: Vec<u8>) -> Self {
+        Self {
+            url: url.into(),
+            file_name: file_name.into(),
+            content,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statement_rewrites_asset_links() {
+        let html = r#"<img src="/img/a.png"><a href="https://example.com/tools.zip">tools</a>"#;
+        let assets = vec![
+            Asset::new("/img/a.png", "a.png", vec![]),
+            Asset::new("https://example.com/tools.zip", "tools.zip", vec![]),
+        ];
+        let statement = Statement::new("A", html, assets);
+        assert_eq!(
+            statement.html(),
+            r#"<img src="assets/a.png"><a href="assets/tools.zip">tools</a>"#
+        );
+    }
+}
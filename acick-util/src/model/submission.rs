@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Byte, ProblemId};
+
+/// Detail of a submission judged on service.
+#[derive(Serialize, Deserialize, Getters, Debug, Clone, PartialEq, Eq, Hash)]
+#[get = "pub"]
+pub struct Submission {
+    id: String,
+    task: String,
+    user: String,
+    lang_name: String,
+    score: String,
+    #[serde(flatten)]
+    result: JudgeResult,
+    testcases: Vec<TestcaseResult>,
+}
+
+impl Submission {
+    pub fn new(
+        id: impl Into<String>,
+        task: impl Into<String>,
+        user: impl Into<String>,
+        lang_name: impl Into<String>,
+        score: impl Into<String>,
+        result: JudgeResult,
+        testcases: Vec<TestcaseResult>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            task: task.into(),
+            user: user.into(),
+            lang_name: lang_name.into(),
+            score: score.into(),
+            result,
+            testcases,
+        }
+    }
+
+    /// Returns id of problem parsed from task name (e.g. `C` from `C - Linear Approximation`).
+    pub fn problem_id(&self) -> Option<ProblemId> {
+        self.task
+            .split(" - ")
+            .next()
+            .filter(|id| !id.is_empty() && *id != self.task)
+            .map(ProblemId::from)
+    }
+
+    /// Returns testcases that are not accepted.
+    pub fn failed_testcases(&self) -> impl Iterator<Item = &TestcaseResult> {
+        self.testcases
+            .iter()
+            .filter(|testcase| !testcase.result.is_accepted())
+    }
+}
+
+/// Verdict of a submission on a testcase.
+#[derive(Serialize, Deserialize, Getters, Debug, Clone, PartialEq, Eq, Hash)]
+#[get = "pub"]
+pub struct TestcaseResult {
+    /// Name of testcase without extension, which is the same as name of local testcase
+    name: String,
+    #[serde(flatten)]
+    result: JudgeResult,
+}
+
+impl TestcaseResult {
+    pub fn new(name: impl Into<String>, result: JudgeResult) -> Self {
+        Self {
+            name: name.into(),
+            result,
+        }
+    }
+}
+
+/// Verdict and resource usage reported by service.
+#[derive(Serialize, Deserialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct JudgeResult {
+    #[get = "pub"]
+    verdict: String,
+    #[serde(with = "humantime_serde")]
+    #[get_copy = "pub"]
+    exec_time: Option<Duration>,
+    #[get_copy = "pub"]
+    memory: Option<Byte>,
+}
+
+impl JudgeResult {
+    pub fn new(
+        verdict: impl Into<String>,
+        exec_time: Option<Duration>,
+        memory: Option<Byte>,
+    ) -> Self {
+        Self {
+            verdict: verdict.into(),
+            exec_time,
+            memory,
+        }
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        self.verdict == "AC"
+    }
+
+    /// Returns true if the submission is waiting for judge or being judged (e.g. `WJ`, `3/12`).
+    pub fn is_judging(&self) -> bool {
+        matches!(self.verdict.as_str(), "WJ" | "WR") || self.verdict.contains('/')
+    }
+}
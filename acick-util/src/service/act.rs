@@ -1,27 +1,98 @@
-use crate::model::{Contest, ContestId, LangName, LangNameRef, Problem, ProblemId};
+use std::fmt;
+
+use async_trait::async_trait;
+
+use crate::model::{
+    Contest, ContestId, ContestInfo, LangName, LangNameRef, Problem, ProblemId, Standings,
+    Statement, Submission,
+};
+use crate::service::scrape::PatternReport;
 use crate::{Console, Result};
 
+/// Interacts with a service.
+///
+/// Methods that send requests are async, so that all requests of a command
+/// run inside one tokio runtime.
+#[async_trait(?Send)]
 pub trait Act {
-    fn current_user(&self, cnsl: &mut Console) -> Result<Option<String>>;
+    async fn current_user(&self, cnsl: &mut Console) -> Result<Option<String>>;
+
+    /// Checks if cookie storage holds session of user without any request to service.
+    fn has_stored_session(&self) -> Result<bool>;
+
+    async fn login(&self, user: String, pass: String, cnsl: &mut Console) -> Result<bool>;
+
+    async fn participate(&self, contest_id: &ContestId, cnsl: &mut Console) -> Result<bool>;
 
-    fn login(&self, user: String, pass: String, cnsl: &mut Console) -> Result<bool>;
+    async fn fetch(
+        &self,
+        contest_id: &ContestId,
+        problem_ids: &[ProblemId],
+        cnsl: &mut Console,
+    ) -> Result<(Contest, Vec<Problem>, PatternReport)>;
 
-    fn fetch(
+    async fn fetch_contest_info(
         &self,
         contest_id: &ContestId,
-        problem_id: &Option<ProblemId>,
         cnsl: &mut Console,
-    ) -> Result<(Contest, Vec<Problem>)>;
+    ) -> Result<ContestInfo>;
 
-    fn submit<'a>(
+    async fn fetch_statements(
+        &self,
+        contest_id: &ContestId,
+        problems: &[Problem],
+        cnsl: &mut Console,
+    ) -> Result<Vec<Statement>>;
+
+    /// Returns names of languages available in submissions to the contest,
+    /// or an empty list if the service accepts any language.
+    async fn fetch_lang_names(
+        &self,
+        contest_id: &ContestId,
+        cnsl: &mut Console,
+    ) -> Result<Vec<LangName>>;
+
+    /// Submits source in the first of `lang_names` available in the contest,
+    /// failing with `LangNotFound` if none of them is available.
+    async fn submit<'a>(
         &self,
         contest_id: &ContestId,
         problem: &Problem,
         lang_names: &'a [LangName],
         source: &str,
+        is_dry_run: bool,
         cnsl: &mut Console,
     ) -> Result<LangNameRef<'a>>;
 
+    async fn fetch_submission(
+        &self,
+        contest_id: &ContestId,
+        submission_id: &str,
+        cnsl: &mut Console,
+    ) -> Result<Submission>;
+
+    /// Returns source code of the submission.
+    async fn fetch_submission_source(
+        &self,
+        contest_id: &ContestId,
+        submission_id: &str,
+        cnsl: &mut Console,
+    ) -> Result<String>;
+
+    /// Returns the latest submission of the logged-in user to the problem if any.
+    async fn fetch_latest_submission(
+        &self,
+        contest_id: &ContestId,
+        problem: &Problem,
+        cnsl: &mut Console,
+    ) -> Result<Option<Submission>>;
+
+    async fn fetch_standings(
+        &self,
+        contest_id: &ContestId,
+        cnsl: &mut Console,
+    ) -> Result<Standings>;
+
     fn open_problem_url(
         &self,
         contest_id: &ContestId,
@@ -31,3 +102,20 @@ pub trait Act {
 
     fn open_submissions_url(&self, contest_id: &ContestId, cnsl: &mut Console) -> Result<()>;
 }
+
+/// Error returned by `Act::submit` before sending any request
+/// if none of the given languages is available in the contest.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LangNotFound(pub Vec<LangName>);
+
+impl fmt::Display for LangNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Could not find available language from the given language list: {}",
+            self.0.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for LangNotFound {}
@@ -6,12 +6,15 @@ use anyhow::Context as _;
 use cookie::Cookie as RawCookie;
 use cookie_store::CookieStore;
 use fs2::FileExt as _;
-use reqwest::blocking::{Request, Response};
 use reqwest::header::{HeaderValue, COOKIE, SET_COOKIE};
+use reqwest::{Request, Response, Url};
 
-use crate::abs_path::AbsPathBuf;
+use crate::abs_path::{restrict_to_owner, AbsPathBuf};
 use crate::{Error, Result};
 
+// max age of cookies set by `set_value` (a year)
+const MAX_AGE_SECS: u64 = 365 * 24 * 60 * 60;
+
 pub struct CookieStorage {
     file: File,
     store: CookieStore,
@@ -22,6 +25,7 @@ impl CookieStorage {
         let file = path
             .create_dir_all_and_open(true, true)
             .context("Could not open cookies file")?;
+        restrict_to_owner(&file).context("Could not restrict permissions of cookies file")?;
         file.try_lock_exclusive()
             .context("Could not lock cookies file")?;
         let reader = BufReader::new(&file);
@@ -29,6 +33,40 @@ impl CookieStorage {
         Ok(Self { file, store })
     }
 
+    /// Returns value of unexpired cookie named `name` that would be sent to `url`.
+    pub fn get_value(&self, url: &Url, name: &str) -> Option<String> {
+        self.store
+            .get_request_cookies(url)
+            .find(|cookie| cookie.name() == name)
+            .map(|cookie| cookie.value().to_owned())
+    }
+
+    /// Sets persistent cookie named `name` for `url` and saves it.
+    pub fn set_value(&mut self, url: &Url, name: &str, value: &str) -> Result<()> {
+        let cookie = RawCookie::parse(format!("{}={}; Max-Age={}", name, value, MAX_AGE_SECS))
+            .context("Could not parse cookie")?;
+        self.store.insert_raw(&cookie, url).map_err(Error::msg)?;
+        self.save().context("Could not save cookies to json file")
+    }
+
+    /// Returns number of unexpired cookies.
+    pub fn count(&self) -> usize {
+        self.store.iter_unexpired().count()
+    }
+
+    /// Serializes persistent cookies into json lines.
+    pub fn export(&self) -> Result<String> {
+        let mut buf = Vec::new();
+        self.store.save_json(&mut buf).map_err(Error::msg)?;
+        String::from_utf8(buf).context("Could not serialize cookies")
+    }
+
+    /// Replaces cookies with the ones serialized by `export` and saves them.
+    pub fn import(&mut self, cookies: &str) -> Result<()> {
+        self.store = CookieStore::load_json(cookies.as_bytes()).map_err(Error::msg)?;
+        self.save().context("Could not save cookies to json file")
+    }
+
     pub fn load_into(&self, request: &mut Request) -> Result<()> {
         let url = request.url();
         let cookies = self.store.get_request_cookies(url).map(|rc| rc.to_string());
@@ -1,7 +1,6 @@
 use anyhow::Context as _;
-use reqwest::blocking::Response;
 use reqwest::header::LOCATION;
-use reqwest::Url;
+use reqwest::{Response, Url};
 
 use crate::Result;
 
@@ -9,6 +8,7 @@ pub mod act;
 mod cookie;
 pub mod scrape;
 pub mod session;
+pub mod throttle;
 
 pub use self::cookie::CookieStorage;
 pub use act::Act;
@@ -31,18 +31,18 @@ impl ResponseExt for Response {
 
 #[cfg(test)]
 mod tests {
-    use reqwest::blocking::Client;
     use reqwest::redirect::Policy;
+    use reqwest::Client;
 
     use super::*;
 
-    #[test]
-    fn test_location_url() -> anyhow::Result<()> {
+    #[tokio::test]
+    async fn test_location_url() -> anyhow::Result<()> {
         let client = Client::builder()
             .redirect(Policy::none()) // redirects manually
             .build()
             .unwrap();
-        let res = client.get("https://mail.google.com").send()?;
+        let res = client.get("https://mail.google.com").send().await?;
         let actual = res.location_url(&Url::parse("https://mail.google.com").unwrap())?;
         let expected = Url::parse("https://mail.google.com/mail/").unwrap();
         assert_eq!(actual, expected);
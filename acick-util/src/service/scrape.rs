@@ -1,13 +1,40 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
-use reqwest::blocking::Client;
-use reqwest::{StatusCode, Url};
+use anyhow::Context as _;
+use async_trait::async_trait;
+use chrono::Local;
+use reqwest::{Client, StatusCode, Url};
 use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
 
 use crate::abs_path::AbsPathBuf;
-use crate::service::session::WithRetry as _;
-use crate::{Console, Result};
+use crate::metrics::{Metrics, Phase};
+use crate::service::session::{RetryRequestBuilder, WithRetry as _};
+use crate::{Console, Result, DATA_LOCAL_DIR};
+
+static DEBUG_DIR_NAME: &str = "debug";
+static SAVE_HTML: AtomicBool = AtomicBool::new(false);
+
+/// Enables saving html of pages from which data could not be extracted.
+pub fn set_save_html(enabled: bool) {
+    SAVE_HTML.store(enabled, Ordering::Relaxed);
+}
+
+/// Saves html under `DATA_LOCAL_DIR/debug/` with a timestamp and returns the path.
+fn save_html(name: &str, html: &str) -> Result<AbsPathBuf> {
+    let dir = DATA_LOCAL_DIR.join(DEBUG_DIR_NAME);
+    dir.create_dir_all()
+        .with_context(|| format!("Could not create directory : {}", dir))?;
+    let file_name = format!("{}-{}.html", name, Local::now().format("%Y%m%d-%H%M%S%.3f"));
+    let path = dir.join(file_name);
+    fs::write(path.as_ref(), html).with_context(|| format!("Could not save html : {}", path))?;
+    Ok(path)
+}
 
 /// Parses normal (hankaku) digits or zenkaku digits.
 ///
@@ -38,12 +65,29 @@ pub fn parse_zenkaku_digits<T: FromStr>(s: &str) -> std::result::Result<T, T::Er
     })
 }
 
+#[async_trait(?Send)]
 pub trait GetHtml {
     /// Returns a url from which we get html.
     fn url(&self) -> Result<Url>;
 
     /// Request html with http GET method.
-    fn get_html(
+    async fn get_html(
+        &self,
+        client: &Client,
+        cookies_path: &AbsPathBuf,
+        retry_limit: usize,
+        retry_interval: Duration,
+        cnsl: &mut Console,
+    ) -> Result<(StatusCode, Html)> {
+        let builder =
+            client
+                .get(self.url()?)
+                .with_retry(client, cookies_path, retry_limit, retry_interval);
+        send_and_parse(builder, cnsl).await
+    }
+
+    /// Request html with http GET method without cookies.
+    async fn get_html_anonymous(
         &self,
         client: &Client,
         cookies_path: &AbsPathBuf,
@@ -51,16 +95,25 @@ pub trait GetHtml {
         retry_interval: Duration,
         cnsl: &mut Console,
     ) -> Result<(StatusCode, Html)> {
-        let res = client
+        let builder = client
             .get(self.url()?)
             .with_retry(client, cookies_path, retry_limit, retry_interval)
-            .retry_send(cnsl)?;
-        let status = res.status();
-        let html = res.text().map(|text| Html::parse_document(&text))?;
-        Ok((status, html))
+            .without_cookies();
+        send_and_parse(builder, cnsl).await
     }
 }
 
+async fn send_and_parse(
+    builder: RetryRequestBuilder<'_>,
+    cnsl: &mut Console,
+) -> Result<(StatusCode, Html)> {
+    let res = builder.retry_send(cnsl).await?;
+    let status = res.status();
+    let text = res.text().await?;
+    let html = Metrics::measure(Phase::Parse, || Html::parse_document(&text));
+    Ok((status, html))
+}
+
 pub trait Scrape {
     /// Gets the underlying element
     fn elem(&self) -> ElementRef;
@@ -76,6 +129,29 @@ pub trait Scrape {
     fn inner_text(&self) -> String {
         self.elem().text().collect()
     }
+
+    /// Saves html of the underlying element if extraction from it failed
+    /// and saving html is enabled by `set_save_html`, and mentions the path in the error.
+    fn save_html_on_error<T>(&self, name: &str, result: Result<T>) -> Result<T>
+    where
+        Self: Sized,
+    {
+        let err = match result {
+            Ok(val) => return Ok(val),
+            Err(err) => err,
+        };
+        if !SAVE_HTML.load(Ordering::Relaxed) {
+            return Err(err);
+        }
+        match save_html(name, &self.elem().html()) {
+            Ok(path) => Err(err.context(format!(
+                "Could not extract data from page. Saved html of the page to {}",
+                path
+            ))),
+            // coerce error
+            Err(_) => Err(err),
+        }
+    }
 }
 
 impl Scrape for ElementRef<'_> {
@@ -84,6 +160,69 @@ impl Scrape for ElementRef<'_> {
     }
 }
 
+/// Records which of the fallback patterns matched when extracting a piece of data.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PatternHit {
+    /// Kind of extracted data (e.g.: `samples`)
+    pub target: &'static str,
+    /// Subject of the extraction (e.g.: problem id)
+    pub subject: String,
+    /// Name of the matched pattern, or `None` if no patterns matched
+    pub pattern: Option<String>,
+}
+
+impl fmt::Display for PatternHit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} : {}",
+            self.target,
+            self.subject,
+            self.pattern.as_deref().unwrap_or(PatternReport::NO_MATCH)
+        )
+    }
+}
+
+/// Collection of `PatternHit`s recorded while scraping pages.
+#[derive(Serialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct PatternReport(Vec<PatternHit>);
+
+impl PatternReport {
+    pub const NO_MATCH: &'static str = "(no match)";
+
+    pub fn record(
+        &mut self,
+        target: &'static str,
+        subject: impl Into<String>,
+        pattern: Option<String>,
+    ) {
+        self.0.push(PatternHit {
+            target,
+            subject: subject.into(),
+            pattern,
+        });
+    }
+
+    pub fn hits(&self) -> &[PatternHit] {
+        &self.0
+    }
+
+    /// Counts the hits for each pair of target and pattern.
+    pub fn counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for hit in &self.0 {
+            let key = format!(
+                "{}/{}",
+                hit.target,
+                hit.pattern.as_deref().unwrap_or(Self::NO_MATCH)
+            );
+            *counts.entry(key).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use reqwest::redirect::Policy;
@@ -112,8 +251,8 @@ mod tests {
         Ok(())
     }
 
-    #[test]
-    fn test_get_html() -> anyhow::Result<()> {
+    #[tokio::test]
+    async fn test_get_html() -> anyhow::Result<()> {
         struct GoogleComPageBuilder {}
         impl GetHtml for GoogleComPageBuilder {
             fn url(&self) -> Result<Url> {
@@ -125,8 +264,9 @@ mod tests {
         let test_dir = tempdir()?;
         let cookies_path = AbsPathBuf::try_new(&test_dir)?.join("cookies.json");
         let cnsl = &mut Console::sink(ConsoleConfig::default());
-        let (actual_status, actual_html) =
-            builder.get_html(&client(), &cookies_path, 4, Duration::from_secs(2), cnsl)?;
+        let (actual_status, actual_html) = builder
+            .get_html(&client(), &cookies_path, 4, Duration::from_secs(2), cnsl)
+            .await?;
 
         let expected_status = StatusCode::from_u16(301).unwrap();
         let expected_html = Html::parse_document(
@@ -144,6 +284,20 @@ The document has moved
         Ok(())
     }
 
+    #[test]
+    fn test_pattern_report_counts() {
+        let mut report = PatternReport::default();
+        report.record("samples", "A", Some("current-ja/ja".into()));
+        report.record("samples", "B", Some("current-ja/ja".into()));
+        report.record("samples", "C", None);
+
+        let counts = report.counts();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts["samples/current-ja/ja"], 2);
+        assert_eq!(counts["samples/(no match)"], 1);
+        assert_eq!(report.hits()[2].to_string(), "samples of C : (no match)");
+    }
+
     #[test]
     fn test_find_first() -> anyhow::Result<()> {
         let tests = &[
@@ -1,55 +1,118 @@
+use std::fmt;
 use std::io::Write as _;
-use std::time::Duration;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use anyhow::Context as _;
-use reqwest::blocking::{Client, Request, RequestBuilder, Response};
-use retry::{delay, retry, OperationResult};
+use chrono::{DateTime, Local};
+use lazy_static::lazy_static;
+use reqwest::header::DATE;
+use reqwest::{Client, Request, RequestBuilder, Response, StatusCode};
+use tokio::time::delay_for;
 
 use crate::abs_path::AbsPathBuf;
+use crate::error::{find_cause, ErrorKind, WithErrorKind as _};
+use crate::metrics::Metrics;
+use crate::service::throttle::wait_rate_limit;
 use crate::service::CookieStorage;
 use crate::{Console, Error, Result};
 
+lazy_static! {
+    static ref CLOCK_SKEW: Mutex<Option<chrono::Duration>> = Mutex::new(None);
+}
+
+/// Returns how far local clock is ahead of clock of service.
+///
+/// Measured with `Date` header of the last response, so the precision is about one second.
+pub fn clock_skew() -> Option<chrono::Duration> {
+    *CLOCK_SKEW.lock().unwrap()
+}
+
+fn update_clock_skew(response: &Response) {
+    let date = response
+        .headers()
+        .get(DATE)
+        .and_then(|date| date.to_str().ok())
+        .and_then(|date| DateTime::parse_from_rfc2822(date).ok());
+    if let Some(date) = date {
+        *CLOCK_SKEW.lock().unwrap() = Some(Local::now().signed_duration_since(date));
+    }
+}
+
+/// Error status returned by service, which means that the request has reached service.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ServerError(pub StatusCode);
+
+impl fmt::Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Received server error : {}", self.0)
+    }
+}
+
+impl std::error::Error for ServerError {}
+
+/// Returns true if the request that failed with `err` may have been received by service.
+///
+/// Requests with side effects (e.g. submissions) must not be sent again in that case.
+pub fn may_have_reached(err: &Error) -> bool {
+    find_cause::<ServerError>(err).is_some()
+        || find_cause::<reqwest::Error>(err).map_or(false, |err| !err.is_connect())
+}
+
 pub struct RetryRequestBuilder<'a> {
     inner: RequestBuilder,
     client: &'a Client,
-    cookies_path: &'a AbsPathBuf,
+    cookies_path: Option<&'a AbsPathBuf>,
     retry_limit: usize,
     retry_interval: Duration,
 }
 
 impl<'a> RetryRequestBuilder<'a> {
-    pub fn retry_send(mut self, cnsl: &mut Console) -> Result<Response> {
-        let retry_interval = self.retry_interval.as_millis() as u64;
-        let durations = delay::Fixed::from_millis(retry_interval).take(self.retry_limit);
-        retry(durations, || self.send(cnsl)).map_err(|err| match err {
-            retry::Error::Operation { error, .. } => error,
-            retry::Error::Internal(msg) => Error::msg(msg),
-        })
+    /// Neither loads cookies into requests nor stores cookies from responses.
+    pub fn without_cookies(mut self) -> Self {
+        self.cookies_path = None;
+        self
     }
 
-    fn send(&mut self, cnsl: &mut Console) -> OperationResult<Response, Error> {
-        let result = self
+    /// Sends the request, retrying up to `retry_limit` times on network or server errors.
+    pub async fn retry_send(self, cnsl: &mut Console) -> Result<Response> {
+        let mut retries = 0;
+        loop {
+            match self.send(cnsl).await {
+                Ok(res) => return Ok(res),
+                Err(_) if retries < self.retry_limit => {
+                    retries += 1;
+                    delay_for(self.retry_interval).await;
+                    Metrics::record_retry();
+                }
+                Err(err) => {
+                    Metrics::record_failure();
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    async fn send(&self, cnsl: &mut Console) -> Result<Response> {
+        let req = self
             .inner
             .try_clone()
             .ok_or_else(|| Error::msg("Could not create request"))
             .and_then(|builder| Ok(builder.build()?))
-            .context("Could not build request")
-            .and_then(|req| self.exec_session_pretty(req, cnsl));
-        match result {
-            Ok(res) => {
-                if res.status().is_server_error() {
-                    OperationResult::Retry(Error::msg("Received server error"))
-                } else {
-                    OperationResult::Ok(res)
-                }
-            }
-            Err(err) => OperationResult::Retry(err),
+            .context("Could not build request")?;
+        let res = self.exec_session_pretty(req, cnsl).await?;
+        if res.status().is_server_error() {
+            return Err(ErrorKind::Network.tag(ServerError(res.status())));
         }
+        Ok(res)
     }
 
-    fn exec_session_pretty(&mut self, req: Request, cnsl: &mut Console) -> Result<Response> {
+    async fn exec_session_pretty(&self, req: Request, cnsl: &mut Console) -> Result<Response> {
         write!(cnsl, "{:7} {} ... ", req.method().as_str(), req.url()).unwrap_or(());
-        let result = self.exec_session(req).context("Could not send request");
+        let result = self
+            .exec_session(req)
+            .await
+            .context("Could not send request");
         match &result {
             Ok(res) => writeln!(cnsl, "{}", res.status()),
             Err(_) => writeln!(cnsl, "failed"),
@@ -58,16 +121,32 @@ impl<'a> RetryRequestBuilder<'a> {
         result
     }
 
-    fn exec_session(&self, mut request: Request) -> Result<Response> {
-        let mut storage =
-            CookieStorage::open(self.cookies_path).context("Could not open cookie storage")?;
-        storage
-            .load_into(&mut request)
-            .context("Could not load cookies into request")?;
-        let response = self.client.execute(request)?;
-        storage
-            .store_from(&response)
-            .context("Could not store cookies from response")?;
+    async fn exec_session(&self, mut request: Request) -> Result<Response> {
+        if let Some(host) = request.url().host_str() {
+            wait_rate_limit(host).await;
+        }
+        let mut storage = match self.cookies_path {
+            Some(cookies_path) => {
+                Some(CookieStorage::open(cookies_path).context("Could not open cookie storage")?)
+            }
+            None => None,
+        };
+        if let Some(storage) = &storage {
+            storage
+                .load_into(&mut request)
+                .context("Could not load cookies into request")?;
+        }
+        let started_at = Instant::now();
+        let response = self.client.execute(request).await;
+        let elapsed = started_at.elapsed();
+        Metrics::record_request(elapsed);
+        let response = response.with_kind(ErrorKind::Network)?;
+        update_clock_skew(&response);
+        if let Some(storage) = &mut storage {
+            storage
+                .store_from(&response)
+                .context("Could not store cookies from response")?;
+        }
         Ok(response)
     }
 }
@@ -93,9 +172,24 @@ impl WithRetry for RequestBuilder {
         RetryRequestBuilder {
             inner: self,
             client,
-            cookies_path,
+            cookies_path: Some(cookies_path),
             retry_limit,
             retry_interval,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_may_have_reached() {
+        let err = ErrorKind::Network
+            .tag(ServerError(StatusCode::BAD_GATEWAY))
+            .context("Could not submit");
+        assert!(may_have_reached(&err));
+        let err = ErrorKind::Network.tag(Error::msg("Could not resolve host"));
+        assert!(!may_have_reached(&err));
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, Read};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use tokio::time::delay_for;
+
+use crate::model::Byte;
+
+lazy_static! {
+    static ref RATE_LIMITS: Mutex<HashMap<String, RateLimit>> = Mutex::new(HashMap::new());
+}
+
+/// Minimum interval between requests to a host.
+#[derive(Debug, Copy, Clone)]
+struct RateLimit {
+    interval: Duration,
+    next_at: Option<Instant>,
+}
+
+/// Limits requests to `host` to one per `interval` in the current process.
+pub fn set_rate_limit(host: &str, interval: Duration) {
+    RATE_LIMITS.lock().unwrap().insert(
+        host.to_owned(),
+        RateLimit {
+            interval,
+            next_at: None,
+        },
+    );
+}
+
+/// Waits until a request to `host` is allowed by its rate limit.
+///
+/// Slots are reserved in order of calls, so that concurrent requests are also spaced.
+pub async fn wait_rate_limit(host: &str) {
+    let wait = {
+        let mut limits = RATE_LIMITS.lock().unwrap();
+        let limit = match limits.get_mut(host) {
+            Some(limit) => limit,
+            None => return,
+        };
+        let now = Instant::now();
+        let at = limit.next_at.map_or(now, |next_at| next_at.max(now));
+        limit.next_at = Some(at + limit.interval);
+        at - now
+    };
+    if wait > Duration::from_secs(0) {
+        delay_for(wait).await;
+    }
+}
+
+/// Transfer rate in bytes per second (e.g. `2MB/s`).
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct ByteRate(Byte);
+
+impl ByteRate {
+    pub fn bytes_per_sec(self) -> u64 {
+        self.0.as_u64()
+    }
+}
+
+impl FromStr for ByteRate {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        let byte: Byte = s.trim_end_matches("/s").parse()?;
+        if byte.as_u64() == 0 {
+            return Err("Rate must be greater than zero");
+        }
+        Ok(Self(byte))
+    }
+}
+
+impl TryFrom<String> for ByteRate {
+    type Error = &'static str;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+impl From<ByteRate> for String {
+    fn from(rate: ByteRate) -> Self {
+        format!("{}/s", rate.0)
+    }
+}
+
+impl fmt::Display for ByteRate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&String::from(*self))
+    }
+}
+
+/// Limits total transfer rate of readers that share it.
+#[derive(Debug)]
+pub struct Throttle {
+    rate: ByteRate,
+    state: Mutex<Option<(Instant, u64)>>,
+}
+
+impl Throttle {
+    pub fn new(rate: ByteRate) -> Self {
+        Self {
+            rate,
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Records transferred bytes and sleeps until the transfer rate falls below the limit.
+    fn consume(&self, bytes: usize) {
+        if let Some(wait) = self.record(bytes) {
+            sleep(wait);
+        }
+    }
+
+    /// Records transferred bytes and waits until the transfer rate falls below the limit,
+    /// without blocking the thread.
+    pub async fn consume_async(&self, bytes: usize) {
+        if let Some(wait) = self.record(bytes) {
+            delay_for(wait).await;
+        }
+    }
+
+    /// Records transferred bytes and returns how long to wait to keep the rate.
+    fn record(&self, bytes: usize) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        let (started_at, total) = state.get_or_insert_with(|| (Instant::now(), 0));
+        *total += bytes as u64;
+        let expected = Duration::from_secs_f64(*total as f64 / self.rate.bytes_per_sec() as f64);
+        expected.checked_sub(started_at.elapsed())
+    }
+
+    pub fn reader<R: Read>(&self, inner: R) -> ThrottledReader<'_, R> {
+        ThrottledReader {
+            inner,
+            throttle: self,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ThrottledReader<'a, R> {
+    inner: R,
+    throttle: &'a Throttle,
+}
+
+impl<R: Read> Read for ThrottledReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // read in small chunks so that sleeps are spread evenly
+        let len = buf
+            .len()
+            .min(self.throttle.rate.bytes_per_sec() as usize / 10 + 1);
+        let n = self.inner.read(&mut buf[..len])?;
+        self.throttle.consume(n);
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_byte_rate() {
+        let rate: ByteRate = "2MB/s".parse().unwrap();
+        assert_eq!(rate.bytes_per_sec(), 2_000_000);
+        let rate: ByteRate = "500 KB".parse().unwrap();
+        assert_eq!(rate.bytes_per_sec(), 500_000);
+        assert!("0MB/s".parse::<ByteRate>().is_err());
+        assert!("fast".parse::<ByteRate>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_wait_rate_limit() {
+        let host = "rate-limit.example.com";
+        set_rate_limit(host, Duration::from_millis(100));
+        let started_at = Instant::now();
+        for _ in 0..3 {
+            wait_rate_limit(host).await;
+        }
+        assert!(started_at.elapsed() >= Duration::from_millis(200));
+
+        // hosts without limit are not delayed
+        let started_at = Instant::now();
+        for _ in 0..3 {
+            wait_rate_limit("no-limit.example.com").await;
+        }
+        assert!(started_at.elapsed() < Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_consume_async() {
+        let throttle = Throttle::new("10KB/s".parse().unwrap());
+        let started_at = Instant::now();
+        for _ in 0..4 {
+            throttle.consume_async(500).await;
+        }
+        assert!(started_at.elapsed() >= Duration::from_millis(150));
+    }
+
+    #[test]
+    fn test_throttled_reader() -> anyhow::Result<()> {
+        let throttle = Throttle::new("10KB/s".parse().unwrap());
+        let data = vec![0u8; 2_000];
+        let started_at = Instant::now();
+        let mut buf = Vec::new();
+        throttle.reader(data.as_slice()).read_to_end(&mut buf)?;
+        assert_eq!(buf, data);
+        assert!(started_at.elapsed() >= Duration::from_millis(150));
+        Ok(())
+    }
+}
@@ -0,0 +1,339 @@
+//! Keyed store that can be shared by concurrent processes (e.g. watch daemons and commands).
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Seek as _, SeekFrom, Write as _};
+
+use anyhow::Context as _;
+use fs2::FileExt as _;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::abs_path::AbsPathBuf;
+use crate::Result;
+
+// log is compacted when it has at least this number of records
+const COMPACT_MIN_RECORDS: usize = 256;
+
+/// Record appended to log. `None` value means the key is removed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Record<'a> {
+    #[serde(borrow)]
+    key: Cow<'a, str>,
+    #[serde(default)]
+    value: Option<Value>,
+}
+
+/// Keyed store saved as an append-only log of json lines.
+///
+/// Every operation holds a lock on a sibling `.lock` file while it accesses the log,
+/// so that processes never see partially written records.
+/// The log is compacted automatically when it gets much longer than the number of keys.
+/// Compaction writes a new log and renames it over the old one,
+/// so that the log is never lost even if the process is killed on the way.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StateStore {
+    path: AbsPathBuf,
+    lock_path: AbsPathBuf,
+}
+
+impl StateStore {
+    pub fn new(path: AbsPathBuf) -> Self {
+        Self {
+            lock_path: with_suffix(&path, ".lock"),
+            path,
+        }
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let mut entries = self.entries()?;
+        entries
+            .remove(key)
+            .map(|value| serde_json::from_value(value))
+            .transpose()
+            .with_context(|| format!("Could not parse value in store : {}", key))
+    }
+
+    /// Loads all keys and values.
+    pub fn entries(&self) -> Result<BTreeMap<String, Value>> {
+        self.with_lock(false, || Ok(Log::read(&self.path)?.entries))
+    }
+
+    pub fn set<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        let value = serde_json::to_value(value)?;
+        self.with_lock(true, || {
+            Log::read(&self.path)?.append(&self.path, key, Some(value))
+        })
+    }
+
+    pub fn remove(&self, key: &str) -> Result<()> {
+        self.with_lock(true, || {
+            Log::read(&self.path)?.append(&self.path, key, None)
+        })
+    }
+
+    /// Updates value of `key` atomically.
+    ///
+    /// `f` receives the current value and returns the new value, or `None` to remove the key.
+    pub fn update<T, F>(&self, key: &str, f: F) -> Result<()>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce(Option<T>) -> Option<T>,
+    {
+        self.with_lock(true, || {
+            let log = Log::read(&self.path)?;
+            let current = log
+                .entries
+                .get(key)
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .with_context(|| format!("Could not parse value in store : {}", key))?;
+            let value = f(current).map(serde_json::to_value).transpose()?;
+            log.append(&self.path, key, value)
+        })
+    }
+
+    /// Rewrites log so that it has only one record for each key.
+    pub fn compact(&self) -> Result<()> {
+        self.with_lock(true, || Log::read(&self.path)?.rewrite(&self.path))
+    }
+
+    fn with_lock<R>(&self, is_write: bool, f: impl FnOnce() -> Result<R>) -> Result<R> {
+        let lock = if is_write {
+            Some(
+                self.lock_path
+                    .create_dir_all_and_open(false, true)
+                    .with_context(|| format!("Could not open lock of store : {}", self.path))?,
+            )
+        } else {
+            // readers never create files, and nothing has been written if lock file is missing
+            open_if_exists(&self.lock_path, &OpenOptions::new().read(true))
+                .with_context(|| format!("Could not open lock of store : {}", self.path))?
+        };
+        if let Some(lock) = &lock {
+            if is_write {
+                lock.lock_exclusive()
+            } else {
+                lock.lock_shared()
+            }
+            .with_context(|| format!("Could not lock store : {}", self.path))?;
+        }
+        let result = f();
+        if let Some(lock) = &lock {
+            lock.unlock()
+                .with_context(|| format!("Could not unlock store : {}", self.path))?;
+        }
+        result
+    }
+}
+
+fn with_suffix(path: &AbsPathBuf, suffix: &str) -> AbsPathBuf {
+    let mut path = OsString::from(path.as_ref());
+    path.push(suffix);
+    AbsPathBuf::try_new(path).expect("Path with suffix should be absolute")
+}
+
+fn open_if_exists(path: &AbsPathBuf, options: &OpenOptions) -> io::Result<Option<File>> {
+    match options.open(path.as_ref()) {
+        Ok(file) => Ok(Some(file)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Log {
+    entries: BTreeMap<String, Value>,
+    n_records: usize,
+    // length of log without the last record that was not completely written
+    len: u64,
+}
+
+impl Log {
+    fn read(path: &AbsPathBuf) -> Result<Self> {
+        let content = match fs::read_to_string(path.as_ref()) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err).context("Could not read store"),
+        };
+        // ignore the last line if it was not completely written
+        let complete = match content.rfind('\n') {
+            Some(i) => &content[..=i],
+            None => "",
+        };
+
+        let mut entries = BTreeMap::new();
+        let mut n_records = 0;
+        for line in complete.lines() {
+            let record: Record = serde_json::from_str(line).context("Could not parse store")?;
+            match record.value {
+                Some(value) => entries.insert(record.key.into_owned(), value),
+                None => entries.remove(record.key.as_ref()),
+            };
+            n_records += 1;
+        }
+        Ok(Self {
+            entries,
+            n_records,
+            len: complete.len() as u64,
+        })
+    }
+
+    fn append(mut self, path: &AbsPathBuf, key: &str, value: Option<Value>) -> Result<()> {
+        let mut buf = serde_json::to_string(&Record {
+            key: Cow::Borrowed(key),
+            value: value.clone(),
+        })?;
+        buf.push('\n');
+        let mut file = path
+            .create_dir_all_and_open(false, true)
+            .context("Could not open store")?;
+        // overwrite the record that was not completely written
+        file.set_len(self.len)?;
+        file.seek(SeekFrom::End(0))?;
+        file.write_all(buf.as_bytes())
+            .context("Could not write to store")?;
+
+        match value {
+            Some(value) => self.entries.insert(key.to_owned(), value),
+            None => self.entries.remove(key),
+        };
+        self.n_records += 1;
+        if self.n_records >= COMPACT_MIN_RECORDS && self.n_records > 2 * self.entries.len() {
+            self.rewrite(path)?;
+        }
+        Ok(())
+    }
+
+    fn rewrite(self, path: &AbsPathBuf) -> Result<()> {
+        let mut buf = String::new();
+        for (key, value) in self.entries {
+            buf.push_str(&serde_json::to_string(&Record {
+                key: Cow::Owned(key),
+                value: Some(value),
+            })?);
+            buf.push('\n');
+        }
+        // exclusive lock is held, so no other process writes the temporary file
+        let tmp_path = with_suffix(path, ".tmp");
+        let mut file = File::create(tmp_path.as_ref()).context("Could not compact store")?;
+        file.write_all(buf.as_bytes())
+            .and_then(|_| file.sync_all())
+            .context("Could not compact store")?;
+        fs::rename(tmp_path.as_ref(), path.as_ref()).context("Could not compact store")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::thread;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_set_get_remove() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let store = StateStore::new(AbsPathBuf::try_new(test_dir.path())?.join("store.jsonl"));
+        assert_eq!(store.get::<String>("a")?, None);
+
+        store.set("a", &"hoge")?;
+        store.set("b", &1)?;
+        store.set("a", &"fuga")?;
+        assert_eq!(store.get("a")?, Some(String::from("fuga")));
+        assert_eq!(store.get("b")?, Some(1));
+
+        store.remove("a")?;
+        assert_eq!(store.get::<String>("a")?, None);
+        assert_eq!(store.entries()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let path = AbsPathBuf::try_new(test_dir.path())?.join("store.jsonl");
+        let store = StateStore::new(path.clone());
+        let n_lines =
+            || -> anyhow::Result<usize> { Ok(fs::read_to_string(path.as_ref())?.lines().count()) };
+
+        for i in 0..COMPACT_MIN_RECORDS - 1 {
+            store.set("a", &i)?;
+        }
+        assert_eq!(n_lines()?, COMPACT_MIN_RECORDS - 1);
+        store.set("a", &0)?;
+        assert_eq!(n_lines()?, 1);
+
+        store.set("b", &1)?;
+        store.compact()?;
+        assert_eq!(n_lines()?, 2);
+        assert_eq!(store.get("a")?, Some(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ignore_incomplete_record() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let path = AbsPathBuf::try_new(test_dir.path())?.join("store.jsonl");
+        fs::write(
+            path.as_ref(),
+            "{\"key\":\"a\",\"value\":1}\n{\"key\":\"b\",\"val",
+        )?;
+        let store = StateStore::new(path);
+        assert_eq!(store.entries()?.len(), 1);
+
+        store.set("c", &3)?;
+        assert_eq!(store.get("a")?, Some(1));
+        assert_eq!(store.get("c")?, Some(3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_escaped_key() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let store = StateStore::new(AbsPathBuf::try_new(test_dir.path())?.join("store.jsonl"));
+        store.set("C:\\\"a\"", &1)?;
+        assert_eq!(store.get("C:\\\"a\"")?, Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_missing_store() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let path = AbsPathBuf::try_new(test_dir.path())?.join("store/store.jsonl");
+        let store = StateStore::new(path.clone());
+        assert!(store.entries()?.is_empty());
+        // reading does not create any file
+        assert!(!path.parent().unwrap().as_ref().exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrent_update() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let path = AbsPathBuf::try_new(test_dir.path())?.join("store.jsonl");
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let store = StateStore::new(path.clone());
+                thread::spawn(move || -> Result<()> {
+                    for _ in 0..25 {
+                        store
+                            .update("count", |count: Option<usize>| Some(count.unwrap_or(0) + 1))?;
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap()?;
+        }
+        assert_eq!(StateStore::new(path).get("count")?, Some(100));
+        Ok(())
+    }
+}
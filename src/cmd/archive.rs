@@ -0,0 +1,454 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{Read as _, Write as _};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{anyhow, Context as _};
+use chrono::{offset::Local, DateTime};
+use serde::{Deserialize, Serialize};
+use structopt::StructOpt;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::abs_path::AbsPathBuf;
+use crate::cmd::Outcome;
+use crate::history::{History, HistoryEntry};
+use crate::model::{ContestId, ProblemId, Service, ServiceKind};
+use crate::{Config, Console, Error, Result};
+
+/// Version of the layout of archive, which is incremented on incompatible changes.
+const ARCHIVE_VERSION: u32 = 1;
+static MANIFEST_NAME: &str = "manifest.yaml";
+static HISTORY_NAME: &str = "history.jsonl";
+static PROBLEM_NAME: &str = "problem.yaml";
+static SOURCE_DIR: &str = "source";
+static TESTCASES_DIR: &str = "testcases";
+
+type LocalDateTime = DateTime<Local>;
+
+/// Contents of archive, stored as `manifest.yaml` at the root of archive.
+///
+/// Files of each problem are stored in `<problem_id>/` and restored to the paths
+/// specified in config file of the importer, so that archives are portable between layouts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+struct Manifest {
+    version: u32,
+    acick_version: String,
+    service_id: ServiceKind,
+    contest_id: ContestId,
+    exported_at: LocalDateTime,
+    problems: Vec<ManifestProblem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+struct ManifestProblem {
+    id: ProblemId,
+    /// Paths of files relative to the directory of problem in archive
+    files: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArchiveAction {
+    Export,
+    Import,
+}
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct ExportOpt {
+    /// Path of zip file to be created [default: <service>-<contest>.zip]
+    #[structopt(long, short)]
+    output: Option<PathBuf>,
+    /// Excludes full testcases, which may be large
+    #[structopt(long)]
+    no_testcases: bool,
+}
+
+impl ExportOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<ArchiveOutcome> {
+        let path = match &self.output {
+            Some(output) => AbsPathBuf::cwd()?.join(output),
+            None => {
+                let service_id: &str = conf.service_id.into();
+                AbsPathBuf::cwd()?.join(format!("{}-{}.zip", service_id, conf.contest_id))
+            }
+        };
+
+        let mut problem_ids: Vec<ProblemId> = conf
+            .search_problems()?
+            .into_iter()
+            .filter(|(contest_id, _)| contest_id == &conf.contest_id)
+            .map(|(_, problem_id)| problem_id)
+            .collect();
+        problem_ids.sort();
+        if problem_ids.is_empty() {
+            return Err(anyhow!(
+                "Found no problem of contest {}. Run `acick fetch` first.",
+                conf.contest_id
+            ));
+        }
+
+        writeln!(cnsl, "Exporting contest {} to {}", conf.contest_id, path)?;
+        let file = path
+            .create_dir_all_and_open(false, true)
+            .with_context(|| format!("Could not create archive : {}", path))?;
+        // truncate archive exported before
+        file.set_len(0)?;
+        let mut zip = ZipWriter::new(file);
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut add = |name: &str, content: &[u8]| -> Result<()> {
+            zip.start_file(name, options)?;
+            zip.write_all(content)
+                .with_context(|| format!("Could not write to archive : {}", name))
+        };
+
+        let mut problems = Vec::new();
+        for problem_id in problem_ids {
+            let mut files = vec![(
+                PROBLEM_NAME.to_owned(),
+                conf.problem_abs_path(&problem_id)?.as_ref().to_owned(),
+            )];
+            let source_abs_path = conf.source_abs_path(&problem_id)?;
+            if let Some(file_name) = source_abs_path.as_ref().file_name() {
+                if source_abs_path.as_ref().is_file() {
+                    files.push((
+                        format!("{}/{}", SOURCE_DIR, file_name.to_string_lossy()),
+                        source_abs_path.as_ref().to_owned(),
+                    ));
+                }
+            }
+            let testcases_abs_dir = conf.testcases_abs_dir(&problem_id)?;
+            if !self.no_testcases && testcases_abs_dir.as_ref().is_dir() {
+                collect_files(testcases_abs_dir.as_ref(), TESTCASES_DIR, &mut files)?;
+            }
+
+            for (name, path) in &files {
+                let content = fs::read(path)
+                    .with_context(|| format!("Could not read file : {}", path.display()))?;
+                add(&format!("{}/{}", problem_id, name), &content)?;
+            }
+            problems.push(ManifestProblem {
+                id: problem_id,
+                files: files.into_iter().map(|(name, _)| name).collect(),
+            });
+        }
+
+        let history = History::new(conf.history_abs_path())
+            .load()?
+            .into_iter()
+            .filter(|entry| {
+                entry.service_id() == conf.service_id && entry.contest_id() == &conf.contest_id
+            })
+            .map(|entry| Ok(serde_json::to_string(&entry)? + "\n"))
+            .collect::<Result<String>>()?;
+        add(HISTORY_NAME, history.as_bytes())?;
+
+        let manifest = Manifest {
+            version: ARCHIVE_VERSION,
+            acick_version: env!("CARGO_PKG_VERSION").to_owned(),
+            service_id: conf.service_id,
+            contest_id: conf.contest_id.clone(),
+            exported_at: Local::now(),
+            problems,
+        };
+        add(MANIFEST_NAME, serde_yaml::to_string(&manifest)?.as_bytes())?;
+        zip.finish().context("Could not finish archive")?;
+
+        Ok(ArchiveOutcome::new(ArchiveAction::Export, path, &manifest))
+    }
+}
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct ImportOpt {
+    /// Path of zip file created by `acick export`
+    archive: PathBuf,
+    /// Overwrites existing files
+    #[structopt(long)]
+    overwrite: bool,
+}
+
+impl ImportOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<ArchiveOutcome> {
+        let path = AbsPathBuf::cwd()?.join(&self.archive);
+        let file = File::open(path.as_ref())
+            .with_context(|| format!("Could not open archive : {}", path))?;
+        let mut zip = ZipArchive::new(file).context("Could not read archive")?;
+        let mut read = |name: &str| -> Result<Vec<u8>> {
+            let mut entry = zip
+                .by_name(name)
+                .with_context(|| format!("Could not find file in archive : {}", name))?;
+            let mut buf = Vec::new();
+            entry.read_to_end(&mut buf)?;
+            Ok(buf)
+        };
+
+        let manifest: Manifest = serde_yaml::from_slice(&read(MANIFEST_NAME)?)
+            .context("Could not parse manifest of archive")?;
+        if manifest.version != ARCHIVE_VERSION {
+            return Err(anyhow!(
+                "Found archive of unsupported version {} (created by acick {})",
+                manifest.version,
+                manifest.acick_version
+            ));
+        }
+        // ids are used in paths of imported files
+        check_id(manifest.contest_id.as_ref())?;
+        for problem in &manifest.problems {
+            check_id(problem.id.as_ref())?;
+        }
+        let conf = conf
+            .with_service(manifest.service_id)
+            .with_contest(manifest.contest_id.clone());
+
+        let message = format!(
+            "import {} problems of contest {} to {}?",
+            manifest.problems.len(),
+            conf.contest_id,
+            conf.base_dir
+        );
+        if !cnsl.confirm(&message, true)? {
+            return Err(Error::msg("Not imported"));
+        }
+
+        for problem in &manifest.problems {
+            for name in &problem.files {
+                let dest = match import_path(&conf, &problem.id, name)? {
+                    Some(dest) => dest,
+                    None => {
+                        cnsl.warn(&format!("Skipped unknown file in archive : {}", name))?;
+                        continue;
+                    }
+                };
+                let content = read(&format!("{}/{}", problem.id, name))?;
+                dest.save_pretty(
+                    |mut file| Ok(file.write_all(&content)?),
+                    self.overwrite,
+                    Some(&conf.base_dir),
+                    cnsl,
+                )?;
+            }
+        }
+
+        // merge history without duplicating entries imported before
+        let history = History::new(conf.history_abs_path());
+        let existing: HashSet<_> = history.load()?.into_iter().collect();
+        let content = read(HISTORY_NAME)?;
+        for line in String::from_utf8(content)?.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: HistoryEntry =
+                serde_json::from_str(line).context("Could not parse history in archive")?;
+            if !existing.contains(&entry) {
+                history.record(&entry)?;
+            }
+        }
+
+        Ok(ArchiveOutcome::new(ArchiveAction::Import, path, &manifest))
+    }
+}
+
+/// Collects files in `dir` recursively with their names prefixed by `prefix`.
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) -> Result<()> {
+    let mut entries = fs::read_dir(dir)
+        .with_context(|| format!("Could not list files in {}", dir.display()))?
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        if entry.file_type()?.is_dir() {
+            collect_files(&entry.path(), &name, files)?;
+        } else {
+            files.push((name, entry.path()));
+        }
+    }
+    Ok(())
+}
+
+/// Returns path where file of problem in archive is restored,
+/// or None if the file is not known to this version of acick.
+fn import_path(conf: &Config, problem_id: &ProblemId, name: &str) -> Result<Option<AbsPathBuf>> {
+    // reject absolute paths and `..` that would be restored outside of the destination
+    if !Path::new(name)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(anyhow!("Found invalid path in archive : {}", name));
+    }
+    let path = if name == PROBLEM_NAME {
+        conf.problem_abs_path(problem_id)?
+    } else if let Some(file_name) = strip_dir(name, SOURCE_DIR) {
+        // keep file name, which may differ from source_path if written in another language
+        conf.source_abs_path(problem_id)?
+            .parent()
+            .context("Could not get directory of source file")?
+            .join(file_name)
+    } else if let Some(rel_path) = strip_dir(name, TESTCASES_DIR) {
+        conf.testcases_abs_dir(problem_id)?.join(rel_path)
+    } else {
+        return Ok(None);
+    };
+    if !path.starts_with(&conf.base_dir) {
+        return Err(anyhow!(
+            "Found file in archive restored outside of {} : {}",
+            conf.base_dir,
+            name
+        ));
+    }
+    Ok(Some(path))
+}
+
+/// Rejects id in archive that is empty, `.`, `..` or contains characters other than
+/// alphanumerics, `-`, `_` and `.`, which could locate files outside of the destination.
+fn check_id(id: &str) -> Result<()> {
+    let is_valid = !id.is_empty()
+        && id != "."
+        && id != ".."
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.');
+    if is_valid {
+        Ok(())
+    } else {
+        Err(anyhow!("Found invalid id in archive : {}", id))
+    }
+}
+
+fn strip_dir<'a>(name: &'a str, dir: &str) -> Option<&'a str> {
+    name.strip_prefix(dir)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .filter(|rest| !rest.is_empty())
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArchiveOutcome {
+    service: Service,
+    contest_id: ContestId,
+    action: ArchiveAction,
+    path: AbsPathBuf,
+    problems: Vec<ProblemId>,
+    n_files: usize,
+}
+
+impl ArchiveOutcome {
+    fn new(action: ArchiveAction, path: AbsPathBuf, manifest: &Manifest) -> Self {
+        Self {
+            service: Service::new(manifest.service_id),
+            contest_id: manifest.contest_id.clone(),
+            action,
+            path,
+            problems: manifest.problems.iter().map(|p| p.id.clone()).collect(),
+            n_files: manifest.problems.iter().map(|p| p.files.len()).sum(),
+        }
+    }
+}
+
+impl fmt::Display for ArchiveOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (verb, prep) = match self.action {
+            ArchiveAction::Export => ("Exported", "to"),
+            ArchiveAction::Import => ("Imported", "from"),
+        };
+        write!(
+            f,
+            "{} {} problems ({} files) of {} {} {} {}",
+            verb,
+            self.problems.len(),
+            self.n_files,
+            self.service.id(),
+            self.contest_id,
+            prep,
+            self.path
+        )
+    }
+}
+
+impl Outcome for ArchiveOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::cmd::tests::run_with;
+    use crate::model::{Problem, Sample};
+
+    #[test]
+    fn export_and_import() -> anyhow::Result<()> {
+        let src_dir = tempdir()?;
+        let dest_dir = tempdir()?;
+        let archive = src_dir.path().join("arc100.zip");
+
+        let mut problem = Problem::default();
+        problem.set_samples(vec![Sample::new("1", "5\n", "0\n")]);
+        let export = ExportOpt {
+            output: Some(archive.clone()),
+            no_testcases: false,
+        };
+        let exported = run_with(&src_dir, |conf, cnsl| {
+            conf.save_problem(&problem, true, cnsl)?;
+            let source_abs_path = conf.source_abs_path(problem.id())?;
+            source_abs_path.save(|mut file| Ok(file.write_all(b"int main() {}\n")?), true)?;
+            conf.testcases_abs_dir(problem.id())?
+                .join("in")
+                .join("01.txt")
+                .save(|mut file| Ok(file.write_all(b"5\n")?), true)?;
+            export.run(conf, cnsl)
+        })?;
+        assert_eq!(exported.problems, vec![problem.id().clone()]);
+        assert_eq!(exported.n_files, 3);
+
+        let import = ImportOpt {
+            archive,
+            overwrite: false,
+        };
+        run_with(&dest_dir, |conf, cnsl| {
+            let imported = import.run(conf, cnsl)?;
+            assert_eq!(imported.n_files, exported.n_files);
+            assert_eq!(conf.load_problem(problem.id(), cnsl)?, problem);
+            let source = conf.load_source(problem.id(), cnsl)?;
+            assert_eq!(source, "int main() {}\n");
+            let testcase = conf
+                .testcases_abs_dir(problem.id())?
+                .join("in")
+                .join("01.txt");
+            assert_eq!(fs::read_to_string(testcase.as_ref())?, "5\n");
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_import_path() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let conf = Config::default_in_dir(base_dir);
+        let problem_id = ProblemId::from("C");
+        assert!(import_path(&conf, &problem_id, "problem.yaml")?.is_some());
+        assert!(import_path(&conf, &problem_id, "source/main.py")?.is_some());
+        assert!(import_path(&conf, &problem_id, "testcases/in/01.txt")?.is_some());
+        assert!(import_path(&conf, &problem_id, "unknown.txt")?.is_none());
+        assert!(import_path(&conf, &problem_id, "testcases/../../etc/passwd").is_err());
+        assert!(import_path(&conf, &problem_id, "/etc/passwd").is_err());
+        let problem_id = ProblemId::from("../../../..");
+        assert!(import_path(&conf, &problem_id, "problem.yaml").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_id() -> anyhow::Result<()> {
+        for id in &["arc100", "abc100_a", "C", "practice2-a.1"] {
+            check_id(id)?;
+        }
+        for id in &["", ".", "..", "../a", "a/b", "a\\b", "/a", "a b"] {
+            assert!(check_id(id).is_err());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt;
+use std::io::Write as _;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _};
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::time::Instant;
+
+use crate::atcoder::AtcoderActor;
+use crate::cmd::{block_on, Outcome};
+use crate::error::ErrorKind;
+use crate::judge::{Judge, StatusKind};
+use crate::model::{AsSamples, Compare, ContestId, ProblemId, Sample, Service};
+use crate::{Config, Console, Result};
+
+static BENCH_TIME_LIMIT_MS: u64 = 60 * 1000;
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct BenchOpt {
+    /// Id of the problem to be benchmarked
+    #[structopt(name = "problem")]
+    problem_id: ProblemId,
+    /// Name of the sample to be used (defaults to the sample with the largest input)
+    sample_name: Option<String>,
+    /// Uses full testcases (only available for AtCoder)
+    #[structopt(name = "full", long)]
+    is_full: bool,
+    /// Number of runs
+    #[structopt(long, short = "n", default_value = "10")]
+    runs: usize,
+}
+
+impl BenchOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<BenchOutcome> {
+        if self.runs == 0 {
+            return Err(anyhow!("Number of runs must be greater than 0"));
+        }
+        let problem = conf.load_problem(&self.problem_id, cnsl)?;
+        let time_limit = problem.time_limit();
+        let compare = problem.compare();
+        let samples: Box<dyn AsSamples> = if self.is_full {
+            let testcases_dir = conf.testcases_abs_dir(problem.id())?;
+            Box::new(AtcoderActor::load_testcases(
+                testcases_dir,
+                &self.sample_name,
+            )?)
+        } else {
+            Box::new(problem.take_samples(&self.sample_name))
+        };
+        let sample = select_sample(samples)?;
+        let sample_name = sample.name().to_owned();
+
+        let elapsed = block_on(self.bench(sample, compare, conf, cnsl))?;
+
+        Ok(BenchOutcome {
+            service: Service::new(conf.service_id),
+            contest_id: conf.contest_id.clone(),
+            problem_id: self.problem_id.clone(),
+            sample_name,
+            time_limit,
+            stats: BenchStats::new(elapsed),
+        })
+    }
+
+    async fn bench(
+        &self,
+        sample: Sample,
+        compare: Compare,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<Vec<Duration>> {
+        if let Some(mut compile) = conf.exec_compile(&self.problem_id)? {
+            let exit_status = compile.status().await.context("Failed to compile")?;
+            if !exit_status.success() {
+                return Err(ErrorKind::Compile.tag(anyhow!(
+                    "Compile command returned non-zero status : {}",
+                    exit_status
+                )));
+            }
+        }
+
+        let started_at = Instant::now();
+        let mut elapsed = Vec::with_capacity(self.runs);
+        for i in 0..self.runs {
+            cnsl.set_status_line(&format!("[{:>3}/{:>3}] running ...", i + 1, self.runs))?;
+            let judge = Judge::new(
+                sample.clone(),
+                Duration::from_millis(BENCH_TIME_LIMIT_MS),
+                compare,
+                *conf.diff(),
+            )
+            .with_output_filter(conf.test().output_filter())
+            .with_io_files(conf.io_files(&self.problem_id)?);
+            let status = judge.test(conf.exec_run(&self.problem_id)?).await?;
+            // benchmark of wrong solution is meaningless
+            if status.kind() != StatusKind::Ac {
+                cnsl.clear_status_line()?;
+                status.describe(cnsl)?;
+                return Err(anyhow!(
+                    "Run {} finished with {}",
+                    i + 1,
+                    status.kind().as_ref().trim()
+                ));
+            }
+            elapsed.push(status.elapsed());
+        }
+        cnsl.clear_status_line()?;
+        writeln!(
+            cnsl,
+            "Ran {} times in {:.2}s",
+            self.runs,
+            started_at.elapsed().as_secs_f32()
+        )?;
+        Ok(elapsed)
+    }
+}
+
+/// Selects the only sample or the sample with the largest input.
+fn select_sample(samples: Box<dyn AsSamples>) -> Result<Sample> {
+    let mut selected: Option<Sample> = None;
+    for sample in samples {
+        let sample = sample?;
+        if selected
+            .as_ref()
+            .map_or(true, |s| sample.input().len() > s.input().len())
+        {
+            selected = Some(sample);
+        }
+    }
+    selected.context("Found no samples")
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BenchStats {
+    runs: usize,
+    #[serde(with = "humantime_serde")]
+    min: Duration,
+    #[serde(with = "humantime_serde")]
+    median: Duration,
+    #[serde(with = "humantime_serde")]
+    p95: Duration,
+    #[serde(with = "humantime_serde")]
+    max: Duration,
+    #[serde(with = "humantime_serde")]
+    mean: Duration,
+    #[serde(with = "humantime_serde")]
+    stddev: Duration,
+}
+
+impl BenchStats {
+    /// Calculates statistics of elapsed times, which must not be empty.
+    fn new(mut elapsed: Vec<Duration>) -> Self {
+        elapsed.sort();
+        let n = elapsed.len();
+        // nearest-rank percentile
+        let percentile = |p: usize| elapsed[((n * p + 99) / 100).max(1) - 1];
+        let mean = elapsed.iter().sum::<Duration>() / n as u32;
+        let variance = elapsed
+            .iter()
+            .map(|d| (d.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / n as f64;
+        Self {
+            runs: n,
+            min: elapsed[0],
+            median: percentile(50),
+            p95: percentile(95),
+            max: elapsed[n - 1],
+            mean,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BenchOutcome {
+    service: Service,
+    contest_id: ContestId,
+    problem_id: ProblemId,
+    sample_name: String,
+    #[serde(with = "humantime_serde")]
+    time_limit: Option<Duration>,
+    stats: BenchStats,
+}
+
+fn fmt_ms(d: Duration) -> String {
+    format!("{:>9.2} ms", d.as_secs_f64() * 1000.0)
+}
+
+impl fmt::Display for BenchOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let stats = &self.stats;
+        writeln!(
+            f,
+            "{} {} {} (sample {}, {} runs)",
+            self.service.id(),
+            self.contest_id,
+            self.problem_id,
+            self.sample_name,
+            stats.runs
+        )?;
+        writeln!(f, "min    : {}", fmt_ms(stats.min))?;
+        writeln!(f, "median : {}", fmt_ms(stats.median))?;
+        writeln!(f, "p95    : {}", fmt_ms(stats.p95))?;
+        writeln!(f, "max    : {}", fmt_ms(stats.max))?;
+        write!(
+            f,
+            "mean   : {} (stddev {})",
+            fmt_ms(stats.mean),
+            fmt_ms(stats.stddev).trim()
+        )?;
+        if let Some(time_limit) = self.time_limit {
+            write!(
+                f,
+                "\np95 is {:.1}% of time limit ({} ms)",
+                stats.p95.as_secs_f64() / time_limit.as_secs_f64() * 100.0,
+                time_limit.as_millis()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Outcome for BenchOutcome {
+    /// Returns true if p95 exceeds time limit.
+    fn is_error(&self) -> bool {
+        self.time_limit
+            .map_or(false, |time_limit| self.stats.p95 > time_limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Problem;
+
+    #[test]
+    fn calc_stats() {
+        let elapsed = (1..=20).rev().map(Duration::from_millis).collect();
+        let stats = BenchStats::new(elapsed);
+        assert_eq!(stats.runs, 20);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(10));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+        assert_eq!(stats.max, Duration::from_millis(20));
+        assert_eq!(stats.mean, Duration::from_micros(10500));
+
+        let stats = BenchStats::new(vec![Duration::from_millis(5)]);
+        assert_eq!(stats.median, Duration::from_millis(5));
+        assert_eq!(stats.p95, Duration::from_millis(5));
+        assert_eq!(stats.stddev, Duration::from_secs(0));
+    }
+
+    #[test]
+    fn select_largest_sample() -> anyhow::Result<()> {
+        let mut problem = Problem::default();
+        problem.set_samples(vec![
+            Sample::new("1", "3\n", "6\n"),
+            Sample::new("2", "100\n", "200\n"),
+            Sample::new("3", "10\n", "20\n"),
+        ]);
+        let sample = select_sample(Box::new(problem.take_samples(&None)))?;
+        assert_eq!(sample.name(), "2");
+        Ok(())
+    }
+}
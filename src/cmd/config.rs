@@ -0,0 +1,51 @@
+use std::fmt;
+
+use serde::Serialize;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+use crate::cmd::Outcome;
+use crate::config::ConfigBody;
+use crate::Result;
+
+#[derive(
+    Serialize, EnumString, EnumVariantNames, AsRefStr, Debug, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum ConfigAction {
+    Schema,
+}
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct ConfigOpt {
+    /// Prints JSON Schema of config file for validation and completion in editors
+    #[structopt(name = "action", possible_values = &ConfigAction::VARIANTS)]
+    action: ConfigAction,
+}
+
+impl ConfigOpt {
+    pub fn run(&self) -> Result<ConfigOutcome> {
+        match self.action {
+            ConfigAction::Schema => Ok(ConfigOutcome(ConfigBody::json_schema())),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ConfigOutcome(serde_json::Value);
+
+impl fmt::Display for ConfigOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let json_str = serde_json::to_string_pretty(&self.0).map_err(|_| fmt::Error)?;
+        f.write_str(&json_str)
+    }
+}
+
+impl Outcome for ConfigOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
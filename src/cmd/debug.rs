@@ -0,0 +1,78 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::abs_path::AbsPathBuf;
+use crate::atcoder::fixtures::FIXTURES_DIR;
+use crate::cmd::{block_on, with_atcoder_actor, Outcome};
+use crate::model::{ContestId, Service, ServiceKind};
+use crate::{Config, Console, Error, Result};
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct DebugOpt {
+    #[structopt(subcommand)]
+    action: DebugAction,
+}
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub enum DebugAction {
+    /// Records pages of contest as fixtures replayed in tests of scrapers
+    RecordFixtures {
+        /// Directory in which fixtures are saved [default: fixtures dir of acick-atcoder]
+        #[structopt(long)]
+        dir: Option<PathBuf>,
+    },
+}
+
+impl DebugOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<DebugOutcome> {
+        match &self.action {
+            DebugAction::RecordFixtures { dir } => {
+                if conf.service_id != ServiceKind::Atcoder {
+                    return Err(Error::msg("Fixtures are available only for AtCoder"));
+                }
+                let dir = match dir {
+                    Some(dir) => AbsPathBuf::cwd()?.join(dir),
+                    None => AbsPathBuf::try_new(FIXTURES_DIR)?,
+                };
+                let paths = with_atcoder_actor(conf.session(), |actor| {
+                    block_on(actor.record_fixtures(&conf.contest_id, &dir, cnsl))
+                })?;
+                Ok(DebugOutcome {
+                    service: Service::new(conf.service_id),
+                    contest_id: conf.contest_id.clone(),
+                    fixtures: paths.iter().map(ToString::to_string).collect(),
+                })
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DebugOutcome {
+    service: Service,
+    contest_id: ContestId,
+    fixtures: Vec<String>,
+}
+
+impl fmt::Display for DebugOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Recorded {} fixtures of {} {}",
+            self.fixtures.len(),
+            self.service.id(),
+            self.contest_id
+        )
+    }
+}
+
+impl Outcome for DebugOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,279 @@
+use std::fmt;
+use std::io::Write as _;
+use std::process::Stdio;
+
+use anyhow::anyhow;
+use getset::{CopyGetters, Getters};
+use serde::Serialize;
+use structopt::StructOpt;
+use tempfile::{tempdir, NamedTempFile};
+
+use crate::abs_path::AbsPathBuf;
+use crate::cmd::{block_on, with_actor, Outcome};
+use crate::config::Shell;
+use crate::model::{Contest, Problem, Service};
+use crate::service::Act;
+use crate::{Config, Console, Result, DATA_LOCAL_DIR};
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct DoctorOpt {}
+
+impl DoctorOpt {
+    /// Runs checks in order, skipping the ones that depend on config if it cannot be loaded.
+    pub fn run(&self, conf: Result<Config>, cnsl: &mut Console) -> Result<DoctorOutcome> {
+        let mut checks = vec![
+            Check::new("bash", Self::check_bash()),
+            Check::new("data dir", Self::check_data_dir()),
+        ];
+
+        let conf = match conf {
+            Ok(conf) => {
+                checks.push(Check::new(
+                    "config",
+                    Ok(format!("Loaded config in {}", conf.base_dir)),
+                ));
+                conf
+            }
+            Err(err) => {
+                checks.push(Check::new("config", Err(err)));
+                for name in &["compile", "network", "cookies"] {
+                    checks.push(Check::skipped(name, "Config could not be loaded"));
+                }
+                return Ok(DoctorOutcome { checks });
+            }
+        };
+
+        checks.push(Check::new(
+            "compile",
+            block_on(Self::check_compile(&conf, cnsl)),
+        ));
+
+        if conf.session().offline() {
+            for name in &["network", "cookies"] {
+                checks.push(Check::skipped(name, "Skipped by --offline"));
+            }
+        } else {
+            let (network, cookies) = with_actor(conf.service_id, conf.session(), |actor| {
+                block_on(Self::check_session(actor, &conf, cnsl))
+            })?;
+            checks.push(network);
+            checks.push(cookies);
+        }
+
+        Ok(DoctorOutcome { checks })
+    }
+
+    fn check_bash() -> Result<String> {
+        match Shell::search_bash() {
+            Some(bash) => Ok(format!("Found bash at {}", bash.display())),
+            None => Err(anyhow!(
+                "Could not find bash in PATH. Install bash or specify `shell` in config file."
+            )),
+        }
+    }
+
+    fn check_data_dir() -> Result<String> {
+        let dir: &AbsPathBuf = &DATA_LOCAL_DIR;
+        dir.create_dir_all()
+            .map_err(|err| anyhow!("Could not create {} : {}", dir, err))?;
+        let mut file = NamedTempFile::new_in(dir.as_ref())
+            .map_err(|err| anyhow!("Could not write to {} : {}", dir, err))?;
+        file.write_all(b"acick")?;
+        Ok(format!("{} is writable", dir))
+    }
+
+    /// Compiles source code generated from template of the service in a temporary directory.
+    async fn check_compile(conf: &Config, cnsl: &mut Console) -> Result<String> {
+        let dir = tempdir()?;
+        let conf = conf.with_base_dir(AbsPathBuf::try_new(dir.path().to_owned())?);
+        let service = Service::new(conf.service_id);
+        let contest = Contest::new(conf.contest_id.clone(), "acick doctor");
+        let problem = Problem::default();
+        let mut sink = Console::sink(Default::default());
+        if conf
+            .expand_and_save_source(&service, &contest, &problem, true, &mut sink)?
+            .is_none()
+        {
+            return Err(anyhow!(
+                "Could not generate source code because template is not specified"
+            ));
+        }
+        let mut compile = match conf.exec_compile(problem.id())? {
+            Some(compile) => compile,
+            None => return Ok(String::from("No compile command for the language")),
+        };
+        writeln!(cnsl, "Compiling source code generated from template ...")?;
+        let output = compile
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|err| anyhow!("Could not run compile command : {}", err))?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Compile command returned non-zero status : {}\n{}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            ));
+        }
+        Ok(String::from("Compiled source code generated from template"))
+    }
+
+    /// Checks if service is reachable and if the stored session is still valid.
+    async fn check_session(
+        actor: &dyn Act,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<(Check, Check)> {
+        // check cookie before sending request because the request may overwrite it
+        let has_stored_session = actor.has_stored_session();
+        let username = match actor.current_user(cnsl).await {
+            Ok(username) => username,
+            Err(err) => {
+                return Ok((
+                    Check::new("network", Err(err)),
+                    Check::skipped("cookies", "Service is not reachable"),
+                ))
+            }
+        };
+        let network = Check::new("network", Ok(format!("Connected to {}", conf.service_id)));
+        let cookies = match (has_stored_session, username) {
+            (Err(err), _) => Check::new("cookies", Err(err)),
+            (_, Some(username)) => Check::new("cookies", Ok(format!("Logged in as {}", username))),
+            (Ok(true), None) => Check::new(
+                "cookies",
+                Err(anyhow!("Session has expired. Run `acick login` again.")),
+            ),
+            (Ok(false), None) => Check::skipped("cookies", "Not logged in"),
+        };
+        Ok((network, cookies))
+    }
+}
+
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    Skip,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Pass => "PASS",
+            Self::Fail => "FAIL",
+            Self::Skip => "SKIP",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Result of an item of the checklist.
+#[derive(Serialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Check {
+    #[get = "pub"]
+    name: String,
+    #[get_copy = "pub"]
+    status: CheckStatus,
+    #[get = "pub"]
+    message: String,
+}
+
+impl Check {
+    fn new(name: &str, result: Result<String>) -> Self {
+        let (status, message) = match result {
+            Ok(message) => (CheckStatus::Pass, message),
+            Err(err) => (CheckStatus::Fail, format!("{:#}", err)),
+        };
+        Self {
+            name: name.to_owned(),
+            status,
+            message,
+        }
+    }
+
+    fn skipped(name: &str, reason: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            status: CheckStatus::Skip,
+            message: reason.to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize, Getters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DoctorOutcome {
+    #[get = "pub"]
+    checks: Vec<Check>,
+}
+
+impl fmt::Display for DoctorOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self
+            .checks
+            .iter()
+            .map(|check| check.name.len())
+            .max()
+            .unwrap_or(0);
+        for check in &self.checks {
+            writeln!(
+                f,
+                "[{}] {:width$} : {}",
+                check.status,
+                check.name,
+                check.message,
+                width = width
+            )?;
+        }
+        let n_failed = self
+            .checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Fail)
+            .count();
+        if n_failed == 0 {
+            write!(f, "No problems found")
+        } else {
+            write!(f, "Found {} problems", n_failed)
+        }
+    }
+}
+
+impl Outcome for DoctorOutcome {
+    fn is_error(&self) -> bool {
+        self.checks
+            .iter()
+            .any(|check| check.status == CheckStatus::Fail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::tests::run_with;
+
+    #[test]
+    fn run_offline() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let opt = DoctorOpt {};
+
+        let outcome = run_with(&test_dir, |conf, cnsl| {
+            opt.run(Ok(conf.with_offline(true)), cnsl)
+        })?;
+        let names: Vec<_> = outcome.checks.iter().map(|check| check.name()).collect();
+        assert_eq!(
+            names,
+            vec!["bash", "data dir", "config", "compile", "network", "cookies"]
+        );
+        assert_eq!(outcome.checks[2].status(), CheckStatus::Pass);
+        assert_eq!(outcome.checks[4].status(), CheckStatus::Skip);
+
+        let outcome = run_with(&test_dir, |_, cnsl| {
+            opt.run(Err(anyhow!("Could not load config file")), cnsl)
+        })?;
+        assert_eq!(outcome.checks[2].status(), CheckStatus::Fail);
+        assert_eq!(outcome.checks[3].status(), CheckStatus::Skip);
+        assert!(outcome.is_error());
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+use std::fmt;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use serde_json::json;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+use crate::abs_path::AbsPathBuf;
+use crate::cmd::Outcome;
+use crate::config::ConfigBody;
+use crate::{Console, Result};
+
+// infers contest and problem from the directory of the file opened in editor,
+// assuming the default layout `{{ service }}/{{ contest }}/{{ problem | lower }}/`
+static VSCODE_CONTEST_ARG: &str = r#"--contest "$(basename "$(dirname "${fileDirname}")")""#;
+static VSCODE_PROBLEM_ARG: &str = r#""${fileDirnameBasename}""#;
+static VSCODE_TEST_LABEL: &str = "acick: test";
+
+#[derive(
+    Serialize, EnumString, EnumVariantNames, IntoStaticStr, Debug, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum EditorKind {
+    Vscode,
+}
+
+impl EditorKind {
+    /// Returns paths relative to base_dir and contents of files to be generated.
+    fn files(self) -> Vec<(&'static str, serde_json::Value)> {
+        match self {
+            Self::Vscode => vec![
+                (".vscode/tasks.json", vscode_tasks()),
+                (".vscode/launch.json", vscode_launch()),
+            ],
+        }
+    }
+}
+
+fn vscode_task(label: &str, command: String) -> serde_json::Value {
+    json!({
+        "label": label,
+        "type": "shell",
+        "command": command,
+        "options": { "cwd": "${workspaceFolder}" },
+        "problemMatcher": [],
+    })
+}
+
+fn vscode_tasks() -> serde_json::Value {
+    let problem_args = format!("{} {}", VSCODE_CONTEST_ARG, VSCODE_PROBLEM_ARG);
+    let mut test = vscode_task(VSCODE_TEST_LABEL, format!("acick test {}", problem_args));
+    test["group"] = json!({ "kind": "test", "isDefault": true });
+    json!({
+        "version": "2.0.0",
+        "tasks": [
+            vscode_task("acick: fetch", format!("acick fetch {}", VSCODE_CONTEST_ARG)),
+            test,
+            vscode_task("acick: test full", format!("acick test --full {}", problem_args)),
+            vscode_task("acick: test watch", format!("acick test --watch {}", problem_args)),
+            vscode_task("acick: submit", format!("acick submit {}", problem_args)),
+        ],
+    })
+}
+
+fn vscode_launch() -> serde_json::Value {
+    json!({
+        "version": "0.2.0",
+        "configurations": [
+            {
+                "name": "acick: debug",
+                "type": "cppdbg",
+                "request": "launch",
+                // default artifact_path is `./{{ problem | lower }}.out` in working_dir
+                "program": "${fileDirname}/${fileDirnameBasename}.out",
+                "cwd": "${fileDirname}",
+                "preLaunchTask": VSCODE_TEST_LABEL,
+                "MIMode": "gdb",
+            },
+        ],
+    })
+}
+
+/// Saves config files of editor in base_dir.
+///
+/// Returns paths of the saved files. Existing files are skipped unless `overwrite` is true.
+pub(crate) fn save_editor_config(
+    editor: EditorKind,
+    base_dir: &AbsPathBuf,
+    overwrite: bool,
+    cnsl: &mut Console,
+) -> Result<Vec<AbsPathBuf>> {
+    let cwd = AbsPathBuf::cwd()?;
+    let mut saved = Vec::new();
+    for (path, content) in editor.files() {
+        let path = base_dir.join(path);
+        let is_saved = path.save_pretty(
+            |file| {
+                serde_json::to_writer_pretty(file, &content).context("Could not save editor config")
+            },
+            overwrite,
+            Some(&cwd),
+            cnsl,
+        )?;
+        if is_saved.is_some() {
+            saved.push(path);
+        }
+    }
+    Ok(saved)
+}
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct EditorConfigOpt {
+    /// Editor for which tasks and launch configs are generated
+    #[structopt(name = "editor", default_value = EditorKind::Vscode.into(), possible_values = &EditorKind::VARIANTS)]
+    editor: EditorKind,
+    /// Overwrites existing files
+    #[structopt(long, short = "w")]
+    overwrite: bool,
+}
+
+impl EditorConfigOpt {
+    pub fn run(
+        &self,
+        base_dir: Option<AbsPathBuf>,
+        cnsl: &mut Console,
+    ) -> Result<EditorConfigOutcome> {
+        let base_dir = match base_dir {
+            Some(base_dir) => base_dir,
+            None => {
+                let cwd = AbsPathBuf::cwd()?;
+                cwd.search_dir_contains(ConfigBody::FILE_NAME)
+                    .unwrap_or(cwd)
+            }
+        };
+        let files = save_editor_config(self.editor, &base_dir, self.overwrite, cnsl)?;
+        Ok(EditorConfigOutcome {
+            editor: self.editor,
+            files,
+        })
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EditorConfigOutcome {
+    editor: EditorKind,
+    files: Vec<AbsPathBuf>,
+}
+
+impl fmt::Display for EditorConfigOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Saved {} config files of {}",
+            self.files.len(),
+            <&str>::from(self.editor)
+        )
+    }
+}
+
+impl Outcome for EditorConfigOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn save_vscode_config() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let mut cnsl = Console::sink(Default::default());
+
+        let saved = save_editor_config(EditorKind::Vscode, &base_dir, false, &mut cnsl)?;
+        assert_eq!(saved.len(), 2);
+        let tasks: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+            test_dir.path().join(".vscode/tasks.json"),
+        )?)?;
+        let labels: Vec<_> = tasks["tasks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|task| task["label"].as_str().unwrap())
+            .collect();
+        assert!(labels.contains(&VSCODE_TEST_LABEL));
+
+        // existing files are skipped
+        let saved = save_editor_config(EditorKind::Vscode, &base_dir, false, &mut cnsl)?;
+        assert!(saved.is_empty());
+        Ok(())
+    }
+}
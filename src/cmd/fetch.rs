@@ -1,16 +1,23 @@
+use std::collections::BTreeMap;
 use std::env;
 use std::fmt;
+use std::fs;
 use std::io::Write as _;
+use std::path::PathBuf;
 
-use anyhow::Context as _;
+use anyhow::{anyhow, Context as _};
+use getset::Getters;
 use lazy_static::lazy_static;
 use serde::Serialize;
 use structopt::StructOpt;
 
 use crate::abs_path::AbsPathBuf;
-use crate::atcoder::AtcoderActor;
-use crate::cmd::{with_actor, Outcome};
-use crate::model::{Contest, Problem, ProblemId, Service, ServiceKind};
+use crate::atcoder::{AtcoderActor, FullOpts};
+use crate::cmd::{block_on, is_contest_mode, with_actor, with_atcoder_actor, Outcome};
+use crate::config::{HookKind, TestcasesSource};
+use crate::error::ErrorKind;
+use crate::model::{Contest, ContestId, Problem, ProblemId, Service, ServiceKind};
+use crate::service::throttle::ByteRate;
 use crate::service::Act;
 use crate::{Config, Console, Result, DATA_LOCAL_DIR};
 
@@ -25,16 +32,45 @@ lazy_static! {
 pub struct FetchOpt {
     /// If specified, fetches only one problem
     #[structopt(name = "problem")]
-    problem_id: Option<ProblemId>,
+    pub(crate) problem_id: Option<ProblemId>,
+    /// Fetches only the specified problems (e.g. a,b,c)
+    #[structopt(name = "problems", long, use_delimiter = true)]
+    pub(crate) problem_ids: Vec<ProblemId>,
     /// Overwrites existing problem files and source files
     #[structopt(long, short = "w")]
-    overwrite: bool,
+    pub(crate) overwrite: bool,
     /// Opens submissions and problems page in browser
     #[structopt(name = "open", long, short)]
-    need_open: bool,
-    /// Fetches full testcases from dropbox (only available for AtCoder)
+    pub(crate) need_open: bool,
+    /// Fetches full testcases from dropbox or storage in config (only available for AtCoder)
     #[structopt(name = "full", long)]
-    is_full: bool,
+    pub(crate) is_full: bool,
+    /// Saves problem statements along with images and attached files
+    #[structopt(name = "assets", long)]
+    pub(crate) need_assets: bool,
+    /// Limits transfer rate of downloading full testcases (e.g. 2MB/s)
+    #[structopt(long)]
+    pub(crate) limit_rate: Option<ByteRate>,
+    /// Number of parallel downloads of full testcases [default: number of CPUs]
+    #[structopt(long, short)]
+    pub(crate) jobs: Option<usize>,
+    /// Fetches without login unless the service requires it
+    #[structopt(long)]
+    pub(crate) anonymous: bool,
+    /// Guesses samples from all preformatted blocks if the layout of statement is unknown
+    #[structopt(long)]
+    pub(crate) lax_samples: bool,
+    /// Fetches each of the contests instead of the current contest (e.g. abc100,abc101)
+    #[structopt(
+        long,
+        visible_alias = "all-contests",
+        use_delimiter = true,
+        conflicts_with_all = &["problem", "open"]
+    )]
+    pub(crate) contests: Vec<ContestId>,
+    /// Fetches each of the contests listed in the file, one contest id per line
+    #[structopt(long, conflicts_with_all = &["problem", "open"])]
+    pub(crate) contests_file: Option<PathBuf>,
 }
 
 #[cfg(test)]
@@ -42,52 +78,204 @@ impl FetchOpt {
     pub fn default_test() -> Self {
         Self {
             problem_id: None,
+            problem_ids: Vec::new(),
             overwrite: false,
             need_open: false,
             is_full: false,
+            need_assets: false,
+            limit_rate: None,
+            jobs: None,
+            anonymous: false,
+            lax_samples: false,
+            contests: Vec::new(),
+            contests_file: None,
         }
     }
 }
 
 impl FetchOpt {
+    /// Returns options that fetch only one problem.
+    pub fn for_problem(problem_id: ProblemId) -> Self {
+        Self {
+            problem_id: Some(problem_id),
+            problem_ids: Vec::new(),
+            overwrite: false,
+            need_open: false,
+            is_full: false,
+            need_assets: false,
+            limit_rate: None,
+            jobs: None,
+            anonymous: false,
+            lax_samples: false,
+            contests: Vec::new(),
+            contests_file: None,
+        }
+    }
+
     pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<FetchOutcome> {
-        with_actor(conf.service_id, conf.session(), |actor| {
-            self.run_inner(actor, conf, cnsl)
+        check_online(conf)?;
+        let session = conf
+            .session()
+            .with_anonymous(self.anonymous || conf.session().anonymous())
+            .with_lax_samples(self.lax_samples);
+        let difficulties =
+            if conf.service_id == ServiceKind::Atcoder && conf.service().show_difficulty() {
+                with_atcoder_actor(&session, |actor| {
+                    block_on(Self::fetch_difficulties(actor, conf, cnsl))
+                })?
+            } else {
+                None
+            };
+        with_actor(conf.service_id, &session, |actor| {
+            block_on(self.run_inner(actor, difficulties, conf, cnsl))
         })
     }
 
-    fn run_inner(
+    /// Returns true if contests to be fetched are specified instead of the current contest.
+    pub fn is_multi_contests(&self) -> bool {
+        !self.contests.is_empty() || self.contests_file.is_some()
+    }
+
+    /// Fetches each of the specified contests, continuing even if some of them fail.
+    pub fn run_contests(&self, conf: &Config, cnsl: &mut Console) -> Result<FetchContestsOutcome> {
+        check_online(conf)?;
+        let mut contest_ids = self.contests.clone();
+        if let Some(path) = &self.contests_file {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Could not read contests file : {}", path.display()))?;
+            contest_ids.extend(parse_contests_file(&content));
+        }
+
+        let n = contest_ids.len();
+        let mut contests = Vec::with_capacity(n);
+        for (i, contest_id) in contest_ids.into_iter().enumerate() {
+            writeln!(
+                cnsl,
+                "[{}/{}] Fetching contest {} ...",
+                i + 1,
+                n,
+                contest_id
+            )?;
+            let result = self.run(&conf.with_contest(contest_id.clone()), cnsl);
+            let fetched = match result {
+                Ok(outcome) => FetchedContest {
+                    contest_id,
+                    problems: Some(outcome.problems.len()),
+                    message: None,
+                },
+                Err(err) => {
+                    cnsl.warn(&format!(
+                        "Could not fetch contest {} : {:#}",
+                        contest_id, err
+                    ))?;
+                    FetchedContest {
+                        contest_id,
+                        problems: None,
+                        message: Some(format!("{:#}", err)),
+                    }
+                }
+            };
+            contests.push(fetched);
+        }
+
+        Ok(FetchContestsOutcome {
+            service: Service::new(conf.service_id),
+            contests,
+        })
+    }
+
+    async fn run_inner(
         &self,
         actor: &dyn Act,
+        difficulties: Option<BTreeMap<ProblemId, i64>>,
         conf: &Config,
         cnsl: &mut Console,
     ) -> Result<FetchOutcome> {
         let Self {
             ref problem_id,
+            ref problem_ids,
             overwrite,
             need_open,
             is_full,
+            need_assets,
+            limit_rate,
+            jobs,
+            ..
         } = *self;
 
         // fetch data from service
-        let (contest, problems) = actor.fetch(&conf.contest_id, problem_id, cnsl)?;
+        let problem_ids: Vec<ProblemId> = problem_id
+            .iter()
+            .chain(problem_ids.iter())
+            .cloned()
+            .collect();
+        let (contest, mut problems, report) =
+            actor.fetch(&conf.contest_id, &problem_ids, cnsl).await?;
 
         let service = Service::new(conf.service_id);
 
-        // save problem data file
+        // disable risky operations during contest
+        let contest_mode = (overwrite || need_assets || need_open || is_full)
+            && is_contest_mode(actor, conf, cnsl).await?;
+        let overwrite_source = overwrite && !contest_mode;
+        if overwrite && contest_mode {
+            cnsl.warn("Source files and notes are not overwritten in contest mode")?;
+        }
+
+        if let Some(difficulties) = difficulties {
+            for problem in problems.iter_mut() {
+                problem.set_difficulty(difficulties.get(problem.id()).copied());
+            }
+        }
+
+        // problems of the contest are known only after fetch
         for problem in problems.iter() {
+            conf.exec_hooks(HookKind::PreSave, problem.id(), None, cnsl)
+                .await?;
+        }
+
+        // save problem data file
+        for problem in problems.iter_mut() {
+            if overwrite {
+                keep_custom_samples(problem, conf, cnsl)?;
+            }
             conf.save_problem(problem, overwrite, cnsl)
                 .context("Could not save problem data file")?;
         }
 
         // expand source template and save source file
         for problem in problems.iter() {
-            conf.expand_and_save_source(&service, &contest, problem, overwrite, cnsl)
+            conf.expand_and_save_source(&service, &contest, problem, overwrite_source, cnsl)
                 .context("Could not save source file from template")?;
         }
 
+        // expand notes template and save notes file
+        for problem in problems.iter() {
+            conf.expand_and_save_notes(&service, &contest, problem, overwrite_source, cnsl)
+                .context("Could not save notes file from template")?;
+        }
+
+        // save statements and the files they refer to if needed
+        if need_assets && contest_mode && problem_ids.is_empty() {
+            cnsl.warn(
+                "Skipped saving statements of all problems in contest mode. \
+                 Specify problems to save their statements.",
+            )?;
+        } else if need_assets {
+            let statements = actor
+                .fetch_statements(&conf.contest_id, &problems, cnsl)
+                .await?;
+            for (problem, statement) in problems.iter().zip(statements.iter()) {
+                conf.save_statement(problem, statement, overwrite, cnsl)
+                    .context("Could not save statement")?;
+            }
+        }
+
         // open submissions and problem url in browser if needed
         if need_open {
+            if contest_mode {
+                cnsl.warn("Opening pages of contest in browser during contest")?;
+            }
             Self::open_urls(&*actor, &problems, conf, cnsl)
                 .context("Could not open a url in browser")
                 // coerce error
@@ -96,27 +284,67 @@ impl FetchOpt {
 
         if is_full {
             if conf.service_id == ServiceKind::Atcoder {
+                let is_dropbox = conf.service().testcases_source() == &TestcasesSource::Dropbox;
+                if contest_mode && is_dropbox {
+                    cnsl.warn("Sending requests to Dropbox during contest")?;
+                }
                 let access_token = env::var("ACICK_DBX_ACCESS_TOKEN").ok();
                 AtcoderActor::fetch_full(
                     &conf.contest_id,
                     &problems,
                     &DBX_TOKEN_PATH,
                     access_token,
+                    FullOpts {
+                        limit_rate: limit_rate.or_else(|| conf.session().limit_rate()),
+                        jobs,
+                    },
                     conf,
                     cnsl,
-                )?;
+                )
+                .await?;
+                let body = format!(
+                    "{} : downloaded testcases of {} problems",
+                    conf.contest_id,
+                    problems.len()
+                );
+                conf.notify("acick fetch", &body, cnsl)?;
             } else {
                 cnsl.warn("\"--full\" option is only available for AtCoder")?;
             }
         }
 
+        for problem in problems.iter() {
+            conf.exec_hooks(HookKind::PostFetch, problem.id(), None, cnsl)
+                .await?;
+        }
+
         Ok(FetchOutcome {
             service,
             contest,
             problems,
+            pattern_counts: if cnsl.is_verbose() {
+                Some(report.counts())
+            } else {
+                None
+            },
         })
     }
 
+    /// Fetches difficulties estimated by AtCoder Problems, only warning if unavailable.
+    async fn fetch_difficulties(
+        actor: &AtcoderActor,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<Option<BTreeMap<ProblemId, i64>>> {
+        match actor.fetch_difficulties(&conf.contest_id, cnsl).await {
+            Ok(difficulties) => Ok(Some(difficulties)),
+            Err(err) => {
+                cnsl.warn(&format!("Could not fetch difficulties : {:#}", err))?;
+                Ok(None)
+            }
+        }
+    }
+
     fn open_urls(
         actor: &dyn Act,
         problems: &[Problem],
@@ -131,11 +359,55 @@ impl FetchOpt {
     }
 }
 
-#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+/// Fails before sending any requests if network access is forbidden by `--offline` option.
+///
+/// Mock service is allowed because it reads contests from local files.
+fn check_online(conf: &Config) -> Result<()> {
+    if conf.session().offline() && conf.service_id != ServiceKind::Mock {
+        return Err(ErrorKind::Network.tag(anyhow!(
+            "Could not fetch problems in offline mode. Run `acick fetch` while online first, \
+             and then commands such as `acick test` and `acick show` work with the fetched files."
+        )));
+    }
+    Ok(())
+}
+
+/// Appends custom samples in existing problem file to samples of fetched problem.
+fn keep_custom_samples(problem: &mut Problem, conf: &Config, cnsl: &mut Console) -> Result<()> {
+    if !conf.problem_abs_path(problem.id())?.as_ref().is_file() {
+        return Ok(());
+    }
+    let stored = conf
+        .load_problem(problem.id(), cnsl)
+        .context("Could not load custom samples in existing problem file")?;
+    let custom_samples = stored.samples().iter().filter(|sample| sample.is_custom());
+    let mut samples = problem.samples().clone();
+    samples.extend(custom_samples.cloned());
+    problem.set_samples(samples);
+    Ok(())
+}
+
+/// Parses contest ids listed one per line, ignoring blank lines and comments starting with `#`.
+fn parse_contests_file(content: &str) -> Vec<ContestId> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(ContestId::from)
+        .collect()
+}
+
+#[derive(Serialize, Getters, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FetchOutcome {
+    #[get = "pub"]
     service: Service,
+    #[get = "pub"]
     contest: Contest,
+    #[get = "pub"]
     problems: Vec<Problem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[get = "pub"]
+    pattern_counts: Option<BTreeMap<String, usize>>,
 }
 
 impl fmt::Display for FetchOutcome {
@@ -146,7 +418,40 @@ impl fmt::Display for FetchOutcome {
             write!(f, "Successfully fetched 1 problem")
         } else {
             write!(f, "Successfully fetched {} problems", self.problems.len())
+        }?;
+        if self
+            .problems
+            .iter()
+            .any(|problem| problem.points().is_some())
+        {
+            write!(f, "\nPoints:")?;
+            for problem in &self.problems {
+                match problem.points() {
+                    Some(points) => write!(f, "\n    {} : {}", problem.id(), points)?,
+                    None => write!(f, "\n    {} : -", problem.id())?,
+                }
+            }
+        }
+        if self
+            .problems
+            .iter()
+            .any(|problem| problem.difficulty().is_some())
+        {
+            write!(f, "\nDifficulty:")?;
+            for problem in &self.problems {
+                match problem.difficulty() {
+                    Some(difficulty) => write!(f, "\n    {} : {}", problem.id(), difficulty)?,
+                    None => write!(f, "\n    {} : -", problem.id())?,
+                }
+            }
+        }
+        if let Some(pattern_counts) = &self.pattern_counts {
+            write!(f, "\nMatched patterns:")?;
+            for (pattern, count) in pattern_counts {
+                write!(f, "\n    {} : {}", pattern, count)?;
+            }
         }
+        Ok(())
     }
 }
 
@@ -156,6 +461,56 @@ impl Outcome for FetchOutcome {
     }
 }
 
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FetchedContest {
+    contest_id: ContestId,
+    /// Number of problems fetched, or `None` if failed
+    problems: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FetchContestsOutcome {
+    service: Service,
+    contests: Vec<FetchedContest>,
+}
+
+impl FetchContestsOutcome {
+    fn count_failed(&self) -> usize {
+        self.contests
+            .iter()
+            .filter(|contest| contest.problems.is_none())
+            .count()
+    }
+}
+
+impl fmt::Display for FetchContestsOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for contest in &self.contests {
+            match (contest.problems, &contest.message) {
+                (Some(problems), _) => {
+                    writeln!(f, "{} fetched {} problems", contest.contest_id, problems)
+                }
+                (None, Some(message)) => writeln!(f, "{} FAILED ({})", contest.contest_id, message),
+                (None, None) => writeln!(f, "{} FAILED", contest.contest_id),
+            }?;
+        }
+        write!(
+            f,
+            "Fetched {} contests ({} failed)",
+            self.contests.len(),
+            self.count_failed()
+        )
+    }
+}
+
+impl Outcome for FetchContestsOutcome {
+    fn is_error(&self) -> bool {
+        self.count_failed() > 0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::tempdir;
@@ -169,4 +524,34 @@ mod tests {
         run_with(&tempdir()?, |conf, cnsl| opt.run(conf, cnsl))?;
         Ok(())
     }
+
+    #[test]
+    fn run_offline() -> anyhow::Result<()> {
+        let opt = FetchOpt::default_test();
+        let err = run_with(&tempdir()?, |conf, cnsl| {
+            opt.run(&conf.with_offline(true), cnsl)
+        })
+        .unwrap_err();
+        assert_eq!(ErrorKind::of(&err), ErrorKind::Network);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_problems() {
+        let opt = FetchOpt::from_iter(&["fetch", "--problems", "a,b", "--problems", "c"]);
+        let expected: Vec<ProblemId> = vec!["a".into(), "b".into(), "c".into()];
+        assert_eq!(opt.problem_ids, expected);
+    }
+
+    #[test]
+    fn parse_contests() {
+        let opt = FetchOpt::from_iter(&["fetch", "--all-contests", "abc100,abc101"]);
+        let expected: Vec<ContestId> = vec!["abc100".into(), "abc101".into()];
+        assert_eq!(opt.contests, expected);
+        assert!(opt.is_multi_contests());
+
+        let contest_ids = parse_contests_file("abc100\n\n# arc\n  arc100  \n");
+        let expected: Vec<ContestId> = vec!["abc100".into(), "arc100".into()];
+        assert_eq!(contest_ids, expected);
+    }
 }
@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use chrono::{offset::Local, DateTime, SecondsFormat};
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::abs_path::AbsPathBuf;
+use crate::cmd::Outcome;
+use crate::model::{Byte, ContestId, ProblemId, Service};
+use crate::{Config, Console, Error, Result};
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct GcOpt {
+    /// Cleans up contests not touched for the given number of days
+    #[structopt(long, required_unless = "max-size")]
+    days: Option<u64>,
+    /// Cleans up least recently touched contests until total size fits in the given size (e.g. 10GB)
+    #[structopt(long)]
+    max_size: Option<Byte>,
+}
+
+impl GcOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<GcOutcome> {
+        // collect contests in base_dir
+        let mut problems_map: BTreeMap<ContestId, Vec<ProblemId>> = BTreeMap::new();
+        for (contest_id, problem_id) in conf.search_problems()? {
+            problems_map.entry(contest_id).or_default().push(problem_id);
+        }
+        let mut targets = problems_map
+            .into_iter()
+            .map(|(contest_id, problem_ids)| GcTarget::new(conf, contest_id, &problem_ids))
+            .collect::<Result<Vec<_>>>()?;
+        targets.sort_by_key(|target| target.touched_at);
+
+        // select contests to be cleaned up
+        let mut total: u64 = targets.iter().map(|target| target.size).sum();
+        let expire_at = self
+            .days
+            .map(|days| SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60));
+        let targets: Vec<GcTarget> = targets
+            .into_iter()
+            .filter(|target| target.size > 0)
+            .take_while(|target| {
+                let is_expired = expire_at.map_or(false, |expire_at| target.touched_at < expire_at);
+                let is_over = self
+                    .max_size
+                    .map_or(false, |max_size| total > max_size.as_u64());
+                if is_expired || is_over {
+                    total -= target.size;
+                    true
+                } else {
+                    false
+                }
+            })
+            .collect();
+
+        if !targets.is_empty() {
+            let reclaimed: u64 = targets.iter().map(|target| target.size).sum();
+            let message = format!(
+                "remove full testcases and build artifacts of {} contests ({})?",
+                targets.len(),
+                Byte::from(reclaimed)
+            );
+            if !cnsl.confirm(&message, false)? {
+                return Err(Error::msg("Not removed"));
+            }
+        }
+
+        let mut contests = Vec::new();
+        for target in targets {
+            for path in &target.paths {
+                if path.as_ref().is_dir() {
+                    path.remove_dir_all_pretty(Some(&conf.base_dir), cnsl)?;
+                } else {
+                    path.remove_file_pretty(Some(&conf.base_dir), cnsl)?;
+                }
+            }
+            contests.push(GcContest {
+                contest_id: target.contest_id,
+                touched_at: target.touched_at.into(),
+                reclaimed: Byte::from(target.size),
+            });
+        }
+
+        Ok(GcOutcome {
+            service: Service::new(conf.service_id),
+            reclaimed: Byte::from(contests.iter().map(|c| c.reclaimed.as_u64()).sum::<u64>()),
+            contests,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GcTarget {
+    contest_id: ContestId,
+    touched_at: SystemTime,
+    size: u64,
+    paths: Vec<AbsPathBuf>,
+}
+
+impl GcTarget {
+    fn new(conf: &Config, contest_id: ContestId, problem_ids: &[ProblemId]) -> Result<Self> {
+        let conf = conf.with_contest(contest_id.clone());
+        let mut touched_at = SystemTime::UNIX_EPOCH;
+        let mut size = 0;
+        let mut paths = Vec::new();
+        for problem_id in problem_ids {
+            // problem files and sources are kept and used to check when the contest was touched
+            for kept in &[
+                conf.problem_abs_path(problem_id)?,
+                conf.source_abs_path(problem_id)?,
+            ] {
+                if let Some(modified) = kept.last_modified()? {
+                    touched_at = touched_at.max(modified);
+                }
+            }
+            let mut removed = conf.artifact_abs_paths(problem_id)?;
+            removed.push(conf.testcases_abs_dir(problem_id)?);
+            for path in removed {
+                let path_size = path.disk_usage()?;
+                if path_size > 0 {
+                    size += path_size;
+                    paths.push(path);
+                }
+            }
+        }
+        Ok(Self {
+            contest_id,
+            touched_at,
+            size,
+            paths,
+        })
+    }
+}
+
+pub type LocalDateTime = DateTime<Local>;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GcContest {
+    contest_id: ContestId,
+    touched_at: LocalDateTime,
+    reclaimed: Byte,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GcOutcome {
+    service: Service,
+    contests: Vec<GcContest>,
+    reclaimed: Byte,
+}
+
+impl fmt::Display for GcOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for contest in &self.contests {
+            writeln!(
+                f,
+                "{} {} (last touched: {}, reclaimed: {})",
+                self.service.id(),
+                contest.contest_id,
+                contest
+                    .touched_at
+                    .to_rfc3339_opts(SecondsFormat::Secs, false),
+                contest.reclaimed
+            )?;
+        }
+        write!(
+            f,
+            "Reclaimed {} from {} contests",
+            self.reclaimed,
+            self.contests.len()
+        )
+    }
+}
+
+impl Outcome for GcOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::cmd::tests::run_with;
+
+    #[test]
+    fn run_default() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let problem_dir = test_dir.path().join("atcoder/arc100/c");
+        fs::create_dir_all(problem_dir.join("testcases/in"))?;
+        fs::write(problem_dir.join("problem.yaml"), "")?;
+        fs::write(problem_dir.join("Main.cpp"), "")?;
+        fs::write(problem_dir.join("c.out"), "12345")?;
+        fs::write(problem_dir.join("testcases/in/01.txt"), "123")?;
+
+        let opt = GcOpt {
+            days: None,
+            max_size: Some(Byte::from(0)),
+        };
+        let outcome = run_with(&test_dir, |conf, cnsl| opt.run(conf, cnsl))?;
+
+        assert_eq!(outcome.contests.len(), 1);
+        assert_eq!(outcome.reclaimed, Byte::from(8));
+        assert!(problem_dir.join("problem.yaml").exists());
+        assert!(problem_dir.join("Main.cpp").exists());
+        assert!(!problem_dir.join("c.out").exists());
+        assert!(!problem_dir.join("testcases").exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+
+use chrono::SecondsFormat;
+use getset::Getters;
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::cmd::Outcome;
+use crate::history::{History, HistoryEntry};
+use crate::model::ProblemId;
+use crate::{Config, Result};
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct HistoryOpt {
+    /// If specified, shows history of only one problem
+    #[structopt(name = "problem")]
+    pub(crate) problem_id: Option<ProblemId>,
+    /// Shows history of all contests
+    #[structopt(long)]
+    pub(crate) all_contests: bool,
+    /// Shows only the latest entries
+    #[structopt(long, short = "n", default_value = "20")]
+    pub(crate) limit: usize,
+}
+
+impl HistoryOpt {
+    pub fn run(&self, conf: &Config) -> Result<HistoryOutcome> {
+        let entries = History::new(conf.history_abs_path()).load()?;
+        let mut entries: Vec<HistoryEntry> = entries
+            .into_iter()
+            .filter(|entry| entry.service_id() == conf.service_id)
+            .filter(|entry| self.all_contests || entry.contest_id() == &conf.contest_id)
+            .filter(|entry| match &self.problem_id {
+                Some(problem_id) => entry.problem_id() == problem_id,
+                None => true,
+            })
+            .collect();
+        let n_skipped = entries.len().saturating_sub(self.limit);
+        entries.drain(..n_skipped);
+        Ok(HistoryOutcome { entries })
+    }
+}
+
+#[derive(Serialize, Getters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HistoryOutcome {
+    #[get = "pub"]
+    entries: Vec<HistoryEntry>,
+}
+
+impl fmt::Display for HistoryOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.entries.is_empty() {
+            return write!(f, "Found no history");
+        }
+        for (i, entry) in self.entries.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{} {:6} {} {} {} {}",
+                entry
+                    .recorded_at()
+                    .to_rfc3339_opts(SecondsFormat::Secs, false),
+                entry.kind().as_ref(),
+                entry.service_id(),
+                entry.contest_id(),
+                entry.problem_id(),
+                entry.verdict()
+            )?;
+            if let Some(elapsed) = entry.elapsed() {
+                write!(f, " ({:.2}s)", elapsed.as_secs_f32())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Outcome for HistoryOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::cmd::tests::run_with;
+    use crate::history::HistoryKind;
+
+    #[test]
+    fn run_default() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+
+        let opt = HistoryOpt {
+            problem_id: Some("c".into()),
+            all_contests: false,
+            limit: 1,
+        };
+        let outcome = run_with(&test_dir, |conf, _| {
+            let history = History::new(conf.history_abs_path());
+            for (problem_id, verdict) in &[("C", "WA"), ("D", "AC"), ("C", "AC")] {
+                history.record(&HistoryEntry::new(
+                    HistoryKind::Test,
+                    conf.service_id,
+                    conf.contest_id.clone(),
+                    (*problem_id).into(),
+                    *verdict,
+                    Some(Duration::from_secs(1)),
+                ))?;
+            }
+            opt.run(conf)
+        })?;
+
+        assert_eq!(outcome.entries.len(), 1);
+        assert_eq!(outcome.entries[0].verdict(), "AC");
+        Ok(())
+    }
+}
@@ -3,18 +3,40 @@ use std::fmt;
 use anyhow::{anyhow, Context as _};
 use serde::Serialize;
 use structopt::StructOpt;
+use strum::VariantNames as _;
 
 use crate::abs_path::AbsPathBuf;
-use crate::cmd::Outcome;
-use crate::config::ConfigBody;
-use crate::{Console, Result};
+use crate::cmd::editor_config::{save_editor_config, EditorKind};
+use crate::cmd::{FetchOpt, FetchOutcome, Outcome};
+use crate::config::{ConfigBody, DefaultsConfig, Layout, Profile};
+use crate::model::{ProblemId, ServiceKind};
+use crate::{Config, Console, Result};
 
 #[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
 #[structopt(rename_all = "kebab")]
 pub struct InitOpt {
     /// Overwrites config file if exists
     #[structopt(long, short = "w")]
-    overwrite: bool,
+    pub(crate) overwrite: bool,
+    /// Also generates tasks and launch configs of the editor
+    #[structopt(long, possible_values = &EditorKind::VARIANTS)]
+    pub(crate) editor: Option<EditorKind>,
+    /// Language of which default configs for services are generated
+    #[structopt(long, default_value = Profile::Cpp.into(), possible_values = &Profile::VARIANTS)]
+    pub(crate) profile: Profile,
+    /// Arrangement of files of problems: a directory for each problem (nested)
+    /// or all problems side by side in the directory of their contest (flat)
+    #[structopt(long, default_value = Layout::Nested.into(), possible_values = &Layout::VARIANTS)]
+    pub(crate) layout: Layout,
+    /// Url of contest page, from which default service and contest are inferred
+    #[structopt(long)]
+    pub(crate) from_url: Option<String>,
+    /// Fetches problems of the contest after creating config file
+    #[structopt(long, requires = "from-url")]
+    pub(crate) fetch: bool,
+    /// Fetches only the specified problems (e.g. a,b,c)
+    #[structopt(name = "problems", long, use_delimiter = true, requires = "fetch")]
+    pub(crate) problem_ids: Vec<ProblemId>,
 }
 
 impl InitOpt {
@@ -28,10 +50,28 @@ impl InitOpt {
             return Err(anyhow!("Could not find directory : {}", base_dir));
         }
 
+        // infer service and contest from url
+        let defaults = match &self.from_url {
+            Some(url) => {
+                let (service_id, contest_id) = ServiceKind::parse_contest_url(url)
+                    .with_context(|| format!("Could not infer contest from url : {}", url))?;
+                DefaultsConfig::new(service_id, contest_id)
+            }
+            None => DefaultsConfig::default(),
+        };
+
         // save config to yaml file
         let config_path = base_dir.join(ConfigBody::FILE_NAME);
         let is_saved = config_path.save_pretty(
-            |mut file| ConfigBody::generate_to(&mut file).context("Could not save config"),
+            |mut file| {
+                ConfigBody::generate_with_defaults_to(
+                    &mut file,
+                    &defaults,
+                    self.profile,
+                    self.layout,
+                )
+                .context("Could not save config")
+            },
             self.overwrite,
             Some(&cwd),
             cnsl,
@@ -42,18 +82,59 @@ impl InitOpt {
             return Err(anyhow!("Config file already exists : {}", config_path));
         }
 
-        Ok(InitOutcome { config_path })
+        let editor_files = match self.editor {
+            Some(editor) => save_editor_config(editor, &base_dir, self.overwrite, cnsl)?,
+            None => Vec::new(),
+        };
+
+        let fetch = if self.fetch {
+            let conf = Config::load(
+                defaults.service.unwrap_or_default(),
+                defaults.contest.clone().unwrap_or_default(),
+                Some(base_dir),
+                cnsl,
+            )?;
+            let mut opt = FetchOpt::from_iter(&["fetch"]);
+            opt.problem_ids = self.problem_ids.clone();
+            Some(opt.run(&conf, cnsl)?)
+        } else {
+            None
+        };
+
+        Ok(InitOutcome {
+            config_path,
+            editor_files,
+            defaults,
+            fetch,
+        })
     }
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct InitOutcome {
     config_path: AbsPathBuf,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    editor_files: Vec<AbsPathBuf>,
+    defaults: DefaultsConfig,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fetch: Option<FetchOutcome>,
 }
 
 impl fmt::Display for InitOutcome {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Saved config file : {}", self.config_path)
+        write!(f, "Saved config file : {}", self.config_path)?;
+        for path in &self.editor_files {
+            write!(f, "\nSaved editor config file : {}", path)?;
+        }
+        if let (Some(service_id), Some(contest_id)) =
+            (self.defaults.service, &self.defaults.contest)
+        {
+            write!(f, "\nSet default contest : {} {}", service_id, contest_id)?;
+        }
+        if let Some(fetch) = &self.fetch {
+            write!(f, "\n{}", fetch)?;
+        }
+        Ok(())
     }
 }
 
@@ -75,9 +156,150 @@ mod tests {
         let cnsl = &mut Console::buf(ConsoleConfig::default());
 
         let test_dir = tempdir()?;
-        let opt = InitOpt { overwrite: false };
+        let opt = InitOpt {
+            overwrite: false,
+            editor: None,
+            profile: Profile::default(),
+            layout: Layout::default(),
+            from_url: None,
+            fetch: false,
+            problem_ids: Vec::new(),
+        };
         let base_dir = AbsPathBuf::try_new(test_dir.path())?;
         opt.run(Some(base_dir), cnsl)?;
         Ok(())
     }
+
+    #[test]
+    fn run_with_editor() -> anyhow::Result<()> {
+        let cnsl = &mut Console::buf(ConsoleConfig::default());
+
+        let test_dir = tempdir()?;
+        let opt = InitOpt {
+            overwrite: false,
+            editor: Some(EditorKind::Vscode),
+            profile: Profile::default(),
+            layout: Layout::default(),
+            from_url: None,
+            fetch: false,
+            problem_ids: Vec::new(),
+        };
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let outcome = opt.run(Some(base_dir), cnsl)?;
+        assert_eq!(outcome.editor_files.len(), 2);
+        assert!(test_dir.path().join(".vscode/tasks.json").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn run_with_profile() -> anyhow::Result<()> {
+        let cnsl = &mut Console::buf(ConsoleConfig::default());
+
+        let test_dir = tempdir()?;
+        let opt = InitOpt {
+            overwrite: false,
+            editor: None,
+            profile: Profile::Rust,
+            layout: Layout::default(),
+            from_url: None,
+            fetch: false,
+            problem_ids: Vec::new(),
+        };
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        opt.run(Some(base_dir.clone()), cnsl)?;
+
+        let conf = Config::load(
+            ServiceKind::default(),
+            Default::default(),
+            Some(base_dir),
+            cnsl,
+        )?;
+        assert_eq!(conf.service().lang_names(), &["Rust (1.42.0)"]);
+        Ok(())
+    }
+
+    #[test]
+    fn run_with_flat_layout() -> anyhow::Result<()> {
+        let cnsl = &mut Console::buf(ConsoleConfig::default());
+
+        let test_dir = tempdir()?;
+        let opt = InitOpt {
+            overwrite: false,
+            editor: None,
+            profile: Profile::default(),
+            layout: Layout::Flat,
+            from_url: None,
+            fetch: false,
+            problem_ids: Vec::new(),
+        };
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        opt.run(Some(base_dir.clone()), cnsl)?;
+
+        let conf = Config::load(
+            ServiceKind::default(),
+            Default::default(),
+            Some(base_dir.clone()),
+            cnsl,
+        )?;
+        assert_eq!(
+            conf.source_abs_path(&"C".into())?,
+            base_dir.join("atcoder").join("arc100").join("c.cpp")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn run_from_url() -> anyhow::Result<()> {
+        let cnsl = &mut Console::buf(ConsoleConfig::default());
+
+        let test_dir = tempdir()?;
+        let opt = InitOpt {
+            overwrite: false,
+            editor: None,
+            profile: Profile::default(),
+            layout: Layout::default(),
+            from_url: Some("https://atcoder.jp/contests/abc150/tasks/abc150_a".into()),
+            fetch: false,
+            problem_ids: Vec::new(),
+        };
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        opt.run(Some(base_dir.clone()), cnsl)?;
+
+        let conf = Config::load(
+            ServiceKind::default(),
+            Default::default(),
+            Some(base_dir),
+            cnsl,
+        )?;
+        assert_eq!(
+            conf.defaults(),
+            &DefaultsConfig::new(ServiceKind::Atcoder, "abc150".into())
+        );
+
+        let opt = InitOpt {
+            overwrite: true,
+            editor: None,
+            profile: Profile::default(),
+            layout: Layout::default(),
+            from_url: Some("https://example.com/contests/abc150".into()),
+            fetch: false,
+            problem_ids: Vec::new(),
+        };
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        assert!(opt.run(Some(base_dir), cnsl).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_problems() -> anyhow::Result<()> {
+        let url = "https://atcoder.jp/contests/abc150";
+        let opt =
+            InitOpt::from_iter_safe(&["init", "--from-url", url, "--fetch", "--problems", "a,b"])?;
+        assert_eq!(
+            opt.problem_ids,
+            vec![ProblemId::from("A"), ProblemId::from("B")]
+        );
+        assert!(InitOpt::from_iter_safe(&["init", "--from-url", url, "--problems", "a"]).is_err());
+        Ok(())
+    }
 }
@@ -5,7 +5,8 @@ use anyhow::Context as _;
 use serde::Serialize;
 use structopt::StructOpt;
 
-use crate::cmd::{with_actor, Outcome};
+use crate::cmd::{block_on, with_actor, Outcome};
+use crate::config::Credentials;
 use crate::model::Service;
 use crate::service::Act;
 use crate::{Config, Console, Result};
@@ -17,30 +18,28 @@ pub struct LoginOpt {}
 impl LoginOpt {
     pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<LoginOutcome> {
         with_actor(conf.service_id, conf.session(), |actor| {
-            self.run_inner(actor, conf, cnsl)
+            block_on(self.run_inner(actor, conf, cnsl))
         })
     }
 
-    fn run_inner(
+    async fn run_inner(
         &self,
         actor: &dyn Act,
         conf: &Config,
         cnsl: &mut Console,
     ) -> Result<LoginOutcome> {
-        let (user_env, pass_env) = conf.service_id.to_user_pass_env_names();
-        let user = cnsl
-            .get_env_or_prompt_and_read(user_env, "username: ", false)
-            .context("Could not read username")?;
-        let pass = cnsl
-            .get_env_or_prompt_and_read(pass_env, "password: ", true)
-            .context("Could not read password")?;
+        let Credentials { username, password } = conf
+            .session()
+            .credentials()
+            .read(conf.service_id, true, cnsl)
+            .context("Could not read credentials")?;
         writeln!(cnsl)?;
 
-        let is_not_already = actor.login(user.to_owned(), pass, cnsl)?;
+        let is_not_already = actor.login(username.clone(), password, cnsl).await?;
 
         let outcome = LoginOutcome {
             service: Service::new(conf.service_id),
-            username: user,
+            username,
             is_already: !is_not_already,
         };
         Ok(outcome)
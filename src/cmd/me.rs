@@ -1,53 +1,95 @@
 use std::fmt;
 
 use anyhow::anyhow;
+use getset::{CopyGetters, Getters};
 use serde::Serialize;
 use structopt::StructOpt;
 
-use crate::cmd::{with_actor, Outcome};
+use crate::cmd::{block_on, for_each_service, with_actor, Outcome};
 use crate::model::Service;
 use crate::service::Act;
 use crate::{Config, Console, Result};
 
 #[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
 #[structopt(rename_all = "kebab")]
-pub struct MeOpt {}
+pub struct MeOpt {
+    /// Shows login status of all services
+    #[structopt(long, visible_alias = "all")]
+    pub(crate) all_services: bool,
+}
 
 impl MeOpt {
     pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<MeOutcome> {
-        with_actor(conf.service_id, conf.session(), |actor| {
-            self.run_inner(actor, conf, cnsl)
+        let users = if self.all_services {
+            for_each_service(conf, |actor, conf| {
+                block_on(Self::fetch_user(actor, conf, cnsl))
+            })?
+        } else {
+            let user = with_actor(conf.service_id, conf.session(), |actor| {
+                block_on(Self::fetch_user(actor, conf, cnsl))
+            })?;
+            if user.username.is_none() {
+                return Err(anyhow!("Not logged in to {}", conf.service_id));
+            }
+            vec![user]
+        };
+
+        Ok(MeOutcome { users })
+    }
+
+    async fn fetch_user(actor: &dyn Act, conf: &Config, cnsl: &mut Console) -> Result<ServiceUser> {
+        // check cookie before sending request because the request may overwrite it
+        let has_stored_session = actor.has_stored_session()?;
+        let username = actor.current_user(cnsl).await?;
+        Ok(ServiceUser {
+            service: Service::new(conf.service_id),
+            is_expired: has_stored_session && username.is_none(),
+            username,
         })
     }
+}
 
-    fn run_inner(&self, actor: &dyn Act, conf: &Config, cnsl: &mut Console) -> Result<MeOutcome> {
-        let user = actor
-            .current_user(cnsl)?
-            .ok_or_else(|| anyhow!("Not logged in to {}", conf.service_id))?;
+#[derive(Serialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServiceUser {
+    #[get = "pub"]
+    service: Service,
+    #[get = "pub"]
+    username: Option<String>,
+    #[get_copy = "pub"]
+    is_expired: bool,
+}
 
-        let outcome = MeOutcome {
-            service: Service::new(conf.service_id),
-            username: user,
-        };
-        Ok(outcome)
+impl fmt::Display for ServiceUser {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.username {
+            Some(username) => write!(f, "Logged in to {} as {}", self.service.id(), username),
+            None if self.is_expired => write!(f, "Session of {} has expired", self.service.id()),
+            None => write!(f, "Not logged in to {}", self.service.id()),
+        }
     }
 }
 
-#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Serialize, Getters, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MeOutcome {
-    service: Service,
-    username: String,
+    #[get = "pub"]
+    users: Vec<ServiceUser>,
 }
 
 impl fmt::Display for MeOutcome {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Logged in to {} as {}", self.service.id(), self.username)
+        for (i, user) in self.users.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", user)?;
+        }
+        Ok(())
     }
 }
 
 impl Outcome for MeOutcome {
     fn is_error(&self) -> bool {
-        false
+        self.users.iter().any(|user| user.is_expired)
     }
 }
 
@@ -66,11 +108,15 @@ mod tests {
         let login_opt = crate::cmd::LoginOpt {};
         run_with(&test_dir, |conf, cnsl| login_opt.run(conf, cnsl))?;
 
-        let opt = MeOpt {};
+        let opt = MeOpt {
+            all_services: false,
+        };
         let outcome = run_with(&test_dir, |conf, cnsl| opt.run(conf, cnsl))?;
 
-        assert_eq!(outcome.service.id(), ServiceKind::Atcoder);
-        assert_eq!(&outcome.username, "acick_test");
+        assert_eq!(outcome.users.len(), 1);
+        assert_eq!(outcome.users[0].service.id(), ServiceKind::Atcoder);
+        assert_eq!(outcome.users[0].username.as_deref(), Some("acick_test"));
+        assert!(!outcome.is_error());
         Ok(())
     }
 }
@@ -0,0 +1,67 @@
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use structopt::StructOpt;
+
+use crate::abs_path::AbsPathBuf;
+use crate::cmd::TestOutcome;
+use crate::{Console, Result};
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct MergeOutcomesOpt {
+    /// Files of test outcomes printed in json or yaml format
+    #[structopt(name = "files", required = true)]
+    paths: Vec<PathBuf>,
+}
+
+impl MergeOutcomesOpt {
+    pub fn run(&self, _cnsl: &mut Console) -> Result<TestOutcome> {
+        let cwd = AbsPathBuf::cwd()?;
+        let outcomes = self
+            .paths
+            .iter()
+            .map(|path| {
+                let abs_path = cwd.join(path);
+                abs_path
+                    .load(|file| serde_yaml::from_reader(file).map_err(Into::into))
+                    .with_context(|| format!("Could not load test outcome : {}", abs_path))
+            })
+            .collect::<Result<Vec<TestOutcome>>>()?;
+        TestOutcome::merge(outcomes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::cmd::OutcomeSerialize as _;
+    use crate::ConsoleConfig;
+
+    #[test]
+    fn run_default() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let shards = [
+            TestOutcome::shard("c", &["00", "01"], "0"),
+            TestOutcome::shard("c", &["02"], "0"),
+        ];
+        let mut paths = Vec::new();
+        for (i, shard) in shards.iter().enumerate() {
+            let path = test_dir.path().join(format!("shard{}.json", i));
+            let mut buf = Vec::new();
+            shard.write_json(&mut buf)?;
+            fs::write(&path, buf)?;
+            paths.push(path);
+        }
+
+        let opt = MergeOutcomesOpt { paths };
+        let mut cnsl = Console::buf(ConsoleConfig::default());
+        let outcome = opt.run(&mut cnsl)?;
+        assert_eq!(outcome, TestOutcome::merge(shards.to_vec())?);
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use std::fmt;
+
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::abs_path::AbsPathBuf;
+use crate::cmd::Outcome;
+use crate::config::{ConfigBody, MigrationReport};
+use crate::{Console, Result};
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct MigrateOpt {}
+
+impl MigrateOpt {
+    pub fn run(&self, base_dir: Option<AbsPathBuf>, cnsl: &mut Console) -> Result<MigrateOutcome> {
+        let report = ConfigBody::migrate(base_dir, cnsl)?;
+        Ok(MigrateOutcome { report })
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MigrateOutcome {
+    #[serde(flatten)]
+    report: MigrationReport,
+}
+
+impl fmt::Display for MigrateOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let report = &self.report;
+        match &report.backup_path {
+            Some(backup_path) => write!(
+                f,
+                "Migrated config file from version {} to {} (backup : {})",
+                report.from_version, report.to_version, backup_path
+            ),
+            None => write!(
+                f,
+                "Config file is already compatible with version {}",
+                report.to_version
+            ),
+        }
+    }
+}
+
+impl Outcome for MigrateOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
@@ -1,38 +1,108 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::Write as _;
+use std::rc::Rc;
+use std::time::Duration;
 use std::{fmt, io};
 
 use anyhow::Context as _;
+use chrono::Local;
 use serde::Serialize;
 use structopt::StructOpt;
-use strum::VariantNames;
+use strum::{IntoEnumIterator as _, VariantNames};
+use tokio::runtime::{self, Runtime};
 
 use crate::abs_path::AbsPathBuf;
 use crate::config::SessionConfig;
-use crate::model::{ContestId, ServiceKind, DEFAULT_CONTEST_ID_STR};
+use crate::error::{ErrorKind, WithErrorKind as _};
+use crate::metrics::Metrics;
+use crate::model::{ContestId, ContestPhase, ServiceKind};
 use crate::service::act::Act;
+use crate::service::session::clock_skew;
+use crate::virtual_contest::VirtualSessions;
 use crate::{Config, Console, OutputFormat, Result};
 
+mod archive;
+mod bench;
+mod config;
+#[cfg(feature = "fixtures")]
+mod debug;
+mod doctor;
+mod editor_config;
 mod fetch;
+mod gc;
+mod history;
 mod init;
 mod login;
 mod logout;
 mod me;
+mod merge_outcomes;
+mod migrate;
+mod oj;
+mod participate;
+mod practice;
+mod quicktest;
+mod sample;
+mod serve;
+mod session;
 mod show;
+mod standings;
+mod status;
+mod submission;
 mod submit;
 mod test;
+mod tui;
+mod verify_lib;
+mod verify_samples;
+mod virtual_contest;
+mod which;
 
-pub use fetch::FetchOpt;
+pub use archive::{ArchiveAction, ArchiveOutcome, ExportOpt, ImportOpt};
+pub use bench::{BenchOpt, BenchOutcome};
+pub use config::{ConfigOpt, ConfigOutcome};
+#[cfg(feature = "fixtures")]
+pub use debug::{DebugAction, DebugOpt, DebugOutcome};
+pub use doctor::{Check, CheckStatus, DoctorOpt, DoctorOutcome};
+pub use editor_config::{EditorConfigOpt, EditorConfigOutcome, EditorKind};
+pub use fetch::{FetchContestsOutcome, FetchOpt, FetchOutcome};
+pub use gc::{GcOpt, GcOutcome};
+pub use history::{HistoryOpt, HistoryOutcome};
 pub use init::{InitOpt, InitOutcome};
 pub use login::{LoginOpt, LoginOutcome};
 pub use logout::{LogoutOpt, LogoutOutcome};
-pub use me::{MeOpt, MeOutcome};
+pub use me::{MeOpt, MeOutcome, ServiceUser};
+pub use merge_outcomes::MergeOutcomesOpt;
+pub use migrate::{MigrateOpt, MigrateOutcome};
+pub use oj::{OjOpt, OjOutcome};
+pub use participate::{ParticipateOpt, ParticipateOutcome};
+pub use practice::{PracticeOpt, PracticeOutcome};
+pub use quicktest::{QuicktestOpt, QuicktestOutcome};
+pub use sample::{SampleOpt, SampleOutcome};
+pub use serve::{ServeOpt, ServeOutcome};
+pub use session::{SessionAction, SessionOpt, SessionOutcome};
 pub use show::{ShowOpt, ShowOutcome};
-pub use submit::{SubmitOpt, SubmitOutcome};
+pub use standings::{StandingsOpt, StandingsOutcome};
+pub use status::{ServiceStatus, StatusOpt, StatusOutcome};
+pub use submission::{SubmissionOpt, SubmissionOutcome};
+pub use submit::{RetryPendingOutcome, SubmitOpt, SubmitOutcome};
 pub use test::{TestOpt, TestOutcome};
+pub use tui::{TuiOpt, TuiOutcome};
+pub use verify_lib::{VerifyLibOpt, VerifyLibOutcome};
+pub use verify_samples::{VerifySamplesOpt, VerifySamplesOutcome};
+pub use virtual_contest::{VirtualOpt, VirtualOutcome};
+pub use which::{WhichOpt, WhichOutcome};
 
 use crate::atcoder::AtcoderActor;
+use crate::mock::MockActor;
 
 pub trait Outcome: OutcomeSerialize {
     fn is_error(&self) -> bool;
+
+    /// Returns kind of the error, which is used only if `is_error` returns true.
+    fn error_kind(&self) -> ErrorKind {
+        ErrorKind::Failed
+    }
 }
 
 pub trait OutcomeSerialize: fmt::Display + fmt::Debug {
@@ -40,12 +110,24 @@ pub trait OutcomeSerialize: fmt::Display + fmt::Debug {
 
     fn write_yaml(&self, writer: &mut dyn io::Write) -> Result<()>;
 
+    fn write_tsv(&self, writer: &mut dyn io::Write) -> Result<()>;
+
+    /// Prints outcome with `error` and `metrics` attached as fields of the same names.
+    fn print_attached(
+        &self,
+        stdout: &mut dyn io::Write,
+        format: OutputFormat,
+        error: Option<&ErrorReport>,
+        metrics: Option<&Metrics>,
+    ) -> Result<()>;
+
     fn print(&self, stdout: &mut dyn io::Write, format: OutputFormat) -> Result<()> {
         match format {
             OutputFormat::Default => writeln!(stdout, "{}", self)?,
             OutputFormat::Debug => writeln!(stdout, "{:?}", self)?,
             OutputFormat::Json => self.write_json(stdout)?,
             OutputFormat::Yaml => self.write_yaml(stdout)?,
+            OutputFormat::Tsv => self.write_tsv(stdout)?,
         }
         Ok(())
     }
@@ -59,6 +141,102 @@ impl<T: Serialize + fmt::Display + fmt::Debug> OutcomeSerialize for T {
     fn write_yaml(&self, writer: &mut dyn io::Write) -> Result<()> {
         serde_yaml::to_writer(writer, self).context("Could not print outcome as json")
     }
+
+    /// Writes each field of outcome as a line of tab-separated key and value.
+    ///
+    /// Keys of nested fields are joined with `.` (e.g. `problems.0.id`).
+    fn write_tsv(&self, writer: &mut dyn io::Write) -> Result<()> {
+        let value = serde_json::to_value(self).context("Could not print outcome as tsv")?;
+        let mut rows = Vec::new();
+        flatten_value(String::new(), &value, &mut rows);
+        for (key, value) in rows {
+            writeln!(writer, "{}\t{}", key, value)?;
+        }
+        Ok(())
+    }
+
+    fn print_attached(
+        &self,
+        stdout: &mut dyn io::Write,
+        format: OutputFormat,
+        error: Option<&ErrorReport>,
+        metrics: Option<&Metrics>,
+    ) -> Result<()> {
+        Attached {
+            outcome: self,
+            error,
+            metrics,
+        }
+        .print(stdout, format)
+    }
+}
+
+/// Error of command printed in structured output formats.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ErrorReport {
+    kind: ErrorKind,
+    exit_code: i32,
+    message: String,
+}
+
+impl ErrorReport {
+    pub fn new(kind: ErrorKind, message: String) -> Self {
+        Self {
+            kind,
+            exit_code: kind.exit_code(),
+            message,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct Attached<'a, T> {
+    #[serde(flatten)]
+    outcome: &'a T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a ErrorReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metrics: Option<&'a Metrics>,
+}
+
+impl<T: fmt::Display> fmt::Display for Attached<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.outcome.fmt(f)
+    }
+}
+
+fn flatten_value(key: String, value: &serde_json::Value, rows: &mut Vec<(String, String)>) {
+    use serde_json::Value;
+
+    let join = |child: &str| {
+        if key.is_empty() {
+            child.to_owned()
+        } else {
+            format!("{}.{}", key, child)
+        }
+    };
+    match value {
+        Value::Object(map) => {
+            for (child, value) in map {
+                flatten_value(join(child), value, rows);
+            }
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                flatten_value(join(&i.to_string()), value, rows);
+            }
+        }
+        Value::Null => rows.push((key, String::new())),
+        Value::String(s) => rows.push((key, escape_tsv(s))),
+        value => rows.push((key, value.to_string())),
+    }
+}
+
+fn escape_tsv(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
 }
 
 #[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
@@ -66,13 +244,33 @@ impl<T: Serialize + fmt::Display + fmt::Debug> OutcomeSerialize for T {
 pub enum Cmd {
     /// Creates config file
     Init(InitOpt),
-    /// Shows current config
+    /// Shows current config or fetched problem
     Show {
         #[structopt(flatten)]
         sc: ServiceContest,
         #[structopt(flatten)]
         opt: ShowOpt,
     },
+    /// Prints paths of source file, working dir, testcases dir and problem file of problem
+    Which {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: WhichOpt,
+    },
+    /// Prints JSON Schema of config file
+    Config(ConfigOpt),
+    /// Rewrites config file of older version of acick so that it is valid for the current version
+    Migrate(MigrateOpt),
+    /// Generates tasks and launch configs of editor bound to acick commands
+    EditorConfig(EditorConfigOpt),
+    /// Checks environment (e.g. shell, compiler, network and session) and prints checklist
+    Doctor {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: DoctorOpt,
+    },
     /// Gets info of user currently logged in to service
     Me {
         #[structopt(flatten)]
@@ -95,7 +293,41 @@ pub enum Cmd {
         #[structopt(flatten)]
         opt: LogoutOpt,
     },
-    // Participate(ParticipateOpt),
+    /// Exports or imports cookies of logged-in session
+    Session {
+        #[structopt(skip)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: SessionOpt,
+    },
+    /// Participates in contest
+    Participate {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: ParticipateOpt,
+    },
+    /// Lists problems of AtCoder by difficulty from AtCoder Problems and fetches one of them
+    Practice {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: PracticeOpt,
+    },
+    /// Shows schedule and remaining time of contest
+    Status {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: StatusOpt,
+    },
+    /// Shows rank, score and penalty in standings of contest
+    Standings {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: StandingsOpt,
+    },
     /// Fetches problems from service
     #[structopt(visible_alias("f"))]
     Fetch {
@@ -113,6 +345,15 @@ pub enum Cmd {
         opt: TestOpt,
     },
     // Judge(JudgeOpt), // test full testcases, for AtCoder only
+    /// Measures distribution of runtime of source code on a sample
+    Bench {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: BenchOpt,
+    },
+    /// Tests a source file on given input without config file
+    Quicktest(QuicktestOpt),
     /// Submits source code to service
     #[structopt(visible_alias("s"))]
     Submit {
@@ -121,9 +362,109 @@ pub enum Cmd {
         #[structopt(flatten)]
         opt: SubmitOpt,
     },
+    /// Shows verdicts of submission for each testcase
+    Submission {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: SubmissionOpt,
+    },
+    /// Starts, stops or shows virtual participation in past contest
+    Virtual {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: VirtualOpt,
+    },
+    /// Shows dashboard of problems and verdicts in contest with keybindings to fetch, test and submit
+    Tui {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: TuiOpt,
+    },
+    /// Removes full testcases and build artifacts of old contests
+    Gc {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: GcOpt,
+    },
+    /// Shows history of test and submit commands
+    History {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: HistoryOpt,
+    },
+    /// Imports or exports testcases in the directory format of online-judge-tools
+    Oj {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: OjOpt,
+    },
+    /// Packages problems, sources, testcases and history of contest into zip file
+    Export {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: ExportOpt,
+    },
+    /// Restores contest from zip file created by export command
+    Import {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: ImportOpt,
+    },
+    /// Adds, edits or removes custom samples of problem
+    Sample {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: SampleOpt,
+    },
+    /// Merges outcomes of test command run on shards of testcases
+    MergeOutcomes(MergeOutcomesOpt),
+    /// Verifies stored samples against samples extracted from service
+    VerifySamples {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: VerifySamplesOpt,
+    },
+    /// Verifies library with AtCoder samples or AOJ testcases of problems annotated in files
+    VerifyLib {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: VerifyLibOpt,
+    },
+    /// Serves commands as JSON-RPC over stdio or unix socket for editor integrations
+    Serve {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: ServeOpt,
+    },
+    /// Tools for maintainers of acick (e.g. recording fixtures of scrapers)
+    #[cfg(feature = "fixtures")]
+    Debug {
+        #[structopt(flatten)]
+        sc: ServiceContest,
+        #[structopt(flatten)]
+        opt: DebugOpt,
+    },
 }
 
 impl Cmd {
+    /// Returns true if the command writes its own data to stdout
+    /// (e.g. responses of `serve` over stdio), so that outcome must be printed to stderr.
+    pub fn reserves_stdout(&self) -> bool {
+        matches!(self, Self::Serve { opt, .. } if opt.is_stdio())
+    }
+
     pub fn run(
         &self,
         base_dir: Option<AbsPathBuf>,
@@ -133,56 +474,290 @@ impl Cmd {
         let b = base_dir;
         match self {
             Self::Init(opt) => finish(&opt.run(b, cnsl)?, cnsl),
-            Self::Show { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?)?, cnsl),
+            Self::Show { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Which { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?)?, cnsl),
+            Self::Config(opt) => finish(&opt.run()?, cnsl),
+            Self::Migrate(opt) => finish(&opt.run(b, cnsl)?, cnsl),
+            Self::EditorConfig(opt) => finish(&opt.run(b, cnsl)?, cnsl),
+            Self::Doctor { sc, opt } => finish(&opt.run(sc.load_config(b, cnsl), cnsl)?, cnsl),
             Self::Me { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
             Self::Login { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
             Self::Logout { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Session { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Participate { sc, opt } => {
+                finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl)
+            }
+            Self::Practice { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Status { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Standings { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Fetch { sc, opt } if opt.is_multi_contests() => {
+                finish(&opt.run_contests(&sc.load_config(b, cnsl)?, cnsl)?, cnsl)
+            }
             Self::Fetch { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
             Self::Test { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Bench { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Quicktest(opt) => finish(&opt.run(cnsl)?, cnsl),
+            Self::Submit { sc, opt } if opt.retry_pending => {
+                finish(&opt.run_pending(&sc.load_config(b, cnsl)?, cnsl)?, cnsl)
+            }
             Self::Submit { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Submission { sc, opt } => {
+                finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl)
+            }
+            Self::Virtual { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Tui { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Gc { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::History { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?)?, cnsl),
+            Self::Oj { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Export { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Import { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Sample { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::MergeOutcomes(opt) => finish(&opt.run(cnsl)?, cnsl),
+            Self::VerifySamples { sc, opt } => {
+                finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl)
+            }
+            Self::VerifyLib { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            Self::Serve { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
+            #[cfg(feature = "fixtures")]
+            Self::Debug { sc, opt } => finish(&opt.run(&sc.load_config(b, cnsl)?, cnsl)?, cnsl),
         }
     }
 }
 
 #[derive(Default, StructOpt, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ServiceContest {
+    /// Id of service [default: defaults.service in config file or atcoder]
     #[structopt(
         name = "service",
         long,
         short,
         global = true,
         env = "ACICK_SERVICE",
-        default_value = ServiceKind::default().into(),
         possible_values = &ServiceKind::VARIANTS,
     )]
-    pub service_id: ServiceKind,
-    #[structopt(
-        name = "contest",
-        long,
-        short,
-        global = true,
-        env = "ACICK_CONTEST",
-        default_value = DEFAULT_CONTEST_ID_STR,
-    )]
-    pub contest_id: ContestId,
+    pub service_id: Option<ServiceKind>,
+    /// Id of contest [default: defaults.contest in config file or arc100]
+    #[structopt(name = "contest", long, short, global = true, env = "ACICK_CONTEST")]
+    pub contest_id: Option<ContestId>,
+    /// Disables risky operations during contest (enabled automatically while contest is running)
+    #[structopt(long, global = true)]
+    pub contest_mode: bool,
+    /// Forbids network access and runs commands only with fetched files (e.g. test, show)
+    #[structopt(long, global = true)]
+    pub offline: bool,
 }
 
 impl ServiceContest {
     fn load_config(&self, base_dir: Option<AbsPathBuf>, cnsl: &mut Console) -> Result<Config> {
-        Config::load(self.service_id, self.contest_id.clone(), base_dir, cnsl)
-            .context("Could not load config file")
+        let mut conf = Config::load(
+            self.service_id.unwrap_or_default(),
+            self.contest_id.clone().unwrap_or_default(),
+            base_dir,
+            cnsl,
+        )
+        .context("Could not load config file")
+        .with_kind(ErrorKind::Config)?;
+        // options take precedence over defaults in config file
+        let defaults = conf.defaults().clone();
+        if let (None, Some(service_id)) = (self.service_id, defaults.service) {
+            conf = conf.with_service(service_id);
+        }
+        if let (None, Some(contest_id)) = (&self.contest_id, defaults.contest) {
+            conf = conf.with_contest(contest_id);
+        }
+        conf.contest_mode = self.contest_mode;
+        if self.offline {
+            conf = conf.with_offline(true);
+        }
+        Ok(conf)
     }
 }
 
-fn with_actor<F, R>(service_id: ServiceKind, session: &SessionConfig, f: F) -> R
+fn with_actor<F, R>(service_id: ServiceKind, session: &SessionConfig, f: F) -> Result<R>
 where
-    F: FnOnce(&dyn Act) -> R,
+    F: FnOnce(&dyn Act) -> Result<R>,
 {
-    match service_id {
-        ServiceKind::Atcoder => f(&AtcoderActor::new(session)),
+    with_shared_actor(service_id, session, |actor| f(actor.as_act()))
+}
+
+/// Runs `f` with the actor of AtCoder, for requests only AtCoder serves (e.g. AtCoder Problems).
+fn with_atcoder_actor<F, R>(session: &SessionConfig, f: F) -> Result<R>
+where
+    F: FnOnce(&AtcoderActor) -> Result<R>,
+{
+    with_shared_actor(ServiceKind::Atcoder, session, |actor| match actor {
+        SharedActor::Atcoder(actor) => f(actor),
+        SharedActor::Mock(_) => unreachable!("actor of AtCoder must be created for AtCoder"),
+    })
+}
+
+enum SharedActor {
+    Atcoder(AtcoderActor),
+    Mock(MockActor),
+}
+
+impl SharedActor {
+    fn new(service_id: ServiceKind, session: &SessionConfig) -> Result<Self> {
+        Ok(match service_id {
+            ServiceKind::Atcoder => Self::Atcoder(AtcoderActor::new(session)?),
+            ServiceKind::Mock => Self::Mock(MockActor::new(session)),
+        })
+    }
+
+    fn as_act(&self) -> &dyn Act {
+        match self {
+            Self::Atcoder(actor) => actor,
+            Self::Mock(actor) => actor,
+        }
     }
 }
 
+fn with_shared_actor<F, R>(service_id: ServiceKind, session: &SessionConfig, f: F) -> Result<R>
+where
+    F: FnOnce(&SharedActor) -> Result<R>,
+{
+    // actors are shared only among calls with the same session (e.g. not between anonymous ones
+    // and logged-in ones), because each actor holds the session it was created with
+    let key = (service_id, session.clone());
+    let shared = ACTORS.with(|cell| {
+        cell.borrow()
+            .as_ref()
+            .map(|actors| actors.get(&key).cloned())
+    });
+    match shared {
+        None => f(&SharedActor::new(service_id, session)?),
+        Some(Some(actor)) => f(&actor),
+        Some(None) => {
+            let actor = Rc::new(SharedActor::new(service_id, session)?);
+            ACTORS.with(|cell| {
+                if let Some(actors) = cell.borrow_mut().as_mut() {
+                    actors.insert(key, Rc::clone(&actor));
+                }
+            });
+            f(&*actor)
+        }
+    }
+}
+
+/// Runs `f` sharing an actor of each service and session among all `with_actor` calls inside it,
+/// so that long-running commands (e.g. `serve`) keep using one client and its connections.
+pub(crate) fn with_shared_actors<R>(f: impl FnOnce() -> Result<R>) -> Result<R> {
+    ACTORS.with(|cell| cell.replace(Some(HashMap::new())));
+    let result = f();
+    ACTORS.with(|cell| cell.replace(None));
+    result
+}
+
+thread_local! {
+    static RUNTIME: RefCell<Option<Runtime>> = RefCell::new(None);
+    static ACTORS: RefCell<Option<HashMap<(ServiceKind, SessionConfig), Rc<SharedActor>>>> =
+        RefCell::new(None);
+}
+
+/// Runs `f` with a single-threaded runtime shared by all `block_on` calls inside it.
+pub(crate) fn with_runtime<R>(f: impl FnOnce() -> Result<R>) -> Result<R> {
+    let runtime = new_runtime()?;
+    RUNTIME.with(|cell| cell.replace(Some(runtime)));
+    let result = f();
+    // drop runtime here rather than in destructor of thread local
+    RUNTIME.with(|cell| cell.replace(None));
+    result
+}
+
+/// Runs `future` to completion on the runtime of `with_runtime`,
+/// or on a new runtime if it is called outside `with_runtime` (e.g. in tests).
+///
+/// Commands enter the runtime once and await requests, hooks and git commands inside it,
+/// because a runtime cannot be started inside another one.
+fn block_on<F, R>(future: F) -> Result<R>
+where
+    F: Future<Output = Result<R>>,
+{
+    RUNTIME.with(|cell| match cell.borrow_mut().as_mut() {
+        Some(runtime) => runtime.block_on(future),
+        None => new_runtime()?.block_on(future),
+    })
+}
+
+fn new_runtime() -> Result<Runtime> {
+    runtime::Builder::new()
+        .basic_scheduler()
+        .enable_all()
+        .build()
+        .context("Could not start async runtime")
+}
+
+/// Checks if risky operations should be disabled.
+///
+/// Contest mode is enabled by `--contest-mode` option or automatically while the contest is running.
+async fn is_contest_mode(actor: &dyn Act, conf: &Config, cnsl: &mut Console) -> Result<bool> {
+    if conf.contest_mode {
+        return Ok(true);
+    }
+    match actor.fetch_contest_info(&conf.contest_id, cnsl).await {
+        Ok(info) if info.phase_at(Local::now()) == ContestPhase::Running => {
+            writeln!(cnsl, "Enabled contest mode because contest is running")?;
+            Ok(true)
+        }
+        Ok(_) => Ok(false),
+        // coerce error
+        Err(err) => {
+            writeln!(cnsl, "Could not check contest schedule: {}", err)?;
+            Ok(false)
+        }
+    }
+}
+
+/// Warns if local clock differs from clock of service, which makes contest schedule confusing.
+fn warn_clock_skew(conf: &Config, cnsl: &mut Console) -> Result<()> {
+    let skew = match clock_skew() {
+        Some(skew) => skew,
+        None => return Ok(()),
+    };
+    let max_skew = conf.session().max_clock_skew();
+    let abs_skew = skew.num_milliseconds().unsigned_abs();
+    if abs_skew <= max_skew.as_millis() as u64 {
+        return Ok(());
+    }
+    cnsl.warn(&format!(
+        "Local clock is {} {} clock of service. Check your system clock.",
+        humantime::format_duration(Duration::from_secs(abs_skew / 1000)),
+        if skew > chrono::Duration::zero() {
+            "ahead of"
+        } else {
+            "behind"
+        }
+    ))?;
+    Ok(())
+}
+
+/// Prints elapsed time of virtual participation in the contest if any.
+fn print_virtual_time(conf: &Config, cnsl: &mut Console) -> Result<()> {
+    let path = conf.virtual_abs_path();
+    match VirtualSessions::new(&path).find(conf.service_id, &conf.contest_id) {
+        Ok(Some(session)) => writeln!(cnsl, "{}", session.status_line())?,
+        Ok(None) => {}
+        // coerce error
+        Err(err) => cnsl.warn(&format!("Could not load virtual participation: {:#}", err))?,
+    }
+    Ok(())
+}
+
+/// Runs `f` for each service with config that targets the service.
+fn for_each_service<F, R>(conf: &Config, mut f: F) -> Result<Vec<R>>
+where
+    F: FnMut(&dyn Act, &Config) -> Result<R>,
+{
+    ServiceKind::iter()
+        // mock service is used only if specified explicitly
+        .filter(|&service_id| service_id != ServiceKind::Mock)
+        .map(|service_id| {
+            let conf = conf.with_service(service_id);
+            with_actor(service_id, conf.session(), |actor| f(actor, &conf))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -199,11 +774,92 @@ pub mod tests {
 
         let base_dir = AbsPathBuf::try_new(test_dir.path().to_owned()).unwrap();
         let conf = Config::default_in_dir(base_dir);
-        let mut cnsl = Console::buf(ConsoleConfig { assume_yes: true });
+        let mut cnsl = Console::buf(ConsoleConfig {
+            assume_yes: true,
+            ..Default::default()
+        });
         let result = run(&conf, &mut cnsl);
 
         let output_str = cnsl.take_output()?;
         eprintln!("{}", output_str);
         result
     }
+
+    #[test]
+    fn test_print_attached() -> anyhow::Result<()> {
+        let value = serde_json::json!({ "total": "WA" });
+        let error = ErrorReport::new(ErrorKind::WrongAnswer, "Command exited with error".into());
+        let mut buf = Vec::new();
+        value.print_attached(&mut buf, OutputFormat::Json, Some(&error), None)?;
+        let actual: serde_json::Value = serde_json::from_slice(&buf)?;
+        let expected = serde_json::json!({
+            "total": "WA",
+            "error": {
+                "kind": "wrong-answer",
+                "exit_code": 11,
+                "message": "Command exited with error",
+            },
+        });
+        assert_eq!(actual, expected);
+
+        let metrics = Metrics::default();
+        let mut buf = Vec::new();
+        value.print_attached(&mut buf, OutputFormat::Yaml, None, Some(&metrics))?;
+        let actual: serde_json::Value = serde_yaml::from_slice(&buf)?;
+        assert_eq!(actual["total"], "WA");
+        assert_eq!(actual["metrics"]["requests"], 0);
+        assert!(actual.get("error").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_shared_actors() -> anyhow::Result<()> {
+        let session = SessionConfig::default();
+        let addr = |actor: &dyn Act| actor as *const dyn Act as *const u8 as usize;
+        let actor_addr = |session: &SessionConfig| {
+            with_actor(ServiceKind::Mock, session, |actor| Ok(addr(actor)))
+        };
+
+        let (first, second, offline) = with_shared_actors(|| {
+            Ok((
+                actor_addr(&session)?,
+                actor_addr(&session)?,
+                actor_addr(&session.with_offline(true))?,
+            ))
+        })?;
+        assert_eq!(first, second);
+        // actors are not shared between different sessions
+        assert_ne!(first, offline);
+
+        // actor for requests only AtCoder serves is shared with the one of AtCoder
+        let (atcoder, atcoder_only) = with_shared_actors(|| {
+            Ok((
+                with_actor(ServiceKind::Atcoder, &session, |actor| Ok(addr(actor)))?,
+                with_atcoder_actor(&session, |actor| Ok(addr(actor)))?,
+            ))
+        })?;
+        assert_eq!(atcoder, atcoder_only);
+        // actors are dropped when leaving the scope
+        assert!(ACTORS.with(|cell| cell.borrow().is_none()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_tsv() -> anyhow::Result<()> {
+        let value = serde_json::json!({
+            "service": { "id": "atcoder" },
+            "problems": [{ "id": "A", "name": "a\tb" }, { "id": "B", "name": null }],
+            "is_error": false,
+        });
+        let mut buf = Vec::new();
+        value.write_tsv(&mut buf)?;
+        let expected = "is_error\tfalse\n\
+                        problems.0.id\tA\n\
+                        problems.0.name\ta\\tb\n\
+                        problems.1.id\tB\n\
+                        problems.1.name\t\n\
+                        service.id\tatcoder\n";
+        assert_eq!(String::from_utf8(buf)?, expected);
+        Ok(())
+    }
 }
@@ -0,0 +1,255 @@
+use std::fmt;
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context as _};
+use serde::Serialize;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+use crate::abs_path::AbsPathBuf;
+use crate::atcoder::AtcoderActor;
+use crate::cmd::Outcome;
+use crate::model::{ContestId, ProblemId, Sample, Service};
+use crate::{Config, Console, Result};
+
+// file names of samples downloaded by online-judge-tools are `sample-1.in`, `sample-1.out`, ...
+static OJ_SAMPLE_PREFIX: &str = "sample-";
+static OJ_DEFAULT_DIR: &str = "test";
+static OJ_IN_EXT: &str = "in";
+static OJ_OUT_EXT: &str = "out";
+
+#[derive(
+    Serialize, EnumString, EnumVariantNames, AsRefStr, Debug, Copy, Clone, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum OjAction {
+    Import,
+    Export,
+}
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct OjOpt {
+    /// Imports testcases from or exports testcases to directory of online-judge-tools
+    #[structopt(name = "action", possible_values = &OjAction::VARIANTS)]
+    action: OjAction,
+    /// Id of the problem
+    #[structopt(name = "problem")]
+    problem_id: ProblemId,
+    /// Directory of testcases in the format of online-judge-tools [default: `test` next to problem file]
+    #[structopt(long, short)]
+    dir: Option<PathBuf>,
+    /// Imports to or exports from full testcases instead of samples (only available for AtCoder)
+    #[structopt(name = "full", long)]
+    is_full: bool,
+}
+
+impl OjOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<OjOutcome> {
+        let dir = match &self.dir {
+            Some(dir) => AbsPathBuf::cwd()?.join(dir),
+            None => conf
+                .problem_abs_path(&self.problem_id)?
+                .parent()
+                .ok_or_else(|| anyhow!("Could not get directory of problem file"))?
+                .join(OJ_DEFAULT_DIR),
+        };
+
+        let testcases = match self.action {
+            OjAction::Import => self.import(&dir, conf, cnsl)?,
+            OjAction::Export => self.export(&dir, conf, cnsl)?,
+        };
+        let testcase_names = testcases.iter().map(|t| t.name().to_owned()).collect();
+
+        Ok(OjOutcome {
+            service: Service::new(conf.service_id),
+            contest_id: conf.contest_id.clone(),
+            problem_id: self.problem_id.clone(),
+            action: self.action,
+            is_full: self.is_full,
+            dir,
+            testcases: testcase_names,
+        })
+    }
+
+    fn import(&self, dir: &AbsPathBuf, conf: &Config, cnsl: &mut Console) -> Result<Vec<Sample>> {
+        let testcases = load_oj_testcases(dir, !self.is_full)?;
+        if testcases.is_empty() {
+            return Err(anyhow!("Found no testcase in {}", dir));
+        }
+
+        if self.is_full {
+            let testcases_dir = conf.testcases_abs_dir(&self.problem_id)?;
+            writeln!(cnsl, "Saving testcases to {}", testcases_dir)?;
+            AtcoderActor::save_testcases(&testcases_dir, &testcases)?;
+        } else {
+            let mut problem = conf.load_problem(&self.problem_id, cnsl)?;
+            if !problem.samples().is_empty()
+                && !cnsl.confirm("replace samples in existing problem file?", false)?
+            {
+                return Err(anyhow!("Cancelled importing samples"));
+            }
+            problem.set_samples(testcases.clone());
+            conf.save_problem(&problem, true, cnsl)?;
+        }
+        Ok(testcases)
+    }
+
+    fn export(&self, dir: &AbsPathBuf, conf: &Config, cnsl: &mut Console) -> Result<Vec<Sample>> {
+        let testcases = if self.is_full {
+            let testcases_dir = conf.testcases_abs_dir(&self.problem_id)?;
+            AtcoderActor::load_testcases(testcases_dir, &None)?.collect::<Result<Vec<_>>>()?
+        } else {
+            let problem = conf.load_problem(&self.problem_id, cnsl)?;
+            problem.samples().clone()
+        };
+
+        writeln!(cnsl, "Saving testcases to {}", dir)?;
+        for testcase in &testcases {
+            let name = if self.is_full {
+                testcase.name().to_owned()
+            } else {
+                format!("{}{}", OJ_SAMPLE_PREFIX, testcase.name())
+            };
+            for (ext, content) in &[
+                (OJ_IN_EXT, testcase.input()),
+                (OJ_OUT_EXT, testcase.output()),
+            ] {
+                let file_name = Path::new(&name).with_extension(ext);
+                dir.join(&file_name).save(
+                    |mut file| {
+                        file.write_all(content.as_bytes()).with_context(|| {
+                            format!("Could not save testcase : {}", file_name.display())
+                        })
+                    },
+                    true,
+                )?;
+            }
+        }
+        Ok(testcases)
+    }
+}
+
+/// Loads pairs of `*.in` and `*.out` files in `dir`.
+///
+/// If `is_sample` is true, prefix `sample-` is removed from names of testcases.
+fn load_oj_testcases(dir: &AbsPathBuf, is_sample: bool) -> Result<Vec<Sample>> {
+    let entries = fs::read_dir(dir.as_ref())
+        .with_context(|| format!("Could not list testcase files in {}", dir))?
+        .collect::<std::io::Result<Vec<_>>>()?;
+    let mut names = entries
+        .iter()
+        .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(OJ_IN_EXT) {
+                return None;
+            }
+            path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(String::from)
+        })
+        .collect::<Vec<_>>();
+    // sort `sample-2` before `sample-10`
+    names.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+
+    let load = |name: &str, ext: &str| {
+        let file_name = Path::new(name).with_extension(ext);
+        fs::read_to_string(dir.join(&file_name).as_ref())
+            .with_context(|| format!("Could not load testcase : {}", file_name.display()))
+    };
+    names
+        .iter()
+        .map(|name| {
+            let input = load(name, OJ_IN_EXT)?;
+            let output = load(name, OJ_OUT_EXT)?;
+            let name = match name.strip_prefix(OJ_SAMPLE_PREFIX) {
+                Some(stripped) if is_sample => stripped,
+                _ => name,
+            };
+            Ok(Sample::new(name, input, output))
+        })
+        .collect()
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OjOutcome {
+    service: Service,
+    contest_id: ContestId,
+    problem_id: ProblemId,
+    action: OjAction,
+    is_full: bool,
+    dir: AbsPathBuf,
+    testcases: Vec<String>,
+}
+
+impl fmt::Display for OjOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (verb, prep) = match self.action {
+            OjAction::Import => ("Imported", "from"),
+            OjAction::Export => ("Exported", "to"),
+        };
+        write!(
+            f,
+            "{} {} {} of problem {} {} {}",
+            verb,
+            self.testcases.len(),
+            if self.is_full { "testcases" } else { "samples" },
+            self.problem_id,
+            prep,
+            self.dir
+        )
+    }
+}
+
+impl Outcome for OjOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::cmd::tests::run_with;
+    use crate::model::Problem;
+
+    #[test]
+    fn run_default() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let oj_dir = test_dir.path().join("oj");
+        let opt = |action| OjOpt {
+            action,
+            problem_id: "c".into(),
+            dir: Some(oj_dir.clone()),
+            is_full: false,
+        };
+
+        let outcome = run_with(&test_dir, |conf, cnsl| {
+            let mut problem = Problem::default();
+            problem.set_samples(vec![
+                Sample::new("1", "5\n", "0\n"),
+                Sample::new("2", "10\n", "1\n"),
+            ]);
+            conf.save_problem(&problem, true, cnsl)?;
+            opt(OjAction::Export).run(conf, cnsl)
+        })?;
+        assert_eq!(outcome.testcases, vec!["1", "2"]);
+        assert_eq!(fs::read_to_string(oj_dir.join("sample-2.in"))?, "10\n");
+
+        fs::write(oj_dir.join("sample-10.in"), "100\n")?;
+        fs::write(oj_dir.join("sample-10.out"), "2\n")?;
+        let problem = run_with(&test_dir, |conf, cnsl| {
+            opt(OjAction::Import).run(conf, cnsl)?;
+            conf.load_problem(&"c".into(), cnsl)
+        })?;
+        let names: Vec<_> = problem.samples().iter().map(Sample::name).collect();
+        assert_eq!(names, vec!["1", "2", "10"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::cmd::{block_on, with_actor, Outcome};
+use crate::model::{ContestId, Service};
+use crate::service::Act;
+use crate::{Config, Console, Error, Result};
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct ParticipateOpt {}
+
+impl ParticipateOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<ParticipateOutcome> {
+        with_actor(conf.service_id, conf.session(), |actor| {
+            block_on(self.run_inner(actor, conf, cnsl))
+        })
+    }
+
+    async fn run_inner(
+        &self,
+        actor: &dyn Act,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<ParticipateOutcome> {
+        // confirm
+        let message = format!(
+            "participate in {}? You will be registered as a rated participant if you are eligible.",
+            &conf.contest_id
+        );
+        if !cnsl.confirm(&message, false)? {
+            return Err(Error::msg("Not participated"));
+        }
+
+        let is_not_already = actor.participate(&conf.contest_id, cnsl).await?;
+
+        Ok(ParticipateOutcome {
+            service: Service::new(conf.service_id),
+            contest_id: conf.contest_id.to_owned(),
+            is_already: !is_not_already,
+        })
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParticipateOutcome {
+    service: Service,
+    contest_id: ContestId,
+    is_already: bool,
+}
+
+impl fmt::Display for ParticipateOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} participated in {} {}",
+            if self.is_already {
+                "Already"
+            } else {
+                "Successfully"
+            },
+            self.service.id(),
+            self.contest_id
+        )
+    }
+}
+
+impl Outcome for ParticipateOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::cmd::tests::run_with;
+
+    #[test]
+    #[ignore]
+    fn run_default() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+
+        let login_opt = crate::cmd::LoginOpt {};
+        run_with(&test_dir, |conf, cnsl| login_opt.run(conf, cnsl))?;
+
+        let opt = ParticipateOpt {};
+        run_with(&test_dir, |conf, cnsl| opt.run(conf, cnsl))?;
+        Ok(())
+    }
+}
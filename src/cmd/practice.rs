@@ -0,0 +1,183 @@
+use std::fmt;
+use std::io::Write as _;
+
+use anyhow::{anyhow, Context as _};
+use getset::{CopyGetters, Getters};
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::atcoder::{AtcoderActor, PracticeProblem};
+use crate::cmd::{block_on, with_atcoder_actor, FetchOpt, FetchOutcome, Outcome};
+use crate::model::ServiceKind;
+use crate::service::Act as _;
+use crate::{Config, Console, Error, Result};
+
+/// Half width of the range of difficulty around rating in `--recommend`.
+const RECOMMEND_RANGE: i64 = 200;
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct PracticeOpt {
+    /// Lists problems with difficulty not less than this
+    #[structopt(long)]
+    min: Option<i64>,
+    /// Lists problems with difficulty not more than this
+    #[structopt(long)]
+    max: Option<i64>,
+    /// Lists problems with difficulty around rating of the user
+    #[structopt(long, conflicts_with_all = &["min", "max"])]
+    recommend: bool,
+    /// User whose accepted problems are excluded [default: user currently logged in]
+    #[structopt(long, short)]
+    user: Option<String>,
+    /// Lists problems accepted by the user as well
+    #[structopt(long)]
+    include_solved: bool,
+    /// Max number of problems listed
+    #[structopt(long, short = "n", default_value = "20")]
+    limit: usize,
+    /// Fetches the problem of the id in url (e.g. abc100_c) into the directory of its contest
+    #[structopt(long)]
+    fetch: Option<String>,
+}
+
+impl PracticeOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<PracticeOutcome> {
+        if conf.service_id != ServiceKind::Atcoder {
+            return Err(Error::msg(
+                "Practice problems are available only for AtCoder",
+            ));
+        }
+        let (user, rating, problems) = with_atcoder_actor(conf.session(), |actor| {
+            block_on(self.fetch_problems(actor, cnsl))
+        })?;
+
+        let fetched = match &self.fetch {
+            Some(url_name) => {
+                let problem = problems
+                    .iter()
+                    .find(|problem| problem.url_name() == url_name)
+                    .with_context(|| {
+                        format!("Could not find problem in AtCoder Problems : {}", url_name)
+                    })?;
+                let conf = conf.with_contest(problem.contest_id().clone());
+                Some(FetchOpt::for_problem(problem.problem_id().clone()).run(&conf, cnsl)?)
+            }
+            None => None,
+        };
+
+        let (min, max) = match rating {
+            Some(rating) if self.recommend => (
+                Some(rating - RECOMMEND_RANGE),
+                Some(rating + RECOMMEND_RANGE),
+            ),
+            _ => (self.min, self.max),
+        };
+        let mut problems: Vec<PracticeProblem> = problems
+            .into_iter()
+            .filter(|problem| self.include_solved || !problem.is_solved())
+            .filter(|problem| match problem.difficulty() {
+                Some(difficulty) => {
+                    min.map_or(true, |min| min <= difficulty)
+                        && max.map_or(true, |max| difficulty <= max)
+                }
+                None => min.is_none() && max.is_none(),
+            })
+            .collect();
+        match rating {
+            // problems closest to rating first
+            Some(rating) if self.recommend => problems.sort_by_key(|problem| {
+                problem
+                    .difficulty()
+                    .map_or(i64::MAX, |difficulty| (difficulty - rating).abs())
+            }),
+            _ => problems.sort_by_key(|problem| problem.difficulty()),
+        }
+        problems.truncate(self.limit);
+
+        Ok(PracticeOutcome {
+            user,
+            rating,
+            problems,
+            fetched,
+        })
+    }
+
+    async fn fetch_problems(
+        &self,
+        actor: &AtcoderActor,
+        cnsl: &mut Console,
+    ) -> Result<(Option<String>, Option<i64>, Vec<PracticeProblem>)> {
+        let user = match &self.user {
+            Some(user) => Some(user.clone()),
+            None => actor.current_user(cnsl).await?,
+        };
+        let rating = match (&user, self.recommend) {
+            (Some(user), true) => {
+                let rating = actor.fetch_rating(user, cnsl).await?;
+                if rating.is_none() {
+                    return Err(anyhow!(
+                        "Could not find rating of user {}. Specify --min and --max instead.",
+                        user
+                    ));
+                }
+                rating
+            }
+            (None, true) => {
+                return Err(Error::msg(
+                    "Could not find user to recommend problems. Log in or specify --user.",
+                ))
+            }
+            (_, false) => None,
+        };
+        writeln!(cnsl, "Fetching problems from AtCoder Problems ...")?;
+        let problems = actor.fetch_practice_problems(user.as_deref(), cnsl).await?;
+        Ok((user, rating, problems))
+    }
+}
+
+#[derive(Serialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PracticeOutcome {
+    #[get = "pub"]
+    user: Option<String>,
+    #[get_copy = "pub"]
+    rating: Option<i64>,
+    #[get = "pub"]
+    problems: Vec<PracticeProblem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[get = "pub"]
+    fetched: Option<FetchOutcome>,
+}
+
+impl fmt::Display for PracticeOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let (Some(user), Some(rating)) = (&self.user, self.rating) {
+            writeln!(f, "Rating of {} : {}", user, rating)?;
+        }
+        for problem in &self.problems {
+            let difficulty = match problem.difficulty() {
+                Some(difficulty) if problem.is_experimental() => format!("~{}", difficulty),
+                Some(difficulty) => difficulty.to_string(),
+                None => String::from("-"),
+            };
+            writeln!(
+                f,
+                "{:>6} {:16} {}{}",
+                difficulty,
+                problem.url_name(),
+                problem.name(),
+                if problem.is_solved() { " (solved)" } else { "" }
+            )?;
+        }
+        match &self.fetched {
+            Some(fetched) => write!(f, "{}", fetched),
+            None => write!(f, "Found {} problems", self.problems.len()),
+        }
+    }
+}
+
+impl Outcome for PracticeOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,291 @@
+use std::fmt;
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _};
+use serde::Serialize;
+use structopt::StructOpt;
+use tempfile::tempdir;
+use tokio::process::Command;
+
+use crate::abs_path::AbsPathBuf;
+use crate::cmd::test::error_kind_of;
+use crate::cmd::{block_on, Outcome};
+use crate::error::ErrorKind;
+use crate::judge::{DiffConfig, Judge, Status, StatusKind};
+use crate::model::{Compare, Sample};
+use crate::{Config, Console, Result};
+
+/// Preset of commands to compile and run source code of a language.
+///
+/// SOURCE and ARTIFACT are set in the commands.
+struct Preset {
+    extensions: &'static [&'static str],
+    compile: Option<&'static str>,
+    run: &'static str,
+}
+
+static PRESETS: &[Preset] = &[
+    Preset {
+        extensions: &["cpp", "cc", "cxx"],
+        compile: Some(r#"g++ -std=gnu++17 -O2 -o "$ARTIFACT" "$SOURCE""#),
+        run: r#""$ARTIFACT""#,
+    },
+    Preset {
+        extensions: &["c"],
+        compile: Some(r#"gcc -std=gnu11 -O2 -o "$ARTIFACT" "$SOURCE" -lm"#),
+        run: r#""$ARTIFACT""#,
+    },
+    Preset {
+        extensions: &["rs"],
+        compile: Some(r#"rustc --edition 2018 -O -o "$ARTIFACT" "$SOURCE""#),
+        run: r#""$ARTIFACT""#,
+    },
+    Preset {
+        extensions: &["go"],
+        compile: Some(r#"go build -o "$ARTIFACT" "$SOURCE""#),
+        run: r#""$ARTIFACT""#,
+    },
+    Preset {
+        extensions: &["py"],
+        compile: None,
+        run: r#"python3 "$SOURCE""#,
+    },
+    Preset {
+        extensions: &["rb"],
+        compile: None,
+        run: r#"ruby "$SOURCE""#,
+    },
+    Preset {
+        extensions: &["js"],
+        compile: None,
+        run: r#"node "$SOURCE""#,
+    },
+];
+
+/// Finds preset of commands by extension of the source file.
+fn find_preset(source: &Path) -> Option<&'static Preset> {
+    let ext = source.extension()?.to_str()?;
+    PRESETS
+        .iter()
+        .find(|preset| preset.extensions.contains(&ext))
+}
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct QuicktestOpt {
+    /// Source file to be tested
+    #[structopt(name = "source")]
+    source: PathBuf,
+    /// File to be given as stdin
+    #[structopt(long, short)]
+    input: PathBuf,
+    /// File of expected output (only prints output if not specified)
+    #[structopt(long, short)]
+    expected: Option<PathBuf>,
+    /// Shell script to compile source file, overriding the one inferred from extension (SOURCE and ARTIFACT are set)
+    #[structopt(long)]
+    compile: Option<String>,
+    /// Shell script to run the source file, overriding the one inferred from extension
+    #[structopt(long)]
+    run: Option<String>,
+    /// Time limit in millisecs
+    #[structopt(long, default_value = "2000")]
+    time_limit: u64,
+    /// Way to compare outputs
+    #[structopt(long, default_value = Compare::Default.into(), possible_values = &Compare::VARIANTS)]
+    compare: Compare,
+}
+
+impl QuicktestOpt {
+    pub fn run(&self, cnsl: &mut Console) -> Result<QuicktestOutcome> {
+        let cwd = AbsPathBuf::cwd()?;
+        let source = cwd.join(&self.source);
+        if !source.as_ref().is_file() {
+            return Err(anyhow!("Could not find source file : {}", source));
+        }
+        let preset = find_preset(source.as_ref());
+        let run = match (&self.run, preset) {
+            (Some(run), _) => run.clone(),
+            (None, Some(preset)) => preset.run.to_owned(),
+            (None, None) => {
+                return Err(anyhow!(
+                    "Could not infer how to run source file from its extension. \
+                     Specify --run (and --compile) option."
+                ))
+            }
+        };
+        let compile = self
+            .compile
+            .clone()
+            .or_else(|| preset.and_then(|preset| preset.compile).map(Into::into));
+
+        let input = fs::read_to_string(&self.input)
+            .with_context(|| format!("Could not read input file : {}", self.input.display()))?;
+        let expected = match &self.expected {
+            Some(expected) => Some(fs::read_to_string(expected).with_context(|| {
+                format!(
+                    "Could not read expected output file : {}",
+                    expected.display()
+                )
+            })?),
+            None => None,
+        };
+        let sample_name = self.input.file_stem().map_or_else(
+            || String::from("input"),
+            |stem| stem.to_string_lossy().into(),
+        );
+        let is_judged = expected.is_some();
+        let sample = Sample::new(sample_name, input, expected.unwrap_or_default());
+
+        // built-in defaults are used instead of config file
+        let conf = Config::default_in_dir(cwd);
+        let artifact_dir = tempdir().context("Could not create temp dir for binary")?;
+        let artifact = artifact_dir.path().join("a.out");
+        let env = [
+            ("SOURCE", source.as_ref().as_os_str()),
+            ("ARTIFACT", artifact.as_os_str()),
+        ];
+        let exec = |script: &str| -> Result<Command> {
+            let mut command = conf.exec_shell(script)?;
+            command.envs(env.iter().copied());
+            Ok(command)
+        };
+
+        let (status, output) = block_on(self.compile_and_test(
+            sample,
+            is_judged,
+            compile.as_deref(),
+            &run,
+            exec,
+            cnsl,
+        ))?;
+        Ok(QuicktestOutcome {
+            source: self.source.clone(),
+            status,
+            output,
+        })
+    }
+
+    async fn compile_and_test(
+        &self,
+        sample: Sample,
+        is_judged: bool,
+        compile: Option<&str>,
+        run: &str,
+        exec: impl Fn(&str) -> Result<Command>,
+        cnsl: &mut Console,
+    ) -> Result<(Option<Status>, Option<String>)> {
+        if let Some(compile) = compile {
+            writeln!(cnsl, "Compiling {} ...", self.source.display())?;
+            let exit_status = exec(compile)?.status().await.context("Failed to compile")?;
+            if !exit_status.success() {
+                return Err(ErrorKind::Compile.tag(anyhow!(
+                    "Compile command returned non-zero status : {}",
+                    exit_status
+                )));
+            }
+        }
+
+        let judge = Judge::new(
+            sample,
+            Duration::from_millis(self.time_limit),
+            self.compare,
+            DiffConfig::default(),
+        );
+        if !is_judged {
+            let output = judge.run(exec(run)?).await?;
+            return Ok((None, Some(output)));
+        }
+        let status = judge.test(exec(run)?).await?;
+        writeln!(cnsl, "{}", status)?;
+        status.describe(cnsl)?;
+        Ok((Some(status), None))
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QuicktestOutcome {
+    source: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<Status>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+}
+
+impl fmt::Display for QuicktestOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.status, &self.output) {
+            (Some(status), _) => write!(f, "{} {}", self.source.display(), status),
+            (None, Some(output)) => f.write_str(output.trim_end_matches('\n')),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl Outcome for QuicktestOutcome {
+    fn is_error(&self) -> bool {
+        self.status
+            .as_ref()
+            .map_or(false, |status| status.kind() != StatusKind::Ac)
+    }
+
+    fn error_kind(&self) -> ErrorKind {
+        self.status
+            .as_ref()
+            .map_or(ErrorKind::Unknown, |status| error_kind_of(status.kind()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_preset() {
+        let run_of = |source: &str| find_preset(Path::new(source)).map(|preset| preset.run);
+        assert_eq!(run_of("main.cpp"), Some(r#""$ARTIFACT""#));
+        assert_eq!(run_of("dir/main.py"), Some(r#"python3 "$SOURCE""#));
+        assert!(find_preset(Path::new("main.rs")).unwrap().compile.is_some());
+        assert_eq!(run_of("main.txt"), None);
+        assert_eq!(run_of("Makefile"), None);
+    }
+
+    #[test]
+    fn run_with_expected() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let path = |name: &str| test_dir.path().join(name);
+        fs::write(path("main.py"), "print(int(input()) * 2)\n")?;
+        fs::write(path("in.txt"), "3\n")?;
+        fs::write(path("out.txt"), "6\n")?;
+        fs::write(path("wrong.txt"), "7\n")?;
+
+        let mut opt = QuicktestOpt {
+            source: path("main.py"),
+            input: path("in.txt"),
+            expected: Some(path("out.txt")),
+            compile: None,
+            run: None,
+            time_limit: 2000,
+            compare: Compare::Default,
+        };
+        let mut cnsl = Console::buf(Default::default());
+        let outcome = opt.run(&mut cnsl)?;
+        assert_eq!(
+            outcome.status.as_ref().map(Status::kind),
+            Some(StatusKind::Ac)
+        );
+
+        opt.expected = Some(path("wrong.txt"));
+        let outcome = opt.run(&mut cnsl)?;
+        assert!(outcome.is_error());
+        assert_eq!(outcome.error_kind(), ErrorKind::WrongAnswer);
+
+        opt.expected = None;
+        let outcome = opt.run(&mut cnsl)?;
+        assert_eq!(outcome.output.as_deref(), Some("6\n"));
+        Ok(())
+    }
+}
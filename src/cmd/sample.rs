@@ -0,0 +1,282 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Context as _};
+use serde::Serialize;
+use structopt::StructOpt;
+use tempfile::tempdir;
+
+use crate::cmd::Outcome;
+use crate::model::{ContestId, Problem, ProblemId, Sample, Service};
+use crate::{Config, Console, Result};
+
+static DEFAULT_EDITOR: &str = if cfg!(windows) { "notepad" } else { "vi" };
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct SampleOpt {
+    #[structopt(subcommand)]
+    action: SampleAction,
+}
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub enum SampleAction {
+    /// Adds custom sample to problem (opens editor if input and output files are not specified)
+    Add {
+        /// Id of the problem
+        #[structopt(name = "problem")]
+        problem_id: ProblemId,
+        /// Name of the sample, prefixed with "custom-" if needed [default: next custom-N]
+        sample_name: Option<String>,
+        /// File that contains input of the sample
+        #[structopt(long, requires = "out-file")]
+        in_file: Option<PathBuf>,
+        /// File that contains output of the sample
+        #[structopt(long, requires = "in-file")]
+        out_file: Option<PathBuf>,
+    },
+    /// Edits input and output of sample in editor ($VISUAL or $EDITOR)
+    Edit {
+        /// Id of the problem
+        #[structopt(name = "problem")]
+        problem_id: ProblemId,
+        /// Name of the sample
+        sample_name: String,
+    },
+    /// Removes sample from problem
+    Rm {
+        /// Id of the problem
+        #[structopt(name = "problem")]
+        problem_id: ProblemId,
+        /// Name of the sample
+        sample_name: String,
+    },
+}
+
+impl SampleOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<SampleOutcome> {
+        let (problem_id, sample_name, action) = match &self.action {
+            SampleAction::Add {
+                problem_id,
+                sample_name,
+                in_file,
+                out_file,
+            } => {
+                let mut problem = conf.load_problem(problem_id, cnsl)?;
+                // samples are recognized as custom ones by the prefix
+                let sample_name = match sample_name {
+                    Some(name) if name.starts_with(Sample::CUSTOM_PREFIX) => name.clone(),
+                    Some(name) => format!("{}{}", Sample::CUSTOM_PREFIX, name),
+                    None => next_custom_name(&problem),
+                };
+                if find_sample(&problem, &sample_name).is_some() {
+                    return Err(anyhow!("Sample already exists : {}", sample_name));
+                }
+                let (input, output) = match (in_file, out_file) {
+                    (Some(in_file), Some(out_file)) => (read_file(in_file)?, read_file(out_file)?),
+                    _ => edit_in_editor(&sample_name, "", "")?,
+                };
+                let mut samples = problem.samples().clone();
+                samples.push(Sample::new(sample_name.as_str(), input, output));
+                problem.set_samples(samples);
+                conf.save_problem(&problem, true, cnsl)?;
+                (problem_id, sample_name, SampleChange::Added)
+            }
+            SampleAction::Edit {
+                problem_id,
+                sample_name,
+            } => {
+                let mut problem = conf.load_problem(problem_id, cnsl)?;
+                let i = find_sample(&problem, sample_name)
+                    .ok_or_else(|| anyhow!("Could not find sample : {}", sample_name))?;
+                let mut samples = problem.samples().clone();
+                let sample = &samples[i];
+                let (input, output) = edit_in_editor(sample_name, sample.input(), sample.output())?;
+                samples[i] = Sample::new(sample_name.as_str(), input, output);
+                problem.set_samples(samples);
+                conf.save_problem(&problem, true, cnsl)?;
+                (problem_id, sample_name.clone(), SampleChange::Edited)
+            }
+            SampleAction::Rm {
+                problem_id,
+                sample_name,
+            } => {
+                let mut problem = conf.load_problem(problem_id, cnsl)?;
+                let i = find_sample(&problem, sample_name)
+                    .ok_or_else(|| anyhow!("Could not find sample : {}", sample_name))?;
+                let message = format!("remove sample {} of problem {}?", sample_name, problem_id);
+                if !cnsl.confirm(&message, false)? {
+                    return Err(anyhow!("Cancelled removing sample"));
+                }
+                let mut samples = problem.samples().clone();
+                samples.remove(i);
+                problem.set_samples(samples);
+                conf.save_problem(&problem, true, cnsl)?;
+                (problem_id, sample_name.clone(), SampleChange::Removed)
+            }
+        };
+
+        Ok(SampleOutcome {
+            service: Service::new(conf.service_id),
+            contest_id: conf.contest_id.clone(),
+            problem_id: problem_id.clone(),
+            sample_name,
+            action,
+        })
+    }
+}
+
+fn find_sample(problem: &Problem, sample_name: &str) -> Option<usize> {
+    problem
+        .samples()
+        .iter()
+        .position(|sample| sample.name() == sample_name)
+}
+
+fn next_custom_name(problem: &Problem) -> String {
+    (1..)
+        .map(|i| format!("{}{}", Sample::CUSTOM_PREFIX, i))
+        .find(|name| find_sample(problem, name).is_none())
+        .unwrap()
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Could not read file : {}", path.display()))
+}
+
+/// Opens input and output files in editor and returns their contents after editor exits.
+fn edit_in_editor(sample_name: &str, input: &str, output: &str) -> Result<(String, String)> {
+    let dir = tempdir().context("Could not create temp dir for editing sample")?;
+    let input_path = dir.path().join(format!("{}.in", sample_name));
+    let output_path = dir.path().join(format!("{}.out", sample_name));
+    fs::write(&input_path, input)?;
+    fs::write(&output_path, output)?;
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| DEFAULT_EDITOR.to_owned());
+    let mut editor_args = editor.split_whitespace();
+    let program = editor_args
+        .next()
+        .ok_or_else(|| anyhow!("Found empty editor command"))?;
+    let status = Command::new(program)
+        .args(editor_args)
+        .arg(&input_path)
+        .arg(&output_path)
+        .status()
+        .with_context(|| format!("Could not start editor : {}", editor))?;
+    if !status.success() {
+        return Err(anyhow!("Editor returned non-zero status : {}", status));
+    }
+
+    Ok((read_file(&input_path)?, read_file(&output_path)?))
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SampleOutcome {
+    service: Service,
+    contest_id: ContestId,
+    problem_id: ProblemId,
+    sample_name: String,
+    action: SampleChange,
+}
+
+impl fmt::Display for SampleOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} sample {} of problem {}",
+            self.action, self.sample_name, self.problem_id
+        )
+    }
+}
+
+/// Change made to samples of problem.
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum SampleChange {
+    Added,
+    Edited,
+    Removed,
+}
+
+impl fmt::Display for SampleChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Added => "Added",
+            Self::Edited => "Edited",
+            Self::Removed => "Removed",
+        };
+        f.write_str(s)
+    }
+}
+
+impl Outcome for SampleOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::cmd::tests::run_with;
+
+    #[test]
+    fn run_default() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let input_path = test_dir.path().join("input.txt");
+        let output_path = test_dir.path().join("output.txt");
+        fs::write(&input_path, "3\n")?;
+        fs::write(&output_path, "6\n")?;
+
+        let add = |sample_name: Option<&str>| SampleOpt {
+            action: SampleAction::Add {
+                problem_id: "c".into(),
+                sample_name: sample_name.map(Into::into),
+                in_file: Some(input_path.clone()),
+                out_file: Some(output_path.clone()),
+            },
+        };
+        let rm = |sample_name: &str| SampleOpt {
+            action: SampleAction::Rm {
+                problem_id: "c".into(),
+                sample_name: sample_name.into(),
+            },
+        };
+        let problem = run_with(&test_dir, |conf, cnsl| {
+            let mut problem = Problem::default();
+            problem.set_samples(vec![Sample::new("1", "5\n", "0\n")]);
+            conf.save_problem(&problem, true, cnsl)?;
+
+            let outcome = add(None).run(conf, cnsl)?;
+            assert_eq!(outcome.sample_name, "custom-1");
+            assert_eq!(outcome.action, SampleChange::Added);
+            assert_eq!(add(None).run(conf, cnsl)?.sample_name, "custom-2");
+            rm("custom-1").run(conf, cnsl)?;
+            assert!(rm("custom-1").run(conf, cnsl).is_err());
+            assert_eq!(add(None).run(conf, cnsl)?.sample_name, "custom-1");
+            assert_eq!(add(Some("big")).run(conf, cnsl)?.sample_name, "custom-big");
+            assert_eq!(
+                add(Some("custom-max")).run(conf, cnsl)?.sample_name,
+                "custom-max"
+            );
+            conf.load_problem(&"c".into(), cnsl)
+        })?;
+
+        let names: Vec<_> = problem.samples().iter().map(Sample::name).collect();
+        assert_eq!(
+            names,
+            vec!["1", "custom-2", "custom-1", "custom-big", "custom-max"]
+        );
+        assert!(problem.samples()[1..].iter().all(Sample::is_custom));
+        assert_eq!(problem.samples()[1].input(), "3\n");
+        Ok(())
+    }
+}
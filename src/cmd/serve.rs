@@ -0,0 +1,307 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use structopt::StructOpt;
+
+use crate::abs_path::AbsPathBuf;
+use crate::cmd::{with_shared_actors, Outcome, ShowOpt};
+use crate::commands::{
+    FetchParams, HistoryParams, MeParams, StatusParams, SubmitParams, TestParams,
+};
+use crate::model::ContestId;
+use crate::{Config, Console, ConsoleConfig, Result};
+
+static JSONRPC_VERSION: &str = "2.0";
+
+// error codes defined in JSON-RPC 2.0
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const SERVER_ERROR: i64 = -32000;
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct ServeOpt {
+    /// Listens on unix socket instead of stdio
+    #[structopt(long)]
+    socket: Option<PathBuf>,
+}
+
+impl ServeOpt {
+    /// Returns true if responses are written to stdout.
+    pub fn is_stdio(&self) -> bool {
+        self.socket.is_none()
+    }
+
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<ServeOutcome> {
+        let mut server = Server {
+            conf,
+            n_requests: 0,
+        };
+        // share one actor among requests while the server is running
+        with_shared_actors(|| match &self.socket {
+            Some(socket) => {
+                let socket = AbsPathBuf::cwd()?.join(socket);
+                writeln!(cnsl, "Listening on {}", socket)?;
+                server.serve_socket(&socket)
+            }
+            None => {
+                writeln!(cnsl, "Listening on stdio")?;
+                let stdin = io::stdin();
+                server.serve(stdin.lock(), &mut io::stdout()).map(|_| ())
+            }
+        })?;
+        Ok(ServeOutcome {
+            requests: server.n_requests,
+        })
+    }
+}
+
+struct Server<'a> {
+    conf: &'a Config,
+    n_requests: usize,
+}
+
+impl Server<'_> {
+    #[cfg(unix)]
+    fn serve_socket(&mut self, socket: &AbsPathBuf) -> Result<()> {
+        use std::io::BufReader;
+        use std::os::unix::net::UnixListener;
+
+        let listener = UnixListener::bind(socket.as_ref())?;
+        let result = (|| {
+            for stream in listener.incoming() {
+                let stream = stream?;
+                let reader = BufReader::new(stream.try_clone()?);
+                if self.serve(reader, &mut &stream)? {
+                    break;
+                }
+            }
+            Ok(())
+        })();
+        std::fs::remove_file(socket.as_ref())?;
+        result
+    }
+
+    #[cfg(not(unix))]
+    fn serve_socket(&mut self, _socket: &AbsPathBuf) -> Result<()> {
+        Err(anyhow!("Unix socket is not supported on this platform"))
+    }
+
+    /// Handles requests line by line until EOF or shutdown request.
+    ///
+    /// Returns true if shutdown is requested.
+    fn serve(&mut self, reader: impl BufRead, writer: &mut dyn Write) -> Result<bool> {
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            self.n_requests += 1;
+            let (response, is_shutdown) = self.handle(&line);
+            serde_json::to_writer(&mut *writer, &response)?;
+            writeln!(writer)?;
+            writer.flush()?;
+            if is_shutdown {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn handle(&self, line: &str) -> (Response, bool) {
+        let value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(err) => return (Response::error(Value::Null, PARSE_ERROR, err.into()), false),
+        };
+        let id = value.get("id").cloned().unwrap_or(Value::Null);
+        let request: Request = match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(err) => return (Response::error(id, INVALID_REQUEST, err.into()), false),
+        };
+        if request.jsonrpc != JSONRPC_VERSION {
+            let err = anyhow!("Unsupported version of JSON-RPC : {}", request.jsonrpc);
+            return (Response::error(id, INVALID_REQUEST, err), false);
+        }
+        let is_shutdown = matches!(request.call, Call::Shutdown);
+        let response = match self.call(request.call) {
+            Ok(result) => Response::result(id, result),
+            Err(err) => Response::error(id, SERVER_ERROR, err),
+        };
+        (response, is_shutdown)
+    }
+
+    fn call(&self, call: Call) -> Result<Value> {
+        // never prompt because stdin may be used for requests
+        let mut cnsl = Console::sink(ConsoleConfig {
+            assume_yes: true,
+            verbose: false,
+        });
+        let cnsl = &mut cnsl;
+        match call {
+            Call::Show => to_result(&ShowOpt::default().run(self.conf, cnsl)?),
+            Call::Me(p) => to_result(&p.inner.to_opt().run(&p.config(self.conf), cnsl)?),
+            Call::Status(p) => to_result(&p.inner.to_opt().run(&p.config(self.conf), cnsl)?),
+            Call::Fetch(p) => to_result(&p.inner.to_opt().run(&p.config(self.conf), cnsl)?),
+            Call::Test(p) => to_result(&p.inner.to_opt().run(&p.config(self.conf), cnsl)?),
+            Call::Submit(p) => to_result(&p.inner.to_opt().run(&p.config(self.conf), cnsl)?),
+            Call::History(p) => to_result(&p.inner.to_opt().run(&p.config(self.conf))?),
+            Call::Shutdown => Ok(Value::Null),
+        }
+    }
+}
+
+fn to_result<T: Outcome + Serialize>(outcome: &T) -> Result<Value> {
+    Ok(json!({
+        "outcome": outcome,
+        "is_error": outcome.is_error(),
+    }))
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Request {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Value,
+    #[serde(flatten)]
+    call: Call,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "method", content = "params", rename_all = "kebab-case")]
+enum Call {
+    Show,
+    Me(Params<MeParams>),
+    Status(Params<StatusParams>),
+    Fetch(Params<FetchParams>),
+    Test(Params<TestParams>),
+    Submit(Params<SubmitParams>),
+    History(Params<HistoryParams>),
+    Shutdown,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Params<T> {
+    /// Overrides contest that the server was started with
+    #[serde(default)]
+    contest: Option<ContestId>,
+    #[serde(flatten)]
+    inner: T,
+}
+
+impl<T> Params<T> {
+    fn config(&self, conf: &Config) -> Config {
+        match &self.contest {
+            Some(contest_id) => conf.with_contest(contest_id.clone()),
+            None => conf.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ResponseError>,
+}
+
+impl Response {
+    fn result(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION,
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    fn error(id: Value, code: i64, err: anyhow::Error) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION,
+            id,
+            result: None,
+            error: Some(ResponseError {
+                code,
+                message: format!("{:#}", err),
+            }),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServeOutcome {
+    requests: usize,
+}
+
+impl fmt::Display for ServeOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Served {} requests", self.requests)
+    }
+}
+
+impl Outcome for ServeOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::cmd::tests::run_with;
+
+    #[test]
+    fn test_serve() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let input = [
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "show"}"#,
+            r#"{"jsonrpc": "2.0", "id": 2, "method": "history", "params": {"contest": "arc100"}}"#,
+            r#"{"jsonrpc": "2.0", "id": 3, "method": "unknown"}"#,
+            r#"not json"#,
+            r#"{"jsonrpc": "2.0", "id": 4, "method": "shutdown"}"#,
+            r#"{"jsonrpc": "2.0", "id": 5, "method": "show"}"#,
+        ]
+        .join("\n");
+
+        let mut output = Vec::new();
+        let (is_shutdown, n_requests) = run_with(&test_dir, |conf, _| {
+            let mut server = Server {
+                conf,
+                n_requests: 0,
+            };
+            let is_shutdown = server.serve(Cursor::new(input), &mut output)?;
+            Ok((is_shutdown, server.n_requests))
+        })?;
+        assert!(is_shutdown);
+        assert_eq!(n_requests, 5);
+
+        let responses = String::from_utf8(output)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<serde_json::Result<Vec<Value>>>()?;
+        assert_eq!(responses.len(), 5);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["result"]["is_error"], false);
+        assert_eq!(responses[1]["result"]["outcome"]["entries"], json!([]));
+        assert_eq!(responses[2]["error"]["code"], INVALID_REQUEST);
+        assert_eq!(responses[3]["error"]["code"], PARSE_ERROR);
+        assert_eq!(responses[4]["id"], 4);
+        Ok(())
+    }
+}
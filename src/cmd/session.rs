@@ -0,0 +1,188 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Read as _, Write as _};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context as _};
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::abs_path::{restrict_to_owner, AbsPathBuf};
+use crate::cmd::Outcome;
+use crate::crypto;
+use crate::service::CookieStorage;
+use crate::{Config, Console, Result};
+
+static PASSPHRASE_ENV_NAME: &str = "ACICK_SESSION_PASSPHRASE";
+static STDIN_PATH: &str = "-";
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct SessionOpt {
+    #[structopt(subcommand)]
+    action: SessionAction,
+}
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub enum SessionAction {
+    /// Exports cookies of logged-in session (prints them if output is not specified)
+    Export {
+        /// File to which cookies are written
+        #[structopt(long, short)]
+        output: Option<PathBuf>,
+        /// Encrypts cookies with passphrase (read from env ACICK_SESSION_PASSPHRASE if set)
+        #[structopt(long)]
+        encrypt: bool,
+    },
+    /// Imports cookies exported by `acick session export`, replacing the current ones
+    Import {
+        /// File of exported cookies ("-" reads stdin)
+        #[structopt(name = "file")]
+        file: PathBuf,
+    },
+}
+
+impl SessionOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<SessionOutcome> {
+        let cookies_path = conf.session().cookies_path();
+        match &self.action {
+            SessionAction::Export { output, encrypt } => {
+                let storage = CookieStorage::open(cookies_path)?;
+                let cookies = storage.export()?;
+                let data = if *encrypt {
+                    crypto::encrypt(cookies.as_bytes(), &read_passphrase(cnsl)?)?
+                } else {
+                    cookies
+                };
+                let data = match output {
+                    Some(output) => {
+                        let output = AbsPathBuf::cwd()?.join(output);
+                        output.save_pretty(
+                            |mut file| {
+                                restrict_to_owner(&file)
+                                    .context("Could not restrict permissions of cookies file")?;
+                                file.write_all(data.as_bytes())
+                                    .context("Could not write exported cookies")
+                            },
+                            true,
+                            Some(&conf.base_dir),
+                            cnsl,
+                        )?;
+                        None
+                    }
+                    None => Some(data),
+                };
+                Ok(SessionOutcome {
+                    action: "export",
+                    cookies: storage.count(),
+                    encrypted: *encrypt,
+                    data,
+                })
+            }
+            SessionAction::Import { file } => {
+                let data = read_input(file)?;
+                let encrypted = crypto::is_encrypted(&data);
+                let cookies = if encrypted {
+                    let plain = crypto::decrypt(&data, &read_passphrase(cnsl)?)?;
+                    String::from_utf8(plain).context("Found invalid cookies in decrypted data")?
+                } else {
+                    data
+                };
+                let mut storage = CookieStorage::open(cookies_path)?;
+                storage
+                    .import(&cookies)
+                    .context("Could not import cookies")?;
+                Ok(SessionOutcome {
+                    action: "import",
+                    cookies: storage.count(),
+                    encrypted,
+                    data: None,
+                })
+            }
+        }
+    }
+}
+
+fn read_passphrase(cnsl: &mut Console) -> Result<String> {
+    let passphrase = cnsl.get_env_or_prompt_and_read(PASSPHRASE_ENV_NAME, "passphrase: ", true)?;
+    let passphrase = passphrase.trim_end_matches(&['\r', '\n'][..]).to_owned();
+    if passphrase.is_empty() {
+        return Err(anyhow!("Passphrase must not be empty"));
+    }
+    Ok(passphrase)
+}
+
+fn read_input(file: &Path) -> Result<String> {
+    if file == Path::new(STDIN_PATH) {
+        let mut data = String::new();
+        io::stdin()
+            .read_to_string(&mut data)
+            .context("Could not read cookies from stdin")?;
+        return Ok(data);
+    }
+    fs::read_to_string(file)
+        .with_context(|| format!("Could not read cookies file : {}", file.display()))
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionOutcome {
+    action: &'static str,
+    cookies: usize,
+    encrypted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<String>,
+}
+
+impl fmt::Display for SessionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(data) = &self.data {
+            return f.write_str(data.trim_end_matches('\n'));
+        }
+        write!(
+            f,
+            "Successfully {}ed {} cookies{}",
+            self.action,
+            self.cookies,
+            if self.encrypted { " (encrypted)" } else { "" }
+        )
+    }
+}
+
+impl Outcome for SessionOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::cmd::tests::run_with;
+
+    #[test]
+    fn export_and_import() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let exported = test_dir.path().join("cookies.enc");
+        std::env::set_var(PASSPHRASE_ENV_NAME, "passphrase");
+
+        let opt = SessionOpt {
+            action: SessionAction::Export {
+                output: Some(exported.clone()),
+                encrypt: true,
+            },
+        };
+        let outcome = run_with(&test_dir, |conf, cnsl| opt.run(conf, cnsl))?;
+        assert!(outcome.encrypted);
+        assert!(crypto::is_encrypted(&fs::read_to_string(&exported)?));
+
+        let opt = SessionOpt {
+            action: SessionAction::Import { file: exported },
+        };
+        let outcome = run_with(&test_dir, |conf, cnsl| opt.run(conf, cnsl))?;
+        assert!(outcome.encrypted);
+        Ok(())
+    }
+}
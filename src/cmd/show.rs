@@ -1,30 +1,158 @@
+use std::cmp::max;
 use std::fmt;
 
+use humantime::format_duration;
 use serde::Serialize;
 use structopt::StructOpt;
 
 use crate::cmd::Outcome;
-use crate::{Config, Result};
+use crate::model::{Problem, ProblemId, Sample};
+use crate::{Config, Console, Result};
+
+#[derive(StructOpt, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct ShowOpt {
+    /// Shows fetched problem instead of config
+    #[structopt(subcommand)]
+    pub(crate) view: Option<ShowView>,
+}
 
 #[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
 #[structopt(rename_all = "kebab")]
-pub struct ShowOpt {}
+pub enum ShowView {
+    /// Shows metadata and limits of problem
+    Problem {
+        /// Id of the problem
+        #[structopt(name = "problem")]
+        problem_id: ProblemId,
+    },
+    /// Shows sample inputs and outputs of problem
+    Samples {
+        /// Id of the problem
+        #[structopt(name = "problem")]
+        problem_id: ProblemId,
+        /// If specified, shows only one sample
+        sample_name: Option<String>,
+    },
+}
 
 impl ShowOpt {
-    pub fn run<'a>(&self, conf: &'a Config) -> Result<ShowOutcome<'a>> {
-        Ok(ShowOutcome(conf))
+    pub fn run<'a>(&self, conf: &'a Config, cnsl: &mut Console) -> Result<ShowOutcome<'a>> {
+        let outcome = match &self.view {
+            None => ShowOutcome::Config(conf),
+            Some(ShowView::Problem { problem_id }) => {
+                let problem_id = conf.resolve_problem_id(problem_id, cnsl)?;
+                ShowOutcome::Problem(conf.load_problem(&problem_id, cnsl)?)
+            }
+            Some(ShowView::Samples {
+                problem_id,
+                sample_name,
+            }) => {
+                let problem_id = conf.resolve_problem_id(problem_id, cnsl)?;
+                let problem = conf.load_problem(&problem_id, cnsl)?;
+                let samples = problem
+                    .take_samples(sample_name)
+                    .collect::<Result<Vec<_>>>()?;
+                ShowOutcome::Samples(samples)
+            }
+        };
+        Ok(outcome)
     }
 }
 
 #[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct ShowOutcome<'a>(&'a Config);
+#[serde(untagged)]
+pub enum ShowOutcome<'a> {
+    Config(&'a Config),
+    Problem(Problem),
+    Samples(Vec<Sample>),
+}
 
 impl fmt::Display for ShowOutcome<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.0.fmt(f)
+        match self {
+            Self::Config(conf) => conf.fmt(f),
+            Self::Problem(problem) => fmt_problem(problem, f),
+            Self::Samples(samples) => {
+                for (i, sample) in samples.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    fmt_sample(sample, f)?;
+                }
+                Ok(())
+            }
+        }
     }
 }
 
+fn fmt_problem(problem: &Problem, f: &mut fmt::Formatter) -> fmt::Result {
+    let compare: &str = problem.compare().into();
+    writeln!(f, "Id           : {}", problem.id())?;
+    writeln!(f, "Name         : {}", problem.name())?;
+    writeln!(f, "Url name     : {}", problem.url_name())?;
+    match problem.time_limit() {
+        Some(time_limit) => writeln!(f, "Time limit   : {}", format_duration(time_limit))?,
+        None => writeln!(f, "Time limit   : -")?,
+    }
+    match problem.memory_limit() {
+        Some(memory_limit) => writeln!(f, "Memory limit : {}", memory_limit)?,
+        None => writeln!(f, "Memory limit : -")?,
+    }
+    match problem.points() {
+        Some(points) => writeln!(f, "Points       : {}", points)?,
+        None => writeln!(f, "Points       : -")?,
+    }
+    if let Some(difficulty) = problem.difficulty() {
+        writeln!(f, "Difficulty   : {}", difficulty)?;
+    }
+    writeln!(f, "Compare      : {}", compare)?;
+    write!(f, "Samples      : {}", problem.samples().len())
+}
+
+/// Formats input and output of sample side by side.
+fn fmt_sample(sample: &Sample, f: &mut fmt::Formatter) -> fmt::Result {
+    let (l_title, r_title) = ("input", "output");
+    let inputs: Vec<&str> = sample.input().lines().collect();
+    let outputs: Vec<&str> = sample.output().lines().collect();
+    let width = |lines: &[&str], title: &str| {
+        lines
+            .iter()
+            .map(|line| line.chars().count())
+            .fold(title.len(), max)
+    };
+    let (l_width, r_width) = (width(&inputs, l_title), width(&outputs, r_title));
+
+    writeln!(f, "sample {}", sample.name())?;
+    writeln!(
+        f,
+        "| {:l_width$} | {:r_width$} |",
+        l_title,
+        r_title,
+        l_width = l_width,
+        r_width = r_width
+    )?;
+    writeln!(
+        f,
+        "|-{:-<l_width$}-+-{:-<r_width$}-|",
+        "",
+        "",
+        l_width = l_width,
+        r_width = r_width
+    )?;
+    for i in 0..max(inputs.len(), outputs.len()) {
+        writeln!(
+            f,
+            "| {:l_width$} | {:r_width$} |",
+            inputs.get(i).unwrap_or(&""),
+            outputs.get(i).unwrap_or(&""),
+            l_width = l_width,
+            r_width = r_width
+        )?;
+    }
+    Ok(())
+}
+
 impl Outcome for ShowOutcome<'_> {
     fn is_error(&self) -> bool {
         false
@@ -40,8 +168,35 @@ mod tests {
 
     #[test]
     fn run_default() -> anyhow::Result<()> {
-        let opt = ShowOpt {};
-        run_with(&tempdir()?, |conf, _| opt.run(conf).map(|_| ()))?;
+        let opt = ShowOpt::default();
+        run_with(&tempdir()?, |conf, cnsl| opt.run(conf, cnsl).map(|_| ()))?;
+        Ok(())
+    }
+
+    #[test]
+    fn run_samples() -> anyhow::Result<()> {
+        let opt = ShowOpt {
+            view: Some(ShowView::Samples {
+                problem_id: "c".into(),
+                sample_name: Some("2".into()),
+            }),
+        };
+        let output = run_with(&tempdir()?, |conf, cnsl| {
+            let mut problem = Problem::default();
+            problem.set_samples(vec![
+                Sample::new("1", "5\n", "0\n"),
+                Sample::new("2", "3\n1 2 3\n", "10\n"),
+            ]);
+            conf.save_problem(&problem, true, cnsl)?;
+            Ok(opt.run(conf, cnsl)?.to_string())
+        })?;
+        let expected = r#"sample 2
+| input | output |
+|-------+--------|
+| 3     | 10     |
+| 1 2 3 |        |
+"#;
+        assert_eq!(output, expected);
         Ok(())
     }
 }
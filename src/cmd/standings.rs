@@ -0,0 +1,195 @@
+use std::cmp::max;
+use std::fmt;
+use std::time::Duration;
+
+use getset::{CopyGetters, Getters};
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::cmd::{block_on, with_actor, Outcome};
+use crate::model::{ContestId, Service, Standings, StandingsRow};
+use crate::service::Act;
+use crate::{Config, Console, Result};
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct StandingsOpt {
+    /// Shows users ranked in top N
+    #[structopt(long, value_name = "N")]
+    pub(crate) top: Option<usize>,
+    /// Shows users in the given list (e.g. --friends alice,bob)
+    #[structopt(long, use_delimiter = true)]
+    pub(crate) friends: Vec<String>,
+}
+
+impl StandingsOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<StandingsOutcome> {
+        let (username, standings) = with_actor(conf.service_id, conf.session(), |actor| {
+            block_on(Self::fetch_standings(actor, conf, cnsl))
+        })?;
+
+        let me = username
+            .as_deref()
+            .and_then(|username| standings.find_user(username))
+            .cloned();
+        let rows = self.filter_rows(&standings);
+
+        Ok(StandingsOutcome {
+            service: Service::new(conf.service_id),
+            contest_id: conf.contest_id.clone(),
+            total: standings.rows().len(),
+            me,
+            rows,
+        })
+    }
+
+    async fn fetch_standings(
+        actor: &dyn Act,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<(Option<String>, Standings)> {
+        let username = actor.current_user(cnsl).await?;
+        let standings = actor.fetch_standings(&conf.contest_id, cnsl).await?;
+        Ok((username, standings))
+    }
+
+    /// Returns rows ranked in top N or of friends in order of rank.
+    fn filter_rows(&self, standings: &Standings) -> Vec<StandingsRow> {
+        standings
+            .rows()
+            .iter()
+            .enumerate()
+            .filter(|(i, row)| {
+                self.top.map_or(false, |top| *i < top)
+                    || self
+                        .friends
+                        .iter()
+                        .any(|friend| row.user().eq_ignore_ascii_case(friend))
+            })
+            .map(|(_, row)| row.clone())
+            .collect()
+    }
+}
+
+#[derive(Serialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StandingsOutcome {
+    #[get = "pub"]
+    service: Service,
+    #[get = "pub"]
+    contest_id: ContestId,
+    /// Number of all users in standings
+    #[get_copy = "pub"]
+    total: usize,
+    /// Row of user currently logged in, which is none if the user is not in standings
+    #[get = "pub"]
+    me: Option<StandingsRow>,
+    #[get = "pub"]
+    rows: Vec<StandingsRow>,
+}
+
+impl fmt::Display for StandingsOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} {} standings ({} users)",
+            self.service.id(),
+            self.contest_id,
+            self.total
+        )?;
+
+        if !self.rows.is_empty() {
+            let rows: Vec<[String; 5]> = self.rows.iter().map(to_cells).collect();
+            let titles = ["rank", "user", "score", "penalty", "time"];
+            let mut widths = [0; 5];
+            for (i, title) in titles.iter().enumerate() {
+                widths[i] = rows
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .fold(title.len(), max);
+            }
+            let fmt_row = |f: &mut fmt::Formatter, cells: &[&str]| {
+                writeln!(
+                    f,
+                    "| {:>w0$} | {:w1$} | {:>w2$} | {:>w3$} | {:>w4$} |",
+                    cells[0],
+                    cells[1],
+                    cells[2],
+                    cells[3],
+                    cells[4],
+                    w0 = widths[0],
+                    w1 = widths[1],
+                    w2 = widths[2],
+                    w3 = widths[3],
+                    w4 = widths[4]
+                )
+            };
+
+            writeln!(f)?;
+            fmt_row(f, &titles)?;
+            let sep: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+            writeln!(f, "|-{}-|", sep.join("-+-"))?;
+            for row in &rows {
+                fmt_row(f, &[&row[0], &row[1], &row[2], &row[3], &row[4]])?;
+            }
+            writeln!(f)?;
+        }
+
+        match &self.me {
+            Some(me) => write!(
+                f,
+                "You are ranked {} with score {} (penalty {}, time {})",
+                me.rank(),
+                me.score(),
+                me.penalty(),
+                format_elapsed(me.elapsed())
+            ),
+            None => write!(f, "You are not in standings"),
+        }
+    }
+}
+
+impl Outcome for StandingsOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+fn to_cells(row: &StandingsRow) -> [String; 5] {
+    [
+        row.rank().to_string(),
+        row.user().to_owned(),
+        row.score().to_owned(),
+        row.penalty().to_string(),
+        format_elapsed(row.elapsed()),
+    ]
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_rows() {
+        let standings = Standings::new(vec![
+            StandingsRow::new(1, "alice", "3000", 0, Duration::from_secs(4416)),
+            StandingsRow::new(2, "bob", "2000", 1, Duration::from_secs(3000)),
+            StandingsRow::new(3, "carol", "1000", 2, Duration::from_secs(600)),
+        ]);
+        let opt = StandingsOpt {
+            top: Some(1),
+            friends: vec!["Carol".into()],
+        };
+        let users: Vec<_> = opt
+            .filter_rows(&standings)
+            .iter()
+            .map(|row| row.user().to_owned())
+            .collect();
+        assert_eq!(users, vec!["alice", "carol"]);
+        assert_eq!(format_elapsed(Duration::from_secs(4416)), "01:13:36");
+    }
+}
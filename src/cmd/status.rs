@@ -0,0 +1,174 @@
+use std::fmt;
+use std::time::Duration;
+
+use chrono::{offset::Local, SecondsFormat};
+use getset::{CopyGetters, Getters};
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::cmd::{block_on, for_each_service, warn_clock_skew, with_actor, Outcome};
+use crate::model::{ContestInfo, ContestPhase, Service};
+use crate::service::Act;
+use crate::{Config, Console, Result};
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct StatusOpt {
+    /// Shows status of all services
+    #[structopt(long)]
+    pub(crate) all_services: bool,
+}
+
+impl StatusOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<StatusOutcome> {
+        let statuses = if self.all_services {
+            for_each_service(conf, |actor, conf| {
+                block_on(Self::fetch_status(actor, conf, cnsl))
+            })?
+        } else {
+            vec![with_actor(conf.service_id, conf.session(), |actor| {
+                block_on(Self::fetch_status(actor, conf, cnsl))
+            })?]
+        };
+
+        Ok(StatusOutcome { statuses })
+    }
+
+    async fn fetch_status(
+        actor: &dyn Act,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<ServiceStatus> {
+        let username = actor.current_user(cnsl).await?;
+        let info = actor.fetch_contest_info(&conf.contest_id, cnsl).await?;
+        warn_clock_skew(conf, cnsl)?;
+        let now = Local::now();
+
+        Ok(ServiceStatus {
+            service: Service::new(conf.service_id),
+            username,
+            phase: info.phase_at(now),
+            remaining: info.remaining_at(now),
+            info,
+        })
+    }
+}
+
+#[derive(Serialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ServiceStatus {
+    #[get = "pub"]
+    service: Service,
+    #[get = "pub"]
+    username: Option<String>,
+    #[get = "pub"]
+    info: ContestInfo,
+    #[get_copy = "pub"]
+    phase: ContestPhase,
+    #[serde(with = "humantime_serde")]
+    #[get_copy = "pub"]
+    remaining: Option<Duration>,
+}
+
+impl fmt::Display for ServiceStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let contest = self.info.contest();
+        writeln!(
+            f,
+            "{} {} {}",
+            self.service.id(),
+            contest.id(),
+            contest.name()
+        )?;
+        match &self.username {
+            Some(username) => writeln!(f, "user : {}", username)?,
+            None => writeln!(f, "user : (not logged in)")?,
+        }
+        writeln!(
+            f,
+            "start: {}",
+            self.info
+                .start_at()
+                .to_rfc3339_opts(SecondsFormat::Secs, false)
+        )?;
+        writeln!(
+            f,
+            "end  : {}",
+            self.info
+                .end_at()
+                .to_rfc3339_opts(SecondsFormat::Secs, false)
+        )?;
+        match (self.phase, self.remaining) {
+            (ContestPhase::NotStarted, Some(remaining)) => {
+                write!(f, "Contest starts in {}", format_remaining(remaining))
+            }
+            (ContestPhase::Running, Some(remaining)) => {
+                write!(f, "Contest ends in {}", format_remaining(remaining))
+            }
+            (phase, _) => write!(f, "Contest {}", phase),
+        }
+    }
+}
+
+#[derive(Serialize, Getters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StatusOutcome {
+    #[get = "pub"]
+    statuses: Vec<ServiceStatus>,
+}
+
+impl fmt::Display for StatusOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, status) in self.statuses.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+                writeln!(f)?;
+            }
+            write!(f, "{}", status)?;
+        }
+        Ok(())
+    }
+}
+
+impl Outcome for StatusOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+fn format_remaining(remaining: Duration) -> String {
+    let secs = remaining.as_secs();
+    let (days, hours, mins, secs) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    if days > 0 {
+        format!("{}d {:02}:{:02}:{:02}", days, hours, mins, secs)
+    } else {
+        format!("{:02}:{:02}:{:02}", hours, mins, secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::cmd::tests::run_with;
+    use crate::model::ServiceKind;
+
+    #[test]
+    fn run_default() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+
+        let opt = StatusOpt { all_services: true };
+        let outcome = run_with(&test_dir, |conf, cnsl| opt.run(conf, cnsl))?;
+
+        let status = &outcome.statuses[0];
+        assert_eq!(status.service.id(), ServiceKind::Atcoder);
+        assert_eq!(status.phase, ContestPhase::Ended);
+        assert_eq!(status.remaining, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(Duration::from_secs(3725)), "01:02:05");
+        assert_eq!(format_remaining(Duration::from_secs(90061)), "1d 01:01:01");
+    }
+}
@@ -0,0 +1,180 @@
+use std::cmp::max;
+use std::fmt;
+
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::cmd::{block_on, with_actor, Outcome};
+use crate::model::{ContestId, JudgeResult, Service, Submission};
+use crate::{Config, Console, Result};
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct SubmissionOpt {
+    /// Id of the submission (e.g. 12345678)
+    #[structopt(name = "submission")]
+    submission_id: String,
+}
+
+impl SubmissionOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<SubmissionOutcome> {
+        let submission = with_actor(conf.service_id, conf.session(), |actor| {
+            block_on(actor.fetch_submission(&conf.contest_id, &self.submission_id, cnsl))
+        })?;
+
+        Ok(SubmissionOutcome {
+            service: Service::new(conf.service_id),
+            contest_id: conf.contest_id.clone(),
+            submission,
+        })
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SubmissionOutcome {
+    service: Service,
+    contest_id: ContestId,
+    submission: Submission,
+}
+
+fn fmt_exec_time(result: &JudgeResult) -> String {
+    result
+        .exec_time()
+        .map_or_else(|| String::from("-"), |t| format!("{} ms", t.as_millis()))
+}
+
+fn fmt_memory(result: &JudgeResult) -> String {
+    result
+        .memory()
+        .map_or_else(|| String::from("-"), |m| m.to_string())
+}
+
+impl fmt::Display for SubmissionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let submission = &self.submission;
+        let result = submission.result();
+        writeln!(
+            f,
+            "{} {} submission {}",
+            self.service.id(),
+            self.contest_id,
+            submission.id()
+        )?;
+        writeln!(f, "Task     : {}", submission.task())?;
+        writeln!(f, "User     : {}", submission.user())?;
+        writeln!(f, "Language : {}", submission.lang_name())?;
+        writeln!(f, "Score    : {}", submission.score())?;
+        write!(
+            f,
+            "Status   : {} ({}, {})",
+            result.verdict(),
+            fmt_exec_time(result),
+            fmt_memory(result)
+        )?;
+
+        let testcases = submission.testcases();
+        if testcases.is_empty() {
+            return Ok(());
+        }
+        let rows: Vec<[String; 4]> = testcases
+            .iter()
+            .map(|t| {
+                [
+                    t.name().to_owned(),
+                    t.result().verdict().to_owned(),
+                    fmt_exec_time(t.result()),
+                    fmt_memory(t.result()),
+                ]
+            })
+            .collect();
+        let titles = ["testcase", "status", "time", "memory"];
+        let mut widths = [0; 4];
+        for (i, title) in titles.iter().enumerate() {
+            widths[i] = rows
+                .iter()
+                .map(|row| row[i].chars().count())
+                .fold(title.len(), max);
+        }
+        let fmt_row = |f: &mut fmt::Formatter, cells: &[&str]| {
+            writeln!(
+                f,
+                "| {:w0$} | {:w1$} | {:>w2$} | {:>w3$} |",
+                cells[0],
+                cells[1],
+                cells[2],
+                cells[3],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+                w3 = widths[3]
+            )
+        };
+
+        writeln!(f)?;
+        writeln!(f)?;
+        fmt_row(f, &titles)?;
+        let sep: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+        writeln!(f, "|-{}-|", sep.join("-+-"))?;
+        for row in &rows {
+            fmt_row(f, &[&row[0], &row[1], &row[2], &row[3]])?;
+        }
+
+        let failed: Vec<&str> = submission
+            .failed_testcases()
+            .map(|t| t.name().as_str())
+            .collect();
+        if failed.is_empty() {
+            write!(f, "All {} testcases passed", testcases.len())
+        } else {
+            write!(f, "Failed testcases : {}", failed.join(", "))
+        }
+    }
+}
+
+impl Outcome for SubmissionOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::model::TestcaseResult;
+
+    #[test]
+    fn display_outcome() {
+        let result = |verdict, ms| JudgeResult::new(verdict, Some(Duration::from_millis(ms)), None);
+        let outcome = SubmissionOutcome {
+            service: Service::default(),
+            contest_id: ContestId::default(),
+            submission: Submission::new(
+                "1234",
+                "C - Linear Approximation",
+                "test_user",
+                "C++ (GCC 9.2.1)",
+                "0",
+                result("WA", 25),
+                vec![
+                    TestcaseResult::new("sample_01", result("AC", 2)),
+                    TestcaseResult::new("subtask_1_01", result("WA", 25)),
+                ],
+            ),
+        };
+        let expected = r#"atcoder arc100 submission 1234
+Task     : C - Linear Approximation
+User     : test_user
+Language : C++ (GCC 9.2.1)
+Score    : 0
+Status   : WA (25 ms, -)
+
+| testcase     | status |  time | memory |
+|--------------+--------+-------+--------|
+| sample_01    | AC     |  2 ms |      - |
+| subtask_1_01 | WA     | 25 ms |      - |
+Failed testcases : subtask_1_01"#;
+        assert_eq!(outcome.to_string(), expected);
+    }
+}
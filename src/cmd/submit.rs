@@ -1,69 +1,282 @@
+use std::collections::hash_map::DefaultHasher;
 use std::fmt;
-use std::io::Write as _;
+use std::fs;
+use std::hash::{Hash as _, Hasher as _};
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
 
-use anyhow::Context as _;
+use anyhow::{anyhow, Context as _};
 use chrono::{offset::Local, DateTime, SecondsFormat};
+use getset::{CopyGetters, Getters};
 use serde::Serialize;
 use structopt::StructOpt;
+use tokio::time::delay_for;
 
-use crate::cmd::{with_actor, Outcome};
-use crate::model::{ContestId, LangName, ProblemId, Service};
+use crate::abs_path::AbsPathBuf;
+use crate::cmd::{block_on, print_virtual_time, warn_clock_skew, with_actor, Outcome};
+use crate::config::HookKind;
+use crate::error::{find_cause, ErrorKind};
+use crate::git;
+use crate::history::{History, HistoryEntry, HistoryKind};
+use crate::model::{
+    detect_lang_names, ContestId, ContestPhase, LangName, Problem, ProblemId, Service,
+};
+use crate::service::act::LangNotFound;
+use crate::service::session::may_have_reached;
 use crate::service::Act;
+use crate::submit_queue::{PendingSubmission, SubmitQueue};
 use crate::{Config, Console, Error, Result};
 
+/// Interval between requests that check if the submission has been judged.
+const VERDICT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// Number of requests after which `--wait` gives up waiting for the verdict.
+const MAX_VERDICT_POLLS: usize = 200;
+
+/// Upper limit of source code read from clipboard or `--file`,
+/// which catches pasting or specifying something other than source code.
+const MAX_OVERRIDE_SOURCE_BYTES: usize = 512 * 1024;
+
 #[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
 #[structopt(rename_all = "kebab")]
 pub struct SubmitOpt {
     /// Id of the problem to be submitted
-    #[structopt(name = "problem")]
-    problem_id: ProblemId,
+    #[structopt(name = "problem", required_unless = "retry-pending")]
+    pub(crate) problem_id: Option<ProblemId>,
     /// Overrides the language names specified in config file
     #[structopt(long, short)]
-    lang_name: Option<Vec<LangName>>,
+    pub(crate) lang_name: Option<Vec<LangName>>,
     /// Opens the submission status in browser
     #[structopt(name = "open", long, short)]
-    need_open: bool,
+    pub(crate) need_open: bool,
+    /// Does everything except actually submitting source code
+    #[structopt(name = "dry-run", long)]
+    pub(crate) is_dry_run: bool,
+    /// Submits even if the latest submission has the same source code
+    #[structopt(long)]
+    pub(crate) force: bool,
+    /// Submits source code in clipboard instead of the source file
+    #[structopt(long, conflicts_with = "file")]
+    pub(crate) from_clipboard: bool,
+    /// Submits the file instead of the source file
+    #[structopt(long)]
+    pub(crate) file: Option<PathBuf>,
+    /// Submits again the submissions queued because of network errors
+    #[structopt(long, conflicts_with_all = &["problem", "from-clipboard", "file"])]
+    pub(crate) retry_pending: bool,
+    /// Waits until the submission is judged
+    /// (and removes full testcases if accepted and `testcases.cleanup_on_ac` is enabled)
+    #[structopt(long, conflicts_with = "dry-run")]
+    pub(crate) wait: bool,
 }
 
 impl SubmitOpt {
     pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<SubmitOutcome> {
+        let problem_id = self
+            .problem_id
+            .as_ref()
+            .context("Specify problem to be submitted")?;
+        let problem_id = conf.resolve_problem_id(problem_id, cnsl)?;
         with_actor(conf.service_id, conf.session(), |actor| {
-            self.run_inner(actor, conf, cnsl)
+            block_on(self.run_inner(actor, &problem_id, conf, cnsl))
         })
     }
 
-    pub fn run_inner(
+    /// Submits again the pending submissions in the order they were queued.
+    ///
+    /// Submissions that fail because of network errors again are kept in the queue.
+    pub fn run_pending(&self, conf: &Config, cnsl: &mut Console) -> Result<RetryPendingOutcome> {
+        let queue = SubmitQueue::new(conf.pending_submissions_abs_path());
+        let pending = queue.load().context("Could not load pending submissions")?;
+        if pending.is_empty() {
+            writeln!(cnsl, "Found no pending submissions")?;
+            return Ok(RetryPendingOutcome {
+                submissions: Vec::new(),
+            });
+        }
+        let mut submissions = Vec::with_capacity(pending.len());
+        for submission in pending {
+            let conf = conf
+                .with_service(submission.service_id())
+                .with_contest(submission.contest_id().clone());
+            writeln!(
+                cnsl,
+                "Submitting problem {} to {} (queued at {}) ...",
+                submission.problem_id(),
+                conf.contest_id,
+                submission
+                    .queued_at()
+                    .to_rfc3339_opts(SecondsFormat::Secs, false)
+            )?;
+            let result = with_actor(conf.service_id, conf.session(), |actor| {
+                block_on(self.replay(actor, &submission, &conf, cnsl))
+            });
+            let is_pending = match &result {
+                Ok(_) => self.is_dry_run,
+                // keep the submission only if it could not be sent again
+                Err(err) => is_unsent(err),
+            };
+            if !is_pending {
+                queue
+                    .remove(
+                        submission.service_id(),
+                        submission.contest_id(),
+                        submission.problem_id(),
+                    )
+                    .context("Could not remove pending submission")?;
+            }
+            let (lang_name, message) = match result {
+                Ok(lang_name) => {
+                    if !self.is_dry_run {
+                        let source_hash = submission.source_hash().clone();
+                        record_history(&conf, submission.problem_id(), source_hash, cnsl);
+                    }
+                    (Some(lang_name), None)
+                }
+                Err(err) => {
+                    cnsl.warn(&format!(
+                        "Could not submit problem {} to {} : {:#}",
+                        submission.problem_id(),
+                        conf.contest_id,
+                        err
+                    ))?;
+                    (None, Some(format!("{:#}", err)))
+                }
+            };
+            submissions.push(RetriedSubmission {
+                contest_id: conf.contest_id.clone(),
+                problem_id: submission.problem_id().clone(),
+                lang_name,
+                is_pending,
+                message,
+            });
+        }
+        Ok(RetryPendingOutcome { submissions })
+    }
+
+    /// Submits the pending submission unless its source file has been modified since queued.
+    ///
+    /// The submission goes through the same checks as `acick submit` does.
+    async fn replay(
         &self,
         actor: &dyn Act,
+        submission: &PendingSubmission,
         conf: &Config,
         cnsl: &mut Console,
-    ) -> Result<SubmitOutcome> {
-        // confirm
-        let message = format!(
-            "submit problem {} to {}?",
-            &self.problem_id, &conf.contest_id
-        );
-        if !cnsl.confirm(&message, false)? {
-            return Err(Error::msg("Not submitted"));
+    ) -> Result<LangName> {
+        let source_path = submission.source_path();
+        let source = fs::read_to_string(source_path.as_ref())
+            .with_context(|| format!("Could not read source file : {}", source_path))?;
+        if hash_source(&source) != *submission.source_hash() {
+            return Err(anyhow!(
+                "Found source file modified after the submission was queued : {} . \
+                 Submit it again by `acick submit` command.",
+                source_path
+            ));
         }
+        let problem_id = submission.problem_id();
+        self.confirm(actor, problem_id, conf, cnsl).await?;
+        let problem = conf.load_problem(problem_id, cnsl)?;
+        self.check_before_submit(actor, &problem, &source, conf, cnsl)
+            .await?;
+        let lang_name = actor
+            .submit(
+                &conf.contest_id,
+                &problem,
+                submission.lang_names(),
+                &source,
+                self.is_dry_run,
+                cnsl,
+            )
+            .await?;
+        Ok(lang_name.to_owned())
+    }
+
+    pub async fn run_inner(
+        &self,
+        actor: &dyn Act,
+        problem_id: &ProblemId,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<SubmitOutcome> {
+        self.confirm(actor, problem_id, conf, cnsl).await?;
 
         // load problem file
-        let problem = conf.load_problem(&self.problem_id, cnsl)?;
+        let problem = conf.load_problem(problem_id, cnsl)?;
 
         // load source
-        let source = conf
-            .load_source(&self.problem_id, cnsl)
-            .context("Could not load source file")?;
-        if source.is_empty() {
-            return Err(Error::msg("Found empty source file"));
-        }
+        let source = self.load_source(problem_id, conf, cnsl)?;
+        let source_hash = self
+            .check_before_submit(actor, &problem, &source, conf, cnsl)
+            .await?;
+
+        // remember the latest submission so that the new one can be told apart from it
+        let previous_id = if self.wait {
+            actor
+                .fetch_latest_submission(&conf.contest_id, &problem, cnsl)
+                .await?
+                .map(|submission| submission.id().to_owned())
+        } else {
+            None
+        };
 
-        // submit
-        let lang_names = match &self.lang_name {
-            Some(lang_names) => lang_names,
-            None => conf.service().lang_names(),
+        // submit, queueing the submission if it could not be sent
+        let queue = SubmitQueue::new(conf.pending_submissions_abs_path());
+        let lang_name = match self
+            .submit_source(actor, problem_id, &problem, &source, conf, cnsl)
+            .await
+        {
+            Err(err) if !self.is_dry_run && is_unsent(&err) => {
+                let message = match self.queue_pending(&queue, problem_id, source_hash, conf) {
+                    Ok(()) => "Queued the submission. \
+                               Run `acick submit --retry-pending` once connectivity returns."
+                        .to_owned(),
+                    Err(queue_err) => format!("Could not queue the submission : {:#}", queue_err),
+                };
+                return Err(err.context(message));
+            }
+            result => result?,
         };
-        let lang_name = actor.submit(&conf.contest_id, &problem, lang_names, &source, cnsl)?;
+
+        let outcome = SubmitOutcome {
+            service: Service::new(conf.service_id),
+            contest_id: conf.contest_id.to_owned(),
+            problem_id: problem_id.to_owned(),
+            problem_name: problem.name().to_owned(),
+            submitted_at: Local::now(),
+            lang_name,
+            source_bytes: source.len(),
+            is_dry_run: self.is_dry_run,
+            verdict: None,
+        };
+        if self.is_dry_run {
+            return Ok(outcome);
+        }
+
+        // the pending submission of the problem is no longer needed
+        queue
+            .remove(conf.service_id, &conf.contest_id, problem_id)
+            .context("Could not remove pending submission")
+            // coerce error
+            .unwrap_or_else(|err| cnsl.warn(&format!("{:#}", err)).unwrap_or(()));
+
+        record_history(conf, problem_id, source_hash, cnsl);
+
+        // commit source code if needed (the source file was not submitted if overridden)
+        if !self.is_source_overridden() {
+            git::commit_source(conf, &problem, "submitted", cnsl)
+                .await
+                .context("Could not commit source code")
+                // coerce error
+                .unwrap_or_else(|err| {
+                    cnsl.warn(&format!("{:#}", err)).unwrap_or(());
+                    false
+                });
+        }
+
+        conf.exec_hooks(HookKind::PostSubmit, problem_id, Some("submitted"), cnsl)
+            .await?;
 
         // open submissions in browser if needed
         if self.need_open {
@@ -73,33 +286,520 @@ impl SubmitOpt {
                 .unwrap_or_else(|err| writeln!(cnsl, "{}", err).unwrap_or(()));
         }
 
-        Ok(SubmitOutcome {
-            service: Service::new(conf.service_id),
-            contest_id: conf.contest_id.to_owned(),
-            problem_id: self.problem_id.to_owned(),
-            problem_name: problem.name().to_owned(),
-            submitted_at: Local::now(),
-            lang_name: lang_name.to_owned(),
-            source_bytes: source.len(),
-        })
+        let verdict = if self.wait {
+            wait_verdict(actor, &problem, previous_id.as_deref(), conf, cnsl).await?
+        } else {
+            None
+        };
+        Ok(SubmitOutcome { verdict, ..outcome })
+    }
+
+    /// Warns if contest is not running and asks for confirmation (always in contest mode).
+    async fn confirm(
+        &self,
+        actor: &dyn Act,
+        problem_id: &ProblemId,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<()> {
+        // warn if contest is not running
+        let mut contest_mode = conf.contest_mode;
+        match actor.fetch_contest_info(&conf.contest_id, cnsl).await {
+            Ok(info) => match info.phase_at(Local::now()) {
+                ContestPhase::NotStarted => cnsl.warn("Contest has not started yet")?,
+                ContestPhase::Ended => cnsl.warn("Contest has already ended")?,
+                ContestPhase::Running => contest_mode = true,
+            },
+            // coerce error
+            Err(err) => writeln!(cnsl, "Could not check contest schedule: {}", err)?,
+        }
+        warn_clock_skew(conf, cnsl)?;
+        print_virtual_time(conf, cnsl)?;
+
+        // confirm (always in contest mode)
+        let message = format!("submit problem {} to {}?", problem_id, &conf.contest_id);
+        let is_confirmed = if self.is_dry_run {
+            true
+        } else if contest_mode {
+            cnsl.confirm_always(&message, false)?
+        } else {
+            cnsl.confirm(&message, false)?
+        };
+        if !is_confirmed {
+            return Err(Error::msg("Not submitted"));
+        }
+        Ok(())
+    }
+
+    /// Checks source code and runs hooks before submission, returning hash of source code.
+    async fn check_before_submit(
+        &self,
+        actor: &dyn Act,
+        problem: &Problem,
+        source: &str,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<String> {
+        if source.is_empty() {
+            return Err(Error::msg("Found empty source file"));
+        }
+        conf.check_source(source)
+            .context("Could not pass checks before submission")?;
+        let source_hash = hash_source(source);
+        if !self.force && !self.is_dry_run {
+            self.check_duplicate(actor, problem, source, &source_hash, conf, cnsl)
+                .await?;
+        }
+        conf.exec_hooks(HookKind::PreSubmit, problem.id(), None, cnsl)
+            .await?;
+        Ok(source_hash)
+    }
+
+    /// Sends source code to service in the specified languages,
+    /// or in the language detected from extension of source file if none of them is available.
+    async fn submit_source(
+        &self,
+        actor: &dyn Act,
+        problem_id: &ProblemId,
+        problem: &Problem,
+        source: &str,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<LangName> {
+        let lang_names = self.lang_names(conf);
+        match actor
+            .submit(
+                &conf.contest_id,
+                problem,
+                &lang_names,
+                source,
+                self.is_dry_run,
+                cnsl,
+            )
+            .await
+        {
+            Ok(lang_name) => return Ok(lang_name.to_owned()),
+            // nothing has been sent yet, so submitting again is safe
+            Err(err) if find_cause::<LangNotFound>(&err).is_some() => {}
+            Err(err) => return Err(err),
+        }
+
+        let lang_names = self
+            .fallback_lang_names(actor, problem_id, &lang_names, conf, cnsl)
+            .await?;
+        let lang_name = actor
+            .submit(
+                &conf.contest_id,
+                problem,
+                &lang_names,
+                source,
+                self.is_dry_run,
+                cnsl,
+            )
+            .await?;
+        Ok(lang_name.to_owned())
+    }
+
+    /// Queues the submission to be submitted again by `--retry-pending`.
+    ///
+    /// Source code in clipboard is not queued because it cannot be read again.
+    fn queue_pending(
+        &self,
+        queue: &SubmitQueue,
+        problem_id: &ProblemId,
+        source_hash: String,
+        conf: &Config,
+    ) -> Result<()> {
+        if self.from_clipboard {
+            return Err(anyhow!("Source code in clipboard cannot be queued"));
+        }
+        let source_path = match &self.file {
+            Some(file) => AbsPathBuf::cwd()?.join(file),
+            None => conf.source_abs_path(problem_id)?,
+        };
+        queue.push(&PendingSubmission::new(
+            conf.service_id,
+            conf.contest_id.clone(),
+            problem_id.clone(),
+            self.lang_names(conf),
+            source_hash,
+            source_path,
+        ))
+    }
+
+    /// Returns names of languages specified by option or config file.
+    fn lang_names(&self, conf: &Config) -> Vec<LangName> {
+        match &self.lang_name {
+            Some(lang_names) => lang_names.clone(),
+            None => conf.service().lang_names().to_vec(),
+        }
+    }
+
+    /// Returns the language detected from extension of source file among available languages,
+    /// which is used if none of `lang_names` is available.
+    async fn fallback_lang_names(
+        &self,
+        actor: &dyn Act,
+        problem_id: &ProblemId,
+        lang_names: &[LangName],
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<Vec<LangName>> {
+        let available = actor
+            .fetch_lang_names(&conf.contest_id, cnsl)
+            .await
+            .context("Could not fetch available languages")?;
+
+        let source_path = match &self.file {
+            Some(file) => file.clone(),
+            None => conf.source_abs_path(problem_id)?.as_ref().to_owned(),
+        };
+        let extension = source_path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+        let candidates = detect_lang_names(extension, &available);
+        let lang_name = match candidates.first() {
+            Some(lang_name) => *lang_name,
+            None => {
+                return Err(anyhow!(
+                    "Could not find available language from the given language list: {} \
+                     nor detect language from extension of {} . \
+                     Specify lang_names of the service in config file.",
+                    lang_names.join(", "),
+                    source_path.display()
+                ))
+            }
+        };
+        cnsl.warn(&format!(
+            "Could not find available language from the given language list: {}",
+            lang_names.join(", ")
+        ))?;
+        writeln!(
+            cnsl,
+            "Detected languages from extension of source file: {}",
+            candidates
+                .iter()
+                .map(|name| name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+        let message = format!(
+            "submit in {}? (specify lang_names in config file to skip this)",
+            lang_name
+        );
+        if !cnsl.confirm(&message, false)? {
+            return Err(Error::msg("Not submitted"));
+        }
+        Ok(vec![lang_name.clone()])
+    }
+
+    fn is_source_overridden(&self) -> bool {
+        self.from_clipboard || self.file.is_some()
+    }
+
+    /// Loads source code from clipboard, `--file` or the source file in this order.
+    fn load_source(
+        &self,
+        problem_id: &ProblemId,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<String> {
+        let source = if self.from_clipboard {
+            writeln!(cnsl, "Reading source code from clipboard")?;
+            read_clipboard().context("Could not read source code from clipboard")?
+        } else if let Some(file) = &self.file {
+            if self.lang_name.is_none() {
+                check_extension(file, conf.source_abs_path(problem_id)?.as_ref())?;
+            }
+            writeln!(cnsl, "Reading source code from {}", file.display())?;
+            fs::read_to_string(file)
+                .with_context(|| format!("Could not read file : {}", file.display()))?
+        } else {
+            return conf
+                .load_source(problem_id, cnsl)
+                .context("Could not load source file");
+        };
+        if source.len() > MAX_OVERRIDE_SOURCE_BYTES {
+            return Err(anyhow!(
+                "Found source code larger than {} Bytes ({} Bytes)",
+                MAX_OVERRIDE_SOURCE_BYTES,
+                source.len()
+            ));
+        }
+        Ok(source)
+    }
+
+    /// Returns error if the latest submission of the logged-in user to the problem
+    /// has the same source code.
+    ///
+    /// Local history is checked instead only if the latest submission could not be fetched.
+    async fn check_duplicate(
+        &self,
+        actor: &dyn Act,
+        problem: &Problem,
+        source: &str,
+        source_hash: &str,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<()> {
+        let (submission_id, latest_source) =
+            match fetch_latest_source(actor, problem, conf, cnsl).await {
+                Ok(Some(latest)) => latest,
+                Ok(None) => return Ok(()),
+                // coerce error
+                Err(err) => {
+                    cnsl.warn(&format!(
+                    "Could not fetch the latest submission, checking local history instead: {:#}",
+                    err
+                ))?;
+                    return self.check_duplicate_in_history(problem.id(), source_hash, conf, cnsl);
+                }
+            };
+        if is_same_source(&latest_source, source) {
+            return Err(anyhow!(
+                "Found the same source code in the latest submission {}. \
+                 Use --force to submit it again.",
+                submission_id
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns error if the last submission of the problem in history has the same source code
+    /// and was submitted within `submit_checks.duplicate_window`.
+    fn check_duplicate_in_history(
+        &self,
+        problem_id: &ProblemId,
+        source_hash: &str,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<()> {
+        let entries = match History::new(conf.history_abs_path()).load() {
+            Ok(entries) => entries,
+            // coerce error
+            Err(err) => {
+                cnsl.warn(&format!("Could not check duplicate submission: {:#}", err))?;
+                return Ok(());
+            }
+        };
+        let last = entries.iter().rev().find(|entry| {
+            entry.kind() == HistoryKind::Submit
+                && entry.service_id() == conf.service_id
+                && entry.contest_id() == &conf.contest_id
+                && entry.problem_id() == problem_id
+        });
+        let last = match last {
+            Some(last) if last.source_hash().as_deref() == Some(source_hash) => last,
+            _ => return Ok(()),
+        };
+        let elapsed = Local::now().signed_duration_since(*last.recorded_at());
+        if elapsed
+            .to_std()
+            .map_or(true, |elapsed| elapsed < conf.duplicate_window())
+        {
+            return Err(anyhow!(
+                "Found the same source code submitted at {}. Use --force to submit it again.",
+                last.recorded_at()
+                    .to_rfc3339_opts(SecondsFormat::Secs, false)
+            ));
+        }
+        Ok(())
     }
 }
 
+/// Returns id and source code of the latest submission of the logged-in user to the problem.
+async fn fetch_latest_source(
+    actor: &dyn Act,
+    problem: &Problem,
+    conf: &Config,
+    cnsl: &mut Console,
+) -> Result<Option<(String, String)>> {
+    let submission = match actor
+        .fetch_latest_submission(&conf.contest_id, problem, cnsl)
+        .await?
+    {
+        Some(submission) => submission,
+        None => return Ok(None),
+    };
+    let source = actor
+        .fetch_submission_source(&conf.contest_id, submission.id(), cnsl)
+        .await?;
+    Ok(Some((submission.id().to_owned(), source)))
+}
+
+/// Compares source code ignoring differences of line endings and trailing whitespaces,
+/// which service may normalize.
+fn is_same_source(a: &str, b: &str) -> bool {
+    let normalize = |s: &str| s.replace("\r\n", "\n").trim_end().to_owned();
+    normalize(a) == normalize(b)
+}
+
+/// Polls the latest submission to the problem until it is judged, returning its verdict.
+///
+/// Full testcases of the problem are cleaned up if the submission of the logged-in user is accepted.
+async fn wait_verdict(
+    actor: &dyn Act,
+    problem: &Problem,
+    previous_id: Option<&str>,
+    conf: &Config,
+    cnsl: &mut Console,
+) -> Result<Option<String>> {
+    let user = actor.current_user(cnsl).await?;
+    writeln!(cnsl, "Waiting for the submission to be judged ...")?;
+    for i in 0..MAX_VERDICT_POLLS {
+        if i > 0 {
+            delay_for(VERDICT_POLL_INTERVAL).await;
+        }
+        let submission = match actor
+            .fetch_latest_submission(&conf.contest_id, problem, cnsl)
+            .await?
+        {
+            // the new submission may not be listed yet
+            Some(submission) if Some(submission.id().as_str()) != previous_id => submission,
+            _ => continue,
+        };
+        let result = submission.result();
+        if result.is_judging() {
+            continue;
+        }
+        writeln!(
+            cnsl,
+            "Submission {} was judged as {}",
+            submission.id(),
+            result.verdict()
+        )?;
+        // never remove testcases because of submissions of other users
+        if result.is_accepted() && user.as_deref() == Some(submission.user().as_str()) {
+            conf.cleanup_testcases_on_ac(problem.id(), cnsl)?;
+        }
+        return Ok(Some(result.verdict().to_owned()));
+    }
+    cnsl.warn("Gave up waiting for the submission to be judged")?;
+    Ok(None)
+}
+
+/// Returns error if the file has extension different from the source file,
+/// which probably means the file is written in another language than the configured one.
+fn check_extension(file: &Path, source_path: &Path) -> Result<()> {
+    if file.extension() != source_path.extension() {
+        return Err(anyhow!(
+            "Found file with extension different from the source file {} . \
+             Specify --lang-name to submit it in another language.",
+            source_path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Reads text in clipboard with the command available on the platform.
+fn read_clipboard() -> Result<String> {
+    let commands: &[(&str, &[&str])] = if cfg!(windows) {
+        &[(
+            "powershell",
+            &["-NoProfile", "-Command", "Get-Clipboard -Raw"],
+        )]
+    } else if cfg!(target_os = "macos") {
+        &[("pbpaste", &[])]
+    } else {
+        &[
+            ("wl-paste", &["--no-newline"]),
+            ("xclip", &["-selection", "clipboard", "-out"]),
+            ("xsel", &["--clipboard", "--output"]),
+        ]
+    };
+    for (program, args) in commands {
+        let output = match Command::new(program)
+            .args(*args)
+            .stdin(Stdio::null())
+            .output()
+        {
+            Ok(output) => output,
+            // try next command if not installed
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err.into()),
+        };
+        if !output.status.success() {
+            return Err(anyhow!(
+                "{} returned non-zero status : {}\n{}",
+                program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            ));
+        }
+        return String::from_utf8(output.stdout)
+            .context("Found clipboard content that is not valid UTF-8");
+    }
+    let names: Vec<_> = commands.iter().map(|(program, _)| *program).collect();
+    Err(anyhow!(
+        "Could not find command to read clipboard. Install one of: {}",
+        names.join(", ")
+    ))
+}
+
+/// Returns true if the submission failed without reaching service, which can be queued safely.
+fn is_unsent(err: &Error) -> bool {
+    ErrorKind::of(err) == ErrorKind::Network && !may_have_reached(err)
+}
+
+/// Records submission in history, which is used to detect duplicate submissions.
+fn record_history(conf: &Config, problem_id: &ProblemId, source_hash: String, cnsl: &mut Console) {
+    History::new(conf.history_abs_path())
+        .record(
+            &HistoryEntry::new(
+                HistoryKind::Submit,
+                conf.service_id,
+                conf.contest_id.clone(),
+                problem_id.clone(),
+                "submitted",
+                None,
+            )
+            .with_source_hash(source_hash),
+        )
+        .context("Could not record history")
+        // coerce error
+        .unwrap_or_else(|err| cnsl.warn(&format!("{:#}", err)).unwrap_or(()));
+}
+
+/// Returns hash of source code, which is only compared with recent submissions.
+fn hash_source(source: &str) -> String {
+    // DefaultHasher may change between versions of Rust, which only misses duplicates
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
 pub type LocalDateTime = DateTime<Local>;
 
-#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Serialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SubmitOutcome {
+    #[get = "pub"]
     service: Service,
+    #[get = "pub"]
     contest_id: ContestId,
+    #[get = "pub"]
     problem_id: ProblemId,
+    #[get = "pub"]
     problem_name: String,
+    #[get = "pub"]
     submitted_at: LocalDateTime,
+    #[get = "pub"]
     lang_name: String,
+    #[get_copy = "pub"]
     source_bytes: usize,
+    #[get_copy = "pub"]
+    is_dry_run: bool,
+    /// Verdict of the submission, which is known only with `--wait`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[get = "pub"]
+    verdict: Option<String>,
 }
 
 impl fmt::Display for SubmitOutcome {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_dry_run {
+            write!(f, "(dry run) ")?;
+        }
         write!(
             f,
             "{} {} {} {} (time: {}, lang: {}, code size: {} Bytes)",
@@ -111,7 +811,11 @@ impl fmt::Display for SubmitOutcome {
                 .to_rfc3339_opts(SecondsFormat::Secs, false),
             self.lang_name,
             self.source_bytes
-        )
+        )?;
+        if let Some(verdict) = &self.verdict {
+            write!(f, " => {}", verdict)?;
+        }
+        Ok(())
     }
 }
 
@@ -121,12 +825,250 @@ impl Outcome for SubmitOutcome {
     }
 }
 
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RetryPendingOutcome {
+    submissions: Vec<RetriedSubmission>,
+}
+
+impl RetryPendingOutcome {
+    fn count_failed(&self) -> usize {
+        self.submissions
+            .iter()
+            .filter(|submission| submission.lang_name.is_none())
+            .count()
+    }
+}
+
+impl fmt::Display for RetryPendingOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for submission in &self.submissions {
+            write!(f, "{} {} ", submission.contest_id, submission.problem_id)?;
+            match (&submission.lang_name, &submission.message) {
+                (Some(lang_name), _) => write!(f, "submitted (lang: {})", lang_name),
+                (None, Some(message)) => write!(f, "FAILED ({})", message),
+                (None, None) => write!(f, "FAILED"),
+            }?;
+            if submission.is_pending {
+                write!(f, " [pending]")?;
+            }
+            writeln!(f)?;
+        }
+        write!(
+            f,
+            "Submitted {} pending submissions ({} failed)",
+            self.submissions.len(),
+            self.count_failed()
+        )
+    }
+}
+
+impl Outcome for RetryPendingOutcome {
+    fn is_error(&self) -> bool {
+        self.count_failed() > 0
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RetriedSubmission {
+    contest_id: ContestId,
+    problem_id: ProblemId,
+    /// Language in which source code was submitted, or `None` if failed
+    lang_name: Option<LangName>,
+    /// If true, the submission is still in the queue
+    is_pending: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::tempdir;
 
     use super::*;
     use crate::cmd::tests::run_with;
+    use crate::model::ServiceKind;
+
+    #[tokio::test]
+    async fn check_duplicate() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let opt = SubmitOpt::from_iter(&["submit", "a"]);
+        let conf = Config::default_in_dir(AbsPathBuf::try_new(test_dir.path())?)
+            .with_service(ServiceKind::Mock)
+            .with_contest("demo".into());
+        let actor = crate::mock::MockActor::new(conf.session());
+        let mut cnsl = Console::sink(Default::default());
+        let (_, problems, _) = actor.fetch(&conf.contest_id, &[], &mut cnsl).await?;
+        actor.login("alice".into(), "".into(), &mut cnsl).await?;
+        let source = "int main() {}\n";
+        let source_hash = hash_source(source);
+        let other_source = "int main() { return 0; }\n";
+
+        opt.check_duplicate(&actor, &problems[0], source, &source_hash, &conf, &mut cnsl)
+            .await?;
+        let lang_names = vec![LangName::from("C++ (GCC 9.2.1)")];
+        actor
+            .submit(
+                &conf.contest_id,
+                &problems[0],
+                &lang_names,
+                source,
+                false,
+                &mut cnsl,
+            )
+            .await?;
+        // compared with the latest submission on service regardless of local history
+        assert!(opt
+            .check_duplicate(
+                &actor,
+                &problems[0],
+                "int main() {}\r\n",
+                &source_hash,
+                &conf,
+                &mut cnsl
+            )
+            .await
+            .is_err());
+        opt.check_duplicate(
+            &actor,
+            &problems[0],
+            other_source,
+            &hash_source(other_source),
+            &conf,
+            &mut cnsl,
+        )
+        .await?;
+        opt.check_duplicate(&actor, &problems[1], source, &source_hash, &conf, &mut cnsl)
+            .await
+    }
+
+    #[test]
+    fn check_duplicate_in_history() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let opt = SubmitOpt::from_iter(&["submit", "c"]);
+        let source_hash = hash_source("int main() {}\n");
+        let problem_id = ProblemId::from("c");
+        run_with(&test_dir, |conf, cnsl| {
+            opt.check_duplicate_in_history(&problem_id, &source_hash, conf, cnsl)?;
+            History::new(conf.history_abs_path()).record(
+                &HistoryEntry::new(
+                    HistoryKind::Submit,
+                    conf.service_id,
+                    conf.contest_id.clone(),
+                    "C".into(),
+                    "submitted",
+                    None,
+                )
+                .with_source_hash(source_hash.clone()),
+            )?;
+            assert!(opt
+                .check_duplicate_in_history(&problem_id, &source_hash, conf, cnsl)
+                .is_err());
+            let other_hash = hash_source("int main() { return 0; }\n");
+            opt.check_duplicate_in_history(&problem_id, &other_hash, conf, cnsl)
+        })
+    }
+
+    #[test]
+    fn parse_retry_pending() {
+        let opt = SubmitOpt::from_iter(&["submit", "--retry-pending"]);
+        assert!(opt.retry_pending);
+        assert_eq!(opt.problem_id, None);
+        assert!(SubmitOpt::from_iter_safe(&["submit"]).is_err());
+        assert!(SubmitOpt::from_iter_safe(&["submit", "c", "--retry-pending"]).is_err());
+    }
+
+    #[test]
+    fn run_pending() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let opt = SubmitOpt::from_iter(&["submit", "--retry-pending"]);
+        let source_path = AbsPathBuf::try_new(test_dir.path())?.join("Main.cpp");
+        fs::write(source_path.as_ref(), "int main() {}\n")?;
+        let pending = |service_id, source: &str| {
+            PendingSubmission::new(
+                service_id,
+                "arc100".into(),
+                "C".into(),
+                vec!["C++ (GCC 9.2.1)".into()],
+                hash_source(source),
+                source_path.clone(),
+            )
+        };
+
+        let (outcome, remaining) = run_with(&test_dir, |conf, cnsl| {
+            let queue = SubmitQueue::new(conf.pending_submissions_abs_path());
+            // fails because of network error and is kept in the queue
+            queue.push(&pending(ServiceKind::Atcoder, "int main() {}\n"))?;
+            // fails because source file has been modified and is removed from the queue
+            queue.push(&pending(ServiceKind::Mock, "int main() { return 1; }\n"))?;
+            let outcome = opt.run_pending(&conf.with_offline(true), cnsl)?;
+            Ok((outcome, queue.load()?))
+        })?;
+        assert!(outcome.is_error());
+        let is_pending: Vec<_> = outcome
+            .submissions
+            .iter()
+            .map(|submission| submission.is_pending)
+            .collect();
+        assert_eq!(is_pending, vec![true, false]);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].service_id(), ServiceKind::Atcoder);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wait_verdict() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let conf = Config::default_in_dir(AbsPathBuf::try_new(test_dir.path())?)
+            .with_service(ServiceKind::Mock)
+            .with_contest("demo".into());
+        let actor = crate::mock::MockActor::new(conf.session());
+        let mut cnsl = Console::sink(Default::default());
+        let (_, problems, _) = actor.fetch(&conf.contest_id, &[], &mut cnsl).await?;
+        actor.login("alice".into(), "".into(), &mut cnsl).await?;
+        let lang_names = vec![LangName::from("C++ (GCC 9.2.1)")];
+
+        actor
+            .submit(
+                &conf.contest_id,
+                &problems[0],
+                &lang_names,
+                "",
+                false,
+                &mut cnsl,
+            )
+            .await?;
+        let verdict = wait_verdict(&actor, &problems[0], None, &conf, &mut cnsl).await?;
+        assert_eq!(verdict.as_deref(), Some("AC"));
+
+        actor
+            .submit(
+                &conf.contest_id,
+                &problems[0],
+                &lang_names,
+                "",
+                false,
+                &mut cnsl,
+            )
+            .await?;
+        let verdict = wait_verdict(&actor, &problems[0], Some("1"), &conf, &mut cnsl).await?;
+        assert_eq!(verdict.as_deref(), Some("AC"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_unsent() {
+        let err = ErrorKind::Network.tag(Error::msg("Could not resolve host"));
+        assert!(is_unsent(&err));
+        assert!(!is_unsent(&Error::msg("Found empty source file")));
+    }
+
+    #[test]
+    fn test_check_extension() {
+        let source_path = Path::new("/contests/abc100/c/Main.cpp");
+        assert!(check_extension(Path::new("snippet.cpp"), source_path).is_ok());
+        assert!(check_extension(Path::new("snippet.py"), source_path).is_err());
+        assert!(check_extension(Path::new("snippet"), source_path).is_err());
+    }
 
     #[test]
     #[ignore]
@@ -140,9 +1082,15 @@ mod tests {
         run_with(&test_dir, |conf, cnsl| fetch_opt.run(conf, cnsl))?;
 
         let opt = SubmitOpt {
-            problem_id: "c".into(),
+            problem_id: Some("c".into()),
             lang_name: None,
             need_open: false,
+            is_dry_run: false,
+            force: false,
+            from_clipboard: false,
+            file: None,
+            retry_pending: false,
+            wait: false,
         };
         run_with(&test_dir, |conf, cnsl| opt.run(conf, cnsl))?;
         Ok(())
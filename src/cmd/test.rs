@@ -1,37 +1,102 @@
+use std::collections::HashSet;
 use std::fmt;
+use std::fs;
 use std::io::Write as _;
+use std::path::Path;
+use std::process;
+use std::sync::mpsc;
 use std::time::Duration;
 
 use anyhow::{anyhow, Context as _};
-use serde::Serialize;
+use chrono::Local;
+use getset::{CopyGetters, Getters};
+use notify::{DebouncedEvent, RecursiveMode, Watcher as _};
+use serde::{Deserialize, Serialize};
 use structopt::StructOpt;
+use strum::VariantNames as _;
+use tempfile::tempdir;
 use tokio::time::Instant;
 
 use crate::atcoder::AtcoderActor;
-use crate::cmd::Outcome;
-use crate::judge::{Judge, StatusKind, TotalStatus};
-use crate::model::{AsSamples, ContestId, Problem, ProblemId, Service};
+use crate::cmd::{block_on, print_virtual_time, with_actor, Outcome};
+use crate::config::HookKind;
+use crate::error::ErrorKind;
+use crate::git;
+use crate::history::{History, HistoryEntry, HistoryKind};
+use crate::judge::{DiffView, Judge, StatusKind, TotalStatus};
+use crate::metrics::{Metrics, Phase};
+use crate::model::{AsSamples, Compare, ContestId, Problem, ProblemId, Sample, Service, TestSet};
 use crate::{Config, Console, Result};
 
 static DEFAULT_TIME_LIMIT_MS: u64 = 60 * 1000;
+static WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
 
 #[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
 #[structopt(rename_all = "kebab")]
 pub struct TestOpt {
     /// Id of the problem to be tested
     #[structopt(name = "problem")]
-    problem_id: ProblemId,
+    pub(crate) problem_id: ProblemId,
     /// If specified, uses only one sample
-    sample_name: Option<String>,
-    /// Tests using full testcases (only available for AtCoder)
+    pub(crate) sample_name: Option<String>,
+    /// Tests using full testcases (only available for AtCoder), same as `--set system`
     #[structopt(name = "full", long)]
-    is_full: bool,
+    pub(crate) is_full: bool,
+    /// Set of testcases to be tested
+    #[structopt(long = "set", possible_values = &TestSet::VARIANTS, conflicts_with = "full")]
+    pub(crate) test_set: Option<TestSet>,
     /// Outpus one line per one sample
     #[structopt(long)]
-    one_line: bool,
-    /// Overrides time limit (in millisecs) of the problem
+    pub(crate) one_line: bool,
+    /// Overrides time limit (in millisecs) of the problem, ignoring `test` settings in config file
+    #[structopt(long)]
+    pub(crate) time_limit: Option<u64>,
+    /// Tests again whenever source code or testcases change
+    #[structopt(long, short)]
+    pub(crate) watch: bool,
+    /// Overrides the way to compare outputs specified in problem file
+    #[structopt(long, possible_values = &Compare::VARIANTS)]
+    pub(crate) compare: Option<Compare>,
+    /// Overwrites expected outputs of failed custom samples with actual outputs after confirmation
+    #[structopt(long, conflicts_with_all = &["full", "set", "watch"])]
+    pub(crate) bless: bool,
+    /// Tests only full testcases that failed in the submission on service (e.g. 12345678)
+    #[structopt(long, value_name = "submission", conflicts_with_all = &["sample-name", "bless"])]
+    pub(crate) from_submission: Option<String>,
+    /// External diff tool launched with files of expected and actual outputs of failed samples (e.g. "vimdiff", "code --diff --wait")
+    #[structopt(long, env = "ACICK_DIFF_TOOL")]
+    pub(crate) diff_tool: Option<String>,
+    /// Overrides layout of diff of outputs specified in config file
+    #[structopt(long = "diff", possible_values = &DiffView::VARIANTS)]
+    pub(crate) diff_view: Option<DiffView>,
+    /// Builds with compile_debug command in config file (e.g. with sanitizers) instead of compile
     #[structopt(long)]
-    time_limit: Option<u64>,
+    pub(crate) debug: bool,
+    /// Tests all full testcases again, ignoring results cached for the same binary and testcases
+    #[structopt(long)]
+    pub(crate) no_cache: bool,
+}
+
+/// Launches external diff tool with files of expected and actual outputs, and waits for it.
+fn launch_diff_tool(diff_tool: &str, sample: &Sample, output: &str) -> Result<()> {
+    let mut words = diff_tool.split_whitespace();
+    let program = words.next().context("Diff tool is empty")?;
+    let dir = tempdir().context("Could not create temp dir for diff tool")?;
+    let expected = dir.path().join(format!("{}.expected", sample.name()));
+    let actual = dir.path().join(format!("{}.actual", sample.name()));
+    fs::write(&expected, sample.output()).context("Could not write expected output")?;
+    fs::write(&actual, output).context("Could not write actual output")?;
+    let exit_status = process::Command::new(program)
+        .args(words)
+        .arg(&expected)
+        .arg(&actual)
+        .status()
+        .with_context(|| format!("Could not run diff tool : {}", program))?;
+    // most diff tools return non-zero status when files differ, so only signals are errors
+    if exit_status.code().is_none() {
+        return Err(anyhow!("Diff tool was terminated : {}", exit_status));
+    }
+    Ok(())
 }
 
 fn testcase_or_sample(is_full: bool) -> &'static str {
@@ -44,10 +109,209 @@ fn testcase_or_sample(is_full: bool) -> &'static str {
 
 impl TestOpt {
     pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<TestOutcome> {
+        let problem_id = conf.resolve_problem_id(&self.problem_id, cnsl)?;
+        Self {
+            problem_id,
+            ..self.clone()
+        }
+        .run_resolved(conf, cnsl)
+    }
+
+    fn run_resolved(&self, conf: &Config, cnsl: &mut Console) -> Result<TestOutcome> {
+        // names of server testcases are the same as those of local full testcases
+        let testcase_names = match &self.from_submission {
+            Some(submission_id) => Some(self.fetch_failed_testcases(submission_id, conf, cnsl)?),
+            None => None,
+        };
+        let testcase_names = testcase_names.as_deref();
+        if self.watch {
+            self.run_watch(testcase_names, conf, cnsl)
+        } else {
+            block_on(self.run_once(testcase_names, conf, cnsl))
+        }
+    }
+
+    fn fetch_failed_testcases(
+        &self,
+        submission_id: &str,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<Vec<String>> {
+        let submission = with_actor(conf.service_id, conf.session(), |actor| {
+            block_on(actor.fetch_submission(&conf.contest_id, submission_id, cnsl))
+        })?;
+        if !submission
+            .task()
+            .starts_with(&format!("{} ", self.problem_id))
+        {
+            cnsl.warn(&format!(
+                "Submission {} may be for another problem : {}",
+                submission_id,
+                submission.task()
+            ))?;
+        }
+        let names: Vec<String> = submission
+            .failed_testcases()
+            .map(|testcase| testcase.name().to_owned())
+            .collect();
+        if names.is_empty() {
+            return Err(anyhow!(
+                "Found no failed testcases in submission {} ({})",
+                submission_id,
+                submission.result().verdict()
+            ));
+        }
+        writeln!(
+            cnsl,
+            "Testing {} testcases failed in submission {}",
+            names.len(),
+            submission_id
+        )?;
+        Ok(names)
+    }
+
+    fn test_set(&self) -> TestSet {
+        if self.is_full || self.from_submission.is_some() {
+            TestSet::System
+        } else {
+            self.test_set.unwrap_or_default()
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.test_set() != TestSet::Samples
+    }
+
+    fn run_watch(
+        &self,
+        testcase_names: Option<&[String]>,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<TestOutcome> {
+        let source_path = conf.source_abs_path(&self.problem_id)?;
+        let (samples_path, recursive_mode) =
+            match conf.test_set_abs_dir(&self.problem_id, self.test_set())? {
+                Some(testcases_dir) => (testcases_dir, RecursiveMode::Recursive),
+                None => {
+                    // samples are stored in problem file
+                    let problem_path = conf.problem_abs_path(&self.problem_id)?;
+                    (problem_path, RecursiveMode::NonRecursive)
+                }
+            };
+
+        // watch parent dir of source file because editors may replace the file
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::watcher(tx, WATCH_DEBOUNCE)?;
+        let source_dir = source_path
+            .parent()
+            .context("Could not get parent dir of source file")?;
+        watcher.watch(source_dir.as_ref(), RecursiveMode::NonRecursive)?;
+        watcher.watch(samples_path.as_ref(), recursive_mode)?;
+
+        let is_target =
+            |path: &Path| path == source_path.as_ref() || path.starts_with(samples_path.as_ref());
+        loop {
+            cnsl.clear_status_line()?;
+            let status = match block_on(self.run_once(testcase_names, conf, cnsl)) {
+                Ok(outcome) => {
+                    writeln!(cnsl, "{}", outcome)?;
+                    outcome.total.kind().as_ref().trim().to_owned()
+                }
+                Err(err) => {
+                    writeln!(cnsl, "{:?}", err)?;
+                    String::from("ERROR")
+                }
+            };
+            cnsl.set_status_line(&format!(
+                "[{}] {} : Watching for changes (press Ctrl-C to quit) ...",
+                Local::now().format("%H:%M:%S"),
+                status
+            ))?;
+
+            // wait until any target file changes
+            loop {
+                match rx.recv().context("Stopped watching files")? {
+                    DebouncedEvent::Create(path)
+                    | DebouncedEvent::Write(path)
+                    | DebouncedEvent::Remove(path)
+                    | DebouncedEvent::Rename(_, path)
+                        if is_target(&path) =>
+                    {
+                        break
+                    }
+                    DebouncedEvent::Error(err, _) => return Err(err.into()),
+                    _ => {}
+                }
+            }
+            writeln!(cnsl)?;
+        }
+    }
+
+    async fn run_once(
+        &self,
+        testcase_names: Option<&[String]>,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<TestOutcome> {
         let problem = conf.load_problem(&self.problem_id, cnsl)?;
         let problem_name = problem.name().to_owned();
 
-        let (total, compile_elapsed, test_elapsed) = self.compile_and_test(problem, conf, cnsl)?;
+        print_virtual_time(conf, cnsl)?;
+        conf.exec_hooks(HookKind::PreTest, &self.problem_id, None, cnsl)
+            .await?;
+        let (total, compile_elapsed, test_elapsed, outputs) = self
+            .compile_and_test(problem.clone(), testcase_names, conf, cnsl)
+            .await?;
+        let binary_hash = conf.binary_hash(&self.problem_id)?;
+        let blessed = if self.bless {
+            self.bless_samples(problem.clone(), outputs, conf, cnsl)?
+        } else {
+            Vec::new()
+        };
+
+        let verdict = format!("{:?}", total.kind()).to_uppercase();
+        conf.exec_hooks(HookKind::PostTest, &self.problem_id, Some(&verdict), cnsl)
+            .await?;
+        if self.is_full() {
+            let body = format!(
+                "{} {} : {} ({} testcases)",
+                conf.contest_id,
+                self.problem_id,
+                verdict,
+                total.count()
+            );
+            conf.notify("acick test", &body, cnsl)?;
+        }
+
+        // commit source code if passed all testcases
+        // (testing only failed testcases of a submission does not count)
+        if self.test_set() == TestSet::System
+            && self.from_submission.is_none()
+            && total.kind() == StatusKind::Ac
+        {
+            git::commit_source(conf, &problem, "AC", cnsl)
+                .await
+                .context("Could not commit source code")
+                // coerce error
+                .unwrap_or_else(|err| {
+                    cnsl.warn(&format!("{:#}", err)).unwrap_or(());
+                    false
+                });
+        }
+
+        // record history
+        History::new(conf.history_abs_path())
+            .record(&HistoryEntry::new(
+                HistoryKind::Test,
+                conf.service_id,
+                conf.contest_id.clone(),
+                self.problem_id.clone(),
+                verdict,
+                Some(test_elapsed),
+            ))
+            .context("Could not record history")
+            // coerce error
+            .unwrap_or_else(|err| cnsl.warn(&format!("{:#}", err)).unwrap_or(()));
 
         // build output
         Ok(TestOutcome {
@@ -58,21 +322,78 @@ impl TestOpt {
             total,
             compile_elapsed,
             test_elapsed,
-            is_full: self.is_full,
+            is_full: self.is_full(),
+            binary_hash,
+            blessed,
         })
     }
 
-    async fn compile(&self, conf: &Config) -> Result<Duration> {
+    /// Overwrites expected outputs of custom samples with actual outputs after confirmation.
+    fn bless_samples(
+        &self,
+        mut problem: Problem,
+        outputs: Vec<(String, String)>,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<Vec<String>> {
+        let mut samples = problem.samples().clone();
+        let mut blessed = Vec::new();
+        for (name, output) in outputs {
+            let sample = match samples.iter_mut().find(|sample| sample.name() == &name) {
+                Some(sample) => sample,
+                None => continue,
+            };
+            writeln!(cnsl, "actual output of sample {}:\n{}", name, output)?;
+            let message = format!("overwrite expected output of sample {}?", name);
+            if cnsl.confirm(&message, false)? {
+                *sample = Sample::new(name.as_str(), sample.input(), output);
+                blessed.push(name);
+            }
+        }
+        if !blessed.is_empty() {
+            problem.set_samples(samples);
+            conf.save_problem(&problem, true, cnsl)?;
+        }
+        Ok(blessed)
+    }
+
+    async fn compile(&self, conf: &Config, cnsl: &mut Console) -> Result<Duration> {
+        let compile = if self.debug {
+            Some(conf.exec_compile_debug(&self.problem_id)?)
+        } else {
+            conf.exec_compile(&self.problem_id)?
+        };
+        let mut compile = match compile {
+            Some(compile) => compile,
+            None => return Ok(Duration::from_secs(0)), // no compile for interpreted languages
+        };
+
+        // cache is not used if source code cannot be read, leaving the error to compile command
+        let cache = conf.compile_cache(&self.problem_id, self.debug).ok();
+        if let Some(cache) = &cache {
+            if cache.is_up_to_date()? {
+                writeln!(cnsl, "compile skipped (up to date)")?;
+                return Ok(Duration::from_secs(0));
+            }
+        }
+
         let started_at = Instant::now();
-        let mut compile = conf.exec_compile(&self.problem_id)?;
         let exit_status = compile.status().await?;
         let elapsed = started_at.elapsed();
+        Metrics::record(Phase::Compile, elapsed);
 
         if !exit_status.success() {
-            return Err(anyhow!(
+            return Err(ErrorKind::Compile.tag(anyhow!(
                 "Compile command returned non-zero status : {}",
                 exit_status
-            ));
+            )));
+        }
+        if let Some(cache) = cache {
+            cache
+                .save()
+                .context("Could not save compile cache")
+                // coerce error
+                .unwrap_or_else(|err| cnsl.warn(&format!("{:#}", err)).unwrap_or(()));
         }
         Ok(elapsed)
     }
@@ -80,16 +401,21 @@ impl TestOpt {
     async fn test(
         &self,
         problem: Problem,
+        testcase_names: Option<&[String]>,
         conf: &Config,
         cnsl: &mut Console,
-    ) -> Result<(TotalStatus, Duration)> {
+    ) -> Result<(TotalStatus, Duration, Vec<(String, String)>)> {
         let time_limit = self
             .time_limit
             .map(Duration::from_millis)
-            .or_else(|| problem.time_limit())
+            .or_else(|| {
+                problem
+                    .time_limit()
+                    .map(|time_limit| conf.test().scale_time_limit(time_limit))
+            })
             .unwrap_or_else(|| Duration::from_millis(DEFAULT_TIME_LIMIT_MS));
-        let compare = problem.compare();
-        let samples = self.load_samples(problem, conf)?;
+        let compare = self.compare.unwrap_or_else(|| problem.compare());
+        let samples = self.load_samples(problem, testcase_names, conf)?;
         let n_samples = samples.len();
         let max_sample_name_len = samples.max_name_len();
 
@@ -97,9 +423,22 @@ impl TestOpt {
             return Err(anyhow!("Found no samples"));
         }
 
+        let diff_conf = match self.diff_view {
+            Some(view) => conf.diff().with_view(view),
+            None => *conf.diff(),
+        };
+        let io_files = conf.io_files(&self.problem_id)?;
+        // results of full testcases are reused unless the binary or testcases change
+        let cache = if self.is_full() && !self.no_cache {
+            Some(conf.test_cache(&self.problem_id, time_limit, compare)?)
+        } else {
+            None
+        };
+
         // test source code with samples
         let started_at = Instant::now();
         let mut statuses = Vec::new();
+        let mut outputs = Vec::new();
         writeln!(cnsl)?;
         for (i, sample) in samples.enumerate() {
             let sample = sample?;
@@ -109,26 +448,93 @@ impl TestOpt {
                 "[{:>2}/{:>2}] {} {:>l$} ... ",
                 i + 1,
                 n_samples,
-                testcase_or_sample(self.is_full),
+                testcase_or_sample(self.is_full()),
                 sample.name(),
                 l = max_sample_name_len,
             )?;
-            let status = Judge::new(sample, time_limit, compare).test(run).await?;
-            writeln!(cnsl, "{}", status)?;
+            let blessable = self.bless && sample.is_custom();
+            let judge = |sample| {
+                Judge::new(sample, time_limit, compare, diff_conf)
+                    .with_output_filter(conf.test().output_filter())
+                    .with_io_files(io_files.clone())
+            };
+            // output of the test run is needed to bless the sample or to launch diff tool
+            let keep_output = blessable || self.diff_tool.is_some();
+            let cached = match &cache {
+                Some(cache) if !blessable => cache.get(&sample)?,
+                _ => None,
+            };
+            let (status, output) = match cached {
+                Some(status) => {
+                    writeln!(cnsl, "{} (cached)", status)?;
+                    (status, None)
+                }
+                None => {
+                    let (status, output) = if keep_output {
+                        judge(sample.clone()).test_with_output(run).await?
+                    } else {
+                        (judge(sample.clone()).test(run).await?, None)
+                    };
+                    writeln!(cnsl, "{}", status)?;
+                    if let Some(cache) = &cache {
+                        cache
+                            .save(&sample, &status)
+                            .context("Could not save test cache")
+                            // coerce error
+                            .unwrap_or_else(|err| cnsl.warn(&format!("{:#}", err)).unwrap_or(()));
+                    }
+                    (status, output)
+                }
+            };
             if !self.one_line {
                 status.describe(cnsl)?;
             }
+            if let (Some(diff_tool), StatusKind::Wa) = (&self.diff_tool, status.kind()) {
+                // cached status has no output, so the binary is run again
+                let output = match &output {
+                    Some(output) => Ok(output.clone()),
+                    None => {
+                        let run = conf.exec_run(&self.problem_id)?;
+                        judge(sample.clone()).run(run).await
+                    }
+                };
+                if let Err(err) =
+                    output.and_then(|output| launch_diff_tool(diff_tool, &sample, &output))
+                {
+                    cnsl.warn(&format!("Could not launch diff tool : {:#}", err))?;
+                }
+            }
+            if blessable && status.kind() != StatusKind::Ac {
+                // bless exactly the output judged above rather than output of another run
+                match output {
+                    Some(output) => outputs.push((sample.name().to_owned(), output)),
+                    None => cnsl.warn(&format!(
+                        "Could not bless sample {} : binary did not exit successfully",
+                        sample.name()
+                    ))?,
+                }
+            }
             statuses.push(status);
         }
         let elapsed = started_at.elapsed();
+        Metrics::record(Phase::Judge, elapsed);
 
         let total = TotalStatus::new(statuses);
-        Ok((total, elapsed))
+        Ok((total, elapsed, outputs))
     }
 
-    fn load_samples(&self, problem: Problem, conf: &Config) -> Result<Box<dyn AsSamples>> {
-        if self.is_full {
+    fn load_samples(
+        &self,
+        problem: Problem,
+        testcase_names: Option<&[String]>,
+        conf: &Config,
+    ) -> Result<Box<dyn AsSamples>> {
+        if let Some(testcase_names) = testcase_names {
             let testcases_dir = conf.testcases_abs_dir(problem.id())?;
+            let testcases =
+                AtcoderActor::load_testcases_with_names(testcases_dir, testcase_names.to_vec());
+            Ok(Box::new(testcases))
+        } else if let Some(testcases_dir) = conf.test_set_abs_dir(problem.id(), self.test_set())? {
             let testcases = AtcoderActor::load_testcases(testcases_dir, &self.sample_name)?;
             Ok(Box::new(testcases))
         } else {
@@ -136,29 +542,124 @@ impl TestOpt {
         }
     }
 
-    #[tokio::main]
     async fn compile_and_test(
         &self,
         problem: Problem,
+        testcase_names: Option<&[String]>,
         conf: &Config,
         cnsl: &mut Console,
-    ) -> Result<(TotalStatus, Duration, Duration)> {
-        let compile_elapsed = self.compile(conf).await.context("Failed to compile")?;
-        let (total, test_elapsed) = self.test(problem, conf, cnsl).await?;
-        Ok((total, compile_elapsed, test_elapsed))
+    ) -> Result<(TotalStatus, Duration, Duration, Vec<(String, String)>)> {
+        let compile_elapsed = self
+            .compile(conf, cnsl)
+            .await
+            .context("Failed to compile")?;
+        let (total, test_elapsed, outputs) = self.test(problem, testcase_names, conf, cnsl).await?;
+        Ok((total, compile_elapsed, test_elapsed, outputs))
     }
 }
 
-#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TestOutcome {
+    #[get = "pub"]
     service: Service,
+    #[get = "pub"]
     contest_id: ContestId,
+    #[get = "pub"]
     problem_id: ProblemId,
+    #[get = "pub"]
     problem_name: String,
+    #[get = "pub"]
     total: TotalStatus,
+    #[get_copy = "pub"]
     compile_elapsed: Duration,
+    #[get_copy = "pub"]
     test_elapsed: Duration,
+    #[get_copy = "pub"]
     is_full: bool,
+    /// Hash of the source code and artifact that were tested
+    #[get = "pub"]
+    binary_hash: String,
+    /// Names of samples whose expected outputs are overwritten by `--bless`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[get = "pub"]
+    blessed: Vec<String>,
+}
+
+impl TestOutcome {
+    /// Merges outcomes of shards that tested the same problem into one outcome.
+    ///
+    /// Compile time is the longest one and test time is the sum of all shards.
+    pub fn merge(outcomes: Vec<TestOutcome>) -> Result<Self> {
+        let mut outcomes = outcomes.into_iter();
+        let mut merged = outcomes.next().context("Found no outcomes to merge")?;
+        let mut statuses = merged.total.clone().into_statuses();
+        let mut sample_names: HashSet<String> = statuses
+            .iter()
+            .map(|status| status.sample_name().to_owned())
+            .collect();
+        for outcome in outcomes {
+            if (&outcome.service, &outcome.contest_id, &outcome.problem_id)
+                != (&merged.service, &merged.contest_id, &merged.problem_id)
+            {
+                return Err(anyhow!(
+                    "Found outcome of another problem : {} {} {}",
+                    outcome.service.id(),
+                    outcome.contest_id,
+                    outcome.problem_id
+                ));
+            }
+            if outcome.is_full != merged.is_full {
+                return Err(anyhow!(
+                    "Could not merge outcomes of samples and full testcases"
+                ));
+            }
+            if outcome.binary_hash != merged.binary_hash {
+                return Err(anyhow!(
+                    "Could not merge outcomes of different binaries : {} and {}",
+                    merged.binary_hash,
+                    outcome.binary_hash
+                ));
+            }
+            for status in outcome.total.into_statuses() {
+                if !sample_names.insert(status.sample_name().to_owned()) {
+                    return Err(anyhow!(
+                        "Found {} tested in multiple outcomes : {}",
+                        testcase_or_sample(merged.is_full),
+                        status.sample_name()
+                    ));
+                }
+                statuses.push(status);
+            }
+            merged.compile_elapsed = merged.compile_elapsed.max(outcome.compile_elapsed);
+            merged.test_elapsed += outcome.test_elapsed;
+            merged.blessed.extend(outcome.blessed);
+        }
+        merged.total = TotalStatus::new(statuses);
+        Ok(merged)
+    }
+}
+
+#[cfg(test)]
+impl TestOutcome {
+    /// Returns outcome of a shard that passed the samples, which is used to test merge.
+    pub(crate) fn shard(problem_id: &str, sample_names: &[&str], binary_hash: &str) -> Self {
+        let statuses = sample_names
+            .iter()
+            .map(|&name| crate::judge::Status::ac(name.to_owned(), Duration::from_millis(10)))
+            .collect();
+        Self {
+            service: Service::default(),
+            contest_id: "arc100".into(),
+            problem_id: problem_id.into(),
+            problem_name: "Linear Approximation".into(),
+            total: TotalStatus::new(statuses),
+            compile_elapsed: Duration::from_secs(1),
+            test_elapsed: Duration::from_secs(2),
+            is_full: true,
+            binary_hash: binary_hash.into(),
+            blessed: Vec::new(),
+        }
+    }
 }
 
 impl fmt::Display for TestOutcome {
@@ -175,7 +676,11 @@ impl fmt::Display for TestOutcome {
             (self.compile_elapsed.as_secs_f32()),
             (self.test_elapsed.as_secs_f32()),
         )?;
-        write!(f, "{}", self.total)
+        write!(f, "{}", self.total)?;
+        if !self.blessed.is_empty() {
+            write!(f, "\nBlessed samples : {}", self.blessed.join(", "))?;
+        }
+        Ok(())
     }
 }
 
@@ -183,6 +688,20 @@ impl Outcome for TestOutcome {
     fn is_error(&self) -> bool {
         self.total.kind() != StatusKind::Ac
     }
+
+    fn error_kind(&self) -> ErrorKind {
+        error_kind_of(self.total.kind())
+    }
+}
+
+/// Maps verdict of judge to kind of error.
+pub(crate) fn error_kind_of(kind: StatusKind) -> ErrorKind {
+    match kind {
+        StatusKind::Ac => ErrorKind::Unknown,
+        StatusKind::Wa => ErrorKind::WrongAnswer,
+        StatusKind::Tle => ErrorKind::TimeLimitExceeded,
+        StatusKind::Re => ErrorKind::RuntimeError,
+    }
 }
 
 #[cfg(test)]
@@ -203,10 +722,190 @@ mod tests {
             problem_id: "c".into(),
             sample_name: None,
             is_full: false,
+            test_set: None,
             one_line: false,
             time_limit: None,
+            watch: false,
+            compare: None,
+            bless: false,
+            from_submission: None,
+            diff_tool: None,
+            diff_view: None,
+            debug: false,
+            no_cache: false,
         };
         run_with(&test_dir, |conf, cnsl| opt.run(conf, cnsl))?;
         Ok(())
     }
+
+    #[test]
+    fn run_bless() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let source = "#include <iostream>\nint main() { int n; std::cin >> n; std::cout << n * 2 << std::endl; }\n";
+        std::fs::create_dir_all(test_dir.path().join("atcoder/arc100/c"))?;
+        std::fs::write(test_dir.path().join("atcoder/arc100/c/Main.cpp"), source)?;
+
+        let opt = TestOpt {
+            problem_id: "c".into(),
+            sample_name: None,
+            is_full: false,
+            test_set: None,
+            one_line: true,
+            time_limit: None,
+            watch: false,
+            compare: None,
+            bless: true,
+            from_submission: None,
+            diff_tool: None,
+            diff_view: None,
+            debug: false,
+            no_cache: false,
+        };
+        let (outcome, problem) = run_with(&test_dir, |conf, cnsl| {
+            let mut problem = Problem::default();
+            problem.set_samples(vec![
+                Sample::new("1", "3\n", "0\n"),
+                Sample::new("custom-1", "5\n", "\n"),
+            ]);
+            conf.save_problem(&problem, true, cnsl)?;
+            let outcome = opt.run(conf, cnsl)?;
+            Ok((outcome, conf.load_problem(&"c".into(), cnsl)?))
+        })?;
+        assert_eq!(outcome.blessed, vec!["custom-1"]);
+        // samples fetched from service are never blessed
+        assert_eq!(problem.samples()[0].output(), "0\n");
+        assert_eq!(problem.samples()[1].output(), "10\n");
+        Ok(())
+    }
+
+    #[test]
+    fn run_offline() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let source = "#include <iostream>\nint main() { int n; std::cin >> n; std::cout << n * 2 << std::endl; }\n";
+        std::fs::create_dir_all(test_dir.path().join("atcoder/arc100/c"))?;
+        std::fs::write(test_dir.path().join("atcoder/arc100/c/Main.cpp"), source)?;
+
+        let mut opt = TestOpt {
+            problem_id: "c".into(),
+            sample_name: None,
+            is_full: false,
+            test_set: None,
+            one_line: true,
+            time_limit: None,
+            watch: false,
+            compare: None,
+            bless: false,
+            from_submission: None,
+            diff_tool: None,
+            diff_view: None,
+            debug: false,
+            no_cache: false,
+        };
+        let outcome = run_with(&test_dir, |conf, cnsl| {
+            let mut problem = Problem::default();
+            problem.set_samples(vec![Sample::new("1", "3\n", "6\n")]);
+            conf.save_problem(&problem, true, cnsl)?;
+            opt.run(&conf.with_offline(true), cnsl)
+        })?;
+        assert!(!outcome.is_error());
+
+        // failed testcases of submission are fetched from service
+        opt.from_submission = Some("1234".into());
+        let err = run_with(&test_dir, |conf, cnsl| {
+            opt.run(&conf.with_offline(true), cnsl)
+        })
+        .unwrap_err();
+        assert_eq!(ErrorKind::of(&err), ErrorKind::Network);
+        Ok(())
+    }
+
+    #[test]
+    fn run_once_with_testcase_names() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let source = "#include <iostream>\nint main() { int n; std::cin >> n; std::cout << n * 2 << std::endl; }\n";
+        std::fs::create_dir_all(test_dir.path().join("atcoder/arc100/c"))?;
+        std::fs::write(test_dir.path().join("atcoder/arc100/c/Main.cpp"), source)?;
+        for (inout, content) in &[("in", "3\n"), ("out", "6\n")] {
+            let dir = test_dir
+                .path()
+                .join("atcoder/arc100/c/testcases")
+                .join(inout);
+            std::fs::create_dir_all(&dir)?;
+            for name in &["sample_01", "subtask_1_01", "subtask_1_02"] {
+                std::fs::write(dir.join(format!("{}.txt", name)), content)?;
+            }
+        }
+
+        let opt = TestOpt {
+            problem_id: "c".into(),
+            sample_name: None,
+            is_full: false,
+            test_set: None,
+            one_line: true,
+            time_limit: None,
+            watch: false,
+            compare: None,
+            bless: false,
+            from_submission: Some("1234".into()),
+            diff_tool: None,
+            diff_view: None,
+            debug: false,
+            no_cache: false,
+        };
+        let names = vec!["subtask_1_01".to_owned(), "subtask_1_02".to_owned()];
+        let outcome = run_with(&test_dir, |conf, cnsl| {
+            conf.save_problem(&Problem::default(), true, cnsl)?;
+            block_on(opt.run_once(Some(&names), conf, cnsl))
+        })?;
+        assert!(outcome.is_full);
+        let tested: Vec<_> = outcome
+            .total
+            .into_statuses()
+            .iter()
+            .map(|status| status.sample_name().to_owned())
+            .collect();
+        assert_eq!(tested, names);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge() -> anyhow::Result<()> {
+        let merged = TestOutcome::merge(vec![
+            TestOutcome::shard("c", &["00", "01"], "0"),
+            TestOutcome::shard("c", &["02"], "0"),
+        ])?;
+        assert_eq!(merged.total.count(), 3);
+        assert_eq!(merged.compile_elapsed, Duration::from_secs(1));
+        assert_eq!(merged.test_elapsed, Duration::from_secs(4));
+
+        assert!(TestOutcome::merge(vec![
+            TestOutcome::shard("c", &["00"], "0"),
+            TestOutcome::shard("d", &["01"], "0")
+        ])
+        .is_err());
+        assert!(TestOutcome::merge(vec![
+            TestOutcome::shard("c", &["00"], "0"),
+            TestOutcome::shard("c", &["00"], "0")
+        ])
+        .is_err());
+        // shards must test the same binary
+        assert!(TestOutcome::merge(vec![
+            TestOutcome::shard("c", &["00"], "0"),
+            TestOutcome::shard("c", &["01"], "1")
+        ])
+        .is_err());
+        assert!(TestOutcome::merge(Vec::new()).is_err());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_launch_diff_tool() -> anyhow::Result<()> {
+        let sample = Sample::new("sample 1", "1\n", "2\n");
+        // diff returns non-zero status because the outputs differ
+        launch_diff_tool("diff -u", &sample, "3\n")?;
+        assert!(launch_diff_tool("", &sample, "3\n").is_err());
+        assert!(launch_diff_tool("acick-no-such-diff-tool", &sample, "3\n").is_err());
+        Ok(())
+    }
 }
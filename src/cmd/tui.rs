@@ -0,0 +1,328 @@
+use std::fmt;
+use std::io::Write as _;
+
+use anyhow::anyhow;
+use chrono::Local;
+use console::{style, Key, Term};
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::cmd::{block_on, with_actor, FetchOpt, Outcome, SubmitOpt, TestOpt};
+use crate::console::{sty_dim, sty_g, sty_r};
+use crate::history::{History, HistoryEntry, HistoryKind};
+use crate::model::{ContestId, ContestInfo, ProblemId, Service};
+use crate::{Config, Console, Result};
+
+static KEY_HELP: &str = "[↑/k ↓/j] select  [f] fetch  [t] test  [s] submit  [r] reload  [q] quit";
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct TuiOpt {
+    /// Id of the problem selected at start
+    #[structopt(name = "problem")]
+    problem_id: Option<ProblemId>,
+}
+
+impl TuiOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<TuiOutcome> {
+        let term = Term::stderr();
+        if !term.is_term() {
+            return Err(anyhow!("Dashboard is only available on terminal"));
+        }
+
+        // schedule is used only to show remaining time
+        let info = match with_actor(conf.service_id, conf.session(), |actor| {
+            block_on(actor.fetch_contest_info(&conf.contest_id, cnsl))
+        }) {
+            Ok(info) => Some(info),
+            // coerce error
+            Err(err) => {
+                writeln!(cnsl, "Could not check contest schedule: {}", err)?;
+                None
+            }
+        };
+
+        let mut dashboard = Dashboard {
+            conf,
+            info,
+            rows: Vec::new(),
+            selected: 0,
+            message: None,
+        };
+        dashboard.reload()?;
+        if let Some(problem_id) = &self.problem_id {
+            dashboard.select(problem_id);
+        }
+
+        let mut commands = 0;
+        term.hide_cursor()?;
+        let result = dashboard.run_loop(&term, &mut commands, cnsl);
+        term.show_cursor()?;
+        term.clear_screen()?;
+        result?;
+
+        Ok(TuiOutcome {
+            service: Service::new(conf.service_id),
+            contest_id: conf.contest_id.clone(),
+            commands,
+        })
+    }
+}
+
+/// Action bound to key in dashboard.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Action {
+    Fetch,
+    Test,
+    Submit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ProblemRow {
+    problem_id: ProblemId,
+    test: Option<HistoryEntry>,
+    submit: Option<HistoryEntry>,
+}
+
+#[derive(Debug)]
+struct Dashboard<'a> {
+    conf: &'a Config,
+    info: Option<ContestInfo>,
+    rows: Vec<ProblemRow>,
+    selected: usize,
+    message: Option<String>,
+}
+
+impl Dashboard<'_> {
+    /// Loads problems of the contest stored locally and their latest verdicts in history.
+    fn reload(&mut self) -> Result<()> {
+        let conf = self.conf;
+        let mut problem_ids: Vec<ProblemId> = conf
+            .search_problems()?
+            .into_iter()
+            .filter(|(contest_id, _)| contest_id == &conf.contest_id)
+            .map(|(_, problem_id)| problem_id)
+            .collect();
+        problem_ids.sort();
+        problem_ids.dedup();
+
+        let entries = History::new(conf.history_abs_path()).load()?;
+        let latest = |problem_id: &ProblemId, kind: HistoryKind| {
+            entries
+                .iter()
+                .rev()
+                .find(|entry| {
+                    entry.kind() == kind
+                        && entry.service_id() == conf.service_id
+                        && entry.contest_id() == &conf.contest_id
+                        && entry.problem_id() == problem_id
+                })
+                .cloned()
+        };
+        self.rows = problem_ids
+            .into_iter()
+            .map(|problem_id| ProblemRow {
+                test: latest(&problem_id, HistoryKind::Test),
+                submit: latest(&problem_id, HistoryKind::Submit),
+                problem_id,
+            })
+            .collect();
+        self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+        Ok(())
+    }
+
+    fn select(&mut self, problem_id: &ProblemId) {
+        if let Some(i) = self
+            .rows
+            .iter()
+            .position(|row| &row.problem_id == problem_id)
+        {
+            self.selected = i;
+        }
+    }
+
+    fn selected_problem(&self) -> Option<&ProblemId> {
+        self.rows.get(self.selected).map(|row| &row.problem_id)
+    }
+
+    fn run_loop(&mut self, term: &Term, commands: &mut usize, cnsl: &mut Console) -> Result<()> {
+        loop {
+            self.draw(term)?;
+            let action = match term.read_key()? {
+                Key::ArrowUp | Key::Char('k') => {
+                    self.selected = self.selected.saturating_sub(1);
+                    continue;
+                }
+                Key::ArrowDown | Key::Char('j') => {
+                    if self.selected + 1 < self.rows.len() {
+                        self.selected += 1;
+                    }
+                    continue;
+                }
+                Key::Char('r') => {
+                    self.message = None;
+                    self.reload()?;
+                    continue;
+                }
+                Key::Char('q') | Key::Escape => return Ok(()),
+                Key::Char('f') => Action::Fetch,
+                Key::Char('t') => Action::Test,
+                Key::Char('s') => Action::Submit,
+                _ => continue,
+            };
+
+            // commands run on plain screen so that their messages and prompts are shown as usual
+            term.clear_screen()?;
+            term.show_cursor()?;
+            self.message = Some(self.exec(action, cnsl));
+            *commands += 1;
+            writeln!(
+                cnsl,
+                "\n{}",
+                sty_dim("Press any key to return to dashboard")
+            )?;
+            term.read_key()?;
+            term.hide_cursor()?;
+            self.reload()?;
+        }
+    }
+
+    /// Runs command of the action and returns message that describes the result.
+    fn exec(&self, action: Action, cnsl: &mut Console) -> String {
+        let conf = self.conf;
+        let problem_id = self.selected_problem().map(|id| id.as_ref().to_owned());
+        let result = match (action, problem_id) {
+            (Action::Fetch, _) => FetchOpt::from_iter(&["fetch"])
+                .run(conf, cnsl)
+                .map(|outcome| outcome.to_string()),
+            (Action::Test, Some(problem_id)) => TestOpt::from_iter(&["test", problem_id.as_str()])
+                .run(conf, cnsl)
+                .map(|outcome| format!("test {} : {}", problem_id, outcome.total().kind())),
+            (Action::Submit, Some(problem_id)) => {
+                SubmitOpt::from_iter(&["submit", problem_id.as_str()])
+                    .run(conf, cnsl)
+                    .map(|outcome| outcome.to_string())
+            }
+            (_, None) => Err(anyhow!("Found no problems. Fetch problems first.")),
+        };
+        let message = match result {
+            Ok(message) => message,
+            Err(err) => format!("Error: {:#}", err),
+        };
+        writeln!(cnsl, "{}", message).unwrap_or(());
+        message
+    }
+
+    fn draw(&self, term: &Term) -> Result<()> {
+        term.clear_screen()?;
+        let conf = self.conf;
+        let mut header = format!("{} {}", conf.service_id, conf.contest_id);
+        if let Some(info) = &self.info {
+            let now = Local::now();
+            header += &format!(" ({})", info.phase_at(now));
+            if let Some(remaining) = info.remaining_at(now) {
+                let secs = remaining.as_secs();
+                header += &format!(
+                    " remaining {:02}:{:02}:{:02}",
+                    secs / 3600,
+                    secs / 60 % 60,
+                    secs % 60
+                );
+            }
+        }
+        term.write_line(&style(header).bold().to_string())?;
+        term.write_line("")?;
+        term.write_line(&format!(
+            "  {:<10} {:<16} {:<16}",
+            "problem", "test", "submit"
+        ))?;
+        if self.rows.is_empty() {
+            term.write_line(&sty_dim("  (no problems fetched)").to_string())?;
+        }
+        for (i, row) in self.rows.iter().enumerate() {
+            let line = format!(
+                "{:<10} {:<16} {:<16}",
+                row.problem_id,
+                fmt_verdict(&row.test),
+                fmt_verdict(&row.submit)
+            );
+            if i == self.selected {
+                term.write_line(&format!("> {}", style(line).reverse()))?;
+            } else {
+                term.write_line(&format!("  {}", line))?;
+            }
+        }
+        term.write_line("")?;
+        if let Some(message) = &self.message {
+            term.write_line(message.lines().last().unwrap_or(""))?;
+        }
+        term.write_line(&sty_dim(KEY_HELP).to_string())?;
+        Ok(())
+    }
+}
+
+/// Formats verdict of history entry with time at which it was recorded.
+fn fmt_verdict(entry: &Option<HistoryEntry>) -> String {
+    let entry = match entry {
+        Some(entry) => entry,
+        None => return String::from("-"),
+    };
+    let verdict = format!(
+        "{:<4} {}",
+        entry.verdict(),
+        entry.recorded_at().format("%H:%M")
+    );
+    // pad before styling so that escape sequences do not break alignment
+    let verdict = format!("{:<16}", verdict);
+    if entry.verdict() == "AC" {
+        sty_g(verdict).to_string()
+    } else {
+        sty_r(verdict).to_string()
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TuiOutcome {
+    service: Service,
+    contest_id: ContestId,
+    commands: usize,
+}
+
+impl fmt::Display for TuiOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Closed dashboard of {} {} after running {} commands",
+            self.service.id(),
+            self.contest_id,
+            self.commands
+        )
+    }
+}
+
+impl Outcome for TuiOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fmt_verdict() {
+        assert_eq!(fmt_verdict(&None), "-");
+        let entry = HistoryEntry::new(
+            HistoryKind::Test,
+            Default::default(),
+            "arc100".into(),
+            "C".into(),
+            "AC",
+            None,
+        );
+        let verdict = console::strip_ansi_codes(&fmt_verdict(&Some(entry))).into_owned();
+        assert!(verdict.starts_with("AC   "));
+        assert_eq!(verdict.len(), 16);
+    }
+}
@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _};
+use serde::Serialize;
+use structopt::StructOpt;
+use tempfile::tempdir;
+use tokio::process::Command;
+
+use crate::abs_path::AbsPathBuf;
+use crate::atcoder::AtcoderActor;
+use crate::cmd::{block_on, with_atcoder_actor, Outcome};
+use crate::error::ErrorKind;
+use crate::judge::{Judge, StatusKind, TotalStatus};
+use crate::model::{ContestId, Problem, Service, ServiceKind};
+use crate::service::Act as _;
+use crate::{Config, Console, Result};
+
+static DEFAULT_COMPILE: &str = r#"g++ -std=gnu++17 -O2 -I "$LIBRARY_DIR" -o "$ARTIFACT" "$SOURCE""#;
+static DEFAULT_RUN: &str = r#""$ARTIFACT""#;
+static DEFAULT_TIME_LIMIT: Duration = Duration::from_secs(10);
+static ATCODER_TASK_URL_PREFIX: &str = "https://atcoder.jp/contests/";
+static AOJ_PROBLEM_URL_PREFIXES: &[&str] = &[
+    "https://onlinejudge.u-aizu.ac.jp/problems/",
+    "https://onlinejudge.u-aizu.ac.jp/courses/",
+    "https://judge.u-aizu.ac.jp/onlinejudge/description.jsp?id=",
+];
+
+// annotations of competitive-verifier and oj-verify-helper
+static ANNOTATIONS: &[&str] = &["competitive-verifier: PROBLEM", "#define PROBLEM"];
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct VerifyLibOpt {
+    /// Directory of library to be verified [default: current directory]
+    #[structopt(name = "dir")]
+    dir: Option<PathBuf>,
+    /// Extensions of verification files
+    #[structopt(long, default_value = "cpp", use_delimiter = true)]
+    extensions: Vec<String>,
+    /// Shell script to compile verification file (SOURCE, ARTIFACT and LIBRARY_DIR are set)
+    #[structopt(long, default_value = DEFAULT_COMPILE)]
+    compile: String,
+    /// Shell script to run the binary built with the compile script
+    #[structopt(long, default_value = DEFAULT_RUN)]
+    run: String,
+}
+
+impl VerifyLibOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<VerifyLibOutcome> {
+        let lib_dir = match &self.dir {
+            Some(dir) => AbsPathBuf::cwd()?.join(dir),
+            None => AbsPathBuf::cwd()?,
+        };
+        let mut sources = Vec::new();
+        self.search_sources(&lib_dir, &mut sources)?;
+        sources.sort_by(|(a, _), (b, _)| a.as_ref().cmp(b.as_ref()));
+
+        let files = with_atcoder_actor(conf.session(), |actor| {
+            let mut problems_cache = HashMap::new();
+            let mut files = Vec::new();
+            for (source, problem_url) in sources {
+                let path = source.strip_prefix(&lib_dir).to_owned();
+                writeln!(cnsl, "Verifying {} ...", path.display())?;
+                let result = block_on(fetch_problem(
+                    actor,
+                    &mut problems_cache,
+                    &problem_url,
+                    cnsl,
+                ))
+                .and_then(|problem| match problem {
+                    Some(problem) => self.verify_file(problem, &lib_dir, &source, conf).map(Some),
+                    None => Ok(None),
+                });
+                let (status, total, message) = match result {
+                    Ok(Some(total)) if total.kind() == StatusKind::Ac => {
+                        (VerifyStatus::Verified, Some(total), None)
+                    }
+                    Ok(Some(total)) => (VerifyStatus::Failed, Some(total), None),
+                    Ok(None) => (
+                        VerifyStatus::Skipped,
+                        None,
+                        Some(String::from("unsupported problem url")),
+                    ),
+                    Err(err) => (VerifyStatus::Failed, None, Some(format!("{:#}", err))),
+                };
+                writeln!(cnsl, "{}", status)?;
+                files.push(VerifiedFile {
+                    path,
+                    problem_url,
+                    status,
+                    total,
+                    message,
+                });
+            }
+            Ok(files)
+        })?;
+
+        Ok(VerifyLibOutcome {
+            service: Service::new(ServiceKind::Atcoder),
+            files,
+        })
+    }
+
+    /// Searches `dir` recursively for files that have annotation of problem url.
+    fn search_sources(
+        &self,
+        dir: &AbsPathBuf,
+        sources: &mut Vec<(AbsPathBuf, String)>,
+    ) -> Result<()> {
+        for entry in fs::read_dir(dir.as_ref())
+            .with_context(|| format!("Could not list files in {}", dir))?
+        {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            if file_name.to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = dir.join(&file_name);
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                self.search_sources(&path, sources)?;
+                continue;
+            }
+            let ext = path.as_ref().extension().and_then(|ext| ext.to_str());
+            if !file_type.is_file() || !self.extensions.iter().any(|e| Some(e.as_str()) == ext) {
+                continue;
+            }
+            // skip files that are not valid UTF-8
+            if let Ok(content) = fs::read_to_string(path.as_ref()) {
+                if let Some(problem_url) = find_problem_url(&content) {
+                    sources.push((path, problem_url));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn verify_file(
+        &self,
+        problem: Problem,
+        lib_dir: &AbsPathBuf,
+        source: &AbsPathBuf,
+        conf: &Config,
+    ) -> Result<TotalStatus> {
+        if problem.samples().is_empty() {
+            return Err(anyhow!("Found no testcases"));
+        }
+
+        let artifact_dir = tempdir().context("Could not create temp dir for binary")?;
+        let artifact = artifact_dir.path().join("a.out");
+        let env = [
+            ("SOURCE", source.as_ref().as_os_str()),
+            ("ARTIFACT", artifact.as_os_str()),
+            ("LIBRARY_DIR", lib_dir.as_ref().as_os_str()),
+        ];
+        let exec = |script: &str| -> Result<Command> {
+            let mut command = conf.exec_shell(script)?;
+            command
+                .current_dir(lib_dir.as_ref())
+                .envs(env.iter().copied());
+            Ok(command)
+        };
+        block_on(self.compile_and_test(problem, exec, conf))
+    }
+
+    async fn compile_and_test(
+        &self,
+        problem: Problem,
+        exec: impl Fn(&str) -> Result<Command>,
+        conf: &Config,
+    ) -> Result<TotalStatus> {
+        let exit_status = exec(&self.compile)?.status().await?;
+        if !exit_status.success() {
+            return Err(ErrorKind::Compile.tag(anyhow!(
+                "Compile command returned non-zero status : {}",
+                exit_status
+            )));
+        }
+
+        let time_limit = problem.time_limit().unwrap_or(DEFAULT_TIME_LIMIT);
+        let compare = problem.compare();
+        let mut statuses = Vec::new();
+        for sample in problem.samples().iter().cloned() {
+            let status = Judge::new(sample, time_limit, compare, *conf.diff())
+                .with_output_filter(conf.test().output_filter())
+                .test(exec(&self.run)?)
+                .await?;
+            statuses.push(status);
+        }
+        Ok(TotalStatus::new(statuses))
+    }
+}
+
+/// Fetches problem of the url with testcases to verify with,
+/// which are samples on AtCoder and all of the judge data on AOJ.
+///
+/// Returns `None` if the problem is not on supported services.
+async fn fetch_problem(
+    actor: &AtcoderActor,
+    problems_cache: &mut HashMap<ContestId, Vec<Problem>>,
+    problem_url: &str,
+    cnsl: &mut Console,
+) -> Result<Option<Problem>> {
+    if let Some(problem_id) = parse_aoj_problem_url(problem_url) {
+        return Ok(Some(actor.fetch_aoj_problem(&problem_id, cnsl).await?));
+    }
+    let (contest_id, url_name) = match parse_atcoder_task_url(problem_url) {
+        Some(ids) => ids,
+        None => return Ok(None),
+    };
+    if !problems_cache.contains_key(&contest_id) {
+        let (_, problems, _) = actor.fetch(&contest_id, &[], cnsl).await?;
+        problems_cache.insert(contest_id.clone(), problems);
+    }
+    let problem = problems_cache[&contest_id]
+        .iter()
+        .find(|problem| problem.url_name() == &url_name)
+        .ok_or_else(|| anyhow!("Could not find problem in contest : {}", problem_url))?;
+    Ok(Some(problem.clone()))
+}
+
+/// Finds url of problem annotated in verification file.
+fn find_problem_url(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let pos = ANNOTATIONS
+            .iter()
+            .find_map(|annotation| line.find(annotation).map(|pos| pos + annotation.len()))?;
+        let url = line[pos..].trim().trim_matches('"').trim();
+        if url.starts_with("http") {
+            Some(url.to_owned())
+        } else {
+            None
+        }
+    })
+}
+
+/// Parses url of AtCoder task and returns contest id and url name of problem.
+fn parse_atcoder_task_url(url: &str) -> Option<(ContestId, String)> {
+    let path = url.strip_prefix(ATCODER_TASK_URL_PREFIX)?;
+    let path = path.split(|c| c == '?' || c == '#').next()?;
+    let mut segments = path.split('/');
+    match (segments.next(), segments.next(), segments.next()) {
+        (Some(contest_id), Some("tasks"), Some(url_name)) if !url_name.is_empty() => {
+            Some((contest_id.into(), url_name.to_owned()))
+        }
+        _ => None,
+    }
+}
+
+/// Parses url of problem of AOJ and returns id of the problem (e.g. `ITP1_1_A`).
+fn parse_aoj_problem_url(url: &str) -> Option<String> {
+    let path = AOJ_PROBLEM_URL_PREFIXES
+        .iter()
+        .find_map(|prefix| url.strip_prefix(prefix))?;
+    let path = path.split(|c| c == '?' || c == '#' || c == '&').next()?;
+    // urls of courses end with id of the problem (e.g. `lesson/2/ITP1/1/ITP1_1_A`)
+    let problem_id = path.trim_end_matches('/').rsplit('/').next()?;
+    if problem_id.is_empty() {
+        None
+    } else {
+        Some(problem_id.to_owned())
+    }
+}
+
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum VerifyStatus {
+    Verified,
+    Failed,
+    Skipped,
+}
+
+impl fmt::Display for VerifyStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Verified => "verified",
+            Self::Failed => "FAILED",
+            Self::Skipped => "skipped",
+        };
+        f.write_str(s)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VerifiedFile {
+    path: PathBuf,
+    problem_url: String,
+    status: VerifyStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total: Option<TotalStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VerifyLibOutcome {
+    service: Service,
+    files: Vec<VerifiedFile>,
+}
+
+impl VerifyLibOutcome {
+    fn count(&self, status: VerifyStatus) -> usize {
+        self.files
+            .iter()
+            .filter(|file| file.status == status)
+            .count()
+    }
+}
+
+impl fmt::Display for VerifyLibOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for file in &self.files {
+            write!(f, "{} {}", file.path.display(), file.status)?;
+            if let Some(total) = &file.total {
+                write!(f, " {}", total)?;
+            }
+            if let Some(message) = &file.message {
+                write!(f, " ({})", message)?;
+            }
+            writeln!(f)?;
+        }
+        write!(
+            f,
+            "Verified {} files ({} failed, {} skipped)",
+            self.count(VerifyStatus::Verified),
+            self.count(VerifyStatus::Failed),
+            self.count(VerifyStatus::Skipped)
+        )
+    }
+}
+
+impl Outcome for VerifyLibOutcome {
+    fn is_error(&self) -> bool {
+        self.count(VerifyStatus::Failed) > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_problem_url() {
+        let url = find_problem_url(
+            "// competitive-verifier: PROBLEM https://atcoder.jp/contests/abc100/tasks/abc100_a\n",
+        );
+        assert_eq!(
+            url.as_deref(),
+            Some("https://atcoder.jp/contests/abc100/tasks/abc100_a")
+        );
+        let url = find_problem_url(
+            "#include <iostream>\n#define PROBLEM \"https://onlinejudge.u-aizu.ac.jp/problems/ITP1_1_A\"\n",
+        );
+        assert_eq!(
+            url.as_deref(),
+            Some("https://onlinejudge.u-aizu.ac.jp/problems/ITP1_1_A")
+        );
+        assert_eq!(find_problem_url("#define PROBLEM_ID 1\n"), None);
+    }
+
+    #[test]
+    fn test_parse_aoj_problem_url() {
+        for url in &[
+            "https://onlinejudge.u-aizu.ac.jp/problems/ITP1_1_A",
+            "https://onlinejudge.u-aizu.ac.jp/courses/lesson/2/ITP1/1/ITP1_1_A",
+            "https://judge.u-aizu.ac.jp/onlinejudge/description.jsp?id=ITP1_1_A&lang=jp",
+        ] {
+            assert_eq!(
+                parse_aoj_problem_url(url).as_deref(),
+                Some("ITP1_1_A"),
+                "{}",
+                url
+            );
+        }
+        assert_eq!(
+            parse_aoj_problem_url("https://atcoder.jp/contests/abc100/tasks/abc100_a"),
+            None
+        );
+        assert_eq!(
+            parse_aoj_problem_url("https://onlinejudge.u-aizu.ac.jp/problems/"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_atcoder_task_url() {
+        assert_eq!(
+            parse_atcoder_task_url("https://atcoder.jp/contests/abc100/tasks/abc100_a?lang=en"),
+            Some(("abc100".into(), String::from("abc100_a")))
+        );
+        assert_eq!(
+            parse_atcoder_task_url("https://onlinejudge.u-aizu.ac.jp/problems/ITP1_1_A"),
+            None
+        );
+        assert_eq!(
+            parse_atcoder_task_url("https://atcoder.jp/contests/abc100"),
+            None
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use std::fmt;
+use std::io::Write as _;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _};
+use humantime::parse_duration;
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::time::delay_for;
+
+use crate::cmd::{block_on, with_actor, Outcome};
+use crate::model::{ContestId, ProblemId, Service};
+use crate::service::Act;
+use crate::{Config, Console, Error, Result};
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct VerifySamplesOpt {
+    /// Contests to verify (e.g. abc001..abc100,arc100)
+    #[structopt(name = "contests", required = true, use_delimiter = true)]
+    contest_ranges: Vec<ContestRange>,
+    /// Interval between fetching contests
+    #[structopt(long, default_value = "3s", parse(try_from_str = parse_duration))]
+    interval: Duration,
+}
+
+impl VerifySamplesOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<VerifySamplesOutcome> {
+        with_actor(conf.service_id, conf.session(), |actor| {
+            block_on(self.run_inner(actor, conf, cnsl))
+        })
+    }
+
+    async fn run_inner(
+        &self,
+        actor: &dyn Act,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<VerifySamplesOutcome> {
+        let contest_ids: Vec<ContestId> = self
+            .contest_ranges
+            .iter()
+            .flat_map(ContestRange::contest_ids)
+            .collect();
+
+        let mut contests = Vec::new();
+        for (i, contest_id) in contest_ids.into_iter().enumerate() {
+            if i > 0 {
+                // be polite to the service
+                delay_for(self.interval).await;
+            }
+            let conf = conf.with_contest(contest_id.clone());
+            let (problems, is_failed) = match Self::verify_contest(actor, &conf, cnsl).await {
+                Ok(problems) => (problems, false),
+                Err(err) => {
+                    writeln!(cnsl, "Could not verify contest {} : {:#}", contest_id, err)?;
+                    (Vec::new(), true)
+                }
+            };
+            contests.push(VerifiedContest {
+                contest_id,
+                problems,
+                is_failed,
+            });
+        }
+
+        Ok(VerifySamplesOutcome {
+            service: Service::new(conf.service_id),
+            contests,
+        })
+    }
+
+    async fn verify_contest(
+        actor: &dyn Act,
+        conf: &Config,
+        cnsl: &mut Console,
+    ) -> Result<Vec<VerifiedProblem>> {
+        let (_, problems, _) = actor.fetch(&conf.contest_id, &[], cnsl).await?;
+        let mut verified = Vec::new();
+        for problem in problems {
+            let drift = if conf.problem_abs_path(problem.id())?.as_ref().is_file() {
+                let stored = conf
+                    .load_problem(problem.id(), cnsl)
+                    .context("Could not load stored problem")?;
+                if stored.samples() == problem.samples() {
+                    SampleDrift::Unchanged
+                } else {
+                    SampleDrift::Changed
+                }
+            } else {
+                SampleDrift::NotStored
+            };
+            verified.push(VerifiedProblem {
+                problem_id: problem.id().clone(),
+                drift,
+            });
+        }
+        Ok(verified)
+    }
+}
+
+/// Contest id or range of contest ids that have the same prefix followed by sequential numbers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ContestRange {
+    Single(ContestId),
+    Sequence {
+        prefix: String,
+        start: u64,
+        end: u64,
+        width: usize,
+    },
+}
+
+impl ContestRange {
+    fn contest_ids(&self) -> Vec<ContestId> {
+        match self {
+            Self::Single(contest_id) => vec![contest_id.clone()],
+            Self::Sequence {
+                prefix,
+                start,
+                end,
+                width,
+            } => (*start..=*end)
+                .map(|n| format!("{}{:0width$}", prefix, n, width = width).into())
+                .collect(),
+        }
+    }
+
+    fn split_number(id: &str) -> Option<(&str, &str)> {
+        let pos = id.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+        if pos == id.len() {
+            None
+        } else {
+            Some(id.split_at(pos))
+        }
+    }
+}
+
+impl FromStr for ContestRange {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let pos = match s.find("..") {
+            Some(pos) => pos,
+            None if s.is_empty() => return Err(anyhow!("Found empty contest id")),
+            None => return Ok(Self::Single(s.into())),
+        };
+        let (start_id, end_id) = (&s[..pos], &s[pos + 2..]);
+        match (Self::split_number(start_id), Self::split_number(end_id)) {
+            (Some((prefix, start)), Some((end_prefix, end))) if prefix == end_prefix => {
+                let (start, end) = (start.parse()?, end.parse()?);
+                if start > end {
+                    return Err(anyhow!("Found empty range of contests : {}", s));
+                }
+                Ok(Self::Sequence {
+                    prefix: prefix.to_owned(),
+                    start,
+                    end,
+                    width: start_id.len() - prefix.len(),
+                })
+            }
+            _ => Err(anyhow!("Could not parse range of contests : {}", s)),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum SampleDrift {
+    Unchanged,
+    Changed,
+    NotStored,
+}
+
+impl fmt::Display for SampleDrift {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Unchanged => "unchanged",
+            Self::Changed => "CHANGED",
+            Self::NotStored => "not stored",
+        };
+        f.write_str(s)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VerifiedProblem {
+    problem_id: ProblemId,
+    drift: SampleDrift,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VerifiedContest {
+    contest_id: ContestId,
+    problems: Vec<VerifiedProblem>,
+    is_failed: bool,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VerifySamplesOutcome {
+    service: Service,
+    contests: Vec<VerifiedContest>,
+}
+
+impl VerifySamplesOutcome {
+    fn count_changed(&self) -> usize {
+        self.contests
+            .iter()
+            .flat_map(|contest| contest.problems.iter())
+            .filter(|problem| problem.drift == SampleDrift::Changed)
+            .count()
+    }
+}
+
+impl fmt::Display for VerifySamplesOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for contest in &self.contests {
+            if contest.is_failed {
+                writeln!(f, "{} {} FAILED", self.service.id(), contest.contest_id)?;
+                continue;
+            }
+            for problem in &contest.problems {
+                writeln!(
+                    f,
+                    "{} {} {} {}",
+                    self.service.id(),
+                    contest.contest_id,
+                    problem.problem_id,
+                    problem.drift
+                )?;
+            }
+        }
+        let n_failed = self.contests.iter().filter(|c| c.is_failed).count();
+        write!(
+            f,
+            "Found {} problems with changed samples in {} contests ({} failed)",
+            self.count_changed(),
+            self.contests.len(),
+            n_failed
+        )
+    }
+}
+
+impl Outcome for VerifySamplesOutcome {
+    fn is_error(&self) -> bool {
+        self.count_changed() > 0 || self.contests.iter().any(|c| c.is_failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contest_range_from_str() -> anyhow::Result<()> {
+        let ids = |s: &str| -> anyhow::Result<Vec<String>> {
+            let range: ContestRange = s.parse()?;
+            Ok(range
+                .contest_ids()
+                .iter()
+                .map(|id| id.to_string())
+                .collect())
+        };
+        assert_eq!(
+            ids("abc008..abc011")?,
+            ["abc008", "abc009", "abc010", "abc011"]
+        );
+        assert_eq!(ids("arc100")?, ["arc100"]);
+        assert_eq!(ids("practice")?, ["practice"]);
+        assert!(ids("abc010..abc009").is_err());
+        assert!(ids("abc001..arc002").is_err());
+        assert!(ids("abc..abd").is_err());
+        Ok(())
+    }
+}
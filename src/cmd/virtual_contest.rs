@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use chrono::{offset::Local, SecondsFormat};
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::cmd::{block_on, with_actor, Outcome};
+use crate::history::{History, HistoryKind};
+use crate::model::{ContestId, ProblemId, Service};
+use crate::virtual_contest::{fmt_hms, VirtualSession, VirtualSessions};
+use crate::{Config, Console, Result};
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct VirtualOpt {
+    #[structopt(subcommand)]
+    action: VirtualAction,
+}
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub enum VirtualAction {
+    /// Starts virtual participation in the contest now
+    Start {
+        /// Duration of the contest (e.g. 100m) [default: duration of the contest on service]
+        #[structopt(long, parse(try_from_str = humantime::parse_duration))]
+        duration: Option<Duration>,
+    },
+    /// Shows elapsed time and local scoreboard of virtual participation
+    Status,
+    /// Stops virtual participation and shows its local scoreboard
+    Stop,
+}
+
+impl VirtualOpt {
+    pub fn run(&self, conf: &Config, cnsl: &mut Console) -> Result<VirtualOutcome> {
+        let path = conf.virtual_abs_path();
+        let sessions = VirtualSessions::new(&path);
+        let session = match self.action {
+            VirtualAction::Start { duration } => {
+                if let Some(session) = sessions.find(conf.service_id, &conf.contest_id)? {
+                    let message = format!(
+                        "discard virtual participation started at {}?",
+                        session
+                            .started_at()
+                            .to_rfc3339_opts(SecondsFormat::Secs, false)
+                    );
+                    if !cnsl.confirm(&message, false)? {
+                        return Err(anyhow!("Not started"));
+                    }
+                }
+                let duration = match duration {
+                    Some(duration) => Some(duration),
+                    None => fetch_duration(conf, cnsl)?,
+                };
+                let session = VirtualSession::new(
+                    conf.service_id,
+                    conf.contest_id.clone(),
+                    Local::now(),
+                    duration,
+                );
+                sessions.save(&session)?;
+                session
+            }
+            VirtualAction::Status => sessions
+                .find(conf.service_id, &conf.contest_id)?
+                .ok_or_else(|| anyhow!("Found no virtual participation in the contest"))?,
+            VirtualAction::Stop => sessions
+                .remove(conf.service_id, &conf.contest_id)?
+                .ok_or_else(|| anyhow!("Found no virtual participation in the contest"))?,
+        };
+
+        let scoreboard = scoreboard(&session, conf)?;
+        Ok(VirtualOutcome {
+            service: Service::new(conf.service_id),
+            contest_id: conf.contest_id.clone(),
+            status: session.status_line(),
+            session,
+            scoreboard,
+        })
+    }
+}
+
+/// Fetches duration of the contest from service. Failure is only warned.
+fn fetch_duration(conf: &Config, cnsl: &mut Console) -> Result<Option<Duration>> {
+    let result = with_actor(conf.service_id, conf.session(), |actor| {
+        block_on(actor.fetch_contest_info(&conf.contest_id, cnsl))
+    });
+    match result {
+        Ok(info) => Ok((info.end_at() - info.start_at()).to_std().ok()),
+        Err(err) => {
+            cnsl.warn(&format!(
+                "Could not fetch duration of the contest. Specify it by --duration. ({:#})",
+                err
+            ))?;
+            Ok(None)
+        }
+    }
+}
+
+/// Collects first local AC and first submission of each problem during virtual participation.
+fn scoreboard(session: &VirtualSession, conf: &Config) -> Result<Vec<VirtualProblem>> {
+    let mut problems: BTreeMap<ProblemId, VirtualProblem> = BTreeMap::new();
+    let entries = History::new(conf.history_abs_path()).load()?;
+    for entry in entries {
+        if entry.service_id() != session.service_id()
+            || entry.contest_id() != session.contest_id()
+            || session.is_ended_at(*entry.recorded_at())
+        {
+            continue;
+        }
+        let elapsed = match session.elapsed_at(*entry.recorded_at()) {
+            Some(elapsed) => elapsed,
+            None => continue,
+        };
+        let problem = problems
+            .entry(entry.problem_id().clone())
+            .or_insert_with(|| VirtualProblem {
+                problem_id: entry.problem_id().clone(),
+                accepted_at: None,
+                submitted_at: None,
+            });
+        match entry.kind() {
+            HistoryKind::Test if entry.verdict() == "AC" => {
+                problem.accepted_at = problem.accepted_at.or(Some(elapsed))
+            }
+            HistoryKind::Submit => problem.submitted_at = problem.submitted_at.or(Some(elapsed)),
+            _ => {}
+        }
+    }
+    Ok(problems.into_iter().map(|(_, problem)| problem).collect())
+}
+
+/// Elapsed times of a problem from the start of virtual participation.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VirtualProblem {
+    problem_id: ProblemId,
+    /// First time when all samples or testcases passed in test command
+    #[serde(with = "humantime_serde")]
+    accepted_at: Option<Duration>,
+    /// First time when source code was submitted
+    #[serde(with = "humantime_serde")]
+    submitted_at: Option<Duration>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VirtualOutcome {
+    service: Service,
+    contest_id: ContestId,
+    session: VirtualSession,
+    status: String,
+    scoreboard: Vec<VirtualProblem>,
+}
+
+impl fmt::Display for VirtualOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} started at {}\n{}",
+            self.service.id(),
+            self.contest_id,
+            self.session
+                .started_at()
+                .to_rfc3339_opts(SecondsFormat::Secs, false),
+            self.status
+        )?;
+        if self.scoreboard.is_empty() {
+            return Ok(());
+        }
+        let fmt_elapsed = |elapsed: Option<Duration>| elapsed.map_or_else(|| "-".into(), fmt_hms);
+        write!(f, "\n\n| problem | AC (test) | submitted |")?;
+        write!(f, "\n|---------+-----------+-----------|")?;
+        for problem in &self.scoreboard {
+            write!(
+                f,
+                "\n| {:7} | {:>9} | {:>9} |",
+                problem.problem_id.to_string(),
+                fmt_elapsed(problem.accepted_at),
+                fmt_elapsed(problem.submitted_at)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Outcome for VirtualOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::cmd::tests::run_with;
+    use crate::history::HistoryEntry;
+    use crate::model::ServiceKind;
+
+    #[test]
+    fn run_start_status_stop() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let opt = |action| VirtualOpt { action };
+        let start = opt(VirtualAction::Start {
+            duration: Some(Duration::from_secs(100 * 60)),
+        });
+        let (started, status, stopped) = run_with(&test_dir, |conf, cnsl| {
+            let started = start.run(conf, cnsl)?;
+            let history = History::new(conf.history_abs_path());
+            for (kind, problem_id, verdict) in &[
+                (HistoryKind::Test, "C", "WA"),
+                (HistoryKind::Test, "C", "AC"),
+                (HistoryKind::Submit, "C", "submitted"),
+                (HistoryKind::Test, "D", "AC"),
+            ] {
+                history.record(&HistoryEntry::new(
+                    *kind,
+                    ServiceKind::Atcoder,
+                    conf.contest_id.clone(),
+                    (*problem_id).into(),
+                    *verdict,
+                    None,
+                ))?;
+            }
+            let status = opt(VirtualAction::Status).run(conf, cnsl)?;
+            let stopped = opt(VirtualAction::Stop).run(conf, cnsl)?;
+            assert!(opt(VirtualAction::Status).run(conf, cnsl).is_err());
+            Ok((started, status, stopped))
+        })?;
+
+        assert!(started.scoreboard.is_empty());
+        assert_eq!(status.scoreboard.len(), 2);
+        assert!(status.scoreboard[0].accepted_at.is_some());
+        assert!(status.scoreboard[0].submitted_at.is_some());
+        assert!(status.scoreboard[1].submitted_at.is_none());
+        assert_eq!(status.session, stopped.session);
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use std::fmt;
+
+use getset::Getters;
+use serde::Serialize;
+use structopt::StructOpt;
+
+use crate::abs_path::AbsPathBuf;
+use crate::cmd::Outcome;
+use crate::model::ProblemId;
+use crate::{Config, Result};
+
+#[derive(StructOpt, Debug, Clone, PartialEq, Eq, Hash)]
+#[structopt(rename_all = "kebab")]
+pub struct WhichOpt {
+    /// Id of the problem
+    #[structopt(name = "problem")]
+    problem_id: ProblemId,
+}
+
+impl WhichOpt {
+    pub fn run(&self, conf: &Config) -> Result<WhichOutcome> {
+        let problem_id = &self.problem_id;
+        Ok(WhichOutcome {
+            problem_id: problem_id.clone(),
+            source_path: conf.source_abs_path(problem_id)?,
+            working_dir: conf.working_abs_dir(problem_id)?,
+            testcases_dir: conf.testcases_abs_dir(problem_id)?,
+            problem_path: conf.problem_abs_path(problem_id)?,
+        })
+    }
+}
+
+/// Paths of the problem expanded from templates in config file.
+#[derive(Serialize, Getters, Debug, Clone, PartialEq, Eq, Hash)]
+#[get = "pub"]
+pub struct WhichOutcome {
+    problem_id: ProblemId,
+    source_path: AbsPathBuf,
+    working_dir: AbsPathBuf,
+    testcases_dir: AbsPathBuf,
+    problem_path: AbsPathBuf,
+}
+
+impl fmt::Display for WhichOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "source_path   : {}", self.source_path)?;
+        writeln!(f, "working_dir   : {}", self.working_dir)?;
+        writeln!(f, "testcases_dir : {}", self.testcases_dir)?;
+        write!(f, "problem_path  : {}", self.problem_path)
+    }
+}
+
+impl Outcome for WhichOutcome {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::cmd::tests::run_with;
+
+    #[test]
+    fn run_default() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let opt = WhichOpt {
+            problem_id: "C".into(),
+        };
+        let outcome = run_with(&test_dir, |conf, _| opt.run(conf))?;
+        assert!(outcome
+            .source_path()
+            .as_ref()
+            .ends_with("atcoder/arc100/c/Main.cpp"));
+        assert!(outcome.working_dir().as_ref().ends_with("atcoder/arc100/c"));
+        Ok(())
+    }
+}
@@ -0,0 +1,326 @@
+//! Typed entry points of commands for embedding acick in other tools.
+//!
+//! Each function loads config file and runs the same logic as the corresponding subcommand
+//! without parsing command line arguments nor writing any messages to terminal.
+//! Prompts are answered with "yes" except the ones asked in contest mode,
+//! which are answered with their default.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::abs_path::AbsPathBuf;
+use crate::cmd::{FetchOpt, HistoryOpt, MeOpt, StatusOpt, SubmitOpt, TestOpt};
+use crate::{Config, Console, ConsoleConfig, Result};
+
+pub use crate::cmd::{FetchOutcome, HistoryOutcome, MeOutcome, StatusOutcome, SubmitOutcome};
+pub use crate::cmd::{ServiceStatus, ServiceUser, TestOutcome};
+pub use crate::history::{HistoryEntry, HistoryKind};
+pub use crate::judge::{Status, StatusKind, TotalStatus};
+pub use crate::model::{Compare, ContestId, LangName, ProblemId, ServiceKind, TestSet};
+
+/// Service and contest that commands work on.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Target {
+    pub service_id: ServiceKind,
+    pub contest_id: ContestId,
+    /// Directory that contains config file (searched from current directory if not specified)
+    pub base_dir: Option<PathBuf>,
+    pub contest_mode: bool,
+    /// If true, commands that need network access fail without sending requests.
+    pub offline: bool,
+}
+
+impl Target {
+    fn load_config(&self, cnsl: &mut Console) -> Result<Config> {
+        let base_dir = match &self.base_dir {
+            Some(base_dir) => Some(AbsPathBuf::cwd()?.join(base_dir)),
+            None => None,
+        };
+        let mut conf = Config::load(self.service_id, self.contest_id.clone(), base_dir, cnsl)?;
+        conf.contest_mode = self.contest_mode;
+        Ok(conf.with_offline(self.offline))
+    }
+}
+
+fn run<T>(target: &Target, f: impl FnOnce(&Config, &mut Console) -> Result<T>) -> Result<T> {
+    let mut cnsl = Console::sink(ConsoleConfig {
+        assume_yes: true,
+        verbose: false,
+    });
+    let conf = target.load_config(&mut cnsl)?;
+    f(&conf, &mut cnsl)
+}
+
+#[derive(Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct FetchParams {
+    #[serde(skip)]
+    pub target: Target,
+    /// Problems to fetch (all problems if empty)
+    pub problem_ids: Vec<ProblemId>,
+    pub overwrite: bool,
+    pub is_full: bool,
+    pub need_assets: bool,
+    pub anonymous: bool,
+    /// Guesses samples of problems in unknown layouts
+    pub lax_samples: bool,
+}
+
+impl FetchParams {
+    pub(crate) fn to_opt(&self) -> FetchOpt {
+        FetchOpt {
+            problem_id: None,
+            problem_ids: self.problem_ids.clone(),
+            overwrite: self.overwrite,
+            need_open: false,
+            is_full: self.is_full,
+            need_assets: self.need_assets,
+            limit_rate: None,
+            jobs: None,
+            anonymous: self.anonymous,
+            lax_samples: self.lax_samples,
+            contests: Vec::new(),
+            contests_file: None,
+        }
+    }
+}
+
+/// Fetches problems from service.
+pub fn fetch(params: FetchParams) -> Result<FetchOutcome> {
+    let opt = params.to_opt();
+    run(&params.target, |conf, cnsl| opt.run(conf, cnsl))
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TestParams {
+    #[serde(skip)]
+    pub target: Target,
+    pub problem_id: ProblemId,
+    /// Sample to test with (all samples if not specified)
+    #[serde(default)]
+    pub sample_name: Option<String>,
+    #[serde(default)]
+    pub is_full: bool,
+    /// Set of testcases to test with (overridden by `is_full`)
+    #[serde(default)]
+    pub test_set: Option<TestSet>,
+    /// Overrides time limit of the problem
+    #[serde(default, with = "humantime_serde")]
+    pub time_limit: Option<Duration>,
+    /// Overrides the way to compare outputs specified in problem file
+    #[serde(default)]
+    pub compare: Option<Compare>,
+}
+
+impl TestParams {
+    pub fn new(target: Target, problem_id: ProblemId) -> Self {
+        Self {
+            target,
+            problem_id,
+            sample_name: None,
+            is_full: false,
+            test_set: None,
+            time_limit: None,
+            compare: None,
+        }
+    }
+
+    pub(crate) fn to_opt(&self) -> TestOpt {
+        TestOpt {
+            problem_id: self.problem_id.clone(),
+            sample_name: self.sample_name.clone(),
+            is_full: self.is_full,
+            test_set: self.test_set,
+            one_line: false,
+            time_limit: self.time_limit.map(|t| t.as_millis() as u64),
+            watch: false,
+            compare: self.compare,
+            bless: false,
+            from_submission: None,
+            diff_tool: None,
+            diff_view: None,
+            debug: false,
+            no_cache: false,
+        }
+    }
+}
+
+/// Tests source code with samples.
+pub fn test(params: TestParams) -> Result<TestOutcome> {
+    let opt = params.to_opt();
+    run(&params.target, |conf, cnsl| opt.run(conf, cnsl))
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SubmitParams {
+    #[serde(skip)]
+    pub target: Target,
+    pub problem_id: ProblemId,
+    /// Overrides language names specified in config file
+    #[serde(default)]
+    pub lang_names: Option<Vec<LangName>>,
+    #[serde(default)]
+    pub is_dry_run: bool,
+    /// Submits even if the same source code was submitted recently
+    #[serde(default)]
+    pub force: bool,
+}
+
+impl SubmitParams {
+    pub fn new(target: Target, problem_id: ProblemId) -> Self {
+        Self {
+            target,
+            problem_id,
+            lang_names: None,
+            is_dry_run: false,
+            force: false,
+        }
+    }
+
+    pub(crate) fn to_opt(&self) -> SubmitOpt {
+        SubmitOpt {
+            problem_id: Some(self.problem_id.clone()),
+            lang_name: self.lang_names.clone(),
+            need_open: false,
+            is_dry_run: self.is_dry_run,
+            force: self.force,
+            from_clipboard: false,
+            file: None,
+            retry_pending: false,
+            wait: false,
+        }
+    }
+}
+
+/// Submits source code to service.
+pub fn submit(params: SubmitParams) -> Result<SubmitOutcome> {
+    let opt = params.to_opt();
+    run(&params.target, |conf, cnsl| opt.run(conf, cnsl))
+}
+
+#[derive(Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct StatusParams {
+    #[serde(skip)]
+    pub target: Target,
+    pub all_services: bool,
+}
+
+impl StatusParams {
+    pub(crate) fn to_opt(&self) -> StatusOpt {
+        StatusOpt {
+            all_services: self.all_services,
+        }
+    }
+}
+
+/// Gets schedule and remaining time of contest.
+pub fn status(params: StatusParams) -> Result<StatusOutcome> {
+    let opt = params.to_opt();
+    run(&params.target, |conf, cnsl| opt.run(conf, cnsl))
+}
+
+#[derive(Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct MeParams {
+    #[serde(skip)]
+    pub target: Target,
+    pub all_services: bool,
+}
+
+impl MeParams {
+    pub(crate) fn to_opt(&self) -> MeOpt {
+        MeOpt {
+            all_services: self.all_services,
+        }
+    }
+}
+
+/// Gets users currently logged in to services.
+pub fn me(params: MeParams) -> Result<MeOutcome> {
+    let opt = params.to_opt();
+    run(&params.target, |conf, cnsl| opt.run(conf, cnsl))
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct HistoryParams {
+    #[serde(skip)]
+    pub target: Target,
+    /// Problem to show history of (all problems if not specified)
+    pub problem_id: Option<ProblemId>,
+    pub all_contests: bool,
+    pub limit: usize,
+}
+
+impl Default for HistoryParams {
+    fn default() -> Self {
+        Self {
+            target: Target::default(),
+            problem_id: None,
+            all_contests: false,
+            limit: usize::MAX,
+        }
+    }
+}
+
+impl HistoryParams {
+    pub(crate) fn to_opt(&self) -> HistoryOpt {
+        HistoryOpt {
+            problem_id: self.problem_id.clone(),
+            all_contests: self.all_contests,
+            limit: self.limit,
+        }
+    }
+}
+
+/// Gets history of test and submit commands.
+pub fn history(params: HistoryParams) -> Result<HistoryOutcome> {
+    let opt = params.to_opt();
+    run(&params.target, |conf, _| opt.run(conf))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::OpenOptions;
+    use std::io::Write as _;
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::cmd::InitOpt;
+    use crate::config::{ConfigBody, Layout, Profile};
+
+    #[test]
+    fn test_history() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let base_dir = AbsPathBuf::try_new(test_dir.path())?;
+        let mut cnsl = Console::sink(ConsoleConfig::default());
+        InitOpt {
+            overwrite: false,
+            editor: None,
+            profile: Profile::default(),
+            layout: Layout::default(),
+            from_url: None,
+            fetch: false,
+            problem_ids: Vec::new(),
+        }
+        .run(Some(base_dir.clone()), &mut cnsl)?;
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(base_dir.join(ConfigBody::FILE_NAME).as_ref())?;
+        writeln!(file, "history_path: {}", base_dir.join("history.jsonl"))?;
+
+        let outcome = history(HistoryParams {
+            target: Target {
+                base_dir: Some(test_dir.path().to_owned()),
+                ..Default::default()
+            },
+            ..Default::default()
+        })?;
+        assert!(outcome.entries().is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead as _, BufReader, Write as _};
+use std::time::Duration;
+
+use anyhow::Context as _;
+use chrono::{offset::Local, DateTime};
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+use crate::abs_path::AbsPathBuf;
+use crate::model::{ContestId, ProblemId, ServiceKind};
+use crate::Result;
+
+pub type LocalDateTime = DateTime<Local>;
+
+#[derive(
+    Serialize, Deserialize, AsRefStr, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum HistoryKind {
+    Test,
+    Submit,
+}
+
+/// Record of an invocation of test or submit command.
+#[derive(Serialize, Deserialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HistoryEntry {
+    #[get_copy = "pub"]
+    kind: HistoryKind,
+    #[get_copy = "pub"]
+    service_id: ServiceKind,
+    #[get = "pub"]
+    contest_id: ContestId,
+    #[get = "pub"]
+    problem_id: ProblemId,
+    #[get = "pub"]
+    recorded_at: LocalDateTime,
+    #[get = "pub"]
+    verdict: String,
+    #[serde(with = "humantime_serde", default)]
+    #[get_copy = "pub"]
+    elapsed: Option<Duration>,
+    /// Hash of submitted source code, which detects duplicate submissions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[get = "pub"]
+    source_hash: Option<String>,
+}
+
+impl HistoryEntry {
+    pub fn new(
+        kind: HistoryKind,
+        service_id: ServiceKind,
+        contest_id: ContestId,
+        problem_id: ProblemId,
+        verdict: impl Into<String>,
+        elapsed: Option<Duration>,
+    ) -> Self {
+        Self {
+            kind,
+            service_id,
+            contest_id,
+            problem_id,
+            recorded_at: Local::now(),
+            verdict: verdict.into(),
+            elapsed,
+            source_hash: None,
+        }
+    }
+
+    pub fn with_source_hash(self, source_hash: String) -> Self {
+        Self {
+            source_hash: Some(source_hash),
+            ..self
+        }
+    }
+}
+
+/// History of test and submit commands stored as json lines.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct History<'a> {
+    path: &'a AbsPathBuf,
+}
+
+impl<'a> History<'a> {
+    pub fn new(path: &'a AbsPathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn record(&self, entry: &HistoryEntry) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            dir.create_dir_all()?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path.as_ref())
+            .context("Could not open history file")?;
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        file.write_all(line.as_bytes())
+            .context("Could not write to history file")
+    }
+
+    pub fn load(&self) -> Result<Vec<HistoryEntry>> {
+        if !self.path.as_ref().is_file() {
+            return Ok(Vec::new());
+        }
+        let file = fs::File::open(self.path.as_ref()).context("Could not open history file")?;
+        BufReader::new(file)
+            .lines()
+            .filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect::<Result<Vec<_>>>()
+            .context("Could not read history file")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_record_and_load() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let path = AbsPathBuf::try_new(test_dir.path())?.join("history.jsonl");
+        let history = History::new(&path);
+        assert!(history.load()?.is_empty());
+
+        let entries = vec![
+            HistoryEntry::new(
+                HistoryKind::Test,
+                ServiceKind::Atcoder,
+                "arc100".into(),
+                "C".into(),
+                "AC",
+                Some(Duration::from_millis(1234)),
+            ),
+            HistoryEntry::new(
+                HistoryKind::Submit,
+                ServiceKind::Atcoder,
+                "arc100".into(),
+                "C".into(),
+                "submitted",
+                None,
+            )
+            .with_source_hash("0123456789abcdef".into()),
+        ];
+        for entry in &entries {
+            history.record(entry)?;
+        }
+        assert_eq!(history.load()?, entries);
+        Ok(())
+    }
+}
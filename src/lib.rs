@@ -5,6 +5,7 @@ extern crate strum;
 
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::time::Instant;
 
 use serde::Serialize;
 use structopt::StructOpt;
@@ -12,14 +13,25 @@ use strum::VariantNames;
 
 use acick_atcoder as atcoder;
 use acick_config as config;
-use acick_util::{abs_path, console, model, service, DATA_LOCAL_DIR};
+use acick_git as git;
+use acick_judge as judge;
+use acick_util::{
+    abs_path, console, crypto, error, metrics, model, service, store, DATA_LOCAL_DIR,
+};
 
 mod cmd;
-mod judge;
+pub mod commands;
+mod history;
+mod mock;
+mod submit_queue;
+mod virtual_contest;
 
-use crate::cmd::{Cmd, Outcome};
+use crate::cmd::{with_runtime, Cmd, ErrorReport, Outcome, OutcomeSerialize as _};
 use crate::config::Config;
 use crate::console::{Console, ConsoleConfig};
+use crate::metrics::Metrics;
+
+pub use crate::error::ErrorKind;
 
 pub type Error = anyhow::Error;
 pub type Result<T> = anyhow::Result<T>;
@@ -34,6 +46,14 @@ pub enum OutputFormat {
     Debug,
     Json,
     Yaml,
+    Tsv,
+}
+
+impl OutputFormat {
+    /// Returns true if the format is meant to be parsed by scripts.
+    fn is_structured(self) -> bool {
+        matches!(self, Self::Json | Self::Yaml | Self::Tsv)
+    }
 }
 
 impl Default for OutputFormat {
@@ -61,14 +81,23 @@ pub struct Opt {
     /// Assumes "yes" as answer to all prompts and run non-interactively
     #[structopt(long, short = "y", global = true)]
     assume_yes: bool,
+    /// Shows detailed diagnostics of commands, also attached to structured outputs as `metrics`
+    #[structopt(long, short, global = true)]
+    verbose: bool,
+    /// Shows time spent in each phase of command (e.g. network, compile)
+    #[structopt(long, global = true)]
+    timings: bool,
     #[structopt(subcommand)]
     cmd: Cmd,
 }
 
 impl Opt {
     pub fn run(&self) -> Result<()> {
-        let assume_yes = self.assume_yes;
-        let cnsl_conf = ConsoleConfig { assume_yes };
+        let started_at = Instant::now();
+        let cnsl_conf = ConsoleConfig {
+            assume_yes: self.assume_yes,
+            verbose: self.verbose,
+        };
         let mut cnsl = if self.quiet {
             Console::sink(cnsl_conf)
         } else {
@@ -79,17 +108,53 @@ impl Opt {
             Some(base_dir) => Some(abs_path::AbsPathBuf::cwd()?.join(base_dir)),
             None => None,
         };
-        self.cmd.run(base_dir, &mut cnsl, |outcome, cnsl| {
-            self.finish(outcome, &mut io::stdout(), cnsl)
-        })
+        // print outcome to stderr if stdout is used by the command (e.g. `serve` over stdio)
+        let mut stdout: Box<dyn Write> = if self.cmd.reserves_stdout() {
+            Box::new(io::stderr())
+        } else {
+            Box::new(io::stdout())
+        };
+        let mut is_finished = false;
+        let result = with_runtime(|| {
+            self.cmd.run(base_dir, &mut cnsl, |outcome, cnsl| {
+                is_finished = true;
+                self.finish(outcome, started_at, &mut *stdout, cnsl)
+            })
+        });
+        match result {
+            // error of outcome has already been printed together with outcome
+            Err(err) if !is_finished && self.output.is_structured() => {
+                let report = ErrorReport::new(ErrorKind::of(&err), format!("{:#}", err));
+                let mut value = serde_json::json!({ "error": report });
+                if let Some(metrics) = self.structured_metrics() {
+                    value["metrics"] = serde_json::to_value(metrics)?;
+                }
+                value.print(&mut *stdout, self.output)?;
+                Err(err)
+            }
+            result => result,
+        }
     }
 
     fn finish(
         &self,
         outcome: &dyn Outcome,
+        started_at: Instant,
         stdout: &mut dyn Write,
         cnsl: &mut Console,
     ) -> Result<()> {
+        let metrics = Metrics::current();
+        if self.verbose && metrics.requests() > 0 {
+            writeln!(cnsl, "Session metrics: {}", metrics)?;
+        }
+        if self.timings {
+            writeln!(
+                cnsl,
+                "Timings: {} (total: {:.2}s)",
+                metrics.timings(),
+                started_at.elapsed().as_secs_f32()
+            )?;
+        }
         cnsl.flush()?;
         if self.quiet {
             stdout.flush()?;
@@ -97,12 +162,33 @@ impl Opt {
             writeln!(stdout)?;
         }
 
-        outcome.print(stdout, self.output)?;
+        let metrics = self.structured_metrics();
+        if !outcome.is_error() {
+            match &metrics {
+                Some(metrics) => {
+                    outcome.print_attached(stdout, self.output, None, Some(metrics))?
+                }
+                None => outcome.print(stdout, self.output)?,
+            }
+            return Ok(());
+        }
+        let kind = outcome.error_kind();
+        let message = "Command exited with error";
+        if self.output.is_structured() {
+            let report = ErrorReport::new(kind, message.into());
+            outcome.print_attached(stdout, self.output, Some(&report), metrics.as_ref())?;
+        } else {
+            outcome.print(stdout, self.output)?;
+        }
+        Err(kind.tag(Error::msg(message)))
+    }
 
-        if outcome.is_error() {
-            Err(Error::msg("Command exited with error"))
+    /// Returns metrics to be attached to outcome printed in structured formats in verbose mode.
+    fn structured_metrics(&self) -> Option<Metrics> {
+        if self.verbose && self.output.is_structured() {
+            Some(Metrics::current())
         } else {
-            Ok(())
+            None
         }
     }
 }
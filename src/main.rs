@@ -1,17 +1,18 @@
 #![warn(clippy::all)]
 
 use std::io::{self, Write as _};
+use std::process;
 
 use structopt::StructOpt;
 
-use acick::{Opt, Result};
+use acick::{ErrorKind, Opt};
 
-fn main() -> Result<()> {
+fn main() {
     let opt = Opt::from_args();
     if let Err(err) = opt.run() {
         io::stdout().flush().expect("Could not flush stdout");
         eprintln!();
-        return Err(err);
+        eprintln!("Error: {:?}", err);
+        process::exit(ErrorKind::of(&err).exit_code());
     }
-    Ok(())
 }
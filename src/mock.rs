@@ -0,0 +1,501 @@
+//! Mock service that serves contests from local files without network or real account.
+//!
+//! A contest is read from `<mock_dir>/<contest>/contest.yaml`,
+//! and submissions are recorded with their source code into
+//! `<mock_dir>/<contest>/submissions/<id>.yaml`.
+//! Contest `demo` is built in so that workflows can be tried without preparing any files.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write as _;
+
+use anyhow::{anyhow, Context as _};
+use async_trait::async_trait;
+use chrono::{DateTime, FixedOffset};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::abs_path::AbsPathBuf;
+use crate::config::SessionConfig;
+use crate::error::ErrorKind;
+use crate::model::{
+    Contest, ContestId, ContestInfo, JudgeResult, LangName, LangNameRef, Problem, ProblemId,
+    Standings, StandingsRow, Statement, Submission,
+};
+use crate::service::act::LangNotFound;
+use crate::service::scrape::PatternReport;
+use crate::service::{Act, CookieStorage};
+use crate::{Console, Result};
+
+static DEMO_CONTEST_ID: &str = "demo";
+static DEMO_CONTEST_YAML: &str = include_str!("../resources/mock/demo.yaml");
+static CONTEST_FILE_NAME: &str = "contest.yaml";
+static SUBMISSIONS_DIR_NAME: &str = "submissions";
+static USER_COOKIE_NAME: &str = "MOCK_USER";
+
+lazy_static! {
+    // user of mock service is stored in cookies file so that logout command removes it
+    static ref SESSION_URL: Url = Url::parse("https://mock.acick.invalid/").unwrap();
+}
+
+/// Contest defined in `contest.yaml`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+struct MockContest {
+    name: String,
+    start_at: DateTime<FixedOffset>,
+    end_at: DateTime<FixedOffset>,
+    /// Languages accepted on submission (any language if empty)
+    #[serde(default)]
+    lang_names: Vec<LangName>,
+    /// Verdict of every submission
+    #[serde(default = "MockContest::default_verdict")]
+    verdict: String,
+    problems: Vec<Problem>,
+    /// Html of statements for each problem id
+    #[serde(default)]
+    statements: BTreeMap<String, String>,
+    #[serde(default)]
+    standings: Vec<StandingsRow>,
+}
+
+impl MockContest {
+    fn default_verdict() -> String {
+        String::from("AC")
+    }
+
+    fn find_problem(&self, problem_id: &ProblemId) -> Option<&Problem> {
+        self.problems
+            .iter()
+            .find(|problem| problem.id() == problem_id)
+    }
+}
+
+/// Submission recorded together with its source code.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct MockSubmission {
+    #[serde(flatten)]
+    submission: Submission,
+    #[serde(default)]
+    source: String,
+}
+
+#[derive(Debug)]
+pub struct MockActor {
+    session: SessionConfig,
+}
+
+impl MockActor {
+    pub fn new(session: &SessionConfig) -> Self {
+        Self {
+            session: session.clone(),
+        }
+    }
+}
+
+impl MockActor {
+    fn contest_dir(&self, contest_id: &ContestId) -> AbsPathBuf {
+        self.session.mock_dir().join(contest_id.to_string())
+    }
+
+    fn submission_path(&self, contest_id: &ContestId, submission_id: &str) -> AbsPathBuf {
+        self.contest_dir(contest_id)
+            .join(SUBMISSIONS_DIR_NAME)
+            .join(format!("{}.yaml", submission_id))
+    }
+
+    fn load_contest(&self, contest_id: &ContestId) -> Result<MockContest> {
+        let path = self.contest_dir(contest_id).join(CONTEST_FILE_NAME);
+        let yaml = if path.as_ref().is_file() {
+            fs::read_to_string(path.as_ref())
+                .with_context(|| format!("Could not read mock contest : {}", path))?
+        } else if contest_id == &ContestId::from(DEMO_CONTEST_ID) {
+            DEMO_CONTEST_YAML.to_owned()
+        } else {
+            return Err(anyhow!(
+                "Could not find mock contest {}. Create {} or use contest {}.",
+                contest_id,
+                path,
+                DEMO_CONTEST_ID
+            ));
+        };
+        serde_yaml::from_str(&yaml)
+            .with_context(|| format!("Could not parse mock contest : {}", path))
+    }
+
+    fn ensure_login(&self) -> Result<String> {
+        self.load_user()?
+            .ok_or_else(|| ErrorKind::Auth.tag(anyhow!("Not logged in to mock service")))
+    }
+
+    fn load_user(&self) -> Result<Option<String>> {
+        let storage = CookieStorage::open(self.session.cookies_path())
+            .context("Could not open cookie storage")?;
+        Ok(storage.get_value(&SESSION_URL, USER_COOKIE_NAME))
+    }
+
+    fn load_submission(
+        &self,
+        contest_id: &ContestId,
+        submission_id: &str,
+    ) -> Result<MockSubmission> {
+        let path = self.submission_path(contest_id, submission_id);
+        if !path.as_ref().is_file() {
+            return Err(anyhow!(
+                "Could not find submission {} in mock contest {}",
+                submission_id,
+                contest_id
+            ));
+        }
+        let yaml = fs::read_to_string(path.as_ref())
+            .with_context(|| format!("Could not read submission : {}", path))?;
+        serde_yaml::from_str(&yaml)
+            .with_context(|| format!("Could not parse submission : {}", path))
+    }
+
+    /// Returns the next id of submission, which is the number of recorded submissions plus one.
+    fn next_submission_id(&self, contest_id: &ContestId) -> Result<usize> {
+        let dir = self.contest_dir(contest_id).join(SUBMISSIONS_DIR_NAME);
+        if !dir.as_ref().is_dir() {
+            return Ok(1);
+        }
+        let count = fs::read_dir(dir.as_ref())
+            .with_context(|| format!("Could not read submissions : {}", dir))?
+            .count();
+        Ok(count + 1)
+    }
+}
+
+#[async_trait(?Send)]
+impl Act for MockActor {
+    async fn current_user(&self, _cnsl: &mut Console) -> Result<Option<String>> {
+        self.load_user()
+    }
+
+    fn has_stored_session(&self) -> Result<bool> {
+        Ok(self.load_user()?.is_some())
+    }
+
+    async fn login(&self, user: String, _pass: String, _cnsl: &mut Console) -> Result<bool> {
+        // any password is accepted
+        if user.is_empty()
+            || !user
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(ErrorKind::Auth.tag(anyhow!("Invalid username : {}", user)));
+        }
+        match self.load_user()? {
+            Some(current_user) if current_user != user => {
+                Err(ErrorKind::Auth.tag(anyhow!("Logged in as another user: {}", current_user)))
+            }
+            Some(_) => Ok(false),
+            None => {
+                let mut storage = CookieStorage::open(self.session.cookies_path())
+                    .context("Could not open cookie storage")?;
+                storage.set_value(&SESSION_URL, USER_COOKIE_NAME, &user)?;
+                Ok(true)
+            }
+        }
+    }
+
+    async fn participate(&self, contest_id: &ContestId, _cnsl: &mut Console) -> Result<bool> {
+        self.ensure_login()?;
+        self.load_contest(contest_id)?;
+        // every user is registered to mock contests
+        Ok(false)
+    }
+
+    async fn fetch(
+        &self,
+        contest_id: &ContestId,
+        problem_ids: &[ProblemId],
+        _cnsl: &mut Console,
+    ) -> Result<(Contest, Vec<Problem>, PatternReport)> {
+        let mock_contest = self.load_contest(contest_id)?;
+        if let Some(problem_id) = problem_ids
+            .iter()
+            .find(|&problem_id| mock_contest.find_problem(problem_id).is_none())
+        {
+            return Err(anyhow!(
+                "Could not find problem \"{}\" in contest {}",
+                problem_id,
+                contest_id
+            ));
+        }
+        let problems: Vec<Problem> = mock_contest
+            .problems
+            .into_iter()
+            .filter(|problem| problem_ids.is_empty() || problem_ids.contains(problem.id()))
+            .collect();
+        if problems.is_empty() {
+            return Err(anyhow!(
+                "Could not find any problems in contest {}",
+                contest_id
+            ));
+        }
+
+        let contest = Contest::new(contest_id.to_owned(), mock_contest.name);
+        Ok((contest, problems, PatternReport::default()))
+    }
+
+    async fn fetch_contest_info(
+        &self,
+        contest_id: &ContestId,
+        _cnsl: &mut Console,
+    ) -> Result<ContestInfo> {
+        let mock_contest = self.load_contest(contest_id)?;
+        let contest = Contest::new(contest_id.to_owned(), mock_contest.name);
+        Ok(ContestInfo::new(
+            contest,
+            mock_contest.start_at,
+            mock_contest.end_at,
+        ))
+    }
+
+    async fn fetch_statements(
+        &self,
+        contest_id: &ContestId,
+        problems: &[Problem],
+        _cnsl: &mut Console,
+    ) -> Result<Vec<Statement>> {
+        let mock_contest = self.load_contest(contest_id)?;
+        let statements = problems
+            .iter()
+            .map(|problem| {
+                let html = mock_contest
+                    .statements
+                    .iter()
+                    .find(|(id, _)| &ProblemId::from(id.as_str()) == problem.id())
+                    .map(|(_, html)| html.to_owned())
+                    .unwrap_or_else(|| format!("<p>{}</p>", problem.name()));
+                Statement::new(problem.id().clone(), html, Vec::new())
+            })
+            .collect();
+        Ok(statements)
+    }
+
+    async fn fetch_lang_names(
+        &self,
+        contest_id: &ContestId,
+        _cnsl: &mut Console,
+    ) -> Result<Vec<LangName>> {
+        self.ensure_login()?;
+        Ok(self.load_contest(contest_id)?.lang_names)
+    }
+
+    async fn submit<'a>(
+        &self,
+        contest_id: &ContestId,
+        problem: &Problem,
+        lang_names: &'a [LangName],
+        source: &str,
+        is_dry_run: bool,
+        cnsl: &mut Console,
+    ) -> Result<LangNameRef<'a>> {
+        let user = self.ensure_login()?;
+        let mock_contest = self.load_contest(contest_id)?;
+        let problem = mock_contest.find_problem(problem.id()).with_context(|| {
+            format!(
+                "Could not find problem \"{}\" in contest {}",
+                problem.id(),
+                contest_id
+            )
+        })?;
+
+        // find lang name
+        let lang_name = lang_names
+            .iter()
+            .find(|lang_name| {
+                mock_contest.lang_names.is_empty() || mock_contest.lang_names.contains(*lang_name)
+            })
+            .ok_or_else(|| LangNotFound(lang_names.to_vec()))?;
+
+        // show what would be submitted without recording it
+        if is_dry_run {
+            writeln!(cnsl, "Would submit {} as {}", problem.url_name(), lang_name)?;
+            return Ok(lang_name);
+        }
+
+        // record submission judged with the verdict of contest
+        let submission_id = self.next_submission_id(contest_id)?.to_string();
+        let is_accepted = mock_contest.verdict == "AC";
+        let score = match problem.points() {
+            Some(points) if is_accepted => points,
+            _ => 0,
+        };
+        let submission = MockSubmission {
+            submission: Submission::new(
+                submission_id.as_str(),
+                format!("{} - {}", problem.id(), problem.name()),
+                user,
+                lang_name.as_str(),
+                score.to_string(),
+                JudgeResult::new(mock_contest.verdict.as_str(), None, None),
+                Vec::new(),
+            ),
+            source: source.to_owned(),
+        };
+        self.submission_path(contest_id, &submission_id)
+            .save_pretty(
+                |file| Ok(serde_yaml::to_writer(file, &submission)?),
+                false,
+                Some(self.session.mock_dir()),
+                cnsl,
+            )
+            .context("Could not record submission")?;
+        writeln!(
+            cnsl,
+            "Submission {} was judged as {}",
+            submission_id, mock_contest.verdict
+        )?;
+
+        Ok(lang_name)
+    }
+
+    async fn fetch_submission(
+        &self,
+        contest_id: &ContestId,
+        submission_id: &str,
+        _cnsl: &mut Console,
+    ) -> Result<Submission> {
+        self.load_submission(contest_id, submission_id)
+            .map(|mock_submission| mock_submission.submission)
+    }
+
+    async fn fetch_submission_source(
+        &self,
+        contest_id: &ContestId,
+        submission_id: &str,
+        _cnsl: &mut Console,
+    ) -> Result<String> {
+        self.load_submission(contest_id, submission_id)
+            .map(|mock_submission| mock_submission.source)
+    }
+
+    async fn fetch_latest_submission(
+        &self,
+        contest_id: &ContestId,
+        problem: &Problem,
+        cnsl: &mut Console,
+    ) -> Result<Option<Submission>> {
+        let user = self.ensure_login()?;
+        let n_submissions = self.next_submission_id(contest_id)? - 1;
+        // ids of submissions are sequential, so the latest one has the largest id
+        for submission_id in (1..=n_submissions).rev() {
+            let submission = self
+                .fetch_submission(contest_id, &submission_id.to_string(), cnsl)
+                .await?;
+            if submission.user() == &user && submission.problem_id().as_ref() == Some(problem.id())
+            {
+                return Ok(Some(submission));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn fetch_standings(
+        &self,
+        contest_id: &ContestId,
+        _cnsl: &mut Console,
+    ) -> Result<Standings> {
+        let mock_contest = self.load_contest(contest_id)?;
+        Ok(Standings::new(mock_contest.standings))
+    }
+
+    fn open_problem_url(
+        &self,
+        contest_id: &ContestId,
+        _problem: &Problem,
+        cnsl: &mut Console,
+    ) -> Result<()> {
+        writeln!(
+            cnsl,
+            "Mock service has no web pages. Problems are defined in {}",
+            self.contest_dir(contest_id).join(CONTEST_FILE_NAME)
+        )?;
+        Ok(())
+    }
+
+    fn open_submissions_url(&self, contest_id: &ContestId, cnsl: &mut Console) -> Result<()> {
+        writeln!(
+            cnsl,
+            "Mock service has no web pages. Submissions are recorded in {}",
+            self.contest_dir(contest_id).join(SUBMISSIONS_DIR_NAME)
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::Config;
+
+    #[tokio::test]
+    async fn run_demo_contest() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let conf = Config::default_in_dir(AbsPathBuf::try_new(test_dir.path().to_owned())?);
+        let actor = MockActor::new(conf.session());
+        let mut cnsl = Console::sink(Default::default());
+        let contest_id = ContestId::from(DEMO_CONTEST_ID);
+
+        let (contest, problems, _) = actor.fetch(&contest_id, &[], &mut cnsl).await?;
+        assert_eq!(contest.name(), "acick Demo Contest");
+        assert_eq!(problems.len(), 2);
+        assert!(actor
+            .submit(&contest_id, &problems[0], &[], "", false, &mut cnsl)
+            .await
+            .is_err());
+
+        assert!(actor.login("alice".into(), "".into(), &mut cnsl).await?);
+        assert_eq!(
+            actor.current_user(&mut cnsl).await?.as_deref(),
+            Some("alice")
+        );
+        let err = actor
+            .submit(&contest_id, &problems[0], &[], "", false, &mut cnsl)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<LangNotFound>().is_some());
+        let lang_names = vec![LangName::from("C++ (GCC 9.2.1)")];
+        actor
+            .submit(
+                &contest_id,
+                &problems[0],
+                &lang_names,
+                "int main() {}\n",
+                false,
+                &mut cnsl,
+            )
+            .await?;
+        let submission = actor.fetch_submission(&contest_id, "1", &mut cnsl).await?;
+        assert_eq!(
+            actor
+                .fetch_submission_source(&contest_id, "1", &mut cnsl)
+                .await?,
+            "int main() {}\n"
+        );
+        assert_eq!(submission.user(), "alice");
+        assert_eq!(submission.score(), "100");
+        assert!(submission.result().is_accepted());
+        let latest = actor
+            .fetch_latest_submission(&contest_id, &problems[0], &mut cnsl)
+            .await?;
+        assert_eq!(latest, Some(submission));
+        assert_eq!(
+            actor
+                .fetch_latest_submission(&contest_id, &problems[1], &mut cnsl)
+                .await?,
+            None
+        );
+
+        assert!(actor
+            .fetch(&ContestId::from("unknown"), &[], &mut cnsl)
+            .await
+            .is_err());
+        Ok(())
+    }
+}
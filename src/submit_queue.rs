@@ -0,0 +1,140 @@
+use chrono::offset::Local;
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+use crate::abs_path::AbsPathBuf;
+use crate::history::LocalDateTime;
+use crate::model::{ContestId, LangName, ProblemId, ServiceKind};
+use crate::store::StateStore;
+use crate::Result;
+
+/// Submission that could not be sent because of network error.
+#[derive(Serialize, Deserialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PendingSubmission {
+    #[get_copy = "pub"]
+    service_id: ServiceKind,
+    #[get = "pub"]
+    contest_id: ContestId,
+    #[get = "pub"]
+    problem_id: ProblemId,
+    /// Languages tried in order when the submission is replayed
+    #[get = "pub"]
+    lang_names: Vec<LangName>,
+    /// Hash of source code, which detects source files modified after queued
+    #[get = "pub"]
+    source_hash: String,
+    #[get = "pub"]
+    source_path: AbsPathBuf,
+    #[get = "pub"]
+    queued_at: LocalDateTime,
+}
+
+impl PendingSubmission {
+    pub fn new(
+        service_id: ServiceKind,
+        contest_id: ContestId,
+        problem_id: ProblemId,
+        lang_names: Vec<LangName>,
+        source_hash: String,
+        source_path: AbsPathBuf,
+    ) -> Self {
+        Self {
+            service_id,
+            contest_id,
+            problem_id,
+            lang_names,
+            source_hash,
+            source_path,
+            queued_at: Local::now(),
+        }
+    }
+}
+
+/// Queue of pending submissions, which has at most one submission for each problem.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SubmitQueue {
+    store: StateStore,
+}
+
+impl SubmitQueue {
+    pub fn new(path: AbsPathBuf) -> Self {
+        Self {
+            store: StateStore::new(path),
+        }
+    }
+
+    /// Adds the submission, replacing the pending one of the same problem.
+    pub fn push(&self, submission: &PendingSubmission) -> Result<()> {
+        let key = key(
+            submission.service_id,
+            &submission.contest_id,
+            &submission.problem_id,
+        );
+        self.store.set(&key, submission)
+    }
+
+    pub fn remove(
+        &self,
+        service_id: ServiceKind,
+        contest_id: &ContestId,
+        problem_id: &ProblemId,
+    ) -> Result<()> {
+        self.store.remove(&key(service_id, contest_id, problem_id))
+    }
+
+    /// Loads pending submissions in the order they were queued.
+    pub fn load(&self) -> Result<Vec<PendingSubmission>> {
+        let mut submissions = self
+            .store
+            .entries()?
+            .into_iter()
+            .map(|(_, value)| Ok(serde_json::from_value(value)?))
+            .collect::<Result<Vec<PendingSubmission>>>()?;
+        submissions.sort_by_key(|submission| submission.queued_at);
+        Ok(submissions)
+    }
+}
+
+fn key(service_id: ServiceKind, contest_id: &ContestId, problem_id: &ProblemId) -> String {
+    format!("{}/{}/{}", service_id, contest_id, problem_id).to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn submission(problem_id: &str, source_hash: &str) -> PendingSubmission {
+        PendingSubmission::new(
+            ServiceKind::Atcoder,
+            "arc100".into(),
+            problem_id.into(),
+            vec!["C++ (GCC 9.2.1)".into()],
+            source_hash.into(),
+            AbsPathBuf::try_new("/tmp/Main.cpp").unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_push_and_remove() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let queue = SubmitQueue::new(AbsPathBuf::try_new(test_dir.path())?.join("queue.jsonl"));
+        assert!(queue.load()?.is_empty());
+
+        queue.push(&submission("D", "0"))?;
+        queue.push(&submission("C", "1"))?;
+        // newer submission of the same problem replaces the older one
+        queue.push(&submission("d", "2"))?;
+        let hashes: Vec<_> = queue
+            .load()?
+            .iter()
+            .map(|submission| submission.source_hash().clone())
+            .collect();
+        assert_eq!(hashes, vec!["1", "2"]);
+
+        queue.remove(ServiceKind::Atcoder, &"ARC100".into(), &"C".into())?;
+        assert_eq!(queue.load()?.len(), 1);
+        Ok(())
+    }
+}
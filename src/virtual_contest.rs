@@ -0,0 +1,188 @@
+use std::fs;
+use std::time::Duration;
+
+use anyhow::Context as _;
+use chrono::offset::Local;
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+use crate::abs_path::AbsPathBuf;
+use crate::history::LocalDateTime;
+use crate::model::{ContestId, ServiceKind};
+use crate::Result;
+
+/// Virtual participation in a past contest.
+#[derive(Serialize, Deserialize, Getters, CopyGetters, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VirtualSession {
+    #[get_copy = "pub"]
+    service_id: ServiceKind,
+    #[get = "pub"]
+    contest_id: ContestId,
+    #[get = "pub"]
+    started_at: LocalDateTime,
+    /// Duration of the contest (no limit if not known)
+    #[serde(with = "humantime_serde", default)]
+    #[get_copy = "pub"]
+    duration: Option<Duration>,
+}
+
+impl VirtualSession {
+    pub fn new(
+        service_id: ServiceKind,
+        contest_id: ContestId,
+        started_at: LocalDateTime,
+        duration: Option<Duration>,
+    ) -> Self {
+        Self {
+            service_id,
+            contest_id,
+            started_at,
+            duration,
+        }
+    }
+
+    /// Returns elapsed time of the virtual contest at `time`, which is None before start.
+    pub fn elapsed_at(&self, time: LocalDateTime) -> Option<Duration> {
+        (time - self.started_at).to_std().ok()
+    }
+
+    pub fn is_ended_at(&self, time: LocalDateTime) -> bool {
+        match (self.elapsed_at(time), self.duration) {
+            (Some(elapsed), Some(duration)) => elapsed >= duration,
+            _ => false,
+        }
+    }
+
+    /// Returns a line that describes elapsed time (e.g. `virtual arc100 : 00:42:13 / 01:40:00`).
+    pub fn status_line(&self) -> String {
+        let now = Local::now();
+        let elapsed = self.elapsed_at(now).unwrap_or_default();
+        let mut line = format!("virtual {} : {}", self.contest_id, fmt_hms(elapsed));
+        if let Some(duration) = self.duration {
+            line.push_str(&format!(" / {}", fmt_hms(duration)));
+            if self.is_ended_at(now) {
+                line.push_str(" (ended)");
+            }
+        }
+        line
+    }
+
+    fn is_for(&self, service_id: ServiceKind, contest_id: &ContestId) -> bool {
+        self.service_id == service_id && &self.contest_id == contest_id
+    }
+}
+
+/// Formats duration as `hh:mm:ss`.
+pub fn fmt_hms(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Virtual participations stored as a json file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VirtualSessions<'a> {
+    path: &'a AbsPathBuf,
+}
+
+impl<'a> VirtualSessions<'a> {
+    pub fn new(path: &'a AbsPathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn find(
+        &self,
+        service_id: ServiceKind,
+        contest_id: &ContestId,
+    ) -> Result<Option<VirtualSession>> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .find(|session| session.is_for(service_id, contest_id)))
+    }
+
+    /// Saves session replacing the existing session of the same contest.
+    pub fn save(&self, session: &VirtualSession) -> Result<()> {
+        let mut sessions = self.load()?;
+        sessions.retain(|s| !s.is_for(session.service_id, &session.contest_id));
+        sessions.push(session.clone());
+        self.write(&sessions)
+    }
+
+    /// Removes session of the contest. Returns the removed session if any.
+    pub fn remove(
+        &self,
+        service_id: ServiceKind,
+        contest_id: &ContestId,
+    ) -> Result<Option<VirtualSession>> {
+        let (removed, kept): (Vec<_>, Vec<_>) = self
+            .load()?
+            .into_iter()
+            .partition(|session| session.is_for(service_id, contest_id));
+        if !removed.is_empty() {
+            self.write(&kept)?;
+        }
+        Ok(removed.into_iter().next())
+    }
+
+    fn load(&self) -> Result<Vec<VirtualSession>> {
+        if !self.path.as_ref().is_file() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(self.path.as_ref())
+            .context("Could not read virtual participation file")?;
+        serde_json::from_str(&content).context("Could not parse virtual participation file")
+    }
+
+    fn write(&self, sessions: &[VirtualSession]) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            dir.create_dir_all()?;
+        }
+        let content = serde_json::to_string_pretty(sessions)?;
+        fs::write(self.path.as_ref(), content).context("Could not write virtual participation file")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_save_find_remove() -> anyhow::Result<()> {
+        let test_dir = tempdir()?;
+        let path = AbsPathBuf::try_new(test_dir.path())?.join("virtual.json");
+        let sessions = VirtualSessions::new(&path);
+        let contest_id = ContestId::from("arc100");
+        assert_eq!(sessions.find(ServiceKind::Atcoder, &contest_id)?, None);
+
+        let started_at = Local::now() - chrono::Duration::minutes(30);
+        let session = VirtualSession::new(
+            ServiceKind::Atcoder,
+            contest_id.clone(),
+            started_at,
+            Some(Duration::from_secs(100 * 60)),
+        );
+        sessions.save(&session)?;
+        sessions.save(&session)?;
+        assert_eq!(
+            sessions.find(ServiceKind::Atcoder, &contest_id)?,
+            Some(session.clone())
+        );
+        assert!(!session.is_ended_at(Local::now()));
+        assert!(session.is_ended_at(started_at + chrono::Duration::minutes(100)));
+
+        assert_eq!(
+            sessions.remove(ServiceKind::Atcoder, &contest_id)?,
+            Some(session)
+        );
+        assert_eq!(sessions.find(ServiceKind::Atcoder, &contest_id)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fmt_hms() {
+        assert_eq!(fmt_hms(Duration::from_secs(42)), "00:00:42");
+        assert_eq!(fmt_hms(Duration::from_secs(2 * 3600 + 61)), "02:01:01");
+    }
+}